@@ -0,0 +1,78 @@
+use anyhow::{bail, Context, Result};
+use std::time::Duration;
+
+/// The result of a completed OAuth2 authorization-code redirect.
+pub struct CallbackResult {
+    pub code: String,
+    pub state: String,
+    pub error: Option<String>,
+    pub error_description: Option<String>,
+}
+
+/// A short-lived local HTTP server that catches the browser redirect from the
+/// OAuth2 authorization endpoint.
+pub struct CallbackServer {
+    server: tiny_http::Server,
+    port: u16,
+}
+
+impl CallbackServer {
+    /// Bind to an ephemeral localhost port and start listening for the redirect.
+    pub async fn new() -> Result<Self> {
+        let server = tiny_http::Server::http("127.0.0.1:0")
+            .map_err(|e| anyhow::anyhow!("failed to start OAuth callback server: {e}"))?;
+        let port = server
+            .server_addr()
+            .to_ip()
+            .context("callback server has no IP address")?
+            .port();
+        Ok(Self { server, port })
+    }
+
+    /// The `redirect_uri` to register with the authorization server.
+    pub fn redirect_uri(&self) -> String {
+        format!("http://127.0.0.1:{}/callback", self.port)
+    }
+
+    /// Block (off the async executor) until the browser hits the redirect, or
+    /// `timeout` elapses.
+    pub async fn wait_for_callback(&mut self, timeout: Duration) -> Result<CallbackResult> {
+        let deadline = std::time::Instant::now() + timeout;
+        loop {
+            let remaining = deadline.saturating_duration_since(std::time::Instant::now());
+            if remaining.is_zero() {
+                bail!("timed out waiting for OAuth callback");
+            }
+            let request = match self.server.recv_timeout(remaining) {
+                Ok(Some(request)) => request,
+                Ok(None) => continue,
+                Err(e) => bail!("callback server error: {e}"),
+            };
+
+            let query = request.url().splitn(2, '?').nth(1).unwrap_or("").to_string();
+            let params = parse_query(&query);
+
+            let body = "<html><body>Login complete — you can close this tab and return to the terminal.</body></html>";
+            let response = tiny_http::Response::from_string(body)
+                .with_header(tiny_http::Header::from_bytes(&b"Content-Type"[..], &b"text/html"[..]).unwrap());
+            let _ = request.respond(response);
+
+            return Ok(CallbackResult {
+                code: params.get("code").cloned().unwrap_or_default(),
+                state: params.get("state").cloned().unwrap_or_default(),
+                error: params.get("error").cloned(),
+                error_description: params.get("error_description").cloned(),
+            });
+        }
+    }
+}
+
+fn parse_query(query: &str) -> std::collections::HashMap<String, String> {
+    query
+        .split('&')
+        .filter_map(|pair| {
+            let (k, v) = pair.split_once('=')?;
+            Some((urlencoding::decode(k).ok()?.into_owned(), urlencoding::decode(v).ok()?.into_owned()))
+        })
+        .collect()
+}
@@ -0,0 +1,337 @@
+use anyhow::{bail, Result};
+use serde::Deserialize;
+
+use super::discovery::{self, OidcDocument};
+use super::pkce::PkceChallenge;
+use super::types::{ClientCredentials, TokenSet};
+
+/// Dynamic Client Registration + OAuth2 client for a single Datadog site.
+///
+/// Endpoints are resolved via OIDC discovery (`super::discovery`), which
+/// caches the result on disk so repeated calls don't re-hit the network.
+pub struct DcrClient {
+    site: String,
+    http: reqwest::Client,
+}
+
+#[derive(Deserialize)]
+struct RegisterResponse {
+    client_id: String,
+    client_name: String,
+    redirect_uris: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    #[serde(default)]
+    refresh_token: String,
+    #[serde(default = "default_token_type")]
+    token_type: String,
+    expires_in: i64,
+    #[serde(default)]
+    scope: String,
+}
+
+fn default_token_type() -> String {
+    "Bearer".to_string()
+}
+
+#[derive(Deserialize)]
+struct OAuthErrorResponse {
+    error: String,
+    #[serde(default)]
+    error_description: Option<String>,
+}
+
+/// Response from the device-authorization endpoint (RFC 8628 section 3.2).
+pub struct DeviceAuthorization {
+    pub device_code: String,
+    pub user_code: String,
+    pub verification_uri: String,
+    pub verification_uri_complete: Option<String>,
+    pub interval: u64,
+    pub expires_in: i64,
+}
+
+#[derive(Deserialize)]
+struct DeviceAuthorizationResponse {
+    device_code: String,
+    user_code: String,
+    verification_uri: String,
+    #[serde(default)]
+    verification_uri_complete: Option<String>,
+    #[serde(default = "default_interval")]
+    interval: u64,
+    expires_in: i64,
+}
+
+fn default_interval() -> u64 {
+    5
+}
+
+impl DcrClient {
+    pub fn new(site: &str) -> Self {
+        Self {
+            site: site.to_string(),
+            http: reqwest::Client::new(),
+        }
+    }
+
+    async fn endpoints(&self) -> Result<OidcDocument> {
+        discovery::discover(&self.site).await
+    }
+
+    /// Register a new OAuth2 client via Dynamic Client Registration.
+    pub async fn register(
+        &self,
+        redirect_uri: &str,
+        scopes: &[&str],
+    ) -> Result<ClientCredentials> {
+        let endpoints = self.endpoints().await?;
+        let resp = self
+            .http
+            .post(endpoints.registration_endpoint)
+            .json(&serde_json::json!({
+                "client_name": "pup CLI",
+                "redirect_uris": [redirect_uri],
+                "scope": scopes.join(" "),
+            }))
+            .send()
+            .await
+            .map_err(|e| anyhow::anyhow!("failed to register OAuth2 client: {e}"))?;
+
+        if !resp.status().is_success() {
+            bail!(
+                "client registration failed: HTTP {} — {}",
+                resp.status(),
+                resp.text().await.unwrap_or_default()
+            );
+        }
+
+        let body: RegisterResponse = resp
+            .json()
+            .await
+            .map_err(|e| anyhow::anyhow!("failed to parse registration response: {e}"))?;
+
+        Ok(ClientCredentials {
+            client_id: body.client_id,
+            client_name: body.client_name,
+            redirect_uris: body.redirect_uris,
+            registered_at: chrono::Utc::now().timestamp(),
+            site: self.site.clone(),
+        })
+    }
+
+    /// Build the browser-facing authorization URL for the PKCE code flow.
+    pub async fn build_authorization_url(
+        &self,
+        client_id: &str,
+        redirect_uri: &str,
+        state: &str,
+        challenge: &PkceChallenge,
+        scopes: &[&str],
+        extra_params: &[(String, String)],
+    ) -> Result<String> {
+        let endpoints = self.endpoints().await?;
+        let mut serializer = url::form_urlencoded::Serializer::new(String::new());
+        serializer
+            .append_pair("response_type", "code")
+            .append_pair("client_id", client_id)
+            .append_pair("redirect_uri", redirect_uri)
+            .append_pair("state", state)
+            .append_pair("code_challenge", &challenge.challenge)
+            .append_pair("code_challenge_method", "S256")
+            .append_pair("scope", &scopes.join(" "));
+        for (key, value) in extra_params {
+            serializer.append_pair(key, value);
+        }
+        Ok(format!("{}?{}", endpoints.authorization_endpoint, serializer.finish()))
+    }
+
+    /// Exchange an authorization code for a token set.
+    pub async fn exchange_code(
+        &self,
+        code: &str,
+        redirect_uri: &str,
+        verifier: &str,
+        creds: &ClientCredentials,
+    ) -> Result<TokenSet> {
+        let params = [
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", redirect_uri),
+            ("client_id", &creds.client_id),
+            ("code_verifier", verifier),
+        ];
+        self.request_token(&params).await
+    }
+
+    /// Exchange a refresh token for a fresh token set. Many providers don't
+    /// return a new `refresh_token` on a refresh grant, so the old one is
+    /// carried over when the response omits it.
+    pub async fn refresh_token(
+        &self,
+        refresh_token: &str,
+        creds: &ClientCredentials,
+    ) -> Result<TokenSet> {
+        let params = [
+            ("grant_type", "refresh_token"),
+            ("refresh_token", refresh_token),
+            ("client_id", &creds.client_id),
+        ];
+        let mut tokens = self.request_token(&params).await?;
+        if tokens.refresh_token.is_empty() {
+            tokens.refresh_token = refresh_token.to_string();
+        }
+        Ok(tokens)
+    }
+
+    /// Start a device authorization grant (RFC 8628): request a `device_code`
+    /// and `user_code` for the user to enter on any device with a browser.
+    pub async fn device_authorize(
+        &self,
+        client_id: &str,
+        scopes: &[&str],
+    ) -> Result<DeviceAuthorization> {
+        let endpoints = self.endpoints().await?;
+        let device_authorization_endpoint = endpoints
+            .device_authorization_endpoint
+            .ok_or_else(|| anyhow::anyhow!("site {} does not advertise a device_authorization_endpoint", self.site))?;
+        let resp = self
+            .http
+            .post(device_authorization_endpoint)
+            .form(&[("client_id", client_id), ("scope", &scopes.join(" "))])
+            .send()
+            .await
+            .map_err(|e| anyhow::anyhow!("failed to start device authorization: {e}"))?;
+
+        if !resp.status().is_success() {
+            bail!(
+                "device authorization request failed: HTTP {} — {}",
+                resp.status(),
+                resp.text().await.unwrap_or_default()
+            );
+        }
+
+        let body: DeviceAuthorizationResponse = resp
+            .json()
+            .await
+            .map_err(|e| anyhow::anyhow!("failed to parse device authorization response: {e}"))?;
+
+        Ok(DeviceAuthorization {
+            device_code: body.device_code,
+            user_code: body.user_code,
+            verification_uri: body.verification_uri,
+            verification_uri_complete: body.verification_uri_complete,
+            interval: body.interval,
+            expires_in: body.expires_in,
+        })
+    }
+
+    /// Poll the token endpoint until the user completes the device flow in
+    /// their browser, or the device code expires.
+    pub async fn poll_device_token(
+        &self,
+        device_auth: &DeviceAuthorization,
+        creds: &ClientCredentials,
+    ) -> Result<TokenSet> {
+        let token_endpoint = self.endpoints().await?.token_endpoint;
+        let mut interval = std::time::Duration::from_secs(device_auth.interval);
+        let deadline =
+            std::time::Instant::now() + std::time::Duration::from_secs(device_auth.expires_in.max(0) as u64);
+
+        loop {
+            tokio::time::sleep(interval).await;
+
+            if std::time::Instant::now() >= deadline {
+                bail!("device code expired before authorization completed");
+            }
+
+            let params = [
+                ("grant_type", "urn:ietf:params:oauth:grant-type:device_code"),
+                ("device_code", &device_auth.device_code),
+                ("client_id", &creds.client_id),
+            ];
+            let resp = self
+                .http
+                .post(&token_endpoint)
+                .form(&params)
+                .send()
+                .await
+                .map_err(|e| anyhow::anyhow!("failed to poll device token endpoint: {e}"))?;
+
+            if resp.status().is_success() {
+                let body: TokenResponse = resp
+                    .json()
+                    .await
+                    .map_err(|e| anyhow::anyhow!("failed to parse token response: {e}"))?;
+                return Ok(TokenSet {
+                    access_token: body.access_token,
+                    refresh_token: body.refresh_token,
+                    token_type: body.token_type,
+                    expires_in: body.expires_in,
+                    issued_at: chrono::Utc::now().timestamp(),
+                    scope: body.scope,
+                    client_id: creds.client_id.clone(),
+                });
+            }
+
+            let err: OAuthErrorResponse = resp
+                .json()
+                .await
+                .map_err(|e| anyhow::anyhow!("failed to parse device flow error response: {e}"))?;
+
+            match err.error.as_str() {
+                "authorization_pending" => continue,
+                "slow_down" => interval += std::time::Duration::from_secs(5),
+                "access_denied" => bail!("authorization denied by user"),
+                "expired_token" => bail!("device code expired before authorization completed"),
+                other => bail!(
+                    "device flow error: {other}: {}",
+                    err.error_description.unwrap_or_default()
+                ),
+            }
+        }
+    }
+
+    async fn request_token(&self, params: &[(&str, &str)]) -> Result<TokenSet> {
+        let endpoints = self.endpoints().await?;
+        let resp = self
+            .http
+            .post(endpoints.token_endpoint)
+            .form(params)
+            .send()
+            .await
+            .map_err(|e| anyhow::anyhow!("failed to reach token endpoint: {e}"))?;
+
+        if !resp.status().is_success() {
+            bail!(
+                "token request failed: HTTP {} — {}",
+                resp.status(),
+                resp.text().await.unwrap_or_default()
+            );
+        }
+
+        let client_id = params
+            .iter()
+            .find(|(k, _)| *k == "client_id")
+            .map(|(_, v)| v.to_string())
+            .unwrap_or_default();
+
+        let body: TokenResponse = resp
+            .json()
+            .await
+            .map_err(|e| anyhow::anyhow!("failed to parse token response: {e}"))?;
+
+        Ok(TokenSet {
+            access_token: body.access_token,
+            refresh_token: body.refresh_token,
+            token_type: body.token_type,
+            expires_in: body.expires_in,
+            issued_at: chrono::Utc::now().timestamp(),
+            scope: body.scope,
+            client_id,
+        })
+    }
+}
@@ -0,0 +1,94 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// The subset of an OpenID Connect discovery document (`.well-known/openid-configuration`)
+/// that `pup` needs to drive the authorization code, device, and refresh flows.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OidcDocument {
+    pub authorization_endpoint: String,
+    pub token_endpoint: String,
+    pub registration_endpoint: String,
+    #[serde(default)]
+    pub device_authorization_endpoint: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CacheEntry {
+    fetched_at: i64,
+    document: OidcDocument,
+}
+
+const DEFAULT_TTL_SECS: i64 = 24 * 3600;
+
+fn sanitize(site: &str) -> String {
+    site.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+fn cache_path(site: &str) -> Result<PathBuf> {
+    let dir = crate::config::config_dir().context("could not determine config directory")?;
+    Ok(dir.join("oidc-cache").join(format!("{}.json", sanitize(site))))
+}
+
+fn read_cache(site: &str) -> Option<CacheEntry> {
+    let path = cache_path(site).ok()?;
+    let contents = std::fs::read_to_string(path).ok()?;
+    serde_json::from_str(&contents).ok()
+}
+
+fn write_cache(site: &str, document: &OidcDocument) -> Result<()> {
+    let path = cache_path(site)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let entry = CacheEntry {
+        fetched_at: chrono::Utc::now().timestamp(),
+        document: document.clone(),
+    };
+    std::fs::write(path, serde_json::to_string_pretty(&entry)?)?;
+    Ok(())
+}
+
+/// Discover `site`'s OAuth2/OIDC endpoints, caching the result on disk for
+/// `DEFAULT_TTL_SECS` so repeated `login`/`refresh`/`token` calls don't re-hit
+/// the network. Falls back to a stale cached copy (with a warning) if live
+/// discovery fails.
+pub async fn discover(site: &str) -> Result<OidcDocument> {
+    if let Some(entry) = read_cache(site) {
+        let age = chrono::Utc::now().timestamp() - entry.fetched_at;
+        if age < DEFAULT_TTL_SECS {
+            return Ok(entry.document);
+        }
+    }
+
+    let url = format!("https://app.{site}/.well-known/openid-configuration");
+    match fetch(&url).await {
+        Ok(document) => {
+            let _ = write_cache(site, &document);
+            Ok(document)
+        }
+        Err(e) => match read_cache(site) {
+            Some(entry) => {
+                eprintln!(
+                    "Warning: OIDC discovery failed ({e:#}), falling back to cached endpoints for {site}"
+                );
+                Ok(entry.document)
+            }
+            None => Err(e),
+        },
+    }
+}
+
+async fn fetch(url: &str) -> Result<OidcDocument> {
+    let resp = reqwest::get(url)
+        .await
+        .map_err(|e| anyhow::anyhow!("failed to fetch OIDC discovery document from {url}: {e}"))?;
+    if !resp.status().is_success() {
+        anyhow::bail!("OIDC discovery request to {url} failed: HTTP {}", resp.status());
+    }
+    resp.json()
+        .await
+        .map_err(|e| anyhow::anyhow!("failed to parse OIDC discovery document from {url}: {e}"))
+}
@@ -0,0 +1,330 @@
+//! OAuth2/OIDC authentication against a gateway fronting the Datadog API,
+//! as an alternative to [`super::dcr`]'s per-site Dynamic Client
+//! Registration flow. `TokenChecker` exchanges client credentials (or an
+//! authorization code) for a bearer token, verifies any returned ID token
+//! against the provider's JWKS, and caches both the verified signing keys
+//! and a userinfo lookup so repeated `pup` invocations don't re-hit the
+//! network or re-verify on every call.
+
+use anyhow::{bail, Context, Result};
+use jsonwebtoken::jwk::JwkSet;
+use jsonwebtoken::{Algorithm, DecodingKey, Validation};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use super::types::TokenSet;
+
+const JWKS_TTL_SECS: i64 = 3600;
+const USERINFO_TTL_SECS: i64 = 300;
+
+struct CachedKeys {
+    fetched_at: i64,
+    keys: JwkSet,
+}
+
+struct CachedUserinfo {
+    fetched_at: i64,
+    value: Value,
+}
+
+/// Configuration + runtime caches for a single OIDC gateway. One instance
+/// is expected to live for the duration of a `pup` invocation (or the
+/// unlock agent, if it grows a resident-process mode later).
+pub struct TokenChecker {
+    http: reqwest::Client,
+    issuer: String,
+    audience: String,
+    jwks_uri: Option<String>,
+    userinfo_uri: Option<String>,
+    required_claims: Vec<String>,
+    /// The only signing algorithm `verify_id_token` will accept, pinned at
+    /// construction time from the gateway's documented signing alg rather
+    /// than trusted from the token header — an ID token whose header names
+    /// a different algorithm is rejected outright.
+    signing_alg: Algorithm,
+    keys_cache: Mutex<Option<CachedKeys>>,
+    userinfo_cache: Mutex<HashMap<String, CachedUserinfo>>,
+}
+
+impl TokenChecker {
+    pub fn new(
+        issuer: impl Into<String>,
+        audience: impl Into<String>,
+        jwks_uri: Option<String>,
+        userinfo_uri: Option<String>,
+        required_claims: Vec<String>,
+        signing_alg: Algorithm,
+    ) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            issuer: issuer.into(),
+            audience: audience.into(),
+            jwks_uri,
+            userinfo_uri,
+            required_claims,
+            signing_alg,
+            keys_cache: Mutex::new(None),
+            userinfo_cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Exchange client credentials for a bearer token via the
+    /// `client_credentials` grant.
+    pub async fn client_credentials(
+        &self,
+        token_endpoint: &str,
+        client_id: &str,
+        client_secret: &str,
+        scope: &str,
+    ) -> Result<TokenSet> {
+        self.request_token(
+            token_endpoint,
+            &[
+                ("grant_type", "client_credentials"),
+                ("client_id", client_id),
+                ("client_secret", client_secret),
+                ("scope", scope),
+            ],
+        )
+        .await
+    }
+
+    /// Exchange an authorization code for a bearer (and, typically, ID)
+    /// token via the `authorization_code` grant.
+    pub async fn authorization_code(
+        &self,
+        token_endpoint: &str,
+        code: &str,
+        redirect_uri: &str,
+        client_id: &str,
+        client_secret: &str,
+    ) -> Result<TokenSet> {
+        self.request_token(
+            token_endpoint,
+            &[
+                ("grant_type", "authorization_code"),
+                ("code", code),
+                ("redirect_uri", redirect_uri),
+                ("client_id", client_id),
+                ("client_secret", client_secret),
+            ],
+        )
+        .await
+    }
+
+    async fn request_token(&self, token_endpoint: &str, params: &[(&str, &str)]) -> Result<TokenSet> {
+        let resp = self
+            .http
+            .post(token_endpoint)
+            .form(params)
+            .send()
+            .await
+            .map_err(|e| anyhow::anyhow!("failed to reach token endpoint {token_endpoint}: {e}"))?;
+
+        if !resp.status().is_success() {
+            bail!(
+                "token request to {token_endpoint} failed: HTTP {} — {}",
+                resp.status(),
+                resp.text().await.unwrap_or_default()
+            );
+        }
+
+        #[derive(serde::Deserialize)]
+        struct TokenResponse {
+            access_token: String,
+            #[serde(default)]
+            refresh_token: String,
+            #[serde(default = "default_token_type")]
+            token_type: String,
+            expires_in: i64,
+            #[serde(default)]
+            scope: String,
+        }
+        fn default_token_type() -> String {
+            "Bearer".to_string()
+        }
+
+        let body: TokenResponse = resp
+            .json()
+            .await
+            .map_err(|e| anyhow::anyhow!("failed to parse token response from {token_endpoint}: {e}"))?;
+
+        Ok(TokenSet {
+            access_token: body.access_token,
+            refresh_token: body.refresh_token,
+            token_type: body.token_type,
+            expires_in: body.expires_in,
+            issued_at: chrono::Utc::now().timestamp(),
+            scope: body.scope,
+            client_id: String::new(),
+        })
+    }
+
+    /// Refresh an expired bearer token transparently.
+    pub async fn refresh(&self, token_endpoint: &str, refresh_token: &str, client_id: &str) -> Result<TokenSet> {
+        self.request_token(
+            token_endpoint,
+            &[
+                ("grant_type", "refresh_token"),
+                ("refresh_token", refresh_token),
+                ("client_id", client_id),
+            ],
+        )
+        .await
+    }
+
+    async fn jwks(&self) -> Result<JwkSet> {
+        let jwks_uri = self
+            .jwks_uri
+            .as_deref()
+            .context("no jwks_uri configured on this TokenChecker")?;
+
+        {
+            let cache = self.keys_cache.lock().unwrap();
+            if let Some(cached) = cache.as_ref() {
+                if chrono::Utc::now().timestamp() - cached.fetched_at < JWKS_TTL_SECS {
+                    return Ok(cached.keys.clone());
+                }
+            }
+        }
+
+        let resp = self
+            .http
+            .get(jwks_uri)
+            .send()
+            .await
+            .map_err(|e| anyhow::anyhow!("failed to fetch JWKS from {jwks_uri}: {e}"))?;
+        if !resp.status().is_success() {
+            bail!("JWKS request to {jwks_uri} failed: HTTP {}", resp.status());
+        }
+        let keys: JwkSet = resp
+            .json()
+            .await
+            .map_err(|e| anyhow::anyhow!("failed to parse JWKS from {jwks_uri}: {e}"))?;
+
+        *self.keys_cache.lock().unwrap() = Some(CachedKeys {
+            fetched_at: chrono::Utc::now().timestamp(),
+            keys: keys.clone(),
+        });
+        Ok(keys)
+    }
+
+    /// Verify `id_token`'s signature against the cached JWKS, its issuer
+    /// and audience, and the configured set of required claims. Returns
+    /// the decoded claim set on success.
+    pub async fn verify_id_token(&self, id_token: &str) -> Result<Value> {
+        let header = jsonwebtoken::decode_header(id_token).context("malformed ID token header")?;
+        let kid = header.kid.context("ID token header has no `kid`")?;
+
+        let jwks = self.jwks().await?;
+        let jwk = jwks
+            .keys
+            .iter()
+            .find(|k| k.common.key_id.as_deref() == Some(kid.as_str()))
+            .with_context(|| format!("no JWKS key matching kid {kid:?}"))?;
+
+        let decoding_key = DecodingKey::from_jwk(jwk)
+            .with_context(|| format!("unsupported JWK for kid {kid:?}"))?;
+
+        let header_alg = header.alg.unwrap_or(Algorithm::RS256);
+        if header_alg != self.signing_alg {
+            bail!(
+                "ID token header alg {header_alg:?} does not match the configured signing algorithm {:?}",
+                self.signing_alg
+            );
+        }
+
+        let mut validation = Validation::new(self.signing_alg);
+        validation.set_issuer(&[&self.issuer]);
+        validation.set_audience(&[&self.audience]);
+        validation.set_required_spec_claims(&["exp", "iss", "aud"]);
+
+        let claims: Value = jsonwebtoken::decode::<Value>(id_token, &decoding_key, &validation)
+            .context("ID token failed signature/claims validation")?
+            .claims;
+
+        let missing: Vec<&str> = self
+            .required_claims
+            .iter()
+            .filter(|c| claims.get(c.as_str()).is_none())
+            .map(String::as_str)
+            .collect();
+        if !missing.is_empty() {
+            bail!("ID token is missing required claim(s): {}", missing.join(", "));
+        }
+
+        Ok(claims)
+    }
+
+    /// Fetch (and cache, for `USERINFO_TTL_SECS`) the userinfo document for
+    /// `access_token`. The token itself is never used as the cache key
+    /// verbatim — only its SHA-256 — so it doesn't linger in memory as
+    /// plaintext any longer than the token set already does.
+    pub async fn userinfo(&self, access_token: &str) -> Result<Value> {
+        let userinfo_uri = self
+            .userinfo_uri
+            .as_deref()
+            .context("no userinfo_uri configured on this TokenChecker")?;
+        let cache_key = token_fingerprint(access_token);
+
+        {
+            let cache = self.userinfo_cache.lock().unwrap();
+            if let Some(cached) = cache.get(&cache_key) {
+                if chrono::Utc::now().timestamp() - cached.fetched_at < USERINFO_TTL_SECS {
+                    return Ok(cached.value.clone());
+                }
+            }
+        }
+
+        let resp = self
+            .http
+            .get(userinfo_uri)
+            .bearer_auth(access_token)
+            .send()
+            .await
+            .map_err(|e| anyhow::anyhow!("failed to fetch userinfo from {userinfo_uri}: {e}"))?;
+        if !resp.status().is_success() {
+            bail!("userinfo request to {userinfo_uri} failed: HTTP {}", resp.status());
+        }
+        let value: Value = resp
+            .json()
+            .await
+            .map_err(|e| anyhow::anyhow!("failed to parse userinfo from {userinfo_uri}: {e}"))?;
+
+        self.userinfo_cache.lock().unwrap().insert(
+            cache_key,
+            CachedUserinfo {
+                fetched_at: chrono::Utc::now().timestamp(),
+                value: value.clone(),
+            },
+        );
+        Ok(value)
+    }
+}
+
+fn token_fingerprint(token: &str) -> String {
+    use sha2::{Digest, Sha256};
+    Sha256::digest(token.as_bytes())
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_fingerprint_is_deterministic_and_not_the_token() {
+        let a = token_fingerprint("secret-token");
+        let b = token_fingerprint("secret-token");
+        assert_eq!(a, b);
+        assert_ne!(a, "secret-token");
+    }
+
+    #[test]
+    fn test_token_fingerprint_differs_by_input() {
+        assert_ne!(token_fingerprint("a"), token_fingerprint("b"));
+    }
+}
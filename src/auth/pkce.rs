@@ -0,0 +1,32 @@
+use anyhow::Result;
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+
+/// A PKCE (RFC 7636) code verifier/challenge pair, using the S256 method.
+pub struct PkceChallenge {
+    pub verifier: String,
+    pub challenge: String,
+}
+
+fn random_url_safe_string(num_bytes: usize) -> String {
+    let mut bytes = vec![0u8; num_bytes];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+/// Generate a PKCE code verifier and its S256 code challenge.
+pub fn generate_pkce_challenge() -> Result<PkceChallenge> {
+    let verifier = random_url_safe_string(32);
+    let digest = Sha256::digest(verifier.as_bytes());
+    let challenge = URL_SAFE_NO_PAD.encode(digest);
+    Ok(PkceChallenge {
+        verifier,
+        challenge,
+    })
+}
+
+/// Generate a random `state` value to guard the authorization code flow against CSRF.
+pub fn generate_state() -> Result<String> {
+    Ok(random_url_safe_string(16))
+}
@@ -3,6 +3,9 @@ use std::path::PathBuf;
 
 use super::types::{ClientCredentials, TokenSet};
 
+#[cfg(all(unix, not(target_arch = "wasm32")))]
+use super::unlock_agent;
+
 // ---------------------------------------------------------------------------
 // Session registry entry — lightweight label (no secrets)
 // ---------------------------------------------------------------------------
@@ -29,6 +32,12 @@ pub trait Storage: Send + Sync {
     fn save_client_credentials(&self, site: &str, creds: &ClientCredentials) -> Result<()>;
     fn load_client_credentials(&self, site: &str) -> Result<Option<ClientCredentials>>;
     fn delete_client_credentials(&self, site: &str) -> Result<()>;
+
+    /// Drop every stored `TokenSet` that is both access-expired (past
+    /// `issued_at + expires_in`, plus `PRUNE_GRACE_SECS` of slack) and
+    /// unusable for refresh (empty `refresh_token`). Returns the number of
+    /// token sets removed.
+    fn prune_expired(&self, now: i64) -> Result<usize>;
 }
 
 #[allow(dead_code)]
@@ -36,6 +45,12 @@ pub trait Storage: Send + Sync {
 pub enum BackendType {
     Keychain,
     File,
+    #[cfg(not(target_arch = "wasm32"))]
+    EncryptedFile,
+    #[cfg(not(target_arch = "wasm32"))]
+    Sqlite,
+    #[cfg(not(target_arch = "wasm32"))]
+    Age,
     #[cfg(feature = "browser")]
     LocalStorage,
 }
@@ -45,6 +60,12 @@ impl std::fmt::Display for BackendType {
         match self {
             BackendType::Keychain => write!(f, "keychain"),
             BackendType::File => write!(f, "file"),
+            #[cfg(not(target_arch = "wasm32"))]
+            BackendType::EncryptedFile => write!(f, "encrypted-file"),
+            #[cfg(not(target_arch = "wasm32"))]
+            BackendType::Sqlite => write!(f, "sqlite"),
+            #[cfg(not(target_arch = "wasm32"))]
+            BackendType::Age => write!(f, "age"),
             #[cfg(feature = "browser")]
             BackendType::LocalStorage => write!(f, "localStorage"),
         }
@@ -59,6 +80,65 @@ pub struct FileStorage {
     base_dir: PathBuf,
 }
 
+/// Write `contents` to `path` crash-safely: write to a sibling temp file,
+/// fsync it, then rename into place. `rename` is atomic on the same
+/// filesystem, so readers never observe a partially-written file.
+fn atomic_write(path: &std::path::Path, contents: &[u8]) -> Result<()> {
+    use std::io::Write;
+
+    let tmp_path = path.with_extension("json.tmp");
+    let mut tmp_file = std::fs::File::create(&tmp_path)
+        .with_context(|| format!("failed to create temp file: {}", tmp_path.display()))?;
+    tmp_file
+        .write_all(contents)
+        .with_context(|| format!("failed to write temp file: {}", tmp_path.display()))?;
+    tmp_file
+        .sync_all()
+        .with_context(|| format!("failed to fsync temp file: {}", tmp_path.display()))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&tmp_path, std::fs::Permissions::from_mode(0o600))?;
+    }
+    std::fs::rename(&tmp_path, path)
+        .with_context(|| format!("failed to replace {}", path.display()))?;
+    Ok(())
+}
+
+/// Advisory cross-process lock (`flock`) held for the duration of a site's
+/// load-mutate-store cycle, so a second `pup` process touching the same
+/// `tokens_<site>.json` can't interleave a write between our read and our
+/// write. Released automatically on drop, including on early-return via `?`.
+struct SiteLock {
+    #[allow(dead_code)]
+    file: std::fs::File,
+}
+
+impl SiteLock {
+    /// `lock_key` is the token/credentials file's stem, e.g. `tokens_app_datadoghq_com`.
+    fn acquire(base_dir: &std::path::Path, lock_key: &str) -> Result<Self> {
+        let lock_path = base_dir.join(format!("{lock_key}.lock"));
+        let file = std::fs::OpenOptions::new()
+            .create(true)
+            .write(true)
+            .open(&lock_path)
+            .with_context(|| format!("failed to open lock file: {}", lock_path.display()))?;
+        {
+            use fs2::FileExt;
+            file.lock_exclusive()
+                .with_context(|| format!("failed to lock {}", lock_path.display()))?;
+        }
+        Ok(Self { file })
+    }
+}
+
+impl Drop for SiteLock {
+    fn drop(&mut self) {
+        use fs2::FileExt;
+        let _ = self.file.unlock();
+    }
+}
+
 impl FileStorage {
     pub fn new() -> Result<Self> {
         let base_dir =
@@ -67,6 +147,22 @@ impl FileStorage {
             .with_context(|| format!("failed to create config dir: {}", base_dir.display()))?;
         Ok(Self { base_dir })
     }
+
+    /// Resolve `{prefix}_{sanitize(site)}.json`, migrating a pre-existing
+    /// `{prefix}_{legacy_sanitize(site)}.json` (written before the
+    /// collision-resistant sanitize() scheme) into place on first access.
+    fn resolve_path(&self, prefix: &str, site: &str) -> PathBuf {
+        let new_path = self.base_dir.join(format!("{prefix}_{}.json", sanitize(site)));
+        if !new_path.exists() {
+            let legacy_path = self
+                .base_dir
+                .join(format!("{prefix}_{}.json", legacy_sanitize(site)));
+            if legacy_path.exists() {
+                let _ = std::fs::rename(&legacy_path, &new_path);
+            }
+        }
+        new_path
+    }
 }
 
 impl Storage for FileStorage {
@@ -79,30 +175,22 @@ impl Storage for FileStorage {
     }
 
     fn save_tokens(&self, site: &str, org: Option<&str>, tokens: &TokenSet) -> Result<()> {
-        let path = self
-            .base_dir
-            .join(format!("tokens_{}.json", sanitize(site)));
+        let lock_key = format!("tokens_{}", sanitize(site));
+        let _lock = SiteLock::acquire(&self.base_dir, &lock_key)?;
+        let path = self.resolve_path("tokens", site);
         let mut map = match std::fs::read_to_string(&path) {
-            Ok(json) => parse_token_map(&json).unwrap_or_default(),
+            Ok(json) => parse_token_map(&json)?,
             Err(e) if e.kind() == std::io::ErrorKind::NotFound => OrgTokenMap::new(),
             Err(e) => return Err(e.into()),
         };
         map.insert(org_map_key(org).to_string(), tokens.clone());
-        let json = serde_json::to_string_pretty(&map)?;
-        std::fs::write(&path, json)
-            .with_context(|| format!("failed to write tokens: {}", path.display()))?;
-        #[cfg(unix)]
-        {
-            use std::os::unix::fs::PermissionsExt;
-            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))?;
-        }
-        Ok(())
+        let json = serialize_token_map(&map, true)?;
+        atomic_write(&path, json.as_bytes())
+            .with_context(|| format!("failed to write tokens: {}", path.display()))
     }
 
     fn load_tokens(&self, site: &str, org: Option<&str>) -> Result<Option<TokenSet>> {
-        let path = self
-            .base_dir
-            .join(format!("tokens_{}.json", sanitize(site)));
+        let path = self.resolve_path("tokens", site);
         match std::fs::read_to_string(&path) {
             Ok(json) => Ok(parse_token_map(&json)?.remove(org_map_key(org))),
             Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
@@ -111,52 +199,38 @@ impl Storage for FileStorage {
     }
 
     fn delete_tokens(&self, site: &str, org: Option<&str>) -> Result<()> {
-        let path = self
-            .base_dir
-            .join(format!("tokens_{}.json", sanitize(site)));
+        let lock_key = format!("tokens_{}", sanitize(site));
+        let _lock = SiteLock::acquire(&self.base_dir, &lock_key)?;
+        let path = self.resolve_path("tokens", site);
         let json = match std::fs::read_to_string(&path) {
             Ok(j) => j,
             Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
             Err(e) => return Err(e.into()),
         };
-        let mut map = parse_token_map(&json).unwrap_or_default();
+        let mut map = parse_token_map(&json)?;
         map.remove(org_map_key(org));
         if map.is_empty() {
             match std::fs::remove_file(&path) {
                 Ok(()) | Err(_) => Ok(()),
             }
         } else {
-            let json = serde_json::to_string_pretty(&map)?;
-            std::fs::write(&path, json)
-                .with_context(|| format!("failed to write tokens: {}", path.display()))?;
-            #[cfg(unix)]
-            {
-                use std::os::unix::fs::PermissionsExt;
-                std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))?;
-            }
-            Ok(())
+            let json = serialize_token_map(&map, true)?;
+            atomic_write(&path, json.as_bytes())
+                .with_context(|| format!("failed to write tokens: {}", path.display()))
         }
     }
 
     fn save_client_credentials(&self, site: &str, creds: &ClientCredentials) -> Result<()> {
-        let path = self
-            .base_dir
-            .join(format!("client_{}.json", sanitize(site)));
+        let lock_key = format!("client_{}", sanitize(site));
+        let _lock = SiteLock::acquire(&self.base_dir, &lock_key)?;
+        let path = self.resolve_path("client", site);
         let json = serde_json::to_string_pretty(creds)?;
-        std::fs::write(&path, json)
-            .with_context(|| format!("failed to write credentials: {}", path.display()))?;
-        #[cfg(unix)]
-        {
-            use std::os::unix::fs::PermissionsExt;
-            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))?;
-        }
-        Ok(())
+        atomic_write(&path, json.as_bytes())
+            .with_context(|| format!("failed to write credentials: {}", path.display()))
     }
 
     fn load_client_credentials(&self, site: &str) -> Result<Option<ClientCredentials>> {
-        let path = self
-            .base_dir
-            .join(format!("client_{}.json", sanitize(site)));
+        let path = self.resolve_path("client", site);
         match std::fs::read_to_string(&path) {
             Ok(json) => Ok(Some(serde_json::from_str(&json)?)),
             Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
@@ -165,589 +239,2430 @@ impl Storage for FileStorage {
     }
 
     fn delete_client_credentials(&self, site: &str) -> Result<()> {
-        let path = self
-            .base_dir
-            .join(format!("client_{}.json", sanitize(site)));
+        let path = self.resolve_path("client", site);
         match std::fs::remove_file(&path) {
             Ok(()) => Ok(()),
             Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
             Err(e) => Err(e.into()),
         }
     }
+
+    fn prune_expired(&self, now: i64) -> Result<usize> {
+        let mut removed = 0;
+        for entry in std::fs::read_dir(&self.base_dir)? {
+            let path = entry?.path();
+            let is_tokens_file = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with("tokens_") && n.ends_with(".json"));
+            if !is_tokens_file {
+                continue;
+            }
+            let lock_key = path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or_default()
+                .to_string();
+            let _lock = SiteLock::acquire(&self.base_dir, &lock_key)?;
+
+            let json = match std::fs::read_to_string(&path) {
+                Ok(j) => j,
+                Err(_) => continue,
+            };
+            let mut map = match parse_token_map(&json) {
+                Ok(m) => m,
+                Err(_) => continue,
+            };
+            let before = map.len();
+            map.retain(|_, tokens| !is_prunable(tokens, now));
+            removed += before - map.len();
+
+            if map.len() == before {
+                continue;
+            }
+            if map.is_empty() {
+                let _ = std::fs::remove_file(&path);
+            } else {
+                let json = serialize_token_map(&map, true)?;
+                atomic_write(&path, json.as_bytes())
+                    .with_context(|| format!("failed to write tokens: {}", path.display()))?;
+            }
+        }
+
+        reconcile_session_registry(self)?;
+        Ok(removed)
+    }
 }
 
 // ---------------------------------------------------------------------------
-// Keychain storage (via keyring crate) — native only
+// Encrypted file storage (~/.config/pup/) — AEAD-sealed blobs
 // ---------------------------------------------------------------------------
 
+/// File storage that seals each payload with Argon2id-derived, XChaCha20-Poly1305
+/// encrypted blobs before writing, instead of relying on file permissions alone.
 #[cfg(not(target_arch = "wasm32"))]
-pub struct KeychainStorage;
+pub struct EncryptedFileStorage {
+    base_dir: PathBuf,
+    passphrase: String,
+}
 
 #[cfg(not(target_arch = "wasm32"))]
-const SERVICE_NAME: &str = "pup";
+const ENCRYPTED_BLOB_MAGIC: &[u8; 8] = b"PUPENC01";
+#[cfg(not(target_arch = "wasm32"))]
+const ENCRYPTED_SALT_LEN: usize = 16;
+#[cfg(not(target_arch = "wasm32"))]
+const ENCRYPTED_NONCE_LEN: usize = 24;
 
 #[cfg(not(target_arch = "wasm32"))]
-impl KeychainStorage {
+impl EncryptedFileStorage {
     pub fn new() -> Result<Self> {
-        // Test keychain availability by attempting an operation
-        let entry = keyring::Entry::new(SERVICE_NAME, "__pup_test__")?;
-        // Try a read — NotFound is fine, other errors mean keychain is unavailable
-        match entry.get_password() {
-            Ok(_) | Err(keyring::Error::NoEntry) => Ok(Self),
-            Err(e) => Err(anyhow::anyhow!("keychain not available: {e}")),
+        let base_dir =
+            crate::config::config_dir().context("could not determine config directory")?;
+        std::fs::create_dir_all(&base_dir)
+            .with_context(|| format!("failed to create config dir: {}", base_dir.display()))?;
+        let passphrase = storage_passphrase()?;
+        Ok(Self {
+            base_dir,
+            passphrase,
+        })
+    }
+
+    /// Read `path`, transparently decrypting a sealed blob or falling back to
+    /// legacy plaintext JSON if the magic header is absent.
+    fn read_sealed(&self, path: &std::path::Path) -> Result<Option<String>> {
+        let bytes = match std::fs::read(path) {
+            Ok(b) => b,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        if bytes.starts_with(ENCRYPTED_BLOB_MAGIC) {
+            Ok(Some(open_sealed(&self.passphrase, &bytes)?))
+        } else {
+            // Legacy plaintext file (pre-encryption, or written by FileStorage) —
+            // migrate transparently; the next write reseals it.
+            Ok(Some(String::from_utf8(bytes).context("legacy token file is not valid UTF-8")?))
         }
     }
+
+    fn write_sealed(&self, path: &std::path::Path, plaintext: &str) -> Result<()> {
+        let blob = seal(&self.passphrase, plaintext.as_bytes())?;
+        std::fs::write(path, blob)
+            .with_context(|| format!("failed to write encrypted store: {}", path.display()))?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+        }
+        Ok(())
+    }
+
+    /// Resolve `{prefix}_{sanitize(site)}.json`, migrating a pre-existing
+    /// `{prefix}_{legacy_sanitize(site)}.json` (written before the
+    /// collision-resistant sanitize() scheme) into place on first access.
+    fn resolve_path(&self, prefix: &str, site: &str) -> PathBuf {
+        let new_path = self.base_dir.join(format!("{prefix}_{}.json", sanitize(site)));
+        if !new_path.exists() {
+            let legacy_path = self
+                .base_dir
+                .join(format!("{prefix}_{}.json", legacy_sanitize(site)));
+            if legacy_path.exists() {
+                let _ = std::fs::rename(&legacy_path, &new_path);
+            }
+        }
+        new_path
+    }
 }
 
 #[cfg(not(target_arch = "wasm32"))]
-impl Storage for KeychainStorage {
+impl Storage for EncryptedFileStorage {
     fn backend_type(&self) -> BackendType {
-        BackendType::Keychain
+        BackendType::EncryptedFile
     }
 
     fn storage_location(&self) -> String {
-        "OS keychain".to_string()
+        format!("{} (encrypted)", self.base_dir.display())
     }
 
     fn save_tokens(&self, site: &str, org: Option<&str>, tokens: &TokenSet) -> Result<()> {
-        let key = format!("tokens_{}", sanitize(site));
-        let entry = keyring::Entry::new(SERVICE_NAME, &key)?;
-        let mut map = match entry.get_password() {
-            Ok(json) => parse_token_map(&json).unwrap_or_default(),
-            Err(keyring::Error::NoEntry) => OrgTokenMap::new(),
-            Err(e) => return Err(e.into()),
+        let path = self.resolve_path("tokens", site);
+        let mut map = match self.read_sealed(&path)? {
+            Some(json) => parse_token_map(&json)?,
+            None => OrgTokenMap::new(),
         };
         map.insert(org_map_key(org).to_string(), tokens.clone());
-        let json = serde_json::to_string(&map)?;
-        entry.set_password(&json)?;
-        Ok(())
+        self.write_sealed(&path, &serialize_token_map(&map, false)?)
     }
 
     fn load_tokens(&self, site: &str, org: Option<&str>) -> Result<Option<TokenSet>> {
-        let key = format!("tokens_{}", sanitize(site));
-        let entry = keyring::Entry::new(SERVICE_NAME, &key)?;
-        match entry.get_password() {
-            Ok(json) => Ok(parse_token_map(&json)?.remove(org_map_key(org))),
-            Err(keyring::Error::NoEntry) => Ok(None),
-            Err(e) => Err(e.into()),
+        let path = self.resolve_path("tokens", site);
+        match self.read_sealed(&path)? {
+            Some(json) => Ok(parse_token_map(&json)?.remove(org_map_key(org))),
+            None => Ok(None),
         }
     }
 
     fn delete_tokens(&self, site: &str, org: Option<&str>) -> Result<()> {
-        let key = format!("tokens_{}", sanitize(site));
-        let entry = keyring::Entry::new(SERVICE_NAME, &key)?;
-        let json = match entry.get_password() {
-            Ok(j) => j,
-            Err(keyring::Error::NoEntry) => return Ok(()),
-            Err(e) => return Err(e.into()),
+        let path = self.resolve_path("tokens", site);
+        let mut map = match self.read_sealed(&path)? {
+            Some(json) => parse_token_map(&json)?,
+            None => return Ok(()),
         };
-        let mut map = parse_token_map(&json).unwrap_or_default();
         map.remove(org_map_key(org));
         if map.is_empty() {
-            match entry.delete_credential() {
-                Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
-                Err(e) => Err(e.into()),
+            match std::fs::remove_file(&path) {
+                Ok(()) | Err(_) => Ok(()),
             }
         } else {
-            let json = serde_json::to_string(&map)?;
-            entry.set_password(&json)?;
-            Ok(())
+            self.write_sealed(&path, &serialize_token_map(&map, false)?)
         }
     }
 
     fn save_client_credentials(&self, site: &str, creds: &ClientCredentials) -> Result<()> {
-        let key = format!("client_{}", sanitize(site));
-        let entry = keyring::Entry::new(SERVICE_NAME, &key)?;
-        let json = serde_json::to_string(creds)?;
-        entry.set_password(&json)?;
-        Ok(())
+        let path = self.resolve_path("client", site);
+        self.write_sealed(&path, &serde_json::to_string(creds)?)
     }
 
     fn load_client_credentials(&self, site: &str) -> Result<Option<ClientCredentials>> {
-        let key = format!("client_{}", sanitize(site));
-        let entry = keyring::Entry::new(SERVICE_NAME, &key)?;
-        match entry.get_password() {
-            Ok(json) => Ok(Some(serde_json::from_str(&json)?)),
-            Err(keyring::Error::NoEntry) => Ok(None),
-            Err(e) => Err(e.into()),
+        let path = self.resolve_path("client", site);
+        match self.read_sealed(&path)? {
+            Some(json) => Ok(Some(serde_json::from_str(&json)?)),
+            None => Ok(None),
         }
     }
 
     fn delete_client_credentials(&self, site: &str) -> Result<()> {
-        let key = format!("client_{}", sanitize(site));
-        let entry = keyring::Entry::new(SERVICE_NAME, &key)?;
-        match entry.delete_credential() {
-            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        let path = self.resolve_path("client", site);
+        match std::fs::remove_file(&path) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
             Err(e) => Err(e.into()),
         }
     }
-}
-
-// ---------------------------------------------------------------------------
-// In-memory storage (WASM) — no persistent storage available
-// ---------------------------------------------------------------------------
 
-#[cfg(target_arch = "wasm32")]
-pub struct InMemoryStorage;
+    fn prune_expired(&self, now: i64) -> Result<usize> {
+        let mut removed = 0;
+        for entry in std::fs::read_dir(&self.base_dir)? {
+            let path = entry?.path();
+            let is_tokens_file = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with("tokens_") && n.ends_with(".json"));
+            if !is_tokens_file {
+                continue;
+            }
 
-#[cfg(target_arch = "wasm32")]
-impl Storage for InMemoryStorage {
-    fn backend_type(&self) -> BackendType {
-        BackendType::File
-    }
+            let json = match self.read_sealed(&path) {
+                Ok(Some(j)) => j,
+                Ok(None) | Err(_) => continue,
+            };
+            let mut map = match parse_token_map(&json) {
+                Ok(m) => m,
+                Err(_) => continue,
+            };
+            let before = map.len();
+            map.retain(|_, tokens| !is_prunable(tokens, now));
+            removed += before - map.len();
+
+            if map.len() == before {
+                continue;
+            }
+            if map.is_empty() {
+                let _ = std::fs::remove_file(&path);
+            } else {
+                self.write_sealed(&path, &serialize_token_map(&map, false)?)?;
+            }
+        }
 
-    fn storage_location(&self) -> String {
-        "in-memory (WASM)".to_string()
+        reconcile_session_registry(self)?;
+        Ok(removed)
     }
+}
 
-    fn save_tokens(&self, _site: &str, _org: Option<&str>, _tokens: &TokenSet) -> Result<()> {
-        anyhow::bail!("token storage not available in WASM — use DD_ACCESS_TOKEN env var")
+/// Resolve the passphrase used to derive the encryption key:
+/// `DD_STORAGE_PASSPHRASE` if set; otherwise the unlock agent's cached
+/// passphrase (spawning the agent first if `PUP_AGENT_AUTOSTART=1`), falling
+/// back to an interactive masked prompt if nothing is cached.
+#[cfg(not(target_arch = "wasm32"))]
+fn storage_passphrase() -> Result<String> {
+    if let Ok(p) = std::env::var("DD_STORAGE_PASSPHRASE") {
+        return Ok(p);
     }
 
-    fn load_tokens(&self, _site: &str, _org: Option<&str>) -> Result<Option<TokenSet>> {
-        Ok(None)
-    }
+    let prompt = || {
+        rpassword::prompt_password("pup storage passphrase: ")
+            .map_err(|e| anyhow::anyhow!("failed to read passphrase: {e}"))
+    };
 
-    fn delete_tokens(&self, _site: &str, _org: Option<&str>) -> Result<()> {
-        Ok(())
+    #[cfg(unix)]
+    {
+        let autostart = std::env::var("PUP_AGENT_AUTOSTART").as_deref() == Ok("1");
+        let idle_ttl = std::env::var("PUP_AGENT_IDLE_TTL_SECS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .map(std::time::Duration::from_secs)
+            .unwrap_or(std::time::Duration::from_secs(600));
+        unlock_agent::ensure_passphrase("storage", autostart, idle_ttl, prompt)
+    }
+    #[cfg(not(unix))]
+    {
+        prompt()
     }
+}
 
-    fn save_client_credentials(&self, _site: &str, _creds: &ClientCredentials) -> Result<()> {
-        anyhow::bail!("client credential storage not available in WASM")
-    }
+/// Derive a 32-byte key from `passphrase` and `salt` using Argon2id.
+#[cfg(not(target_arch = "wasm32"))]
+fn derive_storage_key(passphrase: &str, salt: &[u8]) -> Result<[u8; 32]> {
+    use argon2::Argon2;
+    let mut key = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| anyhow::anyhow!("key derivation failed: {e}"))?;
+    Ok(key)
+}
 
-    fn load_client_credentials(&self, _site: &str) -> Result<Option<ClientCredentials>> {
-        Ok(None)
-    }
+/// Seal `plaintext` into a `salt || nonce || ciphertext` frame, prefixed with a
+/// magic header so readers can tell a sealed blob from legacy plaintext JSON.
+#[cfg(not(target_arch = "wasm32"))]
+fn seal(passphrase: &str, plaintext: &[u8]) -> Result<Vec<u8>> {
+    use chacha20poly1305::aead::{Aead, KeyInit};
+    use chacha20poly1305::XChaCha20Poly1305;
+
+    let mut salt = [0u8; ENCRYPTED_SALT_LEN];
+    rand::RngCore::fill_bytes(&mut rand::thread_rng(), &mut salt);
+    let key = derive_storage_key(passphrase, &salt)?;
+
+    let mut nonce_bytes = [0u8; ENCRYPTED_NONCE_LEN];
+    rand::RngCore::fill_bytes(&mut rand::thread_rng(), &mut nonce_bytes);
+
+    let cipher = XChaCha20Poly1305::new((&key).into());
+    let ciphertext = cipher
+        .encrypt((&nonce_bytes).into(), plaintext)
+        .map_err(|_| anyhow::anyhow!("failed to encrypt storage blob"))?;
+
+    let mut out = Vec::with_capacity(8 + salt.len() + nonce_bytes.len() + ciphertext.len());
+    out.extend_from_slice(ENCRYPTED_BLOB_MAGIC);
+    out.extend_from_slice(&salt);
+    out.extend_from_slice(&nonce_bytes);
+    out.extend_from_slice(&ciphertext);
+    Ok(out)
+}
 
-    fn delete_client_credentials(&self, _site: &str) -> Result<()> {
-        Ok(())
-    }
+/// Open a blob sealed by [`seal`], returning the original plaintext. An
+/// AEAD auth-tag mismatch (wrong passphrase, or corruption) surfaces as a
+/// clear error rather than a confusing JSON parse failure.
+#[cfg(not(target_arch = "wasm32"))]
+fn open_sealed(passphrase: &str, blob: &[u8]) -> Result<String> {
+    use chacha20poly1305::aead::{Aead, KeyInit};
+    use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+
+    let header_len = 8 + ENCRYPTED_SALT_LEN + ENCRYPTED_NONCE_LEN;
+    if blob.len() < header_len {
+        anyhow::bail!("wrong passphrase or corrupted store");
+    }
+    let (magic, rest) = blob.split_at(8);
+    if magic != ENCRYPTED_BLOB_MAGIC {
+        anyhow::bail!("wrong passphrase or corrupted store");
+    }
+    let (salt, rest) = rest.split_at(ENCRYPTED_SALT_LEN);
+    let (nonce_bytes, ciphertext) = rest.split_at(ENCRYPTED_NONCE_LEN);
+
+    let key = derive_storage_key(passphrase, salt)?;
+    let cipher = XChaCha20Poly1305::new((&key).into());
+    let plaintext = cipher
+        .decrypt(XNonce::from_slice(nonce_bytes), ciphertext)
+        .map_err(|_| anyhow::anyhow!("wrong passphrase or corrupted store"))?;
+    String::from_utf8(plaintext).context("decrypted store is not valid UTF-8")
 }
 
 // ---------------------------------------------------------------------------
-// LocalStorage backend (browser WASM) — persists tokens across page reloads
+// Age-encrypted file storage (~/.config/pup/) — age-format encrypted blobs
 // ---------------------------------------------------------------------------
 
-#[cfg(feature = "browser")]
-pub struct LocalStorageBackend;
+/// The key `AgeStorage` seals/opens blobs with: either a passphrase (stretched
+/// through age's scrypt recipient into a symmetric key) or a persistent
+/// X25519 identity file under the config dir.
+#[cfg(not(target_arch = "wasm32"))]
+enum AgeKeyMode {
+    Passphrase(String),
+    Identity(age::x25519::Identity),
+}
 
-#[cfg(feature = "browser")]
-impl LocalStorageBackend {
-    fn storage() -> Result<web_sys::Storage> {
-        let window = web_sys::window().ok_or_else(|| anyhow::anyhow!("no global window object"))?;
-        window
-            .local_storage()
-            .map_err(|_| anyhow::anyhow!("localStorage not available"))?
-            .ok_or_else(|| anyhow::anyhow!("localStorage returned None"))
+/// File storage that encrypts each payload with the `age` encryption format
+/// (https://age-encryption.org) instead of the ad hoc Argon2id + AEAD frame
+/// used by [`EncryptedFileStorage`].
+#[cfg(not(target_arch = "wasm32"))]
+pub struct AgeStorage {
+    base_dir: PathBuf,
+    mode: AgeKeyMode,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+const AGE_IDENTITY_FILE: &str = "age-identity.txt";
+
+#[cfg(not(target_arch = "wasm32"))]
+impl AgeStorage {
+    /// Passphrase mode: the passphrase stretches into a symmetric key via
+    /// age's scrypt recipient.
+    pub fn with_passphrase(passphrase: String) -> Result<Self> {
+        let base_dir =
+            crate::config::config_dir().context("could not determine config directory")?;
+        std::fs::create_dir_all(&base_dir)
+            .with_context(|| format!("failed to create config dir: {}", base_dir.display()))?;
+        Ok(Self {
+            base_dir,
+            mode: AgeKeyMode::Passphrase(passphrase),
+        })
     }
 
-    fn get_item(key: &str) -> Result<Option<String>> {
-        let storage = Self::storage()?;
-        storage
-            .get_item(key)
-            .map_err(|_| anyhow::anyhow!("failed to read from localStorage"))
+    /// Identity-file mode: load the X25519 identity persisted under the
+    /// config dir, generating and writing one on first use.
+    pub fn with_identity_file() -> Result<Self> {
+        let base_dir =
+            crate::config::config_dir().context("could not determine config directory")?;
+        std::fs::create_dir_all(&base_dir)
+            .with_context(|| format!("failed to create config dir: {}", base_dir.display()))?;
+        let identity_path = base_dir.join(AGE_IDENTITY_FILE);
+        let identity = match std::fs::read_to_string(&identity_path) {
+            Ok(contents) => contents
+                .trim()
+                .parse::<age::x25519::Identity>()
+                .map_err(|e| anyhow::anyhow!("failed to parse age identity file: {e}"))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                let identity = age::x25519::Identity::generate();
+                std::fs::write(&identity_path, identity.to_string())
+                    .with_context(|| {
+                        format!("failed to write age identity file: {}", identity_path.display())
+                    })?;
+                #[cfg(unix)]
+                {
+                    use std::os::unix::fs::PermissionsExt;
+                    std::fs::set_permissions(&identity_path, std::fs::Permissions::from_mode(0o600))?;
+                }
+                identity
+            }
+            Err(e) => return Err(e.into()),
+        };
+        Ok(Self {
+            base_dir,
+            mode: AgeKeyMode::Identity(identity),
+        })
+    }
+
+    /// Resolve `{prefix}_{sanitize(site)}.age`, transparently falling back to
+    /// a pre-existing `{prefix}_{sanitize(site)}.json` file written by
+    /// `FileStorage` (or an un-migrated `{prefix}_{legacy_sanitize(site)}.age`
+    /// from before the collision-resistant `sanitize()` scheme).
+    fn resolve_path(&self, prefix: &str, site: &str) -> PathBuf {
+        let new_path = self.base_dir.join(format!("{prefix}_{}.age", sanitize(site)));
+        if !new_path.exists() {
+            let legacy_path = self
+                .base_dir
+                .join(format!("{prefix}_{}.age", legacy_sanitize(site)));
+            if legacy_path.exists() {
+                let _ = std::fs::rename(&legacy_path, &new_path);
+            }
+        }
+        new_path
+    }
+
+    /// Read the token/credentials JSON at `{prefix}_{sanitize(site)}`, transparently
+    /// decrypting a `.age` file or falling back to a legacy plaintext `.json`
+    /// file from `FileStorage` (re-encrypted on the next write).
+    fn read_json(&self, prefix: &str, site: &str) -> Result<Option<String>> {
+        let age_path = self.resolve_path(prefix, site);
+        match std::fs::read(&age_path) {
+            Ok(bytes) => Ok(Some(self.decrypt(&bytes)?)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                let plaintext_path = self.base_dir.join(format!("{prefix}_{}.json", sanitize(site)));
+                match std::fs::read_to_string(&plaintext_path) {
+                    Ok(json) => Ok(Some(json)),
+                    Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+                    Err(e) => Err(e.into()),
+                }
+            }
+            Err(e) => Err(e.into()),
+        }
     }
 
-    fn set_item(key: &str, value: &str) -> Result<()> {
-        let storage = Self::storage()?;
-        storage
-            .set_item(key, value)
-            .map_err(|_| anyhow::anyhow!("failed to write to localStorage"))
+    fn write_json(&self, prefix: &str, site: &str, json: &str) -> Result<()> {
+        let age_path = self.resolve_path(prefix, site);
+        let blob = self.encrypt(json.as_bytes())?;
+        atomic_write(&age_path, &blob)
+            .with_context(|| format!("failed to write age storage blob: {}", age_path.display()))?;
+        // Drop a stale plaintext copy now that the encrypted copy is in place.
+        let plaintext_path = self.base_dir.join(format!("{prefix}_{}.json", sanitize(site)));
+        let _ = std::fs::remove_file(&plaintext_path);
+        Ok(())
     }
 
-    fn remove_item(key: &str) -> Result<()> {
-        let storage = Self::storage()?;
-        storage
-            .remove_item(key)
-            .map_err(|_| anyhow::anyhow!("failed to remove from localStorage"))
+    fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>> {
+        use std::io::Write;
+
+        let encryptor = match &self.mode {
+            AgeKeyMode::Passphrase(passphrase) => {
+                age::Encryptor::with_user_passphrase(age::secrecy::Secret::new(passphrase.clone()))
+            }
+            AgeKeyMode::Identity(identity) => {
+                age::Encryptor::with_recipients(vec![Box::new(identity.to_public())])
+                    .ok_or_else(|| anyhow::anyhow!("failed to build age encryptor"))?
+            }
+        };
+        let mut out = Vec::new();
+        let mut writer = encryptor
+            .wrap_output(&mut out)
+            .map_err(|e| anyhow::anyhow!("failed to seal age storage blob: {e}"))?;
+        writer
+            .write_all(plaintext)
+            .map_err(|e| anyhow::anyhow!("failed to write age storage blob: {e}"))?;
+        writer
+            .finish()
+            .map_err(|e| anyhow::anyhow!("failed to finalize age storage blob: {e}"))?;
+        Ok(out)
+    }
+
+    fn decrypt(&self, blob: &[u8]) -> Result<String> {
+        use std::io::Read;
+
+        let decryptor = age::Decryptor::new(blob)
+            .map_err(|e| anyhow::anyhow!("failed to open age storage blob: {e}"))?;
+        let mut plaintext = Vec::new();
+        let mut reader = match (decryptor, &self.mode) {
+            (age::Decryptor::Passphrase(d), AgeKeyMode::Passphrase(passphrase)) => d
+                .decrypt(&age::secrecy::Secret::new(passphrase.clone()), None)
+                .map_err(|e| anyhow::anyhow!("wrong passphrase or corrupted store: {e}"))?,
+            (age::Decryptor::Recipients(d), AgeKeyMode::Identity(identity)) => d
+                .decrypt(std::iter::once(identity as &dyn age::Identity))
+                .map_err(|e| anyhow::anyhow!("failed to decrypt with identity: {e}"))?,
+            _ => anyhow::bail!("age storage file does not match the configured key mode"),
+        };
+        reader
+            .read_to_end(&mut plaintext)
+            .map_err(|e| anyhow::anyhow!("failed to read age storage blob: {e}"))?;
+        String::from_utf8(plaintext).context("decrypted store is not valid UTF-8")
     }
 }
 
-#[cfg(feature = "browser")]
-impl Storage for LocalStorageBackend {
+#[cfg(not(target_arch = "wasm32"))]
+impl Storage for AgeStorage {
     fn backend_type(&self) -> BackendType {
-        BackendType::LocalStorage
+        BackendType::Age
     }
 
     fn storage_location(&self) -> String {
-        "browser localStorage".to_string()
+        format!("{} (age-encrypted)", self.base_dir.display())
     }
 
     fn save_tokens(&self, site: &str, org: Option<&str>, tokens: &TokenSet) -> Result<()> {
-        let key = format!("pup_tokens_{}", sanitize(site));
-        let mut map = match Self::get_item(&key)? {
-            Some(json) => parse_token_map(&json).unwrap_or_default(),
+        let mut map = match self.read_json("tokens", site)? {
+            Some(json) => parse_token_map(&json)?,
             None => OrgTokenMap::new(),
         };
         map.insert(org_map_key(org).to_string(), tokens.clone());
-        let json = serde_json::to_string(&map)?;
-        Self::set_item(&key, &json)
+        self.write_json("tokens", site, &serialize_token_map(&map, false)?)
     }
 
     fn load_tokens(&self, site: &str, org: Option<&str>) -> Result<Option<TokenSet>> {
-        let key = format!("pup_tokens_{}", sanitize(site));
-        match Self::get_item(&key)? {
+        match self.read_json("tokens", site)? {
             Some(json) => Ok(parse_token_map(&json)?.remove(org_map_key(org))),
             None => Ok(None),
         }
     }
 
     fn delete_tokens(&self, site: &str, org: Option<&str>) -> Result<()> {
-        let key = format!("pup_tokens_{}", sanitize(site));
-        let mut map = match Self::get_item(&key)? {
-            Some(json) => parse_token_map(&json).unwrap_or_default(),
+        let mut map = match self.read_json("tokens", site)? {
+            Some(json) => parse_token_map(&json)?,
             None => return Ok(()),
         };
         map.remove(org_map_key(org));
         if map.is_empty() {
-            Self::remove_item(&key)
+            let age_path = self.resolve_path("tokens", site);
+            match std::fs::remove_file(&age_path) {
+                Ok(()) | Err(_) => Ok(()),
+            }
         } else {
-            let json = serde_json::to_string(&map)?;
-            Self::set_item(&key, &json)
+            self.write_json("tokens", site, &serialize_token_map(&map, false)?)
         }
     }
 
     fn save_client_credentials(&self, site: &str, creds: &ClientCredentials) -> Result<()> {
-        let key = format!("pup_client_{}", sanitize(site));
-        let json = serde_json::to_string(creds)?;
-        Self::set_item(&key, &json)
+        self.write_json("client", site, &serde_json::to_string(creds)?)
     }
 
     fn load_client_credentials(&self, site: &str) -> Result<Option<ClientCredentials>> {
-        let key = format!("pup_client_{}", sanitize(site));
-        match Self::get_item(&key)? {
+        match self.read_json("client", site)? {
             Some(json) => Ok(Some(serde_json::from_str(&json)?)),
             None => Ok(None),
         }
     }
 
     fn delete_client_credentials(&self, site: &str) -> Result<()> {
-        let key = format!("pup_client_{}", sanitize(site));
-        Self::remove_item(&key)
+        let age_path = self.resolve_path("client", site);
+        match std::fs::remove_file(&age_path) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn prune_expired(&self, now: i64) -> Result<usize> {
+        let mut removed = 0;
+        for entry in std::fs::read_dir(&self.base_dir)? {
+            let path = entry?.path();
+            let is_tokens_file = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with("tokens_") && n.ends_with(".age"));
+            if !is_tokens_file {
+                continue;
+            }
+            let lock_key = path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or_default()
+                .to_string();
+            let _lock = SiteLock::acquire(&self.base_dir, &lock_key)?;
+
+            let bytes = match std::fs::read(&path) {
+                Ok(b) => b,
+                Err(_) => continue,
+            };
+            let json = match self.decrypt(&bytes) {
+                Ok(j) => j,
+                Err(_) => continue,
+            };
+            let mut map = match parse_token_map(&json) {
+                Ok(m) => m,
+                Err(_) => continue,
+            };
+            let before = map.len();
+            map.retain(|_, tokens| !is_prunable(tokens, now));
+            removed += before - map.len();
+
+            if map.len() == before {
+                continue;
+            }
+            if map.is_empty() {
+                let _ = std::fs::remove_file(&path);
+            } else {
+                let blob = self.encrypt(serialize_token_map(&map, false)?.as_bytes())?;
+                atomic_write(&path, &blob)
+                    .with_context(|| format!("failed to write tokens: {}", path.display()))?;
+            }
+        }
+
+        reconcile_session_registry(self)?;
+        Ok(removed)
     }
 }
 
 // ---------------------------------------------------------------------------
-// Factory — auto-detect backend, with fallback
+// Keychain storage (via keyring crate) — native only
 // ---------------------------------------------------------------------------
 
-use std::sync::Mutex;
+#[cfg(not(target_arch = "wasm32"))]
+pub struct KeychainStorage;
 
-static STORAGE: Mutex<Option<Box<dyn Storage>>> = Mutex::new(None);
+#[cfg(not(target_arch = "wasm32"))]
+const SERVICE_NAME: &str = "pup";
 
-pub fn get_storage() -> Result<&'static Mutex<Option<Box<dyn Storage>>>> {
-    let mut guard = STORAGE.lock().unwrap();
-    if guard.is_none() {
-        let backend = detect_backend();
-        *guard = Some(backend);
+#[cfg(not(target_arch = "wasm32"))]
+impl KeychainStorage {
+    pub fn new() -> Result<Self> {
+        // Test keychain availability by attempting an operation
+        let entry = keyring::Entry::new(SERVICE_NAME, "__pup_test__")?;
+        // Try a read — NotFound is fine, other errors mean keychain is unavailable
+        match entry.get_password() {
+            Ok(_) | Err(keyring::Error::NoEntry) => Ok(Self),
+            Err(e) => Err(anyhow::anyhow!("keychain not available: {e}")),
+        }
     }
-    drop(guard);
-    Ok(&STORAGE)
 }
 
+/// Resolve the keyring entry for `key_fn(sanitize(site))`, migrating a
+/// pre-existing `key_fn(legacy_sanitize(site))` entry (written before the
+/// collision-resistant sanitize() scheme) into place on first access.
 #[cfg(not(target_arch = "wasm32"))]
-fn detect_backend() -> Box<dyn Storage> {
-    // Check DD_TOKEN_STORAGE env var
-    if let Ok(val) = std::env::var("DD_TOKEN_STORAGE") {
-        match val.as_str() {
-            "file" => return Box::new(FileStorage::new().expect("failed to create file storage")),
-            "keychain" => return Box::new(KeychainStorage::new().expect("keychain not available")),
-            _ => eprintln!("Warning: unknown DD_TOKEN_STORAGE={val:?}, auto-detecting"),
+fn resolve_keyring_entry(key_fn: impl Fn(&str) -> String, site: &str) -> Result<keyring::Entry> {
+    let entry = keyring::Entry::new(SERVICE_NAME, &key_fn(&sanitize(site)))?;
+    if matches!(entry.get_password(), Err(keyring::Error::NoEntry)) {
+        let legacy_entry = keyring::Entry::new(SERVICE_NAME, &key_fn(&legacy_sanitize(site)))?;
+        if let Ok(password) = legacy_entry.get_password() {
+            entry.set_password(&password)?;
+            let _ = legacy_entry.delete_credential();
         }
     }
+    Ok(entry)
+}
 
-    // Try keychain first
-    match KeychainStorage::new() {
-        Ok(ks) => Box::new(ks),
-        Err(_) => {
-            eprintln!("Warning: OS keychain not available, using file storage (~/.config/pup/)");
-            Box::new(FileStorage::new().expect("failed to create file storage"))
+#[cfg(not(target_arch = "wasm32"))]
+impl Storage for KeychainStorage {
+    fn backend_type(&self) -> BackendType {
+        BackendType::Keychain
+    }
+
+    fn storage_location(&self) -> String {
+        "OS keychain".to_string()
+    }
+
+    fn save_tokens(&self, site: &str, org: Option<&str>, tokens: &TokenSet) -> Result<()> {
+        let entry = resolve_keyring_entry(|s| format!("tokens_{s}"), site)?;
+        let mut map = match entry.get_password() {
+            Ok(json) => parse_token_map(&json)?,
+            Err(keyring::Error::NoEntry) => OrgTokenMap::new(),
+            Err(e) => return Err(e.into()),
+        };
+        map.insert(org_map_key(org).to_string(), tokens.clone());
+        let json = serialize_token_map(&map, false)?;
+        entry.set_password(&json)?;
+        Ok(())
+    }
+
+    fn load_tokens(&self, site: &str, org: Option<&str>) -> Result<Option<TokenSet>> {
+        let entry = resolve_keyring_entry(|s| format!("tokens_{s}"), site)?;
+        match entry.get_password() {
+            Ok(json) => Ok(parse_token_map(&json)?.remove(org_map_key(org))),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(e.into()),
         }
     }
-}
 
-#[cfg(all(target_arch = "wasm32", not(feature = "browser")))]
-fn detect_backend() -> Box<dyn Storage> {
-    Box::new(InMemoryStorage)
+    fn delete_tokens(&self, site: &str, org: Option<&str>) -> Result<()> {
+        let entry = resolve_keyring_entry(|s| format!("tokens_{s}"), site)?;
+        let json = match entry.get_password() {
+            Ok(j) => j,
+            Err(keyring::Error::NoEntry) => return Ok(()),
+            Err(e) => return Err(e.into()),
+        };
+        let mut map = parse_token_map(&json)?;
+        map.remove(org_map_key(org));
+        if map.is_empty() {
+            match entry.delete_credential() {
+                Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+                Err(e) => Err(e.into()),
+            }
+        } else {
+            let json = serialize_token_map(&map, false)?;
+            entry.set_password(&json)?;
+            Ok(())
+        }
+    }
+
+    fn save_client_credentials(&self, site: &str, creds: &ClientCredentials) -> Result<()> {
+        let entry = resolve_keyring_entry(client_creds_key, site)?;
+        let json = serde_json::to_string(creds)?;
+        entry.set_password(&json)?;
+        Ok(())
+    }
+
+    fn load_client_credentials(&self, site: &str) -> Result<Option<ClientCredentials>> {
+        let entry = resolve_keyring_entry(client_creds_key, site)?;
+        match entry.get_password() {
+            Ok(json) => Ok(Some(serde_json::from_str(&json)?)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn delete_client_credentials(&self, site: &str) -> Result<()> {
+        let entry = resolve_keyring_entry(client_creds_key, site)?;
+        match entry.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// The OS keychain has no API to enumerate "every entry this service
+    /// wrote", so pruning walks the session registry (which tracks every
+    /// site+org this backend has ever saved tokens for) instead.
+    fn prune_expired(&self, now: i64) -> Result<usize> {
+        let mut removed = 0;
+        for session in list_sessions()? {
+            let org = session.org.as_deref();
+            if let Some(tokens) = self.load_tokens(&session.site, org)? {
+                if is_prunable(&tokens, now) {
+                    self.delete_tokens(&session.site, org)?;
+                    removed += 1;
+                }
+            }
+        }
+        reconcile_session_registry(self)?;
+        Ok(removed)
+    }
 }
 
-#[cfg(feature = "browser")]
-fn detect_backend() -> Box<dyn Storage> {
-    Box::new(LocalStorageBackend)
+/// Client registration (DCR) credentials are site-scoped and shared across
+/// orgs, so they get their own keyring account distinct from the per-site
+/// token map. Takes an already-sanitized site stem.
+#[cfg(not(target_arch = "wasm32"))]
+fn client_creds_key(sanitized_site: &str) -> String {
+    format!("client-creds:{sanitized_site}")
 }
 
 // ---------------------------------------------------------------------------
-// Helpers
+// SQLite storage (~/.config/pup/pup.sqlite) — concurrent, queryable, WAL mode
 // ---------------------------------------------------------------------------
 
-fn sanitize(site: &str) -> String {
-    site.chars()
-        .map(|c| if c.is_alphanumeric() { c } else { '_' })
-        .collect()
+/// SQLite-backed storage: one row per site+org in `tokens`, one row per site
+/// in `client_credentials`, and one row per session in `sessions`. WAL mode
+/// lets multiple `pup` invocations read/write concurrently without the
+/// whole-file read-modify-write races that `FileStorage` has.
+#[cfg(not(target_arch = "wasm32"))]
+pub struct SqliteStorage {
+    conn: Mutex<rusqlite::Connection>,
+    path: PathBuf,
 }
 
-// ---------------------------------------------------------------------------
-// OrgTokenMap — one keychain/file entry per site, keyed by org label
-// ---------------------------------------------------------------------------
+#[cfg(not(target_arch = "wasm32"))]
+const SQLITE_SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS tokens (
+        site TEXT NOT NULL,
+        org TEXT NOT NULL,
+        data TEXT NOT NULL,
+        PRIMARY KEY (site, org)
+    );
+    CREATE TABLE IF NOT EXISTS client_credentials (
+        site TEXT PRIMARY KEY,
+        data TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS sessions (
+        site TEXT NOT NULL,
+        org TEXT NOT NULL,
+        PRIMARY KEY (site, org)
+    );
+    CREATE INDEX IF NOT EXISTS idx_sessions_org ON sessions(org);
+";
 
-/// All orgs for a site are stored under a single key as a JSON map.
-/// The no-org (default) session uses this sentinel as its map key.
-const DEFAULT_ORG_KEY: &str = "__default__";
+#[cfg(not(target_arch = "wasm32"))]
+impl SqliteStorage {
+    pub fn new() -> Result<Self> {
+        let base_dir =
+            crate::config::config_dir().context("could not determine config directory")?;
+        std::fs::create_dir_all(&base_dir)
+            .with_context(|| format!("failed to create config dir: {}", base_dir.display()))?;
+        let path = base_dir.join("pup.sqlite");
 
-type OrgTokenMap = std::collections::HashMap<String, TokenSet>;
+        let conn = rusqlite::Connection::open(&path)
+            .with_context(|| format!("failed to open sqlite storage: {}", path.display()))?;
+        conn.pragma_update(None, "journal_mode", "WAL")
+            .context("failed to enable WAL mode")?;
+        conn.execute_batch(SQLITE_SCHEMA)
+            .context("failed to initialize sqlite schema")?;
 
-fn org_map_key(org: Option<&str>) -> &str {
-    match org {
-        Some(o) if !o.is_empty() => o,
-        _ => DEFAULT_ORG_KEY,
+        *SQLITE_SESSIONS_PATH.lock().unwrap() = Some(path.clone());
+        Ok(Self {
+            conn: Mutex::new(conn),
+            path,
+        })
     }
 }
 
-/// Parse a stored blob as an OrgTokenMap, migrating the legacy single-TokenSet
-/// format (written by pup < multi-org) to {"__default__": <tokens>} transparently.
-fn parse_token_map(json: &str) -> Result<OrgTokenMap> {
-    // New format: {"__default__": {...}, "prod-child": {...}}
-    if let Ok(map) = serde_json::from_str::<OrgTokenMap>(json) {
-        return Ok(map);
+#[cfg(not(target_arch = "wasm32"))]
+impl Storage for SqliteStorage {
+    fn backend_type(&self) -> BackendType {
+        BackendType::Sqlite
     }
-    // Old format: bare TokenSet — promote to map under __default__
-    if let Ok(tokens) = serde_json::from_str::<TokenSet>(json) {
-        let mut map = OrgTokenMap::new();
-        map.insert(DEFAULT_ORG_KEY.to_string(), tokens);
-        return Ok(map);
+
+    fn storage_location(&self) -> String {
+        format!("sqlite://{}", self.path.display())
+    }
+
+    fn save_tokens(&self, site: &str, org: Option<&str>, tokens: &TokenSet) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        let data = serde_json::to_string(tokens)?;
+        conn.execute(
+            "INSERT INTO tokens (site, org, data) VALUES (?1, ?2, ?3)
+             ON CONFLICT(site, org) DO UPDATE SET data = excluded.data",
+            rusqlite::params![site, org_map_key(org), data],
+        )
+        .map_err(|e| anyhow::anyhow!("failed to save tokens: {e}"))?;
+        Ok(())
+    }
+
+    fn load_tokens(&self, site: &str, org: Option<&str>) -> Result<Option<TokenSet>> {
+        use rusqlite::OptionalExtension;
+        let conn = self.conn.lock().unwrap();
+        let data: Option<String> = conn
+            .query_row(
+                "SELECT data FROM tokens WHERE site = ?1 AND org = ?2",
+                rusqlite::params![site, org_map_key(org)],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(|e| anyhow::anyhow!("failed to load tokens: {e}"))?;
+        data.map(|d| serde_json::from_str(&d).map_err(anyhow::Error::from))
+            .transpose()
+    }
+
+    fn delete_tokens(&self, site: &str, org: Option<&str>) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "DELETE FROM tokens WHERE site = ?1 AND org = ?2",
+            rusqlite::params![site, org_map_key(org)],
+        )
+        .map_err(|e| anyhow::anyhow!("failed to delete tokens: {e}"))?;
+        Ok(())
+    }
+
+    fn save_client_credentials(&self, site: &str, creds: &ClientCredentials) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        let data = serde_json::to_string(creds)?;
+        conn.execute(
+            "INSERT INTO client_credentials (site, data) VALUES (?1, ?2)
+             ON CONFLICT(site) DO UPDATE SET data = excluded.data",
+            rusqlite::params![site, data],
+        )
+        .map_err(|e| anyhow::anyhow!("failed to save client credentials: {e}"))?;
+        Ok(())
+    }
+
+    fn load_client_credentials(&self, site: &str) -> Result<Option<ClientCredentials>> {
+        use rusqlite::OptionalExtension;
+        let conn = self.conn.lock().unwrap();
+        let data: Option<String> = conn
+            .query_row(
+                "SELECT data FROM client_credentials WHERE site = ?1",
+                rusqlite::params![site],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(|e| anyhow::anyhow!("failed to load client credentials: {e}"))?;
+        data.map(|d| serde_json::from_str(&d).map_err(anyhow::Error::from))
+            .transpose()
+    }
+
+    fn delete_client_credentials(&self, site: &str) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "DELETE FROM client_credentials WHERE site = ?1",
+            rusqlite::params![site],
+        )
+        .map_err(|e| anyhow::anyhow!("failed to delete client credentials: {e}"))?;
+        Ok(())
+    }
+
+    fn prune_expired(&self, now: i64) -> Result<usize> {
+        let rows: Vec<(String, String, String)> = {
+            let conn = self.conn.lock().unwrap();
+            let mut stmt = conn.prepare("SELECT site, org, data FROM tokens")?;
+            stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+                .map_err(|e| anyhow::anyhow!("failed to scan tokens: {e}"))?
+                .collect::<rusqlite::Result<Vec<_>>>()
+                .map_err(|e| anyhow::anyhow!("failed to scan tokens: {e}"))?
+        };
+
+        let mut removed = 0;
+        for (site, org, data) in rows {
+            let tokens: TokenSet = match serde_json::from_str(&data) {
+                Ok(t) => t,
+                Err(_) => continue,
+            };
+            if !is_prunable(&tokens, now) {
+                continue;
+            }
+            let conn = self.conn.lock().unwrap();
+            conn.execute(
+                "DELETE FROM tokens WHERE site = ?1 AND org = ?2",
+                rusqlite::params![site, org],
+            )
+            .map_err(|e| anyhow::anyhow!("failed to prune tokens: {e}"))?;
+            removed += 1;
+        }
+
+        reconcile_session_registry(self)?;
+        Ok(removed)
     }
-    anyhow::bail!("token storage contains unrecognised format")
 }
 
-// ---------------------------------------------------------------------------
-// Session registry — tracks named org sessions (no secrets stored here)
-// ---------------------------------------------------------------------------
+/// Set by `SqliteStorage::new()` so the backend-agnostic session registry
+/// functions below can fold session tracking into the same database instead
+/// of the separate `sessions.json` file.
+#[cfg(not(target_arch = "wasm32"))]
+static SQLITE_SESSIONS_PATH: Mutex<Option<PathBuf>> = Mutex::new(None);
 
 #[cfg(not(target_arch = "wasm32"))]
-fn sessions_path() -> Option<std::path::PathBuf> {
-    crate::config::config_dir().map(|d| d.join("sessions.json"))
+fn sqlite_sessions_connection(path: &std::path::Path) -> Result<rusqlite::Connection> {
+    let conn = rusqlite::Connection::open(path)
+        .with_context(|| format!("failed to open sqlite storage: {}", path.display()))?;
+    conn.execute_batch(SQLITE_SCHEMA)
+        .context("failed to initialize sqlite schema")?;
+    Ok(conn)
 }
 
-/// List all stored sessions from the registry file.
-/// Returns an empty vec if the file does not exist.
 #[cfg(not(target_arch = "wasm32"))]
-pub fn list_sessions() -> Result<Vec<SessionEntry>> {
-    let path = match sessions_path() {
-        Some(p) => p,
-        None => return Ok(vec![]),
-    };
-    match std::fs::read_to_string(&path) {
-        Ok(json) => Ok(serde_json::from_str(&json).unwrap_or_default()),
-        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(vec![]),
-        Err(e) => Err(e.into()),
-    }
+fn sqlite_session_entry(row: &rusqlite::Row) -> rusqlite::Result<SessionEntry> {
+    let site: String = row.get(0)?;
+    let org: String = row.get(1)?;
+    Ok(SessionEntry {
+        site,
+        org: if org == DEFAULT_ORG_KEY { None } else { Some(org) },
+    })
 }
 
-/// Upsert a session entry into the registry.
 #[cfg(not(target_arch = "wasm32"))]
-pub fn save_session(site: &str, org: Option<&str>) -> Result<()> {
-    let mut sessions = list_sessions()?;
-    let entry = SessionEntry {
-        site: site.to_string(),
-        org: org.map(String::from),
+fn sqlite_list_sessions(path: &std::path::Path) -> Result<Vec<SessionEntry>> {
+    let conn = sqlite_sessions_connection(path)?;
+    let mut stmt = conn.prepare("SELECT site, org FROM sessions")?;
+    let rows = stmt
+        .query_map([], sqlite_session_entry)
+        .map_err(|e| anyhow::anyhow!("failed to list sessions: {e}"))?;
+    rows.collect::<rusqlite::Result<Vec<_>>>()
+        .map_err(|e| anyhow::anyhow!("failed to list sessions: {e}"))
+}
+
+/// Resolve `query` against the `sessions` table: an exact, index-backed
+/// match on `org` first (the `pup use <org>` common case), widening to a
+/// case-insensitive substring scan of `org`/`site` only if that misses.
+#[cfg(not(target_arch = "wasm32"))]
+fn sqlite_find_sessions(path: &std::path::Path, query: &str) -> Result<Vec<SessionEntry>> {
+    let conn = sqlite_sessions_connection(path)?;
+    let exact = {
+        let mut stmt = conn.prepare("SELECT site, org FROM sessions WHERE org = ?1")?;
+        stmt.query_map(rusqlite::params![query], sqlite_session_entry)
+            .map_err(|e| anyhow::anyhow!("failed to find sessions: {e}"))?
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .map_err(|e| anyhow::anyhow!("failed to find sessions: {e}"))?
     };
-    // Dedup: remove any existing entry with same site+org, then append
-    sessions.retain(|s| !(s.site == entry.site && s.org == entry.org));
-    sessions.push(entry);
-    write_sessions(&sessions)
+    if !exact.is_empty() {
+        return Ok(exact);
+    }
+
+    let escaped = query
+        .to_lowercase()
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    let needle = format!("%{escaped}%");
+    let mut stmt = conn.prepare(
+        "SELECT site, org FROM sessions WHERE LOWER(org) LIKE ?1 ESCAPE '\\' OR LOWER(site) LIKE ?1 ESCAPE '\\'",
+    )?;
+    stmt.query_map(rusqlite::params![needle], sqlite_session_entry)
+        .map_err(|e| anyhow::anyhow!("failed to find sessions: {e}"))?
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .map_err(|e| anyhow::anyhow!("failed to find sessions: {e}"))
 }
 
-/// Remove a session entry from the registry.
 #[cfg(not(target_arch = "wasm32"))]
-pub fn remove_session(site: &str, org: Option<&str>) -> Result<()> {
-    let mut sessions = list_sessions()?;
-    sessions.retain(|s| !(s.site == site && s.org.as_deref() == org));
-    write_sessions(&sessions)
+fn sqlite_save_session(path: &std::path::Path, site: &str, org: Option<&str>) -> Result<()> {
+    let conn = sqlite_sessions_connection(path)?;
+    conn.execute(
+        "INSERT INTO sessions (site, org) VALUES (?1, ?2)
+         ON CONFLICT(site, org) DO NOTHING",
+        rusqlite::params![site, org_map_key(org)],
+    )
+    .map_err(|e| anyhow::anyhow!("failed to save session: {e}"))?;
+    Ok(())
 }
 
 #[cfg(not(target_arch = "wasm32"))]
-fn write_sessions(sessions: &[SessionEntry]) -> Result<()> {
-    let path = match sessions_path() {
-        Some(p) => p,
-        None => return Ok(()),
-    };
-    if let Some(parent) = path.parent() {
-        std::fs::create_dir_all(parent)?;
+fn sqlite_remove_session(path: &std::path::Path, site: &str, org: Option<&str>) -> Result<()> {
+    let conn = sqlite_sessions_connection(path)?;
+    conn.execute(
+        "DELETE FROM sessions WHERE site = ?1 AND org = ?2",
+        rusqlite::params![site, org_map_key(org)],
+    )
+    .map_err(|e| anyhow::anyhow!("failed to remove session: {e}"))?;
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// In-memory storage (WASM) — no persistent storage available
+// ---------------------------------------------------------------------------
+
+#[cfg(target_arch = "wasm32")]
+pub struct InMemoryStorage;
+
+#[cfg(target_arch = "wasm32")]
+impl Storage for InMemoryStorage {
+    fn backend_type(&self) -> BackendType {
+        BackendType::File
     }
-    let json = serde_json::to_string_pretty(sessions)?;
-    std::fs::write(&path, &json)
-        .with_context(|| format!("failed to write sessions: {}", path.display()))?;
-    #[cfg(unix)]
-    {
-        use std::os::unix::fs::PermissionsExt;
-        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))?;
+
+    fn storage_location(&self) -> String {
+        "in-memory (WASM)".to_string()
+    }
+
+    fn save_tokens(&self, _site: &str, _org: Option<&str>, _tokens: &TokenSet) -> Result<()> {
+        anyhow::bail!("token storage not available in WASM — use DD_ACCESS_TOKEN env var")
+    }
+
+    fn load_tokens(&self, _site: &str, _org: Option<&str>) -> Result<Option<TokenSet>> {
+        Ok(None)
+    }
+
+    fn delete_tokens(&self, _site: &str, _org: Option<&str>) -> Result<()> {
+        Ok(())
+    }
+
+    fn save_client_credentials(&self, _site: &str, _creds: &ClientCredentials) -> Result<()> {
+        anyhow::bail!("client credential storage not available in WASM")
+    }
+
+    fn load_client_credentials(&self, _site: &str) -> Result<Option<ClientCredentials>> {
+        Ok(None)
+    }
+
+    fn delete_client_credentials(&self, _site: &str) -> Result<()> {
+        Ok(())
+    }
+
+    fn prune_expired(&self, _now: i64) -> Result<usize> {
+        Ok(0)
     }
-    Ok(())
 }
 
 // ---------------------------------------------------------------------------
-// Tests
+// LocalStorage backend (browser WASM) — persists tokens across page reloads
 // ---------------------------------------------------------------------------
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+#[cfg(feature = "browser")]
+pub struct LocalStorageBackend;
+
+#[cfg(feature = "browser")]
+impl LocalStorageBackend {
+    fn storage() -> Result<web_sys::Storage> {
+        let window = web_sys::window().ok_or_else(|| anyhow::anyhow!("no global window object"))?;
+        window
+            .local_storage()
+            .map_err(|_| anyhow::anyhow!("localStorage not available"))?
+            .ok_or_else(|| anyhow::anyhow!("localStorage returned None"))
+    }
+
+    fn get_item(key: &str) -> Result<Option<String>> {
+        let storage = Self::storage()?;
+        storage
+            .get_item(key)
+            .map_err(|_| anyhow::anyhow!("failed to read from localStorage"))
+    }
+
+    fn set_item(key: &str, value: &str) -> Result<()> {
+        let storage = Self::storage()?;
+        storage
+            .set_item(key, value)
+            .map_err(|_| anyhow::anyhow!("failed to write to localStorage"))
+    }
+
+    fn remove_item(key: &str) -> Result<()> {
+        let storage = Self::storage()?;
+        storage
+            .remove_item(key)
+            .map_err(|_| anyhow::anyhow!("failed to remove from localStorage"))
+    }
+
+    /// Resolve the localStorage key for `{prefix}{sanitize(site)}`, migrating
+    /// a pre-existing `{prefix}{legacy_sanitize(site)}` entry (written before
+    /// the collision-resistant sanitize() scheme) into place on first access.
+    fn resolve_key(prefix: &str, site: &str) -> Result<String> {
+        let new_key = format!("{prefix}{}", sanitize(site));
+        if Self::get_item(&new_key)?.is_none() {
+            let legacy_key = format!("{prefix}{}", legacy_sanitize(site));
+            if let Some(value) = Self::get_item(&legacy_key)? {
+                Self::set_item(&new_key, &value)?;
+                Self::remove_item(&legacy_key)?;
+            }
+        }
+        Ok(new_key)
+    }
+}
+
+#[cfg(feature = "browser")]
+impl Storage for LocalStorageBackend {
+    fn backend_type(&self) -> BackendType {
+        BackendType::LocalStorage
+    }
+
+    fn storage_location(&self) -> String {
+        "browser localStorage".to_string()
+    }
+
+    fn save_tokens(&self, site: &str, org: Option<&str>, tokens: &TokenSet) -> Result<()> {
+        let key = Self::resolve_key("pup_tokens_", site)?;
+        let mut map = match Self::get_item(&key)? {
+            Some(json) => parse_token_map(&json)?,
+            None => OrgTokenMap::new(),
+        };
+        map.insert(org_map_key(org).to_string(), tokens.clone());
+        let json = serialize_token_map(&map, false)?;
+        Self::set_item(&key, &json)
+    }
+
+    fn load_tokens(&self, site: &str, org: Option<&str>) -> Result<Option<TokenSet>> {
+        let key = Self::resolve_key("pup_tokens_", site)?;
+        match Self::get_item(&key)? {
+            Some(json) => Ok(parse_token_map(&json)?.remove(org_map_key(org))),
+            None => Ok(None),
+        }
+    }
+
+    fn delete_tokens(&self, site: &str, org: Option<&str>) -> Result<()> {
+        let key = Self::resolve_key("pup_tokens_", site)?;
+        let mut map = match Self::get_item(&key)? {
+            Some(json) => parse_token_map(&json)?,
+            None => return Ok(()),
+        };
+        map.remove(org_map_key(org));
+        if map.is_empty() {
+            Self::remove_item(&key)
+        } else {
+            let json = serialize_token_map(&map, false)?;
+            Self::set_item(&key, &json)
+        }
+    }
+
+    fn save_client_credentials(&self, site: &str, creds: &ClientCredentials) -> Result<()> {
+        let key = Self::resolve_key("pup_client_", site)?;
+        let json = serde_json::to_string(creds)?;
+        Self::set_item(&key, &json)
+    }
+
+    fn load_client_credentials(&self, site: &str) -> Result<Option<ClientCredentials>> {
+        let key = Self::resolve_key("pup_client_", site)?;
+        match Self::get_item(&key)? {
+            Some(json) => Ok(Some(serde_json::from_str(&json)?)),
+            None => Ok(None),
+        }
+    }
+
+    fn delete_client_credentials(&self, site: &str) -> Result<()> {
+        let key = Self::resolve_key("pup_client_", site)?;
+        Self::remove_item(&key)
+    }
+
+    /// No session registry exists in WASM builds, so this only prunes the
+    /// token maps themselves — there's nothing to reconcile afterward.
+    fn prune_expired(&self, now: i64) -> Result<usize> {
+        let storage = Self::storage()?;
+        let len = storage
+            .length()
+            .map_err(|_| anyhow::anyhow!("failed to read localStorage length"))?;
+        let keys: Vec<String> = (0..len)
+            .filter_map(|i| storage.key(i).ok().flatten())
+            .filter(|k| k.starts_with("pup_tokens_"))
+            .collect();
+
+        let mut removed = 0;
+        for key in keys {
+            let json = match Self::get_item(&key)? {
+                Some(j) => j,
+                None => continue,
+            };
+            let mut map = match parse_token_map(&json) {
+                Ok(m) => m,
+                Err(_) => continue,
+            };
+            let before = map.len();
+            map.retain(|_, tokens| !is_prunable(tokens, now));
+            removed += before - map.len();
+
+            if map.len() == before {
+                continue;
+            }
+            if map.is_empty() {
+                Self::remove_item(&key)?;
+            } else {
+                let json = serialize_token_map(&map, false)?;
+                Self::set_item(&key, &json)?;
+            }
+        }
+        Ok(removed)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Factory — auto-detect backend, with fallback
+// ---------------------------------------------------------------------------
+
+use std::sync::Mutex;
+
+static STORAGE: Mutex<Option<Box<dyn Storage>>> = Mutex::new(None);
+
+/// Get the process-wide storage backend, initializing it on first use.
+///
+/// `preferred` is the `storage_backend` config value (`"keyring"`/`"file"`, or
+/// `None` to auto-detect); it only has an effect on the first call — later
+/// calls reuse whatever backend was already initialized.
+pub fn get_storage(preferred: Option<&str>) -> Result<&'static Mutex<Option<Box<dyn Storage>>>> {
+    let mut guard = STORAGE.lock().unwrap();
+    if guard.is_none() {
+        let backend = detect_backend(preferred);
+        *guard = Some(backend);
+    }
+    drop(guard);
+    Ok(&STORAGE)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn detect_backend(preferred: Option<&str>) -> Box<dyn Storage> {
+    // Config (`storage_backend = "keyring" | "file"`) takes precedence, then
+    // the DD_TOKEN_STORAGE env var, then auto-detection.
+    let choice = preferred
+        .map(str::to_string)
+        .or_else(|| std::env::var("DD_TOKEN_STORAGE").ok());
+
+    if let Some(val) = choice {
+        match val.as_str() {
+            "file" => return Box::new(FileStorage::new().expect("failed to create file storage")),
+            "keychain" | "keyring" => {
+                return Box::new(KeychainStorage::new().expect("keychain not available"))
+            }
+            "encrypted-file" => {
+                return Box::new(
+                    EncryptedFileStorage::new().expect("failed to create encrypted file storage"),
+                )
+            }
+            "sqlite" => {
+                return Box::new(SqliteStorage::new().expect("failed to create sqlite storage"))
+            }
+            "age" => {
+                let passphrase = storage_passphrase().expect("failed to read age passphrase");
+                return Box::new(
+                    AgeStorage::with_passphrase(passphrase).expect("failed to create age storage"),
+                );
+            }
+            "age-identity" => {
+                return Box::new(
+                    AgeStorage::with_identity_file().expect("failed to create age storage"),
+                )
+            }
+            _ => eprintln!("Warning: unknown storage backend {val:?}, auto-detecting"),
+        }
+    }
+
+    // Try keychain first
+    match KeychainStorage::new() {
+        Ok(ks) => Box::new(ks),
+        Err(_) => {
+            eprintln!("Warning: OS keychain not available, using file storage (~/.config/pup/)");
+            Box::new(FileStorage::new().expect("failed to create file storage"))
+        }
+    }
+}
+
+#[cfg(all(target_arch = "wasm32", not(feature = "browser")))]
+fn detect_backend(_preferred: Option<&str>) -> Box<dyn Storage> {
+    Box::new(InMemoryStorage)
+}
+
+#[cfg(feature = "browser")]
+fn detect_backend(_preferred: Option<&str>) -> Box<dyn Storage> {
+    Box::new(LocalStorageBackend)
+}
+
+// ---------------------------------------------------------------------------
+// Helpers
+// ---------------------------------------------------------------------------
+
+/// Turn a site into a filename/keychain-entry-safe key. Distinct sites that
+/// collapse to the same sanitized stem (`app.datadoghq.com` vs
+/// `app_datadoghq_com`) are disambiguated by appending a short hash of the
+/// original site string, so they never collide on disk or in the keychain.
+fn sanitize(site: &str) -> String {
+    format!("{}_{}", legacy_sanitize(site), site_fingerprint(site))
+}
+
+/// The pre-collision-resistance sanitize scheme: every non-alphanumeric
+/// character replaced with `_`, with no disambiguating suffix. Kept only so
+/// backends can detect and migrate entries written under the old scheme.
+fn legacy_sanitize(site: &str) -> String {
+    site.chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+/// First 8 hex chars of SHA-256(site) — enough entropy that two real sites
+/// landing on the same sanitized stem and the same fingerprint is not a
+/// realistic concern.
+fn site_fingerprint(site: &str) -> String {
+    use sha2::{Digest, Sha256};
+    Sha256::digest(site.as_bytes())
+        .iter()
+        .take(4)
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+// ---------------------------------------------------------------------------
+// OrgTokenMap — one keychain/file entry per site, keyed by org label
+// ---------------------------------------------------------------------------
+
+/// All orgs for a site are stored under a single key as a JSON map.
+/// The no-org (default) session uses this sentinel as its map key.
+const DEFAULT_ORG_KEY: &str = "__default__";
+
+type OrgTokenMap = std::collections::HashMap<String, TokenSet>;
+
+fn org_map_key(org: Option<&str>) -> &str {
+    match org {
+        Some(o) if !o.is_empty() => o,
+        _ => DEFAULT_ORG_KEY,
+    }
+}
+
+/// Grace period added on top of `issued_at + expires_in` before a token set
+/// with no usable refresh token is considered prunable — keeps a token that
+/// just expired from being wiped out from under an in-flight refresh.
+const PRUNE_GRACE_SECS: i64 = 86_400;
+
+/// Whether `tokens` is safe to drop during `Storage::prune_expired`: the
+/// access token is expired (with `PRUNE_GRACE_SECS` slack) and there's no
+/// refresh token that could bring it back to life.
+fn is_prunable(tokens: &TokenSet, now: i64) -> bool {
+    let expired = now >= tokens.issued_at + tokens.expires_in + PRUNE_GRACE_SECS;
+    expired && tokens.refresh_token.is_empty()
+}
+
+/// Magic string prefixed to every token-map file written by current pup,
+/// followed by a big-endian `u32` format version. Modelled on the header
+/// rustc's incremental-compilation artifacts use: validate the header up
+/// front and dispatch to the matching decoder, instead of discovering a
+/// format mismatch partway through a best-effort parse. A file lacking this
+/// magic predates the header entirely — version 0, decoded by the trial
+/// fallback below.
+const TOKEN_STORE_MAGIC: &[u8] = b"PUPTOKNS";
+
+/// Current on-disk format version. Bump this and add a match arm in
+/// `parse_token_map` whenever the payload encoding changes; old versions
+/// keep decoding via their existing arm. Every backend round-trips this
+/// header through a `String` (the keychain and localStorage backends only
+/// accept UTF-8 strings), so the version's big-endian bytes must stay ASCII
+/// — keep it below 128.
+const TOKEN_STORE_VERSION: u32 = 1;
+
+/// Serialize `map` into the current on-disk token-store format: the header
+/// above followed by JSON (`pretty`, for the plain `FileStorage` file a user
+/// might open directly; compact everywhere else — keychain entries,
+/// encrypted blobs and localStorage values are never hand-read). Every
+/// backend writes this format; only `parse_token_map`'s version-0 fallback
+/// ever reads anything older.
+fn serialize_token_map(map: &OrgTokenMap, pretty: bool) -> Result<String> {
+    debug_assert!(
+        TOKEN_STORE_VERSION < 128,
+        "TOKEN_STORE_VERSION must stay ASCII-safe, see doc comment"
+    );
+    let mut bytes = Vec::new();
+    bytes.extend_from_slice(TOKEN_STORE_MAGIC);
+    bytes.extend_from_slice(&TOKEN_STORE_VERSION.to_be_bytes());
+    let payload = if pretty {
+        serde_json::to_vec_pretty(map)?
+    } else {
+        serde_json::to_vec(map)?
+    };
+    bytes.extend_from_slice(&payload);
+    String::from_utf8(bytes).context(
+        "serialized token store is not valid UTF-8 (TOKEN_STORE_VERSION must stay below 128)",
+    )
+}
+
+/// Parse a stored blob as an OrgTokenMap.
+///
+/// Files carrying the `TOKEN_STORE_MAGIC` header are validated and dispatched
+/// by version, so a store written by a newer pup is reported as a clear
+/// "unsupported version" error rather than falling through to a generic
+/// parse failure. Files without the magic predate the header (version 0) and
+/// are decoded by trial: first the headerless multi-org map format, falling
+/// back to the bare single-TokenSet format written by pup before multi-org
+/// support, which is promoted to {"__default__": <tokens>} transparently.
+fn parse_token_map(raw: &str) -> Result<OrgTokenMap> {
+    if let Some(rest) = raw.as_bytes().strip_prefix(TOKEN_STORE_MAGIC) {
+        if rest.len() < 4 {
+            anyhow::bail!("token store header is truncated");
+        }
+        let version = u32::from_be_bytes([rest[0], rest[1], rest[2], rest[3]]);
+        return match version {
+            1 => {
+                let payload = std::str::from_utf8(&rest[4..])
+                    .context("token store payload is not valid UTF-8")?;
+                serde_json::from_str(payload).context("failed to parse token store payload")
+            }
+            other => anyhow::bail!(
+                "token store is format version {other}, which this build of pup does not support"
+            ),
+        };
+    }
+
+    // Version 0: headerless legacy format.
+    // New format: {"__default__": {...}, "prod-child": {...}}
+    if let Ok(map) = serde_json::from_str::<OrgTokenMap>(raw) {
+        return Ok(map);
+    }
+    // Old format: bare TokenSet — promote to map under __default__
+    if let Ok(tokens) = serde_json::from_str::<TokenSet>(raw) {
+        let mut map = OrgTokenMap::new();
+        map.insert(DEFAULT_ORG_KEY.to_string(), tokens);
+        return Ok(map);
+    }
+    anyhow::bail!("token storage contains unrecognised format")
+}
+
+// ---------------------------------------------------------------------------
+// Session registry — tracks named org sessions (no secrets stored here)
+// ---------------------------------------------------------------------------
+
+#[cfg(not(target_arch = "wasm32"))]
+fn sessions_path() -> Option<std::path::PathBuf> {
+    crate::config::config_dir().map(|d| d.join("sessions.json"))
+}
+
+/// List all stored sessions from the registry.
+/// Returns an empty vec if no sessions have been recorded yet.
+///
+/// When the sqlite storage backend is active, the registry lives in the
+/// `sessions` table of the same database instead of `sessions.json`.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn list_sessions() -> Result<Vec<SessionEntry>> {
+    if let Some(path) = SQLITE_SESSIONS_PATH.lock().unwrap().clone() {
+        return sqlite_list_sessions(&path);
+    }
+
+    let path = match sessions_path() {
+        Some(p) => p,
+        None => return Ok(vec![]),
+    };
+    match std::fs::read_to_string(&path) {
+        Ok(json) => Ok(serde_json::from_str(&json).unwrap_or_default()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(vec![]),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Upsert a session entry into the registry.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn save_session(site: &str, org: Option<&str>) -> Result<()> {
+    if let Some(path) = SQLITE_SESSIONS_PATH.lock().unwrap().clone() {
+        return sqlite_save_session(&path, site, org);
+    }
+
+    let mut sessions = list_sessions()?;
+    let entry = SessionEntry {
+        site: site.to_string(),
+        org: org.map(String::from),
+    };
+    // Dedup: remove any existing entry with same site+org, then append
+    sessions.retain(|s| !(s.site == entry.site && s.org == entry.org));
+    sessions.push(entry);
+    write_sessions(&sessions)
+}
+
+/// Remove a session entry from the registry.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn remove_session(site: &str, org: Option<&str>) -> Result<()> {
+    if let Some(path) = SQLITE_SESSIONS_PATH.lock().unwrap().clone() {
+        return sqlite_remove_session(&path, site, org);
+    }
+
+    let mut sessions = list_sessions()?;
+    sessions.retain(|s| !(s.site == site && s.org.as_deref() == org));
+    write_sessions(&sessions)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn write_sessions(sessions: &[SessionEntry]) -> Result<()> {
+    let path = match sessions_path() {
+        Some(p) => p,
+        None => return Ok(()),
+    };
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let json = serde_json::to_string_pretty(sessions)?;
+    atomic_write(&path, json.as_bytes())
+        .with_context(|| format!("failed to write sessions: {}", path.display()))?;
+    write_session_index(sessions)
+}
+
+// ---------------------------------------------------------------------------
+// Session registry — secondary by-org index
+// ---------------------------------------------------------------------------
+
+/// Secondary lookup directory over the session registry, keyed by org name
+/// — the session-registry equivalent of a keyserver's `by-email`/`by-fpr`
+/// pointer directories — so resolving an org alias to its `(site, org)`
+/// session doesn't require scanning every entry in `sessions.json`.
+/// Regenerated from the authoritative registry on every `write_sessions`
+/// call, and lazily rebuilt by `find_sessions` if it's ever found missing or
+/// unparseable, so it can't drift out of sync for long. The registry and the
+/// index are written as two separate atomic files rather than one, so a
+/// crash between the two writes can leave a stale-but-parseable index behind
+/// until the next mutation (or `prune_expired`'s registry reconciliation)
+/// rewrites it — `sessions.json` itself is never at risk, only the index's
+/// freshness.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(serde::Serialize, serde::Deserialize, Default)]
+struct SessionIndex {
+    by_org: std::collections::HashMap<String, Vec<SessionEntry>>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn sessions_index_path() -> Option<std::path::PathBuf> {
+    crate::config::config_dir().map(|d| d.join("sessions_index.json"))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn build_session_index(sessions: &[SessionEntry]) -> SessionIndex {
+    let mut by_org: std::collections::HashMap<String, Vec<SessionEntry>> =
+        std::collections::HashMap::new();
+    for session in sessions {
+        if let Some(org) = &session.org {
+            by_org.entry(org.clone()).or_default().push(session.clone());
+        }
+    }
+    SessionIndex { by_org }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn write_session_index(sessions: &[SessionEntry]) -> Result<()> {
+    let path = match sessions_index_path() {
+        Some(p) => p,
+        None => return Ok(()),
+    };
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let json = serde_json::to_string_pretty(&build_session_index(sessions))?;
+    atomic_write(&path, json.as_bytes())
+        .with_context(|| format!("failed to write session index: {}", path.display()))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn read_session_index() -> Option<SessionIndex> {
+    let json = std::fs::read_to_string(sessions_index_path()?).ok()?;
+    serde_json::from_str(&json).ok()
+}
+
+/// Resolve `query` to the session(s) it names. Tries an O(1) exact match
+/// against the by-org index first — the `pup use <org>` common case — and
+/// only falls back to scanning every registered session (matching `query`
+/// as a case-insensitive substring of the org name or site) when that
+/// misses, so a query for a site or a partial org name still works. If the
+/// index is missing or corrupt, it's rebuilt from the registry before the
+/// fallback scan runs, so later lookups are O(1) again.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn find_sessions(query: &str) -> Result<Vec<SessionEntry>> {
+    if let Some(path) = SQLITE_SESSIONS_PATH.lock().unwrap().clone() {
+        return sqlite_find_sessions(&path, query);
+    }
+
+    let index = read_session_index();
+    if let Some(matches) = index.as_ref().and_then(|i| i.by_org.get(query)) {
+        if !matches.is_empty() {
+            return Ok(matches.clone());
+        }
+    }
+
+    let sessions = list_sessions()?;
+    if index.is_none() {
+        let _ = write_session_index(&sessions);
+    }
+    let needle = query.to_lowercase();
+    Ok(sessions
+        .into_iter()
+        .filter(|s| {
+            s.org
+                .as_deref()
+                .is_some_and(|org| org.to_lowercase().contains(&needle))
+                || s.site.to_lowercase().contains(&needle)
+        })
+        .collect())
+}
+
+/// Drop every session-registry entry whose tokens no longer exist in `store`
+/// — shared by each backend's `prune_expired` so `list_sessions` never shows
+/// a site/org whose tokens have already been pruned.
+#[cfg(not(target_arch = "wasm32"))]
+fn reconcile_session_registry(store: &dyn Storage) -> Result<()> {
+    for session in list_sessions()? {
+        let org = session.org.as_deref();
+        if store.load_tokens(&session.site, org)?.is_none() {
+            remove_session(&session.site, org)?;
+        }
+    }
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Portable encrypted export/import — for moving sessions between machines
+// ---------------------------------------------------------------------------
+
+#[cfg(not(target_arch = "wasm32"))]
+const EXPORT_ARCHIVE_VERSION: u32 = 1;
+
+/// Everything `export_all`/`import_all` round-trip: every site's token map,
+/// every site's client credentials, and the session registry, all keyed by
+/// site so `import_all` can merge entry-by-entry instead of clobbering.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct ExportArchive {
+    version: u32,
+    tokens: std::collections::HashMap<String, OrgTokenMap>,
+    client_credentials: std::collections::HashMap<String, ClientCredentials>,
+    sessions: Vec<SessionEntry>,
+}
+
+/// Serialize every stored site's tokens, client credentials, and the session
+/// registry into one archive, sealed with `passphrase` via the same
+/// Argon2id + AEAD scheme as [`EncryptedFileStorage`]. Sites are discovered
+/// via the session registry, the same enumeration strategy `prune_expired`
+/// uses for backends (like the OS keychain) that can't list their own entries.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn export_all(store: &dyn Storage, passphrase: &str) -> Result<Vec<u8>> {
+    let sessions = list_sessions()?;
+
+    let mut tokens: std::collections::HashMap<String, OrgTokenMap> =
+        std::collections::HashMap::new();
+    for session in &sessions {
+        if let Some(token_set) = store.load_tokens(&session.site, session.org.as_deref())? {
+            tokens
+                .entry(session.site.clone())
+                .or_default()
+                .insert(org_map_key(session.org.as_deref()).to_string(), token_set);
+        }
+    }
+
+    let mut client_credentials = std::collections::HashMap::new();
+    for site in tokens.keys() {
+        if let Some(creds) = store.load_client_credentials(site)? {
+            client_credentials.insert(site.clone(), creds);
+        }
+    }
+
+    let archive = ExportArchive {
+        version: EXPORT_ARCHIVE_VERSION,
+        tokens,
+        client_credentials,
+        sessions,
+    };
+    seal(passphrase, serde_json::to_string(&archive)?.as_bytes())
+}
+
+/// Open an archive produced by [`export_all`] and upsert every site+org token
+/// set, client credential, and session entry it contains into `store`.
+/// Entries already in `store` but absent from the archive are left alone —
+/// this merges, it never clobbers.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn import_all(store: &dyn Storage, blob: &[u8], passphrase: &str) -> Result<()> {
+    let json = open_sealed(passphrase, blob)?;
+    let archive: ExportArchive = serde_json::from_str(&json)
+        .map_err(|e| anyhow::anyhow!("failed to parse export archive: {e}"))?;
+    if archive.version != EXPORT_ARCHIVE_VERSION {
+        anyhow::bail!(
+            "unsupported export archive version {} (expected {})",
+            archive.version,
+            EXPORT_ARCHIVE_VERSION
+        );
+    }
+
+    for (site, map) in &archive.tokens {
+        for (org_key, token_set) in map {
+            let org = if org_key == DEFAULT_ORG_KEY {
+                None
+            } else {
+                Some(org_key.as_str())
+            };
+            store.save_tokens(site, org, token_set)?;
+        }
+    }
+    for (site, creds) in &archive.client_credentials {
+        store.save_client_credentials(site, creds)?;
+    }
+    for session in &archive.sessions {
+        save_session(&session.site, session.org.as_deref())?;
+    }
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // --- helpers ------------------------------------------------------------
+
+    fn make_token(access: &str) -> TokenSet {
+        TokenSet {
+            access_token: access.to_string(),
+            refresh_token: "refresh".into(),
+            token_type: "Bearer".into(),
+            expires_in: 9_999_999_999, // far future — never expired
+            issued_at: 0,
+            scope: String::new(),
+            client_id: String::new(),
+        }
+    }
+
+    /// A token set that is both expired and has no refresh token — the
+    /// shape `prune_expired` should remove.
+    fn make_expired_token(access: &str) -> TokenSet {
+        TokenSet {
+            access_token: access.to_string(),
+            refresh_token: String::new(),
+            token_type: "Bearer".into(),
+            expires_in: 1,
+            issued_at: 0,
+            scope: String::new(),
+            client_id: String::new(),
+        }
+    }
+
+    /// Temporary directory that removes itself on drop.
+    struct TempDir(std::path::PathBuf);
+
+    impl TempDir {
+        fn new(label: &str) -> Self {
+            let nanos = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.subsec_nanos())
+                .unwrap_or(0);
+            let dir = std::env::temp_dir().join(format!("pup_test_{}_{}", label, nanos));
+            std::fs::create_dir_all(&dir).unwrap();
+            TempDir(dir)
+        }
+
+        fn path(&self) -> &std::path::PathBuf {
+            &self.0
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    // --- org_map_key --------------------------------------------------------
+
+    #[test]
+    fn test_org_map_key_none() {
+        assert_eq!(org_map_key(None), DEFAULT_ORG_KEY);
+    }
+
+    #[test]
+    fn test_org_map_key_empty_string() {
+        assert_eq!(org_map_key(Some("")), DEFAULT_ORG_KEY);
+    }
+
+    #[test]
+    fn test_org_map_key_named() {
+        assert_eq!(org_map_key(Some("prod-child")), "prod-child");
+    }
+
+    // --- parse_token_map ----------------------------------------------------
+
+    #[test]
+    fn test_parse_token_map_new_format() {
+        let map: OrgTokenMap = [(DEFAULT_ORG_KEY.to_string(), make_token("tok1"))]
+            .into_iter()
+            .collect();
+        let json = serde_json::to_string(&map).unwrap();
+        let parsed = parse_token_map(&json).unwrap();
+        assert_eq!(parsed[DEFAULT_ORG_KEY].access_token, "tok1");
+    }
+
+    #[test]
+    fn test_parse_token_map_multiple_orgs() {
+        let map: OrgTokenMap = [
+            (DEFAULT_ORG_KEY.to_string(), make_token("default_tok")),
+            ("prod".to_string(), make_token("prod_tok")),
+        ]
+        .into_iter()
+        .collect();
+        let json = serde_json::to_string(&map).unwrap();
+        let parsed = parse_token_map(&json).unwrap();
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed[DEFAULT_ORG_KEY].access_token, "default_tok");
+        assert_eq!(parsed["prod"].access_token, "prod_tok");
+    }
+
+    #[test]
+    fn test_parse_token_map_legacy_migration() {
+        // Old format: bare TokenSet at the root (written by pup before multi-org)
+        let json = serde_json::to_string(&make_token("legacy_tok")).unwrap();
+        let parsed = parse_token_map(&json).unwrap();
+        assert_eq!(parsed.len(), 1);
+        assert_eq!(parsed[DEFAULT_ORG_KEY].access_token, "legacy_tok");
+    }
+
+    #[test]
+    fn test_parse_token_map_invalid_json() {
+        assert!(parse_token_map("not json at all").is_err());
+        assert!(parse_token_map("{\"bad\": true}").is_err());
+    }
+
+    #[test]
+    fn test_serialize_token_map_roundtrips_through_parse_token_map() {
+        let map: OrgTokenMap = [
+            (DEFAULT_ORG_KEY.to_string(), make_token("default_tok")),
+            ("prod".to_string(), make_token("prod_tok")),
+        ]
+        .into_iter()
+        .collect();
+        let serialized = serialize_token_map(&map, true).unwrap();
+        assert!(serialized.as_bytes().starts_with(TOKEN_STORE_MAGIC));
+
+        let parsed = parse_token_map(&serialized).unwrap();
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed[DEFAULT_ORG_KEY].access_token, "default_tok");
+        assert_eq!(parsed["prod"].access_token, "prod_tok");
+    }
+
+    #[test]
+    fn test_parse_token_map_rejects_newer_version() {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(TOKEN_STORE_MAGIC);
+        bytes.extend_from_slice(&99u32.to_be_bytes());
+        bytes.extend_from_slice(b"{}");
+        let raw = String::from_utf8(bytes).unwrap();
+
+        let err = parse_token_map(&raw).unwrap_err();
+        assert!(err.to_string().contains("version 99"));
+    }
+
+    #[test]
+    fn test_parse_token_map_rejects_truncated_header() {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(TOKEN_STORE_MAGIC);
+        bytes.extend_from_slice(&[0, 1]); // short version field
+        let raw = String::from_utf8(bytes).unwrap();
+
+        assert!(parse_token_map(&raw).is_err());
+    }
+
+    // --- FileStorage — token map behaviour ----------------------------------
+
+    #[test]
+    fn test_file_storage_save_load_default_org() {
+        let tmp = TempDir::new("fs_default");
+        let store = FileStorage {
+            base_dir: tmp.path().clone(),
+        };
+        store
+            .save_tokens("datadoghq.com", None, &make_token("default_tok"))
+            .unwrap();
+        let loaded = store.load_tokens("datadoghq.com", None).unwrap().unwrap();
+        assert_eq!(loaded.access_token, "default_tok");
+    }
+
+    #[test]
+    fn test_file_storage_save_load_named_org() {
+        let tmp = TempDir::new("fs_named");
+        let store = FileStorage {
+            base_dir: tmp.path().clone(),
+        };
+        store
+            .save_tokens("datadoghq.com", Some("prod-child"), &make_token("prod_tok"))
+            .unwrap();
+        let loaded = store
+            .load_tokens("datadoghq.com", Some("prod-child"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(loaded.access_token, "prod_tok");
+    }
+
+    #[test]
+    fn test_file_storage_multiple_orgs_one_file() {
+        let tmp = TempDir::new("fs_multi");
+        let store = FileStorage {
+            base_dir: tmp.path().clone(),
+        };
+
+        store
+            .save_tokens("datadoghq.com", None, &make_token("default_tok"))
+            .unwrap();
+        store
+            .save_tokens("datadoghq.com", Some("prod"), &make_token("prod_tok"))
+            .unwrap();
+        store
+            .save_tokens("datadoghq.com", Some("staging"), &make_token("staging_tok"))
+            .unwrap();
+
+        // Only one file on disk for this site
+        let files: Vec<_> = std::fs::read_dir(tmp.path())
+            .unwrap()
+            .filter_map(|e| e.ok())
+            .collect();
+        assert_eq!(files.len(), 1);
+
+        // All three orgs load independently
+        assert_eq!(
+            store
+                .load_tokens("datadoghq.com", None)
+                .unwrap()
+                .unwrap()
+                .access_token,
+            "default_tok"
+        );
+        assert_eq!(
+            store
+                .load_tokens("datadoghq.com", Some("prod"))
+                .unwrap()
+                .unwrap()
+                .access_token,
+            "prod_tok"
+        );
+        assert_eq!(
+            store
+                .load_tokens("datadoghq.com", Some("staging"))
+                .unwrap()
+                .unwrap()
+                .access_token,
+            "staging_tok"
+        );
+    }
+
+    #[test]
+    fn test_file_storage_org_isolation() {
+        // Loading a different org must not return another org's token
+        let tmp = TempDir::new("fs_isolation");
+        let store = FileStorage {
+            base_dir: tmp.path().clone(),
+        };
+
+        store
+            .save_tokens("datadoghq.com", Some("prod"), &make_token("prod_tok"))
+            .unwrap();
+        assert!(store.load_tokens("datadoghq.com", None).unwrap().is_none());
+        assert!(store
+            .load_tokens("datadoghq.com", Some("staging"))
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_file_storage_delete_last_org_removes_file() {
+        let tmp = TempDir::new("fs_del_last");
+        let store = FileStorage {
+            base_dir: tmp.path().clone(),
+        };
+
+        store
+            .save_tokens("datadoghq.com", None, &make_token("tok"))
+            .unwrap();
+        store.delete_tokens("datadoghq.com", None).unwrap();
+
+        let file_path = tmp
+            .path()
+            .join(format!("tokens_{}.json", sanitize("datadoghq.com")));
+        assert!(
+            !file_path.exists(),
+            "file should be removed when last org is deleted"
+        );
+    }
+
+    #[test]
+    fn test_file_storage_delete_one_org_keeps_others() {
+        let tmp = TempDir::new("fs_del_one");
+        let store = FileStorage {
+            base_dir: tmp.path().clone(),
+        };
+
+        store
+            .save_tokens("datadoghq.com", None, &make_token("default_tok"))
+            .unwrap();
+        store
+            .save_tokens("datadoghq.com", Some("prod"), &make_token("prod_tok"))
+            .unwrap();
+        store.delete_tokens("datadoghq.com", Some("prod")).unwrap();
+
+        // Default session survives
+        assert_eq!(
+            store
+                .load_tokens("datadoghq.com", None)
+                .unwrap()
+                .unwrap()
+                .access_token,
+            "default_tok"
+        );
+        // Deleted org is gone
+        assert!(store
+            .load_tokens("datadoghq.com", Some("prod"))
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_file_storage_delete_nonexistent_is_ok() {
+        let tmp = TempDir::new("fs_del_none");
+        let store = FileStorage {
+            base_dir: tmp.path().clone(),
+        };
+        assert!(store.delete_tokens("datadoghq.com", None).is_ok());
+    }
+
+    #[test]
+    fn test_sanitize_disambiguates_legacy_collisions() {
+        // Both of these collapse to the same stem under legacy_sanitize().
+        assert_eq!(legacy_sanitize("app.datadoghq.com"), legacy_sanitize("app_datadoghq_com"));
+        // ...but sanitize() keeps them distinct via the fingerprint suffix.
+        assert_ne!(sanitize("app.datadoghq.com"), sanitize("app_datadoghq_com"));
+    }
+
+    #[test]
+    fn test_file_storage_legacy_migration() {
+        let tmp = TempDir::new("fs_legacy");
+        let store = FileStorage {
+            base_dir: tmp.path().clone(),
+        };
+
+        // Write old-format file: bare TokenSet, no map wrapper, under the
+        // pre-collision-resistance sanitize() scheme.
+        let legacy_json = serde_json::to_string_pretty(&make_token("legacy_tok")).unwrap();
+        let path = tmp
+            .path()
+            .join(format!("tokens_{}.json", legacy_sanitize("datadoghq.com")));
+        std::fs::write(&path, legacy_json).unwrap();
+
+        // Existing default session loads transparently
+        let loaded = store.load_tokens("datadoghq.com", None).unwrap().unwrap();
+        assert_eq!(loaded.access_token, "legacy_tok");
+
+        // Named org not found in the old-format file
+        assert!(store
+            .load_tokens("datadoghq.com", Some("prod"))
+            .unwrap()
+            .is_none());
+
+        // The legacy file was migrated into place under the new scheme.
+        assert!(!path.exists());
+        assert!(tmp
+            .path()
+            .join(format!("tokens_{}.json", sanitize("datadoghq.com")))
+            .exists());
+    }
 
-    // --- helpers ------------------------------------------------------------
+    // --- Atomic writes & locking ----------------------------------------------
 
-    fn make_token(access: &str) -> TokenSet {
-        TokenSet {
-            access_token: access.to_string(),
-            refresh_token: "refresh".into(),
-            token_type: "Bearer".into(),
-            expires_in: 9_999_999_999, // far future — never expired
-            issued_at: 0,
-            scope: String::new(),
-            client_id: String::new(),
-        }
+    #[test]
+    fn test_atomic_write_leaves_no_temp_file() {
+        let tmp = TempDir::new("atomic_write");
+        let path = tmp.path().join("tokens_datadoghq_com.json");
+        atomic_write(&path, b"{}").unwrap();
+
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "{}");
+        assert!(!tmp.path().join("tokens_datadoghq_com.json.tmp").exists());
     }
 
-    /// Temporary directory that removes itself on drop.
-    struct TempDir(std::path::PathBuf);
+    #[test]
+    fn test_atomic_write_survives_a_crash_mid_write() {
+        let tmp = TempDir::new("atomic_write_crash");
+        let path = tmp.path().join("tokens_datadoghq_com.json");
+        atomic_write(&path, b"{\"__default__\":{}}").unwrap();
 
-    impl TempDir {
-        fn new(label: &str) -> Self {
-            let nanos = std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .map(|d| d.subsec_nanos())
-                .unwrap_or(0);
-            let dir = std::env::temp_dir().join(format!("pup_test_{}_{}", label, nanos));
-            std::fs::create_dir_all(&dir).unwrap();
-            TempDir(dir)
-        }
+        // Simulate a crash partway through a second write: the temp file is
+        // left behind, truncated/garbage, and never renamed into place.
+        let tmp_path = path.with_extension("json.tmp");
+        std::fs::write(&tmp_path, b"{\"__default__\":{\"access_t").unwrap();
 
-        fn path(&self) -> &std::path::PathBuf {
-            &self.0
-        }
+        // The committed file must still be the complete old version, never
+        // the partial one.
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "{\"__default__\":{}}"
+        );
+
+        // A subsequent successful write replaces it with the complete new
+        // version and leaves no temp file behind.
+        atomic_write(&path, b"{\"__default__\":{\"access_token\":\"next\"}}").unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "{\"__default__\":{\"access_token\":\"next\"}}"
+        );
+        assert!(!tmp_path.exists());
     }
 
-    impl Drop for TempDir {
-        fn drop(&mut self) {
-            let _ = std::fs::remove_dir_all(&self.0);
+    #[test]
+    fn test_file_storage_concurrent_writes_do_not_lose_updates() {
+        let tmp = TempDir::new("fs_concurrent");
+        let base_dir = tmp.path().clone();
+
+        let handles: Vec<_> = ["prod", "staging"]
+            .into_iter()
+            .map(|org| {
+                let base_dir = base_dir.clone();
+                std::thread::spawn(move || {
+                    let store = FileStorage { base_dir };
+                    store
+                        .save_tokens("datadoghq.com", Some(org), &make_token(org))
+                        .unwrap();
+                })
+            })
+            .collect();
+        for h in handles {
+            h.join().unwrap();
         }
+
+        let store = FileStorage {
+            base_dir: tmp.path().clone(),
+        };
+        assert_eq!(
+            store
+                .load_tokens("datadoghq.com", Some("prod"))
+                .unwrap()
+                .unwrap()
+                .access_token,
+            "prod"
+        );
+        assert_eq!(
+            store
+                .load_tokens("datadoghq.com", Some("staging"))
+                .unwrap()
+                .unwrap()
+                .access_token,
+            "staging"
+        );
     }
 
-    // --- org_map_key --------------------------------------------------------
+    // --- prune_expired --------------------------------------------------------
 
     #[test]
-    fn test_org_map_key_none() {
-        assert_eq!(org_map_key(None), DEFAULT_ORG_KEY);
+    fn test_is_prunable() {
+        assert!(is_prunable(&make_expired_token("stale"), 200_000));
+        assert!(!is_prunable(&make_token("fresh"), 200_000));
+        // Expired access token but a live refresh token survives.
+        let mut expired_with_refresh = make_expired_token("stale");
+        expired_with_refresh.refresh_token = "refresh".into();
+        assert!(!is_prunable(&expired_with_refresh, 200_000));
     }
 
     #[test]
-    fn test_org_map_key_empty_string() {
-        assert_eq!(org_map_key(Some("")), DEFAULT_ORG_KEY);
-    }
+    fn test_file_storage_prune_expired_removes_stale_entries_and_reconciles_sessions() {
+        let _lock = crate::test_utils::ENV_LOCK
+            .lock()
+            .unwrap_or_else(|p| p.into_inner());
+        let tmp = TempDir::new("fs_prune");
+        std::env::set_var("PUP_CONFIG_DIR", tmp.path());
 
-    #[test]
-    fn test_org_map_key_named() {
-        assert_eq!(org_map_key(Some("prod-child")), "prod-child");
+        let store = FileStorage {
+            base_dir: tmp.path().clone(),
+        };
+        store
+            .save_tokens("datadoghq.com", None, &make_expired_token("stale"))
+            .unwrap();
+        store
+            .save_tokens("datadoghq.com", Some("prod"), &make_token("fresh"))
+            .unwrap();
+        save_session("datadoghq.com", None).unwrap();
+        save_session("datadoghq.com", Some("prod")).unwrap();
+
+        let removed = store.prune_expired(200_000).unwrap();
+
+        assert_eq!(removed, 1);
+        assert!(store.load_tokens("datadoghq.com", None).unwrap().is_none());
+        assert_eq!(
+            store
+                .load_tokens("datadoghq.com", Some("prod"))
+                .unwrap()
+                .unwrap()
+                .access_token,
+            "fresh"
+        );
+
+        let sessions = list_sessions().unwrap();
+        std::env::remove_var("PUP_CONFIG_DIR");
+
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].org.as_deref(), Some("prod"));
     }
 
-    // --- parse_token_map ----------------------------------------------------
+    // --- EncryptedFileStorage -------------------------------------------------
 
     #[test]
-    fn test_parse_token_map_new_format() {
-        let map: OrgTokenMap = [(DEFAULT_ORG_KEY.to_string(), make_token("tok1"))]
-            .into_iter()
-            .collect();
-        let json = serde_json::to_string(&map).unwrap();
-        let parsed = parse_token_map(&json).unwrap();
-        assert_eq!(parsed[DEFAULT_ORG_KEY].access_token, "tok1");
+    fn test_encrypted_file_storage_round_trip() {
+        let tmp = TempDir::new("enc_round_trip");
+        let store = EncryptedFileStorage {
+            base_dir: tmp.path().clone(),
+            passphrase: "correct horse battery staple".to_string(),
+        };
+        store
+            .save_tokens("datadoghq.com", None, &make_token("secret_tok"))
+            .unwrap();
+        let loaded = store.load_tokens("datadoghq.com", None).unwrap().unwrap();
+        assert_eq!(loaded.access_token, "secret_tok");
     }
 
     #[test]
-    fn test_parse_token_map_multiple_orgs() {
-        let map: OrgTokenMap = [
-            (DEFAULT_ORG_KEY.to_string(), make_token("default_tok")),
-            ("prod".to_string(), make_token("prod_tok")),
-        ]
-        .into_iter()
-        .collect();
-        let json = serde_json::to_string(&map).unwrap();
-        let parsed = parse_token_map(&json).unwrap();
-        assert_eq!(parsed.len(), 2);
-        assert_eq!(parsed[DEFAULT_ORG_KEY].access_token, "default_tok");
-        assert_eq!(parsed["prod"].access_token, "prod_tok");
+    fn test_encrypted_file_storage_blob_is_not_plaintext() {
+        let tmp = TempDir::new("enc_not_plaintext");
+        let store = EncryptedFileStorage {
+            base_dir: tmp.path().clone(),
+            passphrase: "hunter2".to_string(),
+        };
+        store
+            .save_tokens("datadoghq.com", None, &make_token("secret_tok"))
+            .unwrap();
+        let path = tmp
+            .path()
+            .join(format!("tokens_{}.json", sanitize("datadoghq.com")));
+        let bytes = std::fs::read(&path).unwrap();
+        assert!(bytes.starts_with(ENCRYPTED_BLOB_MAGIC));
+        assert!(!String::from_utf8_lossy(&bytes).contains("secret_tok"));
     }
 
     #[test]
-    fn test_parse_token_map_legacy_migration() {
-        // Old format: bare TokenSet at the root (written by pup before multi-org)
-        let json = serde_json::to_string(&make_token("legacy_tok")).unwrap();
-        let parsed = parse_token_map(&json).unwrap();
-        assert_eq!(parsed.len(), 1);
-        assert_eq!(parsed[DEFAULT_ORG_KEY].access_token, "legacy_tok");
+    fn test_encrypted_file_storage_wrong_passphrase_fails_clearly() {
+        let tmp = TempDir::new("enc_wrong_pass");
+        let store = EncryptedFileStorage {
+            base_dir: tmp.path().clone(),
+            passphrase: "right-passphrase".to_string(),
+        };
+        store
+            .save_tokens("datadoghq.com", None, &make_token("secret_tok"))
+            .unwrap();
+
+        let other = EncryptedFileStorage {
+            base_dir: tmp.path().clone(),
+            passphrase: "wrong-passphrase".to_string(),
+        };
+        let err = other.load_tokens("datadoghq.com", None).unwrap_err();
+        assert!(err.to_string().contains("wrong passphrase or corrupted store"));
     }
 
     #[test]
-    fn test_parse_token_map_invalid_json() {
-        assert!(parse_token_map("not json at all").is_err());
-        assert!(parse_token_map("{\"bad\": true}").is_err());
+    fn test_encrypted_file_storage_legacy_plaintext_migration() {
+        let tmp = TempDir::new("enc_legacy");
+        let store = EncryptedFileStorage {
+            base_dir: tmp.path().clone(),
+            passphrase: "a-passphrase".to_string(),
+        };
+
+        // A pre-existing plaintext file (no magic header), written under the
+        // pre-collision-resistance sanitize() scheme by FileStorage.
+        let legacy_json = serde_json::to_string_pretty(&make_token("legacy_tok")).unwrap();
+        let path = tmp
+            .path()
+            .join(format!("tokens_{}.json", legacy_sanitize("datadoghq.com")));
+        std::fs::write(&path, legacy_json).unwrap();
+
+        let loaded = store.load_tokens("datadoghq.com", None).unwrap().unwrap();
+        assert_eq!(loaded.access_token, "legacy_tok");
+
+        // The legacy file was migrated into place under the new scheme.
+        assert!(!path.exists());
+
+        // The next save reseals it under the new-scheme path.
+        store
+            .save_tokens("datadoghq.com", None, &make_token("legacy_tok"))
+            .unwrap();
+        let new_path = tmp
+            .path()
+            .join(format!("tokens_{}.json", sanitize("datadoghq.com")));
+        let bytes = std::fs::read(&new_path).unwrap();
+        assert!(bytes.starts_with(ENCRYPTED_BLOB_MAGIC));
     }
 
-    // --- FileStorage — token map behaviour ----------------------------------
+    // --- AgeStorage -------------------------------------------------------------
 
-    #[test]
-    fn test_file_storage_save_load_default_org() {
-        let tmp = TempDir::new("fs_default");
-        let store = FileStorage {
+    fn open_age_storage_with_passphrase(tmp: &TempDir, passphrase: &str) -> AgeStorage {
+        AgeStorage {
             base_dir: tmp.path().clone(),
-        };
+            mode: AgeKeyMode::Passphrase(passphrase.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_age_storage_passphrase_round_trip() {
+        let tmp = TempDir::new("age_round_trip");
+        let store = open_age_storage_with_passphrase(&tmp, "correct horse battery staple");
         store
-            .save_tokens("datadoghq.com", None, &make_token("default_tok"))
+            .save_tokens("datadoghq.com", None, &make_token("secret_tok"))
             .unwrap();
         let loaded = store.load_tokens("datadoghq.com", None).unwrap().unwrap();
-        assert_eq!(loaded.access_token, "default_tok");
+        assert_eq!(loaded.access_token, "secret_tok");
     }
 
     #[test]
-    fn test_file_storage_save_load_named_org() {
-        let tmp = TempDir::new("fs_named");
-        let store = FileStorage {
+    fn test_age_storage_blob_is_not_plaintext() {
+        let tmp = TempDir::new("age_not_plaintext");
+        let store = open_age_storage_with_passphrase(&tmp, "hunter2");
+        store
+            .save_tokens("datadoghq.com", None, &make_token("secret_tok"))
+            .unwrap();
+        let path = tmp
+            .path()
+            .join(format!("tokens_{}.age", sanitize("datadoghq.com")));
+        let bytes = std::fs::read(&path).unwrap();
+        assert!(!String::from_utf8_lossy(&bytes).contains("secret_tok"));
+    }
+
+    #[test]
+    fn test_age_storage_wrong_passphrase_fails_clearly() {
+        let tmp = TempDir::new("age_wrong_pass");
+        let store = open_age_storage_with_passphrase(&tmp, "right-passphrase");
+        store
+            .save_tokens("datadoghq.com", None, &make_token("secret_tok"))
+            .unwrap();
+
+        let other = open_age_storage_with_passphrase(&tmp, "wrong-passphrase");
+        let err = other.load_tokens("datadoghq.com", None).unwrap_err();
+        assert!(err.to_string().contains("wrong passphrase or corrupted store"));
+    }
+
+    #[test]
+    fn test_age_storage_identity_mode_round_trip() {
+        let tmp = TempDir::new("age_identity");
+        let store = AgeStorage {
             base_dir: tmp.path().clone(),
+            mode: AgeKeyMode::Identity(age::x25519::Identity::generate()),
         };
         store
-            .save_tokens("datadoghq.com", Some("prod-child"), &make_token("prod_tok"))
+            .save_tokens("datadoghq.com", Some("prod"), &make_token("identity_tok"))
             .unwrap();
         let loaded = store
-            .load_tokens("datadoghq.com", Some("prod-child"))
+            .load_tokens("datadoghq.com", Some("prod"))
             .unwrap()
             .unwrap();
-        assert_eq!(loaded.access_token, "prod_tok");
+        assert_eq!(loaded.access_token, "identity_tok");
     }
 
     #[test]
-    fn test_file_storage_multiple_orgs_one_file() {
-        let tmp = TempDir::new("fs_multi");
-        let store = FileStorage {
-            base_dir: tmp.path().clone(),
-        };
+    fn test_age_storage_legacy_plaintext_migration() {
+        let tmp = TempDir::new("age_legacy");
+        let store = open_age_storage_with_passphrase(&tmp, "a-passphrase");
+
+        // A pre-existing plaintext file written by FileStorage.
+        let legacy_json = serde_json::to_string_pretty(&make_token("legacy_tok")).unwrap();
+        let path = tmp
+            .path()
+            .join(format!("tokens_{}.json", sanitize("datadoghq.com")));
+        std::fs::write(&path, legacy_json).unwrap();
+
+        let loaded = store.load_tokens("datadoghq.com", None).unwrap().unwrap();
+        assert_eq!(loaded.access_token, "legacy_tok");
 
+        // The next save encrypts it and drops the stale plaintext copy.
         store
-            .save_tokens("datadoghq.com", None, &make_token("default_tok"))
+            .save_tokens("datadoghq.com", None, &make_token("legacy_tok"))
             .unwrap();
+        assert!(!path.exists());
+        let age_path = tmp
+            .path()
+            .join(format!("tokens_{}.age", sanitize("datadoghq.com")));
+        assert!(age_path.exists());
+    }
+
+    // --- SqliteStorage --------------------------------------------------------
+
+    fn open_sqlite_storage(tmp: &TempDir) -> SqliteStorage {
+        let conn = rusqlite::Connection::open(tmp.path().join("pup.sqlite")).unwrap();
+        conn.pragma_update(None, "journal_mode", "WAL").unwrap();
+        conn.execute_batch(SQLITE_SCHEMA).unwrap();
+        SqliteStorage {
+            conn: Mutex::new(conn),
+            path: tmp.path().join("pup.sqlite"),
+        }
+    }
+
+    #[test]
+    fn test_sqlite_storage_save_load_tokens() {
+        let tmp = TempDir::new("sqlite_tokens");
+        let store = open_sqlite_storage(&tmp);
         store
-            .save_tokens("datadoghq.com", Some("prod"), &make_token("prod_tok"))
+            .save_tokens("datadoghq.com", None, &make_token("default_tok"))
             .unwrap();
         store
-            .save_tokens("datadoghq.com", Some("staging"), &make_token("staging_tok"))
+            .save_tokens("datadoghq.com", Some("prod"), &make_token("prod_tok"))
             .unwrap();
 
-        // Only one file on disk for this site
-        let files: Vec<_> = std::fs::read_dir(tmp.path())
-            .unwrap()
-            .filter_map(|e| e.ok())
-            .collect();
-        assert_eq!(files.len(), 1);
-
-        // All three orgs load independently
         assert_eq!(
             store
                 .load_tokens("datadoghq.com", None)
@@ -764,114 +2679,148 @@ mod tests {
                 .access_token,
             "prod_tok"
         );
+    }
+
+    #[test]
+    fn test_sqlite_storage_save_overwrites_existing_row() {
+        let tmp = TempDir::new("sqlite_overwrite");
+        let store = open_sqlite_storage(&tmp);
+        store
+            .save_tokens("datadoghq.com", None, &make_token("first"))
+            .unwrap();
+        store
+            .save_tokens("datadoghq.com", None, &make_token("second"))
+            .unwrap();
         assert_eq!(
             store
-                .load_tokens("datadoghq.com", Some("staging"))
+                .load_tokens("datadoghq.com", None)
                 .unwrap()
                 .unwrap()
                 .access_token,
-            "staging_tok"
+            "second"
         );
     }
 
     #[test]
-    fn test_file_storage_org_isolation() {
-        // Loading a different org must not return another org's token
-        let tmp = TempDir::new("fs_isolation");
-        let store = FileStorage {
-            base_dir: tmp.path().clone(),
+    fn test_sqlite_storage_delete_tokens() {
+        let tmp = TempDir::new("sqlite_delete");
+        let store = open_sqlite_storage(&tmp);
+        store
+            .save_tokens("datadoghq.com", None, &make_token("tok"))
+            .unwrap();
+        store.delete_tokens("datadoghq.com", None).unwrap();
+        assert!(store.load_tokens("datadoghq.com", None).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_sqlite_storage_client_credentials_round_trip() {
+        let tmp = TempDir::new("sqlite_creds");
+        let store = open_sqlite_storage(&tmp);
+        let creds = ClientCredentials {
+            client_id: "abc123".to_string(),
+            client_name: "pup CLI".to_string(),
+            redirect_uris: vec!["http://127.0.0.1:0/callback".to_string()],
+            registered_at: 0,
+            site: "datadoghq.com".to_string(),
         };
+        store.save_client_credentials("datadoghq.com", &creds).unwrap();
+        let loaded = store
+            .load_client_credentials("datadoghq.com")
+            .unwrap()
+            .unwrap();
+        assert_eq!(loaded.client_id, "abc123");
+
+        store.delete_client_credentials("datadoghq.com").unwrap();
+        assert!(store
+            .load_client_credentials("datadoghq.com")
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_sqlite_sessions_save_list_remove() {
+        let tmp = TempDir::new("sqlite_sessions");
+        let path = tmp.path().join("pup.sqlite");
+
+        sqlite_save_session(&path, "datadoghq.com", None).unwrap();
+        sqlite_save_session(&path, "datadoghq.com", Some("prod")).unwrap();
+        sqlite_save_session(&path, "datadoghq.com", Some("prod")).unwrap(); // dedup via PK
+
+        let sessions = sqlite_list_sessions(&path).unwrap();
+        assert_eq!(sessions.len(), 2);
+
+        sqlite_remove_session(&path, "datadoghq.com", Some("prod")).unwrap();
+        let sessions = sqlite_list_sessions(&path).unwrap();
+        assert_eq!(sessions.len(), 1);
+        assert!(sessions[0].org.is_none());
+    }
+
+    #[test]
+    fn test_sqlite_find_sessions_exact_then_substring() {
+        let tmp = TempDir::new("sqlite_find_sessions");
+        let path = tmp.path().join("pup.sqlite");
 
-        store
-            .save_tokens("datadoghq.com", Some("prod"), &make_token("prod_tok"))
-            .unwrap();
-        assert!(store.load_tokens("datadoghq.com", None).unwrap().is_none());
-        assert!(store
-            .load_tokens("datadoghq.com", Some("staging"))
-            .unwrap()
-            .is_none());
+        sqlite_save_session(&path, "datadoghq.com", Some("prod-child")).unwrap();
+        sqlite_save_session(&path, "datadoghq.eu", Some("staging-child")).unwrap();
+
+        let exact = sqlite_find_sessions(&path, "prod-child").unwrap();
+        assert_eq!(exact.len(), 1);
+        assert_eq!(exact[0].site, "datadoghq.com");
+
+        let substring = sqlite_find_sessions(&path, "prod").unwrap();
+        assert_eq!(substring.len(), 1);
+        assert_eq!(substring[0].org.as_deref(), Some("prod-child"));
+
+        let by_site = sqlite_find_sessions(&path, "datadoghq.eu").unwrap();
+        assert_eq!(by_site.len(), 1);
+        assert_eq!(by_site[0].org.as_deref(), Some("staging-child"));
+
+        assert!(sqlite_find_sessions(&path, "nonexistent").unwrap().is_empty());
     }
 
     #[test]
-    fn test_file_storage_delete_last_org_removes_file() {
-        let tmp = TempDir::new("fs_del_last");
-        let store = FileStorage {
-            base_dir: tmp.path().clone(),
-        };
+    fn test_sqlite_find_sessions_escapes_like_metacharacters() {
+        let tmp = TempDir::new("sqlite_find_sessions_like");
+        let path = tmp.path().join("pup.sqlite");
 
-        store
-            .save_tokens("datadoghq.com", None, &make_token("tok"))
-            .unwrap();
-        store.delete_tokens("datadoghq.com", None).unwrap();
+        sqlite_save_session(&path, "datadoghq.com", Some("prod_child")).unwrap();
+        sqlite_save_session(&path, "datadoghq.com", Some("prodXchild")).unwrap();
 
-        let file_path = tmp.path().join("tokens_datadoghq_com.json");
-        assert!(
-            !file_path.exists(),
-            "file should be removed when last org is deleted"
-        );
+        // "_" in the query must match a literal underscore, not "any character".
+        let found = sqlite_find_sessions(&path, "prod_child").unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].org.as_deref(), Some("prod_child"));
     }
 
     #[test]
-    fn test_file_storage_delete_one_org_keeps_others() {
-        let tmp = TempDir::new("fs_del_one");
-        let store = FileStorage {
-            base_dir: tmp.path().clone(),
-        };
+    fn test_sqlite_storage_prune_expired_removes_stale_rows() {
+        let _lock = crate::test_utils::ENV_LOCK
+            .lock()
+            .unwrap_or_else(|p| p.into_inner());
+        let tmp = TempDir::new("sqlite_prune");
+        std::env::set_var("PUP_CONFIG_DIR", tmp.path());
 
+        let store = open_sqlite_storage(&tmp);
         store
-            .save_tokens("datadoghq.com", None, &make_token("default_tok"))
+            .save_tokens("datadoghq.com", None, &make_expired_token("stale"))
             .unwrap();
         store
-            .save_tokens("datadoghq.com", Some("prod"), &make_token("prod_tok"))
+            .save_tokens("datadoghq.com", Some("prod"), &make_token("fresh"))
             .unwrap();
-        store.delete_tokens("datadoghq.com", Some("prod")).unwrap();
 
-        // Default session survives
+        let removed = store.prune_expired(200_000).unwrap();
+        std::env::remove_var("PUP_CONFIG_DIR");
+
+        assert_eq!(removed, 1);
+        assert!(store.load_tokens("datadoghq.com", None).unwrap().is_none());
         assert_eq!(
             store
-                .load_tokens("datadoghq.com", None)
+                .load_tokens("datadoghq.com", Some("prod"))
                 .unwrap()
                 .unwrap()
                 .access_token,
-            "default_tok"
+            "fresh"
         );
-        // Deleted org is gone
-        assert!(store
-            .load_tokens("datadoghq.com", Some("prod"))
-            .unwrap()
-            .is_none());
-    }
-
-    #[test]
-    fn test_file_storage_delete_nonexistent_is_ok() {
-        let tmp = TempDir::new("fs_del_none");
-        let store = FileStorage {
-            base_dir: tmp.path().clone(),
-        };
-        assert!(store.delete_tokens("datadoghq.com", None).is_ok());
-    }
-
-    #[test]
-    fn test_file_storage_legacy_migration() {
-        let tmp = TempDir::new("fs_legacy");
-        let store = FileStorage {
-            base_dir: tmp.path().clone(),
-        };
-
-        // Write old-format file: bare TokenSet, no map wrapper
-        let legacy_json = serde_json::to_string_pretty(&make_token("legacy_tok")).unwrap();
-        let path = tmp.path().join("tokens_datadoghq_com.json");
-        std::fs::write(&path, legacy_json).unwrap();
-
-        // Existing default session loads transparently
-        let loaded = store.load_tokens("datadoghq.com", None).unwrap().unwrap();
-        assert_eq!(loaded.access_token, "legacy_tok");
-
-        // Named org not found in the old-format file
-        assert!(store
-            .load_tokens("datadoghq.com", Some("prod"))
-            .unwrap()
-            .is_none());
     }
 
     // --- Session registry ---------------------------------------------------
@@ -944,6 +2893,28 @@ mod tests {
         assert!(sessions[0].org.is_none());
     }
 
+    #[test]
+    fn test_session_registry_survives_a_crash_mid_write() {
+        let _lock = crate::test_utils::ENV_LOCK
+            .lock()
+            .unwrap_or_else(|p| p.into_inner());
+        let tmp = TempDir::new("sess_crash");
+        std::env::set_var("PUP_CONFIG_DIR", tmp.path());
+
+        save_session("datadoghq.com", None).unwrap();
+        let path = sessions_path().unwrap();
+
+        // Simulate a crash partway through a second write: a truncated temp
+        // file is left behind, never renamed over the committed registry.
+        std::fs::write(path.with_extension("json.tmp"), b"[{\"site\":\"trunc").unwrap();
+
+        let sessions = list_sessions().unwrap();
+        std::env::remove_var("PUP_CONFIG_DIR");
+
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].site, "datadoghq.com");
+    }
+
     #[test]
     fn test_session_registry_remove_nonexistent() {
         let _lock = crate::test_utils::ENV_LOCK
@@ -955,4 +2926,257 @@ mod tests {
         std::env::remove_var("PUP_CONFIG_DIR");
         assert!(result.is_ok());
     }
+
+    // --- find_sessions / by-org index ----------------------------------------
+
+    #[test]
+    fn test_find_sessions_exact_org_match_via_index() {
+        let _lock = crate::test_utils::ENV_LOCK
+            .lock()
+            .unwrap_or_else(|p| p.into_inner());
+        let tmp = TempDir::new("find_exact");
+        std::env::set_var("PUP_CONFIG_DIR", tmp.path());
+
+        save_session("datadoghq.com", None).unwrap();
+        save_session("datadoghq.com", Some("prod-child")).unwrap();
+        let index_path = sessions_index_path().unwrap();
+        let found = find_sessions("prod-child").unwrap();
+        std::env::remove_var("PUP_CONFIG_DIR");
+
+        assert!(index_path.exists());
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].site, "datadoghq.com");
+        assert_eq!(found[0].org.as_deref(), Some("prod-child"));
+    }
+
+    #[test]
+    fn test_find_sessions_falls_back_to_substring_scan() {
+        let _lock = crate::test_utils::ENV_LOCK
+            .lock()
+            .unwrap_or_else(|p| p.into_inner());
+        let tmp = TempDir::new("find_substring");
+        std::env::set_var("PUP_CONFIG_DIR", tmp.path());
+
+        save_session("datadoghq.com", Some("prod-child")).unwrap();
+        save_session("datadoghq.eu", Some("staging-child")).unwrap();
+
+        let by_org_substring = find_sessions("prod").unwrap();
+        let by_site_substring = find_sessions("datadoghq.eu").unwrap();
+        std::env::remove_var("PUP_CONFIG_DIR");
+
+        assert_eq!(by_org_substring.len(), 1);
+        assert_eq!(by_org_substring[0].org.as_deref(), Some("prod-child"));
+        assert_eq!(by_site_substring.len(), 1);
+        assert_eq!(by_site_substring[0].site, "datadoghq.eu");
+    }
+
+    #[test]
+    fn test_find_sessions_no_match() {
+        let _lock = crate::test_utils::ENV_LOCK
+            .lock()
+            .unwrap_or_else(|p| p.into_inner());
+        let tmp = TempDir::new("find_none");
+        std::env::set_var("PUP_CONFIG_DIR", tmp.path());
+
+        save_session("datadoghq.com", Some("prod")).unwrap();
+        let found = find_sessions("nonexistent-org").unwrap();
+        std::env::remove_var("PUP_CONFIG_DIR");
+
+        assert!(found.is_empty());
+    }
+
+    #[test]
+    fn test_find_sessions_rebuilds_a_missing_index() {
+        let _lock = crate::test_utils::ENV_LOCK
+            .lock()
+            .unwrap_or_else(|p| p.into_inner());
+        let tmp = TempDir::new("find_rebuild");
+        std::env::set_var("PUP_CONFIG_DIR", tmp.path());
+
+        save_session("datadoghq.com", Some("prod")).unwrap();
+        let index_path = sessions_index_path().unwrap();
+        std::fs::remove_file(&index_path).unwrap();
+
+        let found = find_sessions("prod").unwrap();
+        let rebuilt = index_path.exists();
+        std::env::remove_var("PUP_CONFIG_DIR");
+
+        assert_eq!(found.len(), 1);
+        assert!(rebuilt, "a missing index should be rebuilt on lookup");
+    }
+
+    #[test]
+    fn test_find_sessions_index_is_dropped_alongside_a_removed_session() {
+        let _lock = crate::test_utils::ENV_LOCK
+            .lock()
+            .unwrap_or_else(|p| p.into_inner());
+        let tmp = TempDir::new("find_removed");
+        std::env::set_var("PUP_CONFIG_DIR", tmp.path());
+
+        save_session("datadoghq.com", Some("prod")).unwrap();
+        remove_session("datadoghq.com", Some("prod")).unwrap();
+        let found = find_sessions("prod").unwrap();
+        std::env::remove_var("PUP_CONFIG_DIR");
+
+        assert!(found.is_empty());
+    }
+
+    // --- export_all / import_all ----------------------------------------------
+
+    fn make_creds(site: &str, client_id: &str) -> ClientCredentials {
+        ClientCredentials {
+            client_id: client_id.to_string(),
+            client_name: "pup CLI".to_string(),
+            redirect_uris: vec!["http://127.0.0.1:0/callback".to_string()],
+            registered_at: 0,
+            site: site.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_export_import_round_trip() {
+        let _lock = crate::test_utils::ENV_LOCK
+            .lock()
+            .unwrap_or_else(|p| p.into_inner());
+        let tmp = TempDir::new("export_roundtrip");
+        std::env::set_var("PUP_CONFIG_DIR", tmp.path());
+
+        let store = FileStorage {
+            base_dir: tmp.path().clone(),
+        };
+        store
+            .save_tokens("datadoghq.com", None, &make_token("default_tok"))
+            .unwrap();
+        store
+            .save_tokens("datadoghq.com", Some("prod"), &make_token("prod_tok"))
+            .unwrap();
+        store
+            .save_client_credentials("datadoghq.com", &make_creds("datadoghq.com", "abc123"))
+            .unwrap();
+        save_session("datadoghq.com", None).unwrap();
+        save_session("datadoghq.com", Some("prod")).unwrap();
+
+        let blob = export_all(&store, "hunter2").unwrap();
+        assert!(blob.starts_with(ENCRYPTED_BLOB_MAGIC));
+
+        let other_dir = TempDir::new("export_roundtrip_target");
+        let other_store = FileStorage {
+            base_dir: other_dir.path().clone(),
+        };
+        import_all(&other_store, &blob, "hunter2").unwrap();
+
+        std::env::remove_var("PUP_CONFIG_DIR");
+
+        assert_eq!(
+            other_store
+                .load_tokens("datadoghq.com", None)
+                .unwrap()
+                .unwrap()
+                .access_token,
+            "default_tok"
+        );
+        assert_eq!(
+            other_store
+                .load_tokens("datadoghq.com", Some("prod"))
+                .unwrap()
+                .unwrap()
+                .access_token,
+            "prod_tok"
+        );
+        assert_eq!(
+            other_store
+                .load_client_credentials("datadoghq.com")
+                .unwrap()
+                .unwrap()
+                .client_id,
+            "abc123"
+        );
+    }
+
+    #[test]
+    fn test_import_all_merges_instead_of_clobbering() {
+        let _lock = crate::test_utils::ENV_LOCK
+            .lock()
+            .unwrap_or_else(|p| p.into_inner());
+        let tmp = TempDir::new("export_merge_source");
+        std::env::set_var("PUP_CONFIG_DIR", tmp.path());
+        let source = FileStorage {
+            base_dir: tmp.path().clone(),
+        };
+        source
+            .save_tokens("datadoghq.com", None, &make_token("from_archive"))
+            .unwrap();
+        save_session("datadoghq.com", None).unwrap();
+        let blob = export_all(&source, "hunter2").unwrap();
+        std::env::remove_var("PUP_CONFIG_DIR");
+
+        let target_dir = TempDir::new("export_merge_target");
+        let target = FileStorage {
+            base_dir: target_dir.path().clone(),
+        };
+        target
+            .save_tokens("eu.datadoghq.com", None, &make_token("pre_existing"))
+            .unwrap();
+
+        std::env::set_var("PUP_CONFIG_DIR", target_dir.path());
+        import_all(&target, &blob, "hunter2").unwrap();
+        std::env::remove_var("PUP_CONFIG_DIR");
+
+        assert_eq!(
+            target
+                .load_tokens("datadoghq.com", None)
+                .unwrap()
+                .unwrap()
+                .access_token,
+            "from_archive"
+        );
+        // Pre-existing entry not present in the archive survives the import.
+        assert_eq!(
+            target
+                .load_tokens("eu.datadoghq.com", None)
+                .unwrap()
+                .unwrap()
+                .access_token,
+            "pre_existing"
+        );
+    }
+
+    #[test]
+    fn test_import_all_rejects_wrong_passphrase() {
+        let _lock = crate::test_utils::ENV_LOCK
+            .lock()
+            .unwrap_or_else(|p| p.into_inner());
+        let tmp = TempDir::new("export_wrong_pass");
+        std::env::set_var("PUP_CONFIG_DIR", tmp.path());
+        let store = FileStorage {
+            base_dir: tmp.path().clone(),
+        };
+        store
+            .save_tokens("datadoghq.com", None, &make_token("secret"))
+            .unwrap();
+        save_session("datadoghq.com", None).unwrap();
+        let blob = export_all(&store, "right-passphrase").unwrap();
+        std::env::remove_var("PUP_CONFIG_DIR");
+
+        let err = import_all(&store, &blob, "wrong-passphrase").unwrap_err();
+        assert!(err.to_string().contains("wrong passphrase or corrupted store"));
+    }
+
+    #[test]
+    fn test_import_all_rejects_future_archive_version() {
+        let tmp = TempDir::new("export_bad_version");
+        let store = FileStorage {
+            base_dir: tmp.path().clone(),
+        };
+        let archive = ExportArchive {
+            version: EXPORT_ARCHIVE_VERSION + 1,
+            tokens: std::collections::HashMap::new(),
+            client_credentials: std::collections::HashMap::new(),
+            sessions: vec![],
+        };
+        let blob = seal("hunter2", serde_json::to_string(&archive).unwrap().as_bytes()).unwrap();
+
+        let err = import_all(&store, &blob, "hunter2").unwrap_err();
+        assert!(err.to_string().contains("unsupported export archive version"));
+    }
 }
@@ -0,0 +1,446 @@
+//! Background unlock agent (modeled on kbs2's `Agent::spawn`): holds the
+//! passphrase for an encrypted storage backend in memory for a configurable
+//! idle TTL, so `pup` only prompts once per session instead of on every
+//! invocation. The agent is a detached process listening on a Unix domain
+//! socket under the config dir; it never touches disk itself and zeroes its
+//! cached passphrases on TTL expiry or shutdown.
+
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use zeroize::Zeroize;
+
+const AGENT_SOCKET_NAME: &str = "agent.sock";
+
+/// Hidden argument the CLI re-execs itself with to become the agent process;
+/// followed by the idle TTL in seconds.
+pub const AGENT_RUN_ARG: &str = "__agent-run";
+
+fn agent_socket_path() -> Result<PathBuf> {
+    let dir = crate::config::config_dir().context("could not determine config directory")?;
+    Ok(dir.join(AGENT_SOCKET_NAME))
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(tag = "op")]
+enum AgentRequest {
+    Unlock { site: String, passphrase: String },
+    Fetch { site: String },
+    ListUnlocked,
+    Flush,
+    Quit,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(tag = "status")]
+enum AgentResponse {
+    Ok,
+    Passphrase { passphrase: String },
+    Locked,
+    Sites { sites: Vec<String> },
+    Error { message: String },
+}
+
+fn send_request(request: &AgentRequest) -> Result<AgentResponse> {
+    let socket_path = agent_socket_path()?;
+    let stream = UnixStream::connect(&socket_path)
+        .with_context(|| format!("failed to connect to agent socket: {}", socket_path.display()))?;
+    let mut writer = stream.try_clone()?;
+    writer.write_all(serde_json::to_string(request)?.as_bytes())?;
+    writer.write_all(b"\n")?;
+
+    let mut response_line = String::new();
+    BufReader::new(stream).read_line(&mut response_line)?;
+    serde_json::from_str(&response_line).context("malformed agent response")
+}
+
+/// True if the agent is reachable over its Unix domain socket.
+fn is_running() -> bool {
+    agent_socket_path()
+        .map(|p| UnixStream::connect(p).is_ok())
+        .unwrap_or(false)
+}
+
+/// Spawn the agent as a detached background process if it isn't already
+/// running. Re-execs the current binary with the hidden [`AGENT_RUN_ARG`],
+/// which the CLI's entry point dispatches to [`run_agent_loop`].
+pub fn spawn_if_needed(idle_ttl: Duration) -> Result<()> {
+    if is_running() {
+        return Ok(());
+    }
+    let exe = std::env::current_exe().context("could not determine current executable")?;
+    std::process::Command::new(exe)
+        .arg(AGENT_RUN_ARG)
+        .arg(idle_ttl.as_secs().to_string())
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .context("failed to spawn unlock agent")?;
+
+    // Give the freshly spawned agent a moment to bind its socket.
+    for _ in 0..20 {
+        if is_running() {
+            break;
+        }
+        std::thread::sleep(Duration::from_millis(25));
+    }
+    Ok(())
+}
+
+/// Return the cached passphrase for `site` from a running agent, spawning it
+/// first if `autostart` is set. Falls back to calling `prompt` (and caching
+/// the result with the agent, if one is running) when nothing is cached yet.
+pub fn ensure_passphrase(
+    site: &str,
+    autostart: bool,
+    idle_ttl: Duration,
+    prompt: impl FnOnce() -> Result<String>,
+) -> Result<String> {
+    if autostart {
+        spawn_if_needed(idle_ttl)?;
+    }
+
+    if is_running() {
+        if let AgentResponse::Passphrase { passphrase } =
+            send_request(&AgentRequest::Fetch { site: site.to_string() })?
+        {
+            return Ok(passphrase);
+        }
+    }
+
+    let passphrase = prompt()?;
+
+    if is_running() {
+        let _ = send_request(&AgentRequest::Unlock {
+            site: site.to_string(),
+            passphrase: passphrase.clone(),
+        });
+    }
+
+    Ok(passphrase)
+}
+
+/// Explicitly drop every cached passphrase from a running agent. A no-op if
+/// no agent is running.
+pub fn flush() -> Result<()> {
+    if !is_running() {
+        return Ok(());
+    }
+    send_request(&AgentRequest::Flush)?;
+    Ok(())
+}
+
+/// Ask a running agent to zero its cached passphrases and shut down. A no-op
+/// if no agent is running.
+pub fn quit() -> Result<()> {
+    if !is_running() {
+        return Ok(());
+    }
+    send_request(&AgentRequest::Quit)?;
+    Ok(())
+}
+
+/// List the sites currently unlocked in a running agent. Empty if no agent
+/// is running.
+pub fn list_unlocked() -> Result<Vec<String>> {
+    if !is_running() {
+        return Ok(vec![]);
+    }
+    match send_request(&AgentRequest::ListUnlocked)? {
+        AgentResponse::Sites { sites } => Ok(sites),
+        _ => Ok(vec![]),
+    }
+}
+
+/// A cached passphrase, zeroed in place when dropped (TTL expiry, `Flush`,
+/// `Quit`, or agent shutdown).
+struct UnlockedEntry {
+    passphrase: String,
+    unlocked_at: Instant,
+}
+
+impl Drop for UnlockedEntry {
+    fn drop(&mut self) {
+        self.passphrase.zeroize();
+    }
+}
+
+/// Entry point for the detached agent process, invoked with
+/// `[AGENT_RUN_ARG] <idle_ttl_secs>`. Listens on the Unix domain socket,
+/// keeps cached passphrases in memory for `idle_ttl`, and zeroes them on TTL
+/// expiry, an explicit flush, or shutdown.
+pub fn run_agent_loop(idle_ttl: Duration) -> Result<()> {
+    let socket_path = agent_socket_path()?;
+    let _ = std::fs::remove_file(&socket_path); // clear a stale socket from a crashed agent
+    let listener = UnixListener::bind(&socket_path)
+        .with_context(|| format!("failed to bind agent socket: {}", socket_path.display()))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&socket_path, std::fs::Permissions::from_mode(0o600))?;
+    }
+    listener
+        .set_nonblocking(true)
+        .context("failed to set agent socket non-blocking")?;
+
+    let mut sites: HashMap<String, UnlockedEntry> = HashMap::new();
+
+    loop {
+        sites.retain(|_, entry| entry.unlocked_at.elapsed() < idle_ttl);
+
+        match listener.accept() {
+            Ok((stream, _)) => {
+                if !handle_connection(stream, &mut sites)? {
+                    break;
+                }
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                std::thread::sleep(Duration::from_millis(200));
+            }
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    sites.clear();
+    let _ = std::fs::remove_file(&socket_path);
+    Ok(())
+}
+
+/// Handle one client connection. Returns `false` if the agent should shut down.
+fn handle_connection(stream: UnixStream, sites: &mut HashMap<String, UnlockedEntry>) -> Result<bool> {
+    let mut line = String::new();
+    BufReader::new(stream.try_clone()?).read_line(&mut line)?;
+
+    let request: AgentRequest = match serde_json::from_str(&line) {
+        Ok(r) => r,
+        Err(e) => {
+            respond(
+                &stream,
+                &AgentResponse::Error {
+                    message: format!("malformed request: {e}"),
+                },
+            )?;
+            return Ok(true);
+        }
+    };
+
+    let (response, keep_running) = match request {
+        AgentRequest::Unlock { site, passphrase } => {
+            sites.insert(
+                site,
+                UnlockedEntry {
+                    passphrase,
+                    unlocked_at: Instant::now(),
+                },
+            );
+            (AgentResponse::Ok, true)
+        }
+        AgentRequest::Fetch { site } => match sites.get(&site) {
+            Some(entry) => (
+                AgentResponse::Passphrase {
+                    passphrase: entry.passphrase.clone(),
+                },
+                true,
+            ),
+            None => (AgentResponse::Locked, true),
+        },
+        AgentRequest::ListUnlocked => (
+            AgentResponse::Sites {
+                sites: sites.keys().cloned().collect(),
+            },
+            true,
+        ),
+        AgentRequest::Flush => {
+            sites.clear();
+            (AgentResponse::Ok, true)
+        }
+        AgentRequest::Quit => {
+            sites.clear();
+            (AgentResponse::Ok, false)
+        }
+    };
+
+    respond(&stream, &response)?;
+    Ok(keep_running)
+}
+
+fn respond(stream: &UnixStream, response: &AgentResponse) -> Result<()> {
+    let mut stream = stream.try_clone()?;
+    stream.write_all(serde_json::to_string(response)?.as_bytes())?;
+    stream.write_all(b"\n")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(label: &str) -> Self {
+            let nanos = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.subsec_nanos())
+                .unwrap_or(0);
+            let dir = std::env::temp_dir().join(format!("pup_test_agent_{}_{}", label, nanos));
+            std::fs::create_dir_all(&dir).unwrap();
+            TempDir(dir)
+        }
+
+        fn path(&self) -> &PathBuf {
+            &self.0
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    /// Spawn `run_agent_loop` on a background thread against a fresh config
+    /// dir, waiting for the socket to come up before returning.
+    fn spawn_test_agent(tmp: &TempDir, idle_ttl: Duration) -> std::thread::JoinHandle<Result<()>> {
+        std::env::set_var("PUP_CONFIG_DIR", tmp.path());
+        let handle = std::thread::spawn(move || run_agent_loop(idle_ttl));
+        for _ in 0..40 {
+            if is_running() {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(25));
+        }
+        assert!(is_running(), "test agent did not come up in time");
+        handle
+    }
+
+    #[test]
+    fn test_agent_request_response_serde_round_trip() {
+        let requests = vec![
+            AgentRequest::Unlock {
+                site: "datadoghq.com".to_string(),
+                passphrase: "hunter2".to_string(),
+            },
+            AgentRequest::Fetch { site: "datadoghq.com".to_string() },
+            AgentRequest::ListUnlocked,
+            AgentRequest::Flush,
+            AgentRequest::Quit,
+        ];
+        for request in requests {
+            let json = serde_json::to_string(&request).unwrap();
+            let round_tripped: AgentRequest = serde_json::from_str(&json).unwrap();
+            // Re-serialize rather than compare (no PartialEq on the enum) — a
+            // successful round trip produces identical JSON.
+            assert_eq!(json, serde_json::to_string(&round_tripped).unwrap());
+        }
+
+        let responses = vec![
+            AgentResponse::Ok,
+            AgentResponse::Passphrase { passphrase: "hunter2".to_string() },
+            AgentResponse::Locked,
+            AgentResponse::Sites { sites: vec!["datadoghq.com".to_string()] },
+            AgentResponse::Error { message: "boom".to_string() },
+        ];
+        for response in responses {
+            let json = serde_json::to_string(&response).unwrap();
+            let round_tripped: AgentResponse = serde_json::from_str(&json).unwrap();
+            assert_eq!(json, serde_json::to_string(&round_tripped).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_ensure_passphrase_falls_back_to_prompt_when_no_agent_running() {
+        let _lock = crate::test_utils::ENV_LOCK
+            .lock()
+            .unwrap_or_else(|p| p.into_inner());
+        let tmp = TempDir::new("no_agent");
+        std::env::set_var("PUP_CONFIG_DIR", tmp.path());
+        assert!(!is_running());
+
+        let passphrase = ensure_passphrase("storage", false, Duration::from_secs(60), || {
+            Ok("from-prompt".to_string())
+        })
+        .unwrap();
+        assert_eq!(passphrase, "from-prompt");
+    }
+
+    #[test]
+    fn test_flush_quit_and_list_unlocked_are_noops_without_an_agent() {
+        let _lock = crate::test_utils::ENV_LOCK
+            .lock()
+            .unwrap_or_else(|p| p.into_inner());
+        let tmp = TempDir::new("no_agent_noops");
+        std::env::set_var("PUP_CONFIG_DIR", tmp.path());
+        assert!(!is_running());
+
+        assert!(list_unlocked().unwrap().is_empty());
+        flush().unwrap();
+        quit().unwrap();
+    }
+
+    #[test]
+    fn test_live_agent_unlock_fetch_list_and_quit() {
+        let _lock = crate::test_utils::ENV_LOCK
+            .lock()
+            .unwrap_or_else(|p| p.into_inner());
+        let tmp = TempDir::new("live");
+        let handle = spawn_test_agent(&tmp, Duration::from_secs(60));
+
+        // Unknown site starts out locked.
+        match send_request(&AgentRequest::Fetch { site: "datadoghq.com".to_string() }).unwrap() {
+            AgentResponse::Locked => {}
+            other => panic!("expected Locked, got a different response: {}", serde_json::to_string(&other).unwrap()),
+        }
+
+        let cached = ensure_passphrase("datadoghq.com", false, Duration::from_secs(60), || {
+            Ok("hunter2".to_string())
+        })
+        .unwrap();
+        assert_eq!(cached, "hunter2");
+
+        // A second call finds it already cached and never reaches the prompt.
+        let refetched = ensure_passphrase("datadoghq.com", false, Duration::from_secs(60), || {
+            panic!("prompt should not be called once the agent has cached the passphrase")
+        })
+        .unwrap();
+        assert_eq!(refetched, "hunter2");
+
+        assert_eq!(list_unlocked().unwrap(), vec!["datadoghq.com".to_string()]);
+
+        flush().unwrap();
+        assert!(list_unlocked().unwrap().is_empty());
+
+        quit().unwrap();
+        handle.join().unwrap().unwrap();
+        assert!(!is_running());
+    }
+
+    #[test]
+    fn test_live_agent_ttl_expiry_locks_site() {
+        let _lock = crate::test_utils::ENV_LOCK
+            .lock()
+            .unwrap_or_else(|p| p.into_inner());
+        let tmp = TempDir::new("ttl");
+        let handle = spawn_test_agent(&tmp, Duration::from_millis(100));
+
+        send_request(&AgentRequest::Unlock {
+            site: "datadoghq.com".to_string(),
+            passphrase: "hunter2".to_string(),
+        })
+        .unwrap();
+
+        std::thread::sleep(Duration::from_millis(500));
+
+        match send_request(&AgentRequest::Fetch { site: "datadoghq.com".to_string() }).unwrap() {
+            AgentResponse::Locked => {}
+            other => panic!("expected the TTL to have evicted the site, got: {}", serde_json::to_string(&other).unwrap()),
+        }
+
+        quit().unwrap();
+        handle.join().unwrap().unwrap();
+    }
+}
@@ -51,7 +51,58 @@ pub fn list(cfg: &crate::config::Config) -> Result<()> {
     Ok(())
 }
 
+/// Validate `template`'s placeholder syntax (`$1`..`$9`, `$@`, `${N:-default}`)
+/// without requiring actual invocation args, so `set()` rejects a malformed
+/// alias up front rather than only surfacing the error the first time it's
+/// run. Argument-count mismatches (too few positional args) are still only
+/// checked by [`expand_template`] at invocation time, since that depends on
+/// how the alias is called.
+fn validate_template(template: &str) -> Result<()> {
+    let bytes = template.as_bytes();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if bytes[i] != b'$' {
+            i += 1;
+            continue;
+        }
+
+        if template[i..].starts_with("$@") {
+            i += 2;
+            continue;
+        }
+
+        if template[i..].starts_with("${") {
+            let close = template[i..]
+                .find('}')
+                .ok_or_else(|| anyhow::anyhow!("unterminated ${{...}} placeholder in alias template"))?;
+            let inner = &template[i + 2..i + close];
+            let n_str = inner.split_once(":-").map_or(inner, |(n, _)| n);
+            let n: usize = n_str
+                .parse()
+                .map_err(|_| anyhow::anyhow!("invalid placeholder ${{{inner}}} in alias template"))?;
+            if n == 0 {
+                bail!("invalid placeholder ${{{inner}}} in alias template: positional args are 1-indexed");
+            }
+            i += close + 1;
+            continue;
+        }
+
+        if let Some(&d) = bytes.get(i + 1) {
+            if d.is_ascii_digit() {
+                i += 2;
+                continue;
+            }
+        }
+
+        i += 1;
+    }
+
+    Ok(())
+}
+
 pub fn set(name: String, command: String) -> Result<()> {
+    validate_template(&command)?;
     let mut aliases = load_aliases()?;
     aliases.insert(name.clone(), command.clone());
     save_aliases(&aliases)?;
@@ -71,6 +122,111 @@ pub fn delete(names: Vec<String>) -> Result<()> {
     Ok(())
 }
 
+/// Expand an alias command template against the args it was invoked with.
+///
+/// Supports `$1`..`$9` (positional), `$@` (all remaining args, space-joined),
+/// and `${N:-default}` (positional with a fallback when absent). Any args not
+/// consumed by an explicit `$N`/`$@` placeholder are appended to the end of
+/// the expanded command, so plain prefix aliases keep working unchanged.
+fn expand_template(template: &str, args: &[String]) -> Result<String> {
+    let mut out = String::new();
+    let mut used = vec![false; args.len()];
+    let mut saw_all = false;
+    let bytes = template.as_bytes();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if bytes[i] != b'$' {
+            let next_dollar = template[i..].find('$').map_or(template.len(), |off| i + off);
+            out.push_str(&template[i..next_dollar]);
+            i = next_dollar;
+            continue;
+        }
+
+        // $@  -> all remaining args
+        if template[i..].starts_with("$@") {
+            out.push_str(&args.join(" "));
+            used.iter_mut().for_each(|u| *u = true);
+            saw_all = true;
+            i += 2;
+            continue;
+        }
+
+        // ${N:-default}
+        if template[i..].starts_with("${") {
+            let close = template[i..]
+                .find('}')
+                .ok_or_else(|| anyhow::anyhow!("unterminated ${{...}} placeholder in alias"))?;
+            let inner = &template[i + 2..i + close];
+            let (n_str, default) = match inner.split_once(":-") {
+                Some((n, d)) => (n, Some(d)),
+                None => (inner, None),
+            };
+            let n: usize = n_str
+                .parse()
+                .map_err(|_| anyhow::anyhow!("invalid placeholder ${{{inner}}} in alias"))?;
+            match args.get(n.wrapping_sub(1)) {
+                Some(v) if n >= 1 => {
+                    out.push_str(v);
+                    used[n - 1] = true;
+                }
+                _ => match default {
+                    Some(d) => out.push_str(d),
+                    None => bail!("alias requires positional argument ${n}, but only {} were given", args.len()),
+                },
+            }
+            i += close + 1;
+            continue;
+        }
+
+        // $1..$9
+        if let Some(&d) = bytes.get(i + 1) {
+            if d.is_ascii_digit() && d != b'0' {
+                let n = (d - b'0') as usize;
+                match args.get(n - 1) {
+                    Some(v) => {
+                        out.push_str(v);
+                        used[n - 1] = true;
+                    }
+                    None => bail!(
+                        "alias requires positional argument ${n}, but only {} were given",
+                        args.len()
+                    ),
+                }
+                i += 2;
+                continue;
+            }
+        }
+
+        // Not a recognised placeholder — keep the literal '$'.
+        out.push('$');
+        i += 1;
+    }
+
+    // Append any args not consumed by an explicit placeholder, unless $@ already
+    // consumed everything.
+    if !saw_all {
+        for (arg, was_used) in args.iter().zip(used.iter()) {
+            if !was_used {
+                out.push(' ');
+                out.push_str(arg);
+            }
+        }
+    }
+
+    Ok(out)
+}
+
+/// Load `name`'s stored template, expand it against `args`, and return the
+/// fully-substituted command line ready to be re-parsed by `pup`'s CLI parser.
+pub fn run(name: &str, args: &[String]) -> Result<String> {
+    let aliases = load_aliases()?;
+    let template = aliases
+        .get(name)
+        .ok_or_else(|| anyhow::anyhow!("alias not found: {name}"))?;
+    expand_template(template, args)
+}
+
 pub fn import(file: &str) -> Result<()> {
     let contents = std::fs::read_to_string(file)
         .with_context(|| format!("failed to read alias file: {file}"))?;
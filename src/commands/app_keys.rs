@@ -12,6 +12,10 @@ use crate::client;
 use crate::config::Config;
 use crate::formatter;
 
+/// Page size `--all` auto-pagination uses when the caller didn't pass an
+/// explicit `--page-size`.
+const DEFAULT_ALL_PAGE_SIZE: i64 = 100;
+
 #[cfg(not(target_arch = "wasm32"))]
 fn parse_sort(s: &str) -> Result<ApplicationKeysSort> {
     match s {
@@ -38,6 +42,7 @@ pub async fn list(
     page_number: i64,
     filter: &str,
     sort: &str,
+    all: bool,
 ) -> Result<()> {
     let dd_cfg = client::make_dd_config(cfg);
     let api = match client::make_bearer_client(cfg) {
@@ -45,6 +50,38 @@ pub async fn list(
         None => KeyManagementAPI::with_config(dd_cfg),
     };
 
+    let effective_page_size = if page_size > 0 { page_size } else { DEFAULT_ALL_PAGE_SIZE };
+
+    if all {
+        let sort_value = if !sort.is_empty() { Some(parse_sort(sort)?) } else { None };
+        let budget = crate::ops::pagination::RequestBudget::unbounded();
+        let outcome = crate::ops::pagination::paginate_pages(
+            &budget,
+            effective_page_size,
+            crate::ops::pagination::DEFAULT_MAX_PAGES,
+            |key| key.id.clone(),
+            |page_number| {
+                let mut params = ListCurrentUserApplicationKeysOptionalParams::default();
+                params.page_size = Some(effective_page_size);
+                params.page_number = Some(page_number);
+                if !filter.is_empty() {
+                    params.filter = Some(filter.to_string());
+                }
+                params.sort = sort_value.clone();
+                let api = &api;
+                async move {
+                    let resp = api
+                        .list_current_user_application_keys(params)
+                        .await
+                        .map_err(|e| formatter::datadog_error("failed to list application keys", e))?;
+                    Ok(resp.data.unwrap_or_default())
+                }
+            },
+        )
+        .await?;
+        return formatter::output(cfg, serde_json::json!({ "data": outcome.items, "truncated": outcome.truncated }));
+    }
+
     let mut params = ListCurrentUserApplicationKeysOptionalParams::default();
     if page_size > 0 {
         params.page_size = Some(page_size);
@@ -62,7 +99,7 @@ pub async fn list(
     let resp = api
         .list_current_user_application_keys(params)
         .await
-        .map_err(|e| anyhow::anyhow!("failed to list application keys: {e:?}"))?;
+        .map_err(|e| formatter::datadog_error("failed to list application keys", e))?;
     formatter::output(cfg, &resp)
 }
 
@@ -73,7 +110,34 @@ pub async fn list(
     page_number: i64,
     filter: &str,
     sort: &str,
+    all: bool,
 ) -> Result<()> {
+    let effective_page_size = if page_size > 0 { page_size } else { DEFAULT_ALL_PAGE_SIZE };
+
+    if all {
+        let budget = crate::ops::pagination::RequestBudget::unbounded();
+        let outcome = crate::ops::pagination::fetch_all(&budget, None, |cursor| {
+            let offset = match cursor {
+                Some(crate::ops::pagination::PageCursor::Offset(o)) => o,
+                _ => 0,
+            };
+            let page_number = offset / effective_page_size.max(1) as u64 + 1;
+            let mut query: Vec<(&str, String)> = vec![
+                ("page[size]", effective_page_size.to_string()),
+                ("page[number]", page_number.to_string()),
+            ];
+            if !filter.is_empty() {
+                query.push(("filter", filter.to_string()));
+            }
+            if !sort.is_empty() {
+                query.push(("sort", sort.to_string()));
+            }
+            async move { crate::api::get(cfg, "/api/v2/current_user/application_keys", &query).await }
+        })
+        .await?;
+        return crate::formatter::output(cfg, serde_json::json!({ "data": outcome.items, "truncated": outcome.truncated }));
+    }
+
     let mut query: Vec<(&str, String)> = Vec::new();
     if page_size > 0 {
         query.push(("page[size]", page_size.to_string()));
@@ -102,6 +166,7 @@ pub async fn list_all(
     page_number: i64,
     filter: &str,
     sort: &str,
+    all: bool,
 ) -> Result<()> {
     let dd_cfg = client::make_dd_config(cfg);
     let api = match client::make_bearer_client(cfg) {
@@ -109,6 +174,38 @@ pub async fn list_all(
         None => KeyManagementAPI::with_config(dd_cfg),
     };
 
+    let effective_page_size = if page_size > 0 { page_size } else { DEFAULT_ALL_PAGE_SIZE };
+
+    if all {
+        let sort_value = if !sort.is_empty() { Some(parse_sort(sort)?) } else { None };
+        let budget = crate::ops::pagination::RequestBudget::unbounded();
+        let outcome = crate::ops::pagination::paginate_pages(
+            &budget,
+            effective_page_size,
+            crate::ops::pagination::DEFAULT_MAX_PAGES,
+            |key| key.id.clone(),
+            |page_number| {
+                let mut params = ListApplicationKeysOptionalParams::default();
+                params.page_size = Some(effective_page_size);
+                params.page_number = Some(page_number);
+                if !filter.is_empty() {
+                    params.filter = Some(filter.to_string());
+                }
+                params.sort = sort_value.clone();
+                let api = &api;
+                async move {
+                    let resp = api
+                        .list_application_keys(params)
+                        .await
+                        .map_err(|e| formatter::datadog_error("failed to list all application keys", e))?;
+                    Ok(resp.data.unwrap_or_default())
+                }
+            },
+        )
+        .await?;
+        return formatter::output(cfg, serde_json::json!({ "data": outcome.items, "truncated": outcome.truncated }));
+    }
+
     let mut params = ListApplicationKeysOptionalParams::default();
     if page_size > 0 {
         params.page_size = Some(page_size);
@@ -126,7 +223,7 @@ pub async fn list_all(
     let resp = api
         .list_application_keys(params)
         .await
-        .map_err(|e| anyhow::anyhow!("failed to list all application keys: {e:?}"))?;
+        .map_err(|e| formatter::datadog_error("failed to list all application keys", e))?;
     formatter::output(cfg, &resp)
 }
 
@@ -137,7 +234,34 @@ pub async fn list_all(
     page_number: i64,
     filter: &str,
     sort: &str,
+    all: bool,
 ) -> Result<()> {
+    let effective_page_size = if page_size > 0 { page_size } else { DEFAULT_ALL_PAGE_SIZE };
+
+    if all {
+        let budget = crate::ops::pagination::RequestBudget::unbounded();
+        let outcome = crate::ops::pagination::fetch_all(&budget, None, |cursor| {
+            let offset = match cursor {
+                Some(crate::ops::pagination::PageCursor::Offset(o)) => o,
+                _ => 0,
+            };
+            let page_number = offset / effective_page_size.max(1) as u64 + 1;
+            let mut query: Vec<(&str, String)> = vec![
+                ("page[size]", effective_page_size.to_string()),
+                ("page[number]", page_number.to_string()),
+            ];
+            if !filter.is_empty() {
+                query.push(("filter", filter.to_string()));
+            }
+            if !sort.is_empty() {
+                query.push(("sort", sort.to_string()));
+            }
+            async move { crate::api::get(cfg, "/api/v2/application_keys", &query).await }
+        })
+        .await?;
+        return crate::formatter::output(cfg, serde_json::json!({ "data": outcome.items, "truncated": outcome.truncated }));
+    }
+
     let mut query: Vec<(&str, String)> = Vec::new();
     if page_size > 0 {
         query.push(("page[size]", page_size.to_string()));
@@ -169,7 +293,7 @@ pub async fn get(cfg: &Config, key_id: &str) -> Result<()> {
     let resp = api
         .get_current_user_application_key(key_id.to_string())
         .await
-        .map_err(|e| anyhow::anyhow!("failed to get application key: {e:?}"))?;
+        .map_err(|e| formatter::datadog_error("failed to get application key", e))?;
     formatter::output(cfg, &resp)
 }
 
@@ -202,6 +326,7 @@ pub async fn create(cfg: &Config, name: &str, scopes: &str) -> Result<()> {
             .map(|s| s.trim().to_string())
             .filter(|s| !s.is_empty())
             .collect();
+        crate::ops::permissions::validate(&scope_list)?;
         attrs.scopes = Some(Some(scope_list));
     }
 
@@ -218,7 +343,7 @@ pub async fn create(cfg: &Config, name: &str, scopes: &str) -> Result<()> {
     let resp = api
         .create_current_user_application_key(body)
         .await
-        .map_err(|e| anyhow::anyhow!("failed to create application key: {e:?}"))?;
+        .map_err(|e| formatter::datadog_error("failed to create application key", e))?;
     formatter::output(cfg, &resp)
 }
 
@@ -226,11 +351,12 @@ pub async fn create(cfg: &Config, name: &str, scopes: &str) -> Result<()> {
 pub async fn create(cfg: &Config, name: &str, scopes: &str) -> Result<()> {
     let mut attrs = serde_json::json!({ "name": name });
     if !scopes.is_empty() {
-        let scope_list: Vec<&str> = scopes
+        let scope_list: Vec<String> = scopes
             .split(',')
-            .map(|s| s.trim())
+            .map(|s| s.trim().to_string())
             .filter(|s| !s.is_empty())
             .collect();
+        crate::ops::permissions::validate(&scope_list)?;
         attrs["scopes"] = serde_json::json!(scope_list);
     }
     let body = serde_json::json!({
@@ -264,6 +390,7 @@ pub async fn update(cfg: &Config, key_id: &str, name: &str, scopes: &str) -> Res
             .map(|s| s.trim().to_string())
             .filter(|s| !s.is_empty())
             .collect();
+        crate::ops::permissions::validate(&scope_list)?;
         attrs.scopes = Some(Some(scope_list));
     }
 
@@ -281,7 +408,7 @@ pub async fn update(cfg: &Config, key_id: &str, name: &str, scopes: &str) -> Res
     let resp = api
         .update_current_user_application_key(key_id.to_string(), body)
         .await
-        .map_err(|e| anyhow::anyhow!("failed to update application key: {e:?}"))?;
+        .map_err(|e| formatter::datadog_error("failed to update application key", e))?;
     formatter::output(cfg, &resp)
 }
 
@@ -292,11 +419,12 @@ pub async fn update(cfg: &Config, key_id: &str, name: &str, scopes: &str) -> Res
         attrs["name"] = serde_json::json!(name);
     }
     if !scopes.is_empty() {
-        let scope_list: Vec<&str> = scopes
+        let scope_list: Vec<String> = scopes
             .split(',')
-            .map(|s| s.trim())
+            .map(|s| s.trim().to_string())
             .filter(|s| !s.is_empty())
             .collect();
+        crate::ops::permissions::validate(&scope_list)?;
         attrs["scopes"] = serde_json::json!(scope_list);
     }
     let body = serde_json::json!({
@@ -328,9 +456,8 @@ pub async fn delete(cfg: &Config, key_id: &str) -> Result<()> {
     };
     api.delete_current_user_application_key(key_id.to_string())
         .await
-        .map_err(|e| anyhow::anyhow!("failed to delete application key: {e:?}"))?;
-    println!("Successfully deleted application key {key_id}");
-    Ok(())
+        .map_err(|e| formatter::datadog_error("failed to delete application key", e))?;
+    formatter::output_deleted(cfg, "application_key", key_id)
 }
 
 #[cfg(target_arch = "wasm32")]
@@ -340,6 +467,39 @@ pub async fn delete(cfg: &Config, key_id: &str) -> Result<()> {
         &format!("/api/v2/current_user/application_keys/{key_id}"),
     )
     .await?;
-    println!("Successfully deleted application key {key_id}");
+    formatter::output_deleted(cfg, "application_key", key_id)
+}
+
+// ---------------------------------------------------------------------------
+// Permission catalog: discoverable, client-validated --scopes strings
+// ---------------------------------------------------------------------------
+
+/// `pup api-keys permissions list`: print every valid app-key scope,
+/// grouped by product area, so `--scopes` on `create`/`update` is
+/// discoverable without a trip to the docs.
+pub async fn permissions_list(cfg: &Config) -> Result<()> {
+    if cfg.agent_mode {
+        return formatter::output(cfg, crate::ops::permissions::CATALOG);
+    }
+    for group in crate::ops::permissions::CATALOG {
+        println!("{}:", group.area);
+        for scope in group.scopes {
+            println!("  {scope}");
+        }
+    }
     Ok(())
 }
+
+/// `pup api-keys permissions grant <key_id> <scope>`: add one scope to an
+/// existing key's scope list.
+pub async fn permissions_grant(cfg: &Config, key_id: &str, scope: &str) -> Result<()> {
+    let resp = crate::ops::permissions::grant(cfg, key_id, scope).await?;
+    formatter::output(cfg, &resp)
+}
+
+/// `pup api-keys permissions revoke <key_id> <scope>`: remove one scope
+/// from an existing key's scope list.
+pub async fn permissions_revoke(cfg: &Config, key_id: &str, scope: &str) -> Result<()> {
+    let resp = crate::ops::permissions::revoke(cfg, key_id, scope).await?;
+    formatter::output(cfg, &resp)
+}
@@ -0,0 +1,42 @@
+use anyhow::{Context, Result};
+
+use crate::config::Config;
+use crate::formatter;
+use crate::ops::apply::{self, Manifest};
+
+/// `pup apply --file manifest.(yaml|json) [--dry-run] [--prune]`: reconcile
+/// the account to match a manifest of resources across every kind
+/// `ops::backup::ResourceKind` knows about.
+pub async fn run(cfg: &Config, file: &str, dry_run: bool, prune: bool) -> Result<()> {
+    let raw = std::fs::read_to_string(file)
+        .with_context(|| format!("failed to read manifest {file}"))?;
+    let manifest: Manifest = if file.ends_with(".json") {
+        serde_json::from_str(&raw).with_context(|| format!("failed to parse {file} as JSON"))?
+    } else {
+        serde_yaml::from_str(&raw).with_context(|| format!("failed to parse {file} as YAML"))?
+    };
+
+    let summary = apply::run(cfg, &manifest, dry_run, prune).await?;
+
+    if cfg.agent_mode {
+        return formatter::output(cfg, &summary);
+    }
+
+    if dry_run {
+        println!("Plan ({} resource(s)):", summary.plan.len());
+        for item in &summary.plan {
+            println!("  {:?} {} {} ({})", item.action, item.kind, item.name, item.id.as_deref().unwrap_or("new"));
+        }
+        return Ok(());
+    }
+
+    println!(
+        "Applied {}: {} created, {} updated, {} deleted, {} unchanged",
+        file,
+        summary.created.len(),
+        summary.updated.len(),
+        summary.deleted.len(),
+        summary.unchanged
+    );
+    Ok(())
+}
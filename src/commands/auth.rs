@@ -1,25 +1,209 @@
-use anyhow::{bail, Result};
+use anyhow::{bail, Context, Result};
 
 use crate::auth::storage;
 use crate::config::Config;
 
 /// Helper to run a closure with the storage lock held (non-async to avoid holding lock across await).
-fn with_storage<F, R>(f: F) -> Result<R>
+/// `cfg.storage_backend` selects the backend (`"keyring"`/`"file"`) on first use.
+fn with_storage<F, R>(cfg: &Config, f: F) -> Result<R>
 where
     F: FnOnce(&mut dyn storage::Storage) -> Result<R>,
 {
-    let guard = storage::get_storage()?;
+    let guard = storage::get_storage(cfg.storage_backend.as_deref())?;
     let mut lock = guard.lock().unwrap();
     let store = lock.as_mut().unwrap();
     f(&mut **store)
 }
 
+/// The scopes to request during login: `default_scopes()` plus `offline_access`
+/// when `cfg.offline_access` is set, since several authorization servers only
+/// issue a `refresh_token` when that scope is explicitly requested.
 #[cfg(not(target_arch = "wasm32"))]
-pub async fn login(cfg: &Config) -> Result<()> {
-    use crate::auth::{dcr, pkce, types};
+fn effective_scopes(cfg: &Config) -> Vec<&'static str> {
+    let mut scopes = crate::auth::types::default_scopes();
+    if cfg.offline_access {
+        scopes.push("offline_access");
+    }
+    scopes
+}
+
+/// Run the interactive login flow. When `device_flow` is true (explicitly
+/// requested, e.g. via `--device`), or the environment looks headless (no
+/// `DISPLAY`/`WAYLAND_DISPLAY` and an active SSH session), use the OAuth2
+/// device authorization grant instead of the local-callback-server + browser
+/// redirect flow.
+#[cfg(not(target_arch = "wasm32"))]
+pub async fn login(cfg: &Config, device_flow: bool, gateway: bool) -> Result<()> {
+    if gateway {
+        login_gateway(cfg).await
+    } else if device_flow || looks_headless() {
+        login_device_flow(cfg).await
+    } else {
+        login_browser_flow(cfg).await
+    }
+}
+
+/// Login via an OIDC gateway fronting the Datadog API (`--gateway`), as an
+/// alternative to the DCR-based flows above: exchange `cfg.oidc_*`
+/// client credentials for a bearer token, verifying any ID token the
+/// gateway returns against its JWKS before the token set is trusted and
+/// stored. Unlike DCR, there is no per-site dynamic registration step —
+/// the client is expected to be pre-registered with the gateway out of
+/// band, so `cfg` must supply its token endpoint and credentials directly.
+#[cfg(not(target_arch = "wasm32"))]
+async fn login_gateway(cfg: &Config) -> Result<()> {
+    use crate::auth::oidc_gateway::TokenChecker;
+    use jsonwebtoken::Algorithm;
+
+    let site = &cfg.site;
+    let org = cfg.org.as_deref();
+    let org_label = org.map(|o| format!(" (org: {o})")).unwrap_or_default();
+
+    let token_endpoint = cfg
+        .oidc_token_endpoint
+        .as_deref()
+        .context("OIDC gateway login requires an oidc-token-endpoint (or DD_OIDC_TOKEN_ENDPOINT)")?;
+    let client_id = cfg
+        .oidc_client_id
+        .as_deref()
+        .context("OIDC gateway login requires an oidc-client-id (or DD_OIDC_CLIENT_ID)")?;
+    let client_secret = cfg
+        .oidc_client_secret
+        .as_deref()
+        .context("OIDC gateway login requires an oidc-client-secret (or DD_OIDC_CLIENT_SECRET)")?;
+    let issuer = cfg.oidc_issuer.clone().unwrap_or_else(|| token_endpoint.to_string());
+    let audience = cfg.oidc_audience.clone().unwrap_or_else(|| client_id.to_string());
+    let scope = cfg.oidc_scope.as_deref().unwrap_or("openid");
+    let signing_alg = match cfg.oidc_signing_alg.as_deref() {
+        None => Algorithm::RS256,
+        Some("RS256") => Algorithm::RS256,
+        Some("RS384") => Algorithm::RS384,
+        Some("RS512") => Algorithm::RS512,
+        Some("ES256") => Algorithm::ES256,
+        Some("ES384") => Algorithm::ES384,
+        Some("PS256") => Algorithm::PS256,
+        Some("PS384") => Algorithm::PS384,
+        Some("PS512") => Algorithm::PS512,
+        Some(other) => bail!("unsupported oidc-signing-alg {other:?}"),
+    };
+
+    eprintln!("\n🔐 Starting OIDC gateway login for site: {site}{org_label}\n");
+
+    let checker = TokenChecker::new(
+        issuer,
+        audience,
+        cfg.oidc_jwks_uri.clone(),
+        cfg.oidc_userinfo_uri.clone(),
+        cfg.oidc_required_claims.clone(),
+        signing_alg,
+    );
+
+    let tokens = checker
+        .client_credentials(token_endpoint, client_id, client_secret, scope)
+        .await?;
+
+    if let Some(id_token) = &cfg.oidc_id_token_hint {
+        checker.verify_id_token(id_token).await?;
+        eprintln!("✓ ID token verified against gateway JWKS");
+    }
+
+    let location = with_storage(cfg, |store| {
+        store.save_tokens(site, org, &tokens)?;
+        Ok(store.storage_location())
+    })?;
+    storage::save_session(site, org)?;
+
+    eprintln!("\n✅ Gateway login successful{org_label}!");
+    eprintln!("   Token stored in: {location}");
+    Ok(())
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn looks_headless() -> bool {
+    let has_display = std::env::var_os("DISPLAY").is_some() || std::env::var_os("WAYLAND_DISPLAY").is_some();
+    let over_ssh = std::env::var_os("SSH_CONNECTION").is_some() || std::env::var_os("SSH_TTY").is_some();
+    over_ssh && !has_display
+}
+
+/// Headless login via the OAuth2 device authorization grant (RFC 8628): no
+/// local callback server or browser redirect required, so this works over
+/// SSH, on servers, and in any other environment without a local browser.
+#[cfg(not(target_arch = "wasm32"))]
+async fn login_device_flow(cfg: &Config) -> Result<()> {
+    use crate::auth::dcr;
+
+    let site = &cfg.site;
+    let org = cfg.org.as_deref();
+    let org_label = org.map(|o| format!(" (org: {o})")).unwrap_or_default();
+    let dcr_client = dcr::DcrClient::new(site);
+    let scopes = effective_scopes(cfg);
+
+    eprintln!("\n🔐 Starting device login for site: {site}{org_label}\n");
+
+    // Client credentials are site-scoped (DCR is per-site, shared across orgs).
+    // The device flow has no redirect URI, so registration uses an
+    // out-of-band placeholder per RFC 8628 section 3.1.
+    let creds = match with_storage(cfg, |store| store.load_client_credentials(site))? {
+        Some(creds) => {
+            eprintln!("✓ Using existing client registration");
+            creds
+        }
+        None => {
+            eprintln!("📝 Registering new OAuth2 client...");
+            let creds = dcr_client
+                .register("urn:ietf:wg:oauth:2.0:oob", &scopes)
+                .await?;
+            with_storage(cfg, |store| store.save_client_credentials(site, &creds))?;
+            eprintln!("✓ Registered client: {}", creds.client_id);
+            creds
+        }
+    };
+
+    let device_auth = dcr_client
+        .device_authorize(&creds.client_id, &scopes)
+        .await?;
+
+    eprintln!(
+        "To authorize this device, visit:\n\n    {}\n",
+        device_auth.verification_uri
+    );
+    eprintln!("and enter code: {}\n", device_auth.user_code);
+    if let Some(complete) = &device_auth.verification_uri_complete {
+        eprintln!("Or open directly: {complete}\n");
+    }
+    eprintln!("⏳ Waiting for authorization...");
+
+    let tokens = dcr_client.poll_device_token(&device_auth, &creds).await?;
+    if tokens.refresh_token.is_empty() {
+        eprintln!(
+            "⚠️  No refresh token was issued — re-authentication will be required once this token expires."
+        );
+    }
+
+    let location = with_storage(cfg, |store| {
+        store.save_tokens(site, org, &tokens)?;
+        Ok(store.storage_location())
+    })?;
+    storage::save_session(site, org)?;
+
+    let expires_at = chrono::DateTime::from_timestamp(tokens.issued_at + tokens.expires_in, 0)
+        .map(|dt| dt.with_timezone(&chrono::Local).to_rfc3339())
+        .unwrap_or_else(|| format!("in {} hours", tokens.expires_in / 3600));
+
+    eprintln!("\n✅ Login successful{org_label}!");
+    eprintln!("   Access token expires: {expires_at}");
+    eprintln!("   Token stored in: {location}");
+
+    Ok(())
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+async fn login_browser_flow(cfg: &Config) -> Result<()> {
+    use crate::auth::{dcr, pkce};
 
     let site = &cfg.site;
     let org = cfg.org.as_deref();
+    let scopes = effective_scopes(cfg);
 
     // 1. Start callback server
     let mut server = crate::auth::callback::CallbackServer::new().await?;
@@ -30,7 +214,7 @@ pub async fn login(cfg: &Config) -> Result<()> {
 
     // 2. Load existing client credentials (lock released before any await)
     // Client credentials are site-scoped (DCR is per-site, shared across orgs)
-    let existing_creds = with_storage(|store| store.load_client_credentials(site))?;
+    let existing_creds = with_storage(cfg, |store| store.load_client_credentials(site))?;
 
     let creds = match existing_creds {
         Some(creds) => {
@@ -40,9 +224,8 @@ pub async fn login(cfg: &Config) -> Result<()> {
         None => {
             eprintln!("📝 Registering new OAuth2 client...");
             let dcr_client = dcr::DcrClient::new(site);
-            let scopes = types::default_scopes();
             let creds = dcr_client.register(&redirect_uri, &scopes).await?;
-            with_storage(|store| store.save_client_credentials(site, &creds))?;
+            with_storage(cfg, |store| store.save_client_credentials(site, &creds))?;
             eprintln!("✓ Registered client: {}", creds.client_id);
             creds
         }
@@ -54,14 +237,16 @@ pub async fn login(cfg: &Config) -> Result<()> {
 
     // 4. Build authorization URL
     let dcr_client = dcr::DcrClient::new(site);
-    let scopes = types::default_scopes();
-    let auth_url = dcr_client.build_authorization_url(
-        &creds.client_id,
-        &redirect_uri,
-        &state,
-        &challenge,
-        &scopes,
-    );
+    let auth_url = dcr_client
+        .build_authorization_url(
+            &creds.client_id,
+            &redirect_uri,
+            &state,
+            &challenge,
+            &scopes,
+            &cfg.extra_auth_params,
+        )
+        .await?;
 
     // 5. Open browser
     eprintln!("\n🌐 Opening browser for authentication...");
@@ -89,7 +274,13 @@ pub async fn login(cfg: &Config) -> Result<()> {
         .exchange_code(&result.code, &redirect_uri, &challenge.verifier, &creds)
         .await?;
 
-    let location = with_storage(|store| {
+    if tokens.refresh_token.is_empty() {
+        eprintln!(
+            "⚠️  No refresh token was issued — re-authentication (not refresh) will be required once this token expires."
+        );
+    }
+
+    let location = with_storage(cfg, |store| {
         store.save_tokens(site, org, &tokens)?;
         Ok(store.storage_location())
     })?;
@@ -109,7 +300,7 @@ pub async fn login(cfg: &Config) -> Result<()> {
 }
 
 #[cfg(target_arch = "wasm32")]
-pub async fn login(_cfg: &Config) -> Result<()> {
+pub async fn login(_cfg: &Config, _device_flow: bool, _gateway: bool) -> Result<()> {
     bail!(
         "OAuth login is not available in WASM builds.\n\
          Use DD_ACCESS_TOKEN env var for bearer token auth,\n\
@@ -121,7 +312,7 @@ pub async fn login(_cfg: &Config) -> Result<()> {
 pub async fn logout(cfg: &Config) -> Result<()> {
     let site = &cfg.site;
     let org = cfg.org.as_deref();
-    with_storage(|store| {
+    with_storage(cfg, |store| {
         store.delete_tokens(site, org)?;
         // Only delete client credentials when logging out the default (no-org) session;
         // client credentials are site-scoped and shared across orgs
@@ -144,15 +335,30 @@ pub async fn logout(_cfg: &Config) -> Result<()> {
     )
 }
 
+/// What kind of credential `cfg` is actually configured with, classified by
+/// format rather than by which env var/flag supplied it — `None` when
+/// neither an access token nor an API/app key pair is present.
+fn configured_credential_kind(cfg: &Config) -> Option<crate::ops::credentials::CredentialKind> {
+    if let Some(token) = &cfg.access_token {
+        Some(crate::ops::credentials::classify(token))
+    } else if cfg.has_api_keys() {
+        Some(crate::ops::credentials::CredentialKind::ApiKey)
+    } else {
+        None
+    }
+}
+
 pub fn status(cfg: &Config) -> Result<()> {
     let site = &cfg.site;
     let org = cfg.org.as_deref();
+    let credential_kind = configured_credential_kind(cfg);
 
     // In WASM, just report env var status
     #[cfg(target_arch = "wasm32")]
     {
         if cfg.has_bearer_token() || cfg.has_api_keys() {
-            println!("✅ Authenticated for site: {site}");
+            let kind = credential_kind.map(|k| k.label()).unwrap_or("unknown");
+            println!("✅ Authenticated for site: {site} (credential: {kind})");
         } else {
             println!("❌ Not authenticated for site: {site}");
         }
@@ -160,7 +366,7 @@ pub fn status(cfg: &Config) -> Result<()> {
     }
 
     #[cfg(not(target_arch = "wasm32"))]
-    with_storage(|store| {
+    with_storage(cfg, |store| {
         match store.load_tokens(site, org)? {
             Some(tokens) => {
                 let expires_at_ts = tokens.issued_at + tokens.expires_in;
@@ -189,6 +395,7 @@ pub fn status(cfg: &Config) -> Result<()> {
 
                 let json = serde_json::json!({
                     "authenticated": true,
+                    "credential_kind": crate::ops::credentials::CredentialKind::Bearer.label(),
                     "expires_at": expires_at,
                     "has_refresh": !tokens.refresh_token.is_empty(),
                     "org": org,
@@ -203,6 +410,7 @@ pub fn status(cfg: &Config) -> Result<()> {
                 eprintln!("❌ Not authenticated for site: {site}{org_label}");
                 let json = serde_json::json!({
                     "authenticated": false,
+                    "credential_kind": credential_kind.map(|k| k.label()),
                     "org": org,
                     "site": site,
                     "status": "no token",
@@ -214,40 +422,112 @@ pub fn status(cfg: &Config) -> Result<()> {
     })
 }
 
-pub fn token(cfg: &Config) -> Result<()> {
+/// Print `token` per `--format`: bare (the default, for
+/// `$(pup auth token)`-style substitution) or `env` (`DD_BEARER_TOKEN=...`,
+/// for `eval $(pup auth token --format env)`).
+fn print_token(token: &str, format: Option<&str>) -> Result<()> {
+    match format {
+        None | Some("bare") => println!("{token}"),
+        Some("env") => println!("DD_BEARER_TOKEN={token}"),
+        Some(other) => bail!("unknown --format '{other}' (expected 'bare' or 'env')"),
+    }
+    Ok(())
+}
+
+pub async fn token(cfg: &Config, no_refresh: bool, format: Option<&str>) -> Result<()> {
     if let Some(token) = &cfg.access_token {
-        println!("{token}");
-        return Ok(());
+        return print_token(token, format);
     }
 
     #[cfg(target_arch = "wasm32")]
-    bail!("no token available — set DD_ACCESS_TOKEN env var");
+    {
+        let _ = (no_refresh, format);
+        bail!("no token available — set DD_ACCESS_TOKEN env var");
+    }
 
     #[cfg(not(target_arch = "wasm32"))]
     {
         let site = &cfg.site;
         let org = cfg.org.as_deref();
-        with_storage(|store| match store.load_tokens(site, org)? {
-            Some(tokens) => {
-                if tokens.is_expired() {
-                    bail!("token is expired — run 'pup auth login' to refresh");
+        if no_refresh {
+            with_storage(cfg, |store| match store.load_tokens(site, org)? {
+                Some(tokens) => {
+                    if tokens.is_expired() {
+                        bail!("token is expired — run 'pup auth login' to refresh");
+                    }
+                    print_token(&tokens.access_token, format)
                 }
-                println!("{}", tokens.access_token);
-                Ok(())
-            }
-            None => bail!("no token available — run 'pup auth login' or set DD_ACCESS_TOKEN"),
-        })
+                None => bail!("no token available — run 'pup auth login' or set DD_ACCESS_TOKEN"),
+            })
+        } else {
+            let tokens = ensure_valid_token(cfg).await?;
+            print_token(&tokens.access_token, format)
+        }
     }
 }
 
+/// Margin before actual expiry within which `ensure_valid_token` proactively
+/// refreshes rather than handing back a token that's about to stop working.
 #[cfg(not(target_arch = "wasm32"))]
-pub async fn refresh(cfg: &Config) -> Result<()> {
+const REFRESH_SKEW_SECS: i64 = 60;
+
+#[cfg(not(target_arch = "wasm32"))]
+fn needs_refresh(tokens: &crate::auth::types::TokenSet, skew_secs: i64) -> bool {
+    let now = chrono::Utc::now().timestamp();
+    now >= (tokens.issued_at + tokens.expires_in - skew_secs)
+}
+
+/// Load the stored token for `cfg.site`/`cfg.org`, transparently refreshing it
+/// first if it's expired (or within `REFRESH_SKEW_SECS` of expiry) and a
+/// refresh token + client credentials are available. Only bails when there's
+/// no token to refresh, or no refresh token to refresh it with — callers that
+/// want the old strict behavior should load tokens directly instead.
+#[cfg(not(target_arch = "wasm32"))]
+pub async fn ensure_valid_token(cfg: &Config) -> Result<crate::auth::types::TokenSet> {
+    let site = &cfg.site;
+    let org = cfg.org.as_deref();
+
+    let tokens = with_storage(cfg, |store| store.load_tokens(site, org))?
+        .ok_or_else(|| anyhow::anyhow!("no token available — run 'pup auth login' or set DD_ACCESS_TOKEN"))?;
+
+    if !needs_refresh(&tokens, REFRESH_SKEW_SECS) {
+        return Ok(tokens);
+    }
+
+    if tokens.refresh_token.is_empty() {
+        bail!("token is expired — run 'pup auth login' to refresh");
+    }
+
+    do_refresh(cfg, site, org, &tokens.refresh_token).await
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+async fn do_refresh(
+    cfg: &Config,
+    site: &str,
+    org: Option<&str>,
+    refresh_token: &str,
+) -> Result<crate::auth::types::TokenSet> {
     use crate::auth::dcr;
 
+    let creds = with_storage(cfg, |store| store.load_client_credentials(site))?.ok_or_else(|| {
+        anyhow::anyhow!("no client credentials found for site {site} — run 'pup auth login' first")
+    })?;
+
+    let dcr_client = dcr::DcrClient::new(site);
+    let new_tokens = dcr_client.refresh_token(refresh_token, &creds).await?;
+
+    with_storage(cfg, |store| store.save_tokens(site, org, &new_tokens))?;
+
+    Ok(new_tokens)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub async fn refresh(cfg: &Config) -> Result<()> {
     let site = &cfg.site;
     let org = cfg.org.as_deref();
 
-    let tokens = with_storage(|store| store.load_tokens(site, org))?.ok_or_else(|| {
+    let tokens = with_storage(cfg, |store| store.load_tokens(site, org))?.ok_or_else(|| {
         anyhow::anyhow!("no tokens found for site {site} — run 'pup auth login' first")
     })?;
 
@@ -255,22 +535,11 @@ pub async fn refresh(cfg: &Config) -> Result<()> {
         bail!("no refresh token available — run 'pup auth login' to re-authenticate");
     }
 
-    let creds = with_storage(|store| store.load_client_credentials(site))?.ok_or_else(|| {
-        anyhow::anyhow!("no client credentials found for site {site} — run 'pup auth login' first")
-    })?;
-
     let org_label = org.map(|o| format!(" (org: {o})")).unwrap_or_default();
     eprintln!("🔄 Refreshing access token for site: {site}{org_label}...");
 
-    let dcr_client = dcr::DcrClient::new(site);
-    let new_tokens = dcr_client
-        .refresh_token(&tokens.refresh_token, &creds)
-        .await?;
-
-    let location = with_storage(|store| {
-        store.save_tokens(site, org, &new_tokens)?;
-        Ok(store.storage_location())
-    })?;
+    let new_tokens = do_refresh(cfg, site, org, &tokens.refresh_token).await?;
+    let location = with_storage(cfg, |store| Ok(store.storage_location()))?;
 
     let expires_at =
         chrono::DateTime::from_timestamp(new_tokens.issued_at + new_tokens.expires_in, 0)
@@ -306,3 +575,141 @@ pub fn list(_cfg: &Config) -> Result<()> {
          Session storage is not available — credentials are read from environment variables."
     )
 }
+
+/// `pup auth scopes [--command <path>] [--manifest]`: least-privilege scope
+/// lookup against the static `ops::scopes::CATALOG`.
+///
+/// - No `command`: print the full command -> scopes table.
+/// - `command` alone: print the scopes for that one command path.
+/// - `command` + `--manifest`: treat `command` as a comma-separated list of
+///   command paths and print the deduplicated, sorted union of scopes
+///   needed to run all of them, e.g. for provisioning one scoped app key.
+pub fn scopes(cfg: &Config, command: Option<&str>, manifest: bool) -> Result<()> {
+    if manifest {
+        let Some(command) = command else {
+            bail!("--manifest requires --command <comma-separated command paths>");
+        };
+        let commands: Vec<&str> = command.split(',').map(str::trim).collect();
+        let scopes = crate::ops::scopes::manifest(&commands)?;
+
+        if cfg.agent_mode {
+            return crate::formatter::output(cfg, &scopes);
+        }
+        for scope in &scopes {
+            println!("{scope}");
+        }
+        return Ok(());
+    }
+
+    if let Some(command) = command {
+        let entry = crate::ops::scopes::lookup(command).ok_or_else(|| {
+            anyhow::anyhow!(
+                "unknown command path {command:?}\nExpected one of: {}",
+                crate::ops::scopes::CATALOG
+                    .iter()
+                    .map(|c| c.command)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
+        })?;
+
+        if cfg.agent_mode {
+            return crate::formatter::output(cfg, entry);
+        }
+        if entry.scopes.is_empty() {
+            println!("{}: (no scopes required)", entry.command);
+        } else {
+            println!("{}: {}", entry.command, entry.scopes.join(", "));
+        }
+        return Ok(());
+    }
+
+    if cfg.agent_mode {
+        return crate::formatter::output(cfg, crate::ops::scopes::CATALOG);
+    }
+    for entry in crate::ops::scopes::CATALOG {
+        if entry.scopes.is_empty() {
+            println!("{}: (no scopes required)", entry.command);
+        } else {
+            println!("{}: {}", entry.command, entry.scopes.join(", "));
+        }
+    }
+    Ok(())
+}
+
+/// `pup auth profiles`: list named credential profiles (`--profile <name>`
+/// selects one of these to overlay onto env/flag-resolved config for the
+/// invocation).
+pub fn profiles_list(cfg: &Config) -> Result<()> {
+    let profiles = crate::ops::credentials::list_profiles()?;
+
+    if cfg.agent_mode {
+        return crate::formatter::output(cfg, &profiles);
+    }
+
+    if profiles.is_empty() {
+        println!("No profiles configured.");
+        return Ok(());
+    }
+    for (name, profile) in &profiles {
+        let kind = if profile.access_token.is_some() {
+            "bearer"
+        } else if profile.api_key.is_some() && profile.app_key.is_some() {
+            "api+app key"
+        } else {
+            "incomplete"
+        };
+        println!("{name}: {kind}{}", profile.site.as_deref().map(|s| format!(" (site: {s})")).unwrap_or_default());
+    }
+    Ok(())
+}
+
+/// `pup auth profiles set <name> [--api-key K] [--app-key K] [--access-token T] [--site S]`.
+pub fn profiles_set(
+    name: &str,
+    api_key: Option<String>,
+    app_key: Option<String>,
+    access_token: Option<String>,
+    site: Option<String>,
+) -> Result<()> {
+    crate::ops::credentials::set_profile(
+        name,
+        crate::ops::credentials::Profile { api_key, app_key, access_token, site },
+    )?;
+    println!("Profile set: {name}");
+    Ok(())
+}
+
+/// `pup auth lock [--keyring]`: seal the currently-configured
+/// `api_key`/`app_key`/`access_token` and rewrite the config file so those
+/// fields are replaced by an `encrypted:` block. With `--keyring`, the
+/// derived key is stashed in the OS keychain instead of requiring a
+/// passphrase on every future `from_env` call.
+pub fn lock(cfg: &Config, keyring: bool) -> Result<()> {
+    use crate::config::crypto::{self, DecryptedCredentials, KeySource};
+
+    if cfg.api_key.is_none() && cfg.app_key.is_none() && cfg.access_token.is_none() {
+        bail!("nothing to lock: no api_key, app_key, or access_token is currently configured");
+    }
+
+    let creds = DecryptedCredentials {
+        api_key: cfg.api_key.clone(),
+        app_key: cfg.app_key.clone(),
+        access_token: cfg.access_token.clone(),
+    };
+    let source = if keyring { KeySource::Keyring } else { KeySource::Passphrase };
+    let block = crypto::seal_credentials(&creds, source)?;
+    crate::config::write_encrypted_credentials(&block)?;
+
+    println!(
+        "Credentials sealed in {} ({})",
+        crate::config::config_dir()
+            .map(|d| d.join("config.yaml").display().to_string())
+            .unwrap_or_else(|| "config.yaml".to_string()),
+        match source {
+            KeySource::Passphrase => "passphrase, set DD_CONFIG_PASSPHRASE or you'll be prompted on each use",
+            KeySource::Keyring => "OS keychain, no passphrase needed on future runs",
+        }
+    );
+    Ok(())
+}
@@ -0,0 +1,67 @@
+use anyhow::{Context, Result};
+
+use crate::api;
+use crate::config::Config;
+use crate::formatter;
+use crate::ops::backup::{self, ResourceKind};
+use crate::ops::terraform;
+
+pub async fn export(cfg: &Config, dir: &str, types: Option<Vec<String>>) -> Result<()> {
+    let summary = backup::export(cfg, dir, types).await?;
+
+    if cfg.agent_mode {
+        return formatter::output(cfg, &summary);
+    }
+
+    println!("Exported to {}:", summary.dir);
+    for (kind, count) in &summary.counts {
+        println!("  - {kind}: {count}");
+    }
+    println!("Total: {} object(s)", summary.total);
+    Ok(())
+}
+
+pub async fn import(cfg: &Config, dir: &str, dry_run: bool, yes: bool) -> Result<()> {
+    let summary = backup::import(cfg, dir, dry_run, yes).await?;
+
+    if cfg.agent_mode {
+        return formatter::output(cfg, &summary);
+    }
+
+    if summary.dry_run {
+        println!("Dry run complete for {}; no changes made.", summary.dir);
+        return Ok(());
+    }
+
+    println!(
+        "Imported from {}: {} created, {} updated, {} failed",
+        summary.dir,
+        summary.created.len(),
+        summary.updated.len(),
+        summary.failed.len()
+    );
+    for (id, err) in &summary.failed {
+        println!("  - {id}: {err}");
+    }
+    Ok(())
+}
+
+/// `pup <resource> <id> --format terraform`: fetch a single object of
+/// `resource_type` (any kind `ops::backup` knows how to export) and print it
+/// either as a `datadog` provider Terraform resource block, or — for any
+/// other `--format` — fall through to the normal formatter output path.
+pub async fn show(cfg: &Config, resource_type: &str, id: &str) -> Result<()> {
+    let kind = ResourceKind::parse(resource_type)?;
+    let obj = api::get(cfg, &kind.object_path(id), &[])
+        .await
+        .with_context(|| format!("failed to get {} {id}", kind.as_str()))?;
+
+    // `Terraform` is a new `OutputFormat` variant alongside `Json`/`Table`/`Yaml`.
+    if matches!(cfg.output_format, crate::config::OutputFormat::Terraform) {
+        let hcl = terraform::render(kind, id, &obj)?;
+        print!("{hcl}");
+        return Ok(());
+    }
+
+    formatter::format_and_print(&obj, &cfg.output_format, cfg.agent_mode, None)
+}
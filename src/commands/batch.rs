@@ -0,0 +1,23 @@
+use anyhow::{Context, Result};
+
+use crate::config::Config;
+use crate::formatter;
+use crate::ops::batch::{self, BatchFile};
+
+/// `pup batch --file ops.(yaml|json) [--continue-on-error]`: run an ordered
+/// list of operations against one resolved `Config` and print a single JSON
+/// array of per-operation `{ index, command, status, data | error }`
+/// results. Honors `--yes`/agent-mode auto-approve the same way a single
+/// write command would — there's no separate per-operation confirmation.
+pub async fn run(cfg: &Config, file: &str, continue_on_error: bool) -> Result<()> {
+    let raw = std::fs::read_to_string(file).with_context(|| format!("failed to read batch file {file}"))?;
+    let batch: BatchFile = if file.ends_with(".json") {
+        serde_json::from_str(&raw).with_context(|| format!("failed to parse {file} as JSON"))?
+    } else {
+        serde_yaml::from_str(&raw).with_context(|| format!("failed to parse {file} as YAML"))?
+    };
+
+    let results = batch::run(cfg, &batch, continue_on_error).await?;
+    formatter::output(cfg, &results)?;
+    batch::check_outcome(&results)
+}
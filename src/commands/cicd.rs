@@ -0,0 +1,133 @@
+use anyhow::Result;
+
+use crate::config::Config;
+use crate::formatter;
+use crate::ops::cicd::{self, DeploymentFlags, EventFlags, EventLevel, EventStatus, FailureFlags};
+use crate::util;
+
+/// `pup cicd pipelines create --file pipeline.json` or
+/// `pup cicd pipelines create --name build --status success [--service ... --env ... --git-sha ... --start ... --end ...]`
+#[allow(clippy::too_many_arguments)]
+pub async fn pipelines_create(
+    cfg: &Config,
+    file: Option<&str>,
+    name: Option<&str>,
+    status: Option<&str>,
+    service: Option<&str>,
+    env: Option<&str>,
+    git_sha: Option<&str>,
+    start: Option<&str>,
+    end: Option<&str>,
+) -> Result<()> {
+    let body = match file {
+        Some(file) => util::read_json_file(file)?,
+        None => {
+            let flags = EventFlags {
+                name: name.map(str::to_string).unwrap_or_default(),
+                status: status.map(EventStatus::parse).transpose()?.unwrap_or_default(),
+                service: service.map(str::to_string),
+                env: env.map(str::to_string),
+                git_sha: git_sha.map(str::to_string),
+                start: start.map(str::to_string),
+                end: end.map(str::to_string),
+            };
+            cicd::event_from_flags(EventLevel::Pipeline, &flags)
+        }
+    };
+    let resp = cicd::submit_pipeline(cfg, body).await?;
+    formatter::output(cfg, &resp)
+}
+
+/// `pup cicd events submit --level job --file job.json` or
+/// `pup cicd events submit --level job --name build-image --status success [...]`
+#[allow(clippy::too_many_arguments)]
+pub async fn events_submit(
+    cfg: &Config,
+    level: &str,
+    file: Option<&str>,
+    name: Option<&str>,
+    status: Option<&str>,
+    service: Option<&str>,
+    env: Option<&str>,
+    git_sha: Option<&str>,
+    start: Option<&str>,
+    end: Option<&str>,
+) -> Result<()> {
+    let level = EventLevel::parse(level)?;
+    let body = match file {
+        Some(file) => util::read_json_file(file)?,
+        None => {
+            let flags = EventFlags {
+                name: name.map(str::to_string).unwrap_or_default(),
+                status: status.map(EventStatus::parse).transpose()?.unwrap_or_default(),
+                service: service.map(str::to_string),
+                env: env.map(str::to_string),
+                git_sha: git_sha.map(str::to_string),
+                start: start.map(str::to_string),
+                end: end.map(str::to_string),
+            };
+            cicd::event_from_flags(level, &flags)
+        }
+    };
+    let resp = cicd::submit_event(cfg, body).await?;
+    formatter::output(cfg, &resp)
+}
+
+/// `pup cicd dora create-deployment --file deployment.json` or
+/// `pup cicd dora create-deployment --service ... --env ... [--git-sha ... --start ... --end ...]`
+pub async fn dora_create_deployment(
+    cfg: &Config,
+    file: Option<&str>,
+    service: Option<&str>,
+    env: Option<&str>,
+    version: Option<&str>,
+    git_sha: Option<&str>,
+    start: Option<&str>,
+    end: Option<&str>,
+) -> Result<()> {
+    let body = match file {
+        Some(file) => util::read_json_file(file)?,
+        None => {
+            let flags = DeploymentFlags {
+                service: service.unwrap_or_default().to_string(),
+                env: env.unwrap_or_default().to_string(),
+                version: version.map(str::to_string),
+                git_sha: git_sha.map(str::to_string),
+                started_at: start.map(str::to_string),
+                finished_at: end.map(str::to_string),
+            };
+            cicd::deployment_event_from_flags(&flags)
+        }
+    };
+    let resp = cicd::dora_create_deployment(cfg, body).await?;
+    formatter::output(cfg, &resp)
+}
+
+/// `pup cicd dora create-failure --file failure.json` or
+/// `pup cicd dora create-failure --service ... --env ... [--git-sha ... --start ... --end ...]`
+pub async fn dora_create_failure(
+    cfg: &Config,
+    file: Option<&str>,
+    service: Option<&str>,
+    env: Option<&str>,
+    git_sha: Option<&str>,
+    start: Option<&str>,
+    end: Option<&str>,
+) -> Result<()> {
+    let body = match file {
+        Some(file) => util::read_json_file(file)?,
+        None => {
+            let flags = FailureFlags {
+                service: service.unwrap_or_default().to_string(),
+                env: env.unwrap_or_default().to_string(),
+                git_sha: git_sha.map(str::to_string),
+                deployment_name: None,
+                started_at: start.map(str::to_string),
+                finished_at: end.map(str::to_string),
+            };
+            cicd::failure_event_from_flags(&flags)
+        }
+    };
+    let resp = cicd::dora_create_failure(cfg, body).await?;
+    formatter::output(cfg, &resp)
+}
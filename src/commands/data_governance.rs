@@ -1,11 +1,31 @@
-use anyhow::Result;
+//! `pup sensitive-data-scanner`: read and manage the org's Sensitive Data
+//! Scanner configuration (scanning groups and the rules inside them), plus
+//! `scan`, which runs those same rule regexes locally against files on disk
+//! so the org's configured detections can gate a commit/push before
+//! anything reaches Datadog.
+
+use anyhow::{Context, Result};
 #[cfg(not(target_arch = "wasm32"))]
 use datadog_api_client::datadogV2::api_sensitive_data_scanner::SensitiveDataScannerAPI;
+#[cfg(not(target_arch = "wasm32"))]
+use datadog_api_client::datadogV2::model::{
+    SensitiveDataScannerConfigDeleteRequest, SensitiveDataScannerConfigDeleteRequestData,
+    SensitiveDataScannerConfigDeleteRequestMeta, SensitiveDataScannerConfigRelationship,
+    SensitiveDataScannerConfigRelationshipData, SensitiveDataScannerGroupAttributes,
+    SensitiveDataScannerGroupCreate, SensitiveDataScannerGroupCreateRequest,
+    SensitiveDataScannerGroupType, SensitiveDataScannerGroupUpdate,
+    SensitiveDataScannerGroupUpdateRequest, SensitiveDataScannerRuleAttributes,
+    SensitiveDataScannerRuleCreate, SensitiveDataScannerRuleCreateRequest,
+    SensitiveDataScannerRuleRelationships, SensitiveDataScannerRuleType,
+    SensitiveDataScannerRuleUpdate, SensitiveDataScannerRuleUpdateRequest,
+    SensitiveDataScannerTextReplacement, SensitiveDataScannerTextReplacementType,
+};
 
 #[cfg(not(target_arch = "wasm32"))]
 use crate::client;
 use crate::config::Config;
 use crate::formatter;
+use crate::ops::sds_scan;
 
 #[cfg(not(target_arch = "wasm32"))]
 pub async fn scanner_rules_list(cfg: &Config) -> Result<()> {
@@ -24,3 +44,309 @@ pub async fn scanner_rules_list(cfg: &Config) -> Result<()> {
     let data = crate::api::get(cfg, "/api/v2/sensitive-data-scanner/config", &[]).await?;
     crate::formatter::output(cfg, &data)
 }
+
+// ---------------------------------------------------------------------------
+// Scanning group CRUD
+// ---------------------------------------------------------------------------
+
+#[cfg(not(target_arch = "wasm32"))]
+pub async fn group_create(cfg: &Config, name: &str, description: &str, is_enabled: bool) -> Result<()> {
+    let mut attrs = SensitiveDataScannerGroupAttributes::new().name(name.to_string()).is_enabled(is_enabled);
+    if !description.is_empty() {
+        attrs = attrs.description(description.to_string());
+    }
+    let body = SensitiveDataScannerGroupCreateRequest::new(SensitiveDataScannerGroupCreate::new(
+        attrs,
+        SensitiveDataScannerGroupType::GROUP,
+    ));
+
+    let dd_cfg = client::make_dd_config(cfg);
+    let dd_client = client::make_dd_client(cfg);
+    let api = SensitiveDataScannerAPI::with_client_and_config(dd_cfg, dd_client);
+    let resp = api
+        .create_scanning_group(body)
+        .await
+        .map_err(|e| anyhow::anyhow!("failed to create scanning group: {e:?}"))?;
+    formatter::output(cfg, &resp)
+}
+
+#[cfg(target_arch = "wasm32")]
+pub async fn group_create(cfg: &Config, name: &str, description: &str, is_enabled: bool) -> Result<()> {
+    let mut attrs = serde_json::json!({ "name": name, "is_enabled": is_enabled });
+    if !description.is_empty() {
+        attrs["description"] = serde_json::json!(description);
+    }
+    let body = serde_json::json!({
+        "data": { "type": "sensitive_data_scanner_group", "attributes": attrs, "relationships": {} }
+    });
+    let data = crate::api::post(cfg, "/api/v2/sensitive-data-scanner/config/groups", &body).await?;
+    crate::formatter::output(cfg, &data)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub async fn group_update(cfg: &Config, group_id: &str, name: &str, description: &str) -> Result<()> {
+    let mut attrs = SensitiveDataScannerGroupAttributes::new();
+    if !name.is_empty() {
+        attrs = attrs.name(name.to_string());
+    }
+    if !description.is_empty() {
+        attrs = attrs.description(description.to_string());
+    }
+    let body = SensitiveDataScannerGroupUpdateRequest::new(SensitiveDataScannerGroupUpdate::new(
+        attrs,
+        group_id.to_string(),
+        SensitiveDataScannerGroupType::GROUP,
+    ));
+
+    let dd_cfg = client::make_dd_config(cfg);
+    let dd_client = client::make_dd_client(cfg);
+    let api = SensitiveDataScannerAPI::with_client_and_config(dd_cfg, dd_client);
+    let resp = api
+        .update_scanning_group(group_id.to_string(), body)
+        .await
+        .map_err(|e| anyhow::anyhow!("failed to update scanning group {group_id}: {e:?}"))?;
+    formatter::output(cfg, &resp)
+}
+
+#[cfg(target_arch = "wasm32")]
+pub async fn group_update(cfg: &Config, group_id: &str, name: &str, description: &str) -> Result<()> {
+    let mut attrs = serde_json::json!({});
+    if !name.is_empty() {
+        attrs["name"] = serde_json::json!(name);
+    }
+    if !description.is_empty() {
+        attrs["description"] = serde_json::json!(description);
+    }
+    let body = serde_json::json!({
+        "data": { "type": "sensitive_data_scanner_group", "id": group_id, "attributes": attrs }
+    });
+    let data = crate::api::patch(
+        cfg,
+        &format!("/api/v2/sensitive-data-scanner/config/groups/{group_id}"),
+        &body,
+    )
+    .await?;
+    crate::formatter::output(cfg, &data)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub async fn group_delete(cfg: &Config, group_id: &str) -> Result<()> {
+    let body = SensitiveDataScannerConfigDeleteRequest::new(
+        SensitiveDataScannerConfigDeleteRequestData::new(group_id.to_string(), SensitiveDataScannerGroupType::GROUP),
+        SensitiveDataScannerConfigDeleteRequestMeta::new(),
+    );
+
+    let dd_cfg = client::make_dd_config(cfg);
+    let dd_client = client::make_dd_client(cfg);
+    let api = SensitiveDataScannerAPI::with_client_and_config(dd_cfg, dd_client);
+    api.delete_scanning_group(group_id.to_string(), body)
+        .await
+        .map_err(|e| anyhow::anyhow!("failed to delete scanning group {group_id}: {e:?}"))?;
+    formatter::output_deleted(cfg, "scanning_group", group_id)
+}
+
+#[cfg(target_arch = "wasm32")]
+pub async fn group_delete(cfg: &Config, group_id: &str) -> Result<()> {
+    crate::api::delete(cfg, &format!("/api/v2/sensitive-data-scanner/config/groups/{group_id}")).await?;
+    formatter::output_deleted(cfg, "scanning_group", group_id)
+}
+
+// ---------------------------------------------------------------------------
+// Scanning rule CRUD
+// ---------------------------------------------------------------------------
+
+#[cfg(not(target_arch = "wasm32"))]
+#[allow(clippy::too_many_arguments)]
+pub async fn rule_create(
+    cfg: &Config,
+    group_id: &str,
+    name: &str,
+    pattern: &str,
+    tags: &str,
+    is_enabled: bool,
+) -> Result<()> {
+    let mut attrs = SensitiveDataScannerRuleAttributes::new()
+        .name(name.to_string())
+        .pattern(pattern.to_string())
+        .is_enabled(is_enabled)
+        .text_replacement(SensitiveDataScannerTextReplacement::new().type_(SensitiveDataScannerTextReplacementType::NONE));
+    if !tags.is_empty() {
+        let tag_list: Vec<String> = tags.split(',').map(|t| t.trim().to_string()).filter(|t| !t.is_empty()).collect();
+        attrs = attrs.tags(tag_list);
+    }
+
+    let relationships = SensitiveDataScannerRuleRelationships::new().group(
+        SensitiveDataScannerConfigRelationship::new().data(
+            SensitiveDataScannerConfigRelationshipData::new(group_id.to_string(), SensitiveDataScannerGroupType::GROUP),
+        ),
+    );
+    let body = SensitiveDataScannerRuleCreateRequest::new(SensitiveDataScannerRuleCreate::new(
+        attrs,
+        relationships,
+        SensitiveDataScannerRuleType::RULE,
+    ));
+
+    let dd_cfg = client::make_dd_config(cfg);
+    let dd_client = client::make_dd_client(cfg);
+    let api = SensitiveDataScannerAPI::with_client_and_config(dd_cfg, dd_client);
+    let resp = api
+        .create_scanning_rule(body)
+        .await
+        .map_err(|e| anyhow::anyhow!("failed to create scanning rule: {e:?}"))?;
+    formatter::output(cfg, &resp)
+}
+
+#[cfg(target_arch = "wasm32")]
+pub async fn rule_create(cfg: &Config, group_id: &str, name: &str, pattern: &str, tags: &str, is_enabled: bool) -> Result<()> {
+    let mut attrs = serde_json::json!({
+        "name": name,
+        "pattern": pattern,
+        "is_enabled": is_enabled,
+        "text_replacement": { "type": "none" },
+    });
+    if !tags.is_empty() {
+        let tag_list: Vec<String> = tags.split(',').map(|t| t.trim().to_string()).filter(|t| !t.is_empty()).collect();
+        attrs["tags"] = serde_json::json!(tag_list);
+    }
+    let body = serde_json::json!({
+        "data": {
+            "type": "sensitive_data_scanner_rule",
+            "attributes": attrs,
+            "relationships": {
+                "group": { "data": { "id": group_id, "type": "sensitive_data_scanner_group" } }
+            }
+        }
+    });
+    let data = crate::api::post(cfg, "/api/v2/sensitive-data-scanner/config/rules", &body).await?;
+    crate::formatter::output(cfg, &data)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub async fn rule_update(cfg: &Config, rule_id: &str, name: &str, pattern: &str, tags: &str) -> Result<()> {
+    let mut attrs = SensitiveDataScannerRuleAttributes::new();
+    if !name.is_empty() {
+        attrs = attrs.name(name.to_string());
+    }
+    if !pattern.is_empty() {
+        attrs = attrs.pattern(pattern.to_string());
+    }
+    if !tags.is_empty() {
+        let tag_list: Vec<String> = tags.split(',').map(|t| t.trim().to_string()).filter(|t| !t.is_empty()).collect();
+        attrs = attrs.tags(tag_list);
+    }
+
+    let body = SensitiveDataScannerRuleUpdateRequest::new(SensitiveDataScannerRuleUpdate::new(
+        attrs,
+        rule_id.to_string(),
+        SensitiveDataScannerRuleType::RULE,
+    ));
+
+    let dd_cfg = client::make_dd_config(cfg);
+    let dd_client = client::make_dd_client(cfg);
+    let api = SensitiveDataScannerAPI::with_client_and_config(dd_cfg, dd_client);
+    let resp = api
+        .update_scanning_rule(rule_id.to_string(), body)
+        .await
+        .map_err(|e| anyhow::anyhow!("failed to update scanning rule {rule_id}: {e:?}"))?;
+    formatter::output(cfg, &resp)
+}
+
+#[cfg(target_arch = "wasm32")]
+pub async fn rule_update(cfg: &Config, rule_id: &str, name: &str, pattern: &str, tags: &str) -> Result<()> {
+    let mut attrs = serde_json::json!({});
+    if !name.is_empty() {
+        attrs["name"] = serde_json::json!(name);
+    }
+    if !pattern.is_empty() {
+        attrs["pattern"] = serde_json::json!(pattern);
+    }
+    if !tags.is_empty() {
+        let tag_list: Vec<String> = tags.split(',').map(|t| t.trim().to_string()).filter(|t| !t.is_empty()).collect();
+        attrs["tags"] = serde_json::json!(tag_list);
+    }
+    let body = serde_json::json!({
+        "data": { "type": "sensitive_data_scanner_rule", "id": rule_id, "attributes": attrs }
+    });
+    let data = crate::api::patch(
+        cfg,
+        &format!("/api/v2/sensitive-data-scanner/config/rules/{rule_id}"),
+        &body,
+    )
+    .await?;
+    crate::formatter::output(cfg, &data)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub async fn rule_delete(cfg: &Config, rule_id: &str) -> Result<()> {
+    let body = SensitiveDataScannerConfigDeleteRequest::new(
+        SensitiveDataScannerConfigDeleteRequestData::new(rule_id.to_string(), SensitiveDataScannerRuleType::RULE),
+        SensitiveDataScannerConfigDeleteRequestMeta::new(),
+    );
+
+    let dd_cfg = client::make_dd_config(cfg);
+    let dd_client = client::make_dd_client(cfg);
+    let api = SensitiveDataScannerAPI::with_client_and_config(dd_cfg, dd_client);
+    api.delete_scanning_rule(rule_id.to_string(), body)
+        .await
+        .map_err(|e| anyhow::anyhow!("failed to delete scanning rule {rule_id}: {e:?}"))?;
+    formatter::output_deleted(cfg, "scanning_rule", rule_id)
+}
+
+#[cfg(target_arch = "wasm32")]
+pub async fn rule_delete(cfg: &Config, rule_id: &str) -> Result<()> {
+    crate::api::delete(cfg, &format!("/api/v2/sensitive-data-scanner/config/rules/{rule_id}")).await?;
+    formatter::output_deleted(cfg, "scanning_rule", rule_id)
+}
+
+// ---------------------------------------------------------------------------
+// Local pre-commit-style scan
+// ---------------------------------------------------------------------------
+
+/// `pup sensitive-data-scanner scan <paths...>`: fetch the org's configured
+/// rules and run them against files on disk, reporting every match
+/// (file, line, rule name) and exiting non-zero if any are found — so this
+/// doubles as a pre-push guard without needing a second, hand-maintained
+/// copy of the org's detection rules.
+#[cfg(not(target_arch = "wasm32"))]
+pub async fn scan(cfg: &Config, paths: &[String]) -> Result<()> {
+    let dd_cfg = client::make_dd_config(cfg);
+    let dd_client = client::make_dd_client(cfg);
+    let api = SensitiveDataScannerAPI::with_client_and_config(dd_cfg, dd_client);
+    let resp = api
+        .list_scanning_groups()
+        .await
+        .map_err(|e| anyhow::anyhow!("failed to fetch scanning rules: {e:?}"))?;
+    let raw = serde_json::to_value(&resp).context("failed to serialize scanning-groups response")?;
+    run_scan(cfg, &raw, paths)
+}
+
+#[cfg(target_arch = "wasm32")]
+pub async fn scan(cfg: &Config, paths: &[String]) -> Result<()> {
+    let raw = crate::api::get(cfg, "/api/v2/sensitive-data-scanner/config", &[]).await?;
+    run_scan(cfg, &raw, paths)
+}
+
+fn run_scan(cfg: &Config, raw: &serde_json::Value, paths: &[String]) -> Result<()> {
+    let rules = sds_scan::rules_from_config(raw);
+    let report = sds_scan::scan_paths(&rules, paths)?;
+
+    let found = !report.matches.is_empty();
+    if cfg.agent_mode || matches!(cfg.output_format, crate::config::OutputFormat::Json | crate::config::OutputFormat::Yaml) {
+        formatter::output(cfg, &report)?;
+    } else {
+        for m in &report.matches {
+            println!("{}:{}: {}", m.path, m.line, m.rule_name);
+        }
+        println!(
+            "\nScanned {} file(s) with {} rule(s); {} match(es) found.",
+            report.files_scanned,
+            report.rules_applied,
+            report.matches.len()
+        );
+    }
+
+    if found {
+        std::process::exit(1);
+    }
+    Ok(())
+}
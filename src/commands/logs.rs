@@ -1,4 +1,5 @@
-use anyhow::{bail, Result};
+use anyhow::{bail, Context, Result};
+use serde::Serialize;
 #[cfg(not(target_arch = "wasm32"))]
 use datadog_api_client::datadogV2::api_logs::{ListLogsOptionalParams, LogsAPI};
 #[cfg(not(target_arch = "wasm32"))]
@@ -38,33 +39,25 @@ fn parse_storage_tier(storage: Option<String>) -> Result<Option<LogsStorageTier>
 }
 
 #[cfg(not(target_arch = "wasm32"))]
-pub async fn search(
+async fn search_once(
     cfg: &Config,
-    query: String,
-    from: String,
-    to: String,
+    query: &str,
+    from: &str,
+    to: &str,
     limit: i32,
     storage: Option<String>,
-) -> Result<()> {
-    // Logs search API doesn't support OAuth/bearer - force API keys
-    if !cfg.has_api_keys() {
-        bail!(
-            "logs search requires API+APP key authentication (DD_API_KEY + DD_APP_KEY).\n\
-             This endpoint does not support bearer token auth."
-        );
-    }
-
+) -> Result<datadog_api_client::datadogV2::model::LogsListResponse> {
     let dd_cfg = client::make_dd_config(cfg);
     // Force API key auth only - do NOT use bearer middleware
     let api = LogsAPI::with_config(dd_cfg);
 
-    let from_ms = util::parse_time_to_unix_millis(&from)?;
-    let to_ms = util::parse_time_to_unix_millis(&to)?;
+    let from_ms = util::parse_time_to_unix_millis(from)?;
+    let to_ms = util::parse_time_to_unix_millis(to)?;
 
     let storage_tier = parse_storage_tier(storage)?;
 
     let mut filter = LogsQueryFilter::new()
-        .query(query)
+        .query(query.to_string())
         .from(from_ms.to_string())
         .to(to_ms.to_string());
     if let Some(tier) = storage_tier {
@@ -78,10 +71,48 @@ pub async fn search(
 
     let params = ListLogsOptionalParams::default().body(body);
 
-    let resp = api
-        .list_logs(params)
+    api.list_logs(params)
         .await
-        .map_err(|e| anyhow::anyhow!("failed to search logs: {:?}", e))?;
+        .map_err(|e| anyhow::anyhow!("failed to search logs: {:?}", e))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[allow(clippy::too_many_arguments)]
+pub async fn search(
+    cfg: &Config,
+    query: String,
+    from: String,
+    to: String,
+    limit: i32,
+    storage: Option<String>,
+    watch: Option<u64>,
+    watch_full: bool,
+) -> Result<()> {
+    // Logs search API doesn't support OAuth/bearer - force API keys
+    if !cfg.has_api_keys() {
+        bail!(
+            "logs search requires API+APP key authentication (DD_API_KEY + DD_APP_KEY).\n\
+             This endpoint does not support bearer token auth."
+        );
+    }
+
+    if let Some(interval_secs) = watch {
+        return crate::ops::watch::poll(cfg, interval_secs, watch_full, || {
+            let storage = storage.clone();
+            async {
+                let resp = search_once(cfg, &query, &from, &to, limit, storage).await?;
+                Ok(resp
+                    .data
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|event| serde_json::to_value(event).unwrap_or(serde_json::Value::Null))
+                    .collect())
+            }
+        })
+        .await;
+    }
+
+    let resp = search_once(cfg, &query, &from, &to, limit, storage).await?;
 
     let meta = if cfg.agent_mode {
         let count = resp.data.as_ref().map(|d| d.len());
@@ -114,7 +145,12 @@ pub async fn search(
     to: String,
     limit: i32,
     storage: Option<String>,
+    watch: Option<u64>,
+    _watch_full: bool,
 ) -> Result<()> {
+    if watch.is_some() {
+        bail!("--watch is not available in WASM builds.");
+    }
     let from_ms = util::parse_time_to_unix_millis(&from)?;
     let to_ms = util::parse_time_to_unix_millis(&to)?;
     let mut filter = serde_json::json!({
@@ -135,6 +171,7 @@ pub async fn search(
 }
 
 /// Alias for `search` with the same interface.
+#[allow(clippy::too_many_arguments)]
 pub async fn list(
     cfg: &Config,
     query: String,
@@ -142,11 +179,14 @@ pub async fn list(
     to: String,
     limit: i32,
     storage: Option<String>,
+    watch: Option<u64>,
+    watch_full: bool,
 ) -> Result<()> {
-    search(cfg, query, from, to, limit, storage).await
+    search(cfg, query, from, to, limit, storage, watch, watch_full).await
 }
 
 /// Alias for `search` with the same interface.
+#[allow(clippy::too_many_arguments)]
 pub async fn query(
     cfg: &Config,
     query: String,
@@ -154,8 +194,172 @@ pub async fn query(
     to: String,
     limit: i32,
     storage: Option<String>,
+    watch: Option<u64>,
+    watch_full: bool,
+) -> Result<()> {
+    search(cfg, query, from, to, limit, storage, watch, watch_full).await
+}
+
+/// How far back to re-query on each poll, to tolerate ingestion delay causing
+/// events to land after their timestamp has already scrolled past.
+const FOLLOW_OVERLAP_MS: i64 = 2_000;
+
+/// Cap on the recently-emitted-ID dedup set before we start evicting the oldest entries.
+const FOLLOW_SEEN_CAP: usize = 10_000;
+
+#[cfg(not(target_arch = "wasm32"))]
+pub async fn follow(
+    cfg: &Config,
+    query: String,
+    from: String,
+    storage: Option<String>,
+    interval_secs: u64,
+) -> Result<()> {
+    if !cfg.has_api_keys() {
+        bail!(
+            "logs follow requires API+APP key authentication (DD_API_KEY + DD_APP_KEY).\n\
+             This endpoint does not support bearer token auth."
+        );
+    }
+
+    let dd_cfg = client::make_dd_config(cfg);
+    let api = LogsAPI::with_config(dd_cfg);
+
+    let storage_tier = parse_storage_tier(storage)?;
+
+    let mut last_ts = util::parse_time_to_unix_millis(&from)?;
+    // Oldest-first queue alongside the membership set so we can evict in FIFO order.
+    let mut seen_order: std::collections::VecDeque<String> = std::collections::VecDeque::new();
+    let mut seen: std::collections::HashSet<String> = std::collections::HashSet::new();
+
+    eprintln!("Following logs matching {query:?}... (Ctrl-C to stop)");
+
+    loop {
+        let poll_from = (last_ts - FOLLOW_OVERLAP_MS).max(0);
+
+        let mut filter = LogsQueryFilter::new()
+            .query(query.clone())
+            .from(poll_from.to_string())
+            .to("now".to_string());
+        if let Some(tier) = storage_tier {
+            filter = filter.storage_tier(tier);
+        }
+
+        let body = LogsListRequest::new()
+            .filter(filter)
+            .page(LogsListRequestPage::new().limit(1000))
+            .sort(LogsSort::TIMESTAMP_ASCENDING);
+
+        let params = ListLogsOptionalParams::default().body(body);
+
+        let poll = tokio::select! {
+            resp = api.list_logs(params) => resp,
+            _ = tokio::signal::ctrl_c() => {
+                eprintln!("\nStopped following.");
+                return Ok(());
+            }
+        };
+
+        let resp = poll.map_err(|e| anyhow::anyhow!("failed to poll logs: {:?}", e))?;
+
+        if let Some(events) = resp.data {
+            for event in events {
+                let id = match &event.id {
+                    Some(id) => id.clone(),
+                    None => continue,
+                };
+                if seen.contains(&id) {
+                    continue;
+                }
+
+                if let Some(ts) = event
+                    .attributes
+                    .as_ref()
+                    .and_then(|a| a.timestamp.as_ref())
+                {
+                    last_ts = last_ts.max(ts.timestamp_millis());
+                }
+
+                formatter::format_and_print(&event, &cfg.output_format, cfg.agent_mode, None)?;
+
+                seen_order.push_back(id.clone());
+                seen.insert(id);
+                while seen_order.len() > FOLLOW_SEEN_CAP {
+                    if let Some(oldest) = seen_order.pop_front() {
+                        seen.remove(&oldest);
+                    }
+                }
+            }
+        }
+
+        tokio::select! {
+            _ = tokio::time::sleep(std::time::Duration::from_secs(interval_secs)) => {},
+            _ = tokio::signal::ctrl_c() => {
+                eprintln!("\nStopped following.");
+                return Ok(());
+            }
+        }
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+pub async fn follow(
+    cfg: &Config,
+    query: String,
+    from: String,
+    storage: Option<String>,
+    interval_secs: u64,
 ) -> Result<()> {
-    search(cfg, query, from, to, limit, storage).await
+    let mut last_ts = util::parse_time_to_unix_millis(&from)?;
+    let mut seen_order: std::collections::VecDeque<String> = std::collections::VecDeque::new();
+    let mut seen: std::collections::HashSet<String> = std::collections::HashSet::new();
+
+    loop {
+        let poll_from = (last_ts - FOLLOW_OVERLAP_MS).max(0);
+        let mut filter = serde_json::json!({
+            "query": query,
+            "from": poll_from.to_string(),
+            "to": "now"
+        });
+        if let Some(tier) = storage.clone() {
+            filter["storage_tier"] = serde_json::Value::String(tier);
+        }
+        let body = serde_json::json!({
+            "filter": filter,
+            "page": { "limit": 1000 },
+            "sort": "timestamp"
+        });
+        let resp = crate::api::post(cfg, "/api/v2/logs/events/search", &body).await?;
+
+        if let Some(events) = resp["data"].as_array() {
+            for event in events {
+                let id = match event["id"].as_str() {
+                    Some(id) => id.to_string(),
+                    None => continue,
+                };
+                if seen.contains(&id) {
+                    continue;
+                }
+                if let Some(ts) = event["attributes"]["timestamp"].as_str() {
+                    if let Ok(parsed) = util::parse_time_to_unix_millis(ts) {
+                        last_ts = last_ts.max(parsed);
+                    }
+                }
+
+                crate::formatter::output(cfg, event)?;
+
+                seen_order.push_back(id.clone());
+                seen.insert(id);
+                while seen_order.len() > FOLLOW_SEEN_CAP {
+                    if let Some(oldest) = seen_order.pop_front() {
+                        seen.remove(&oldest);
+                    }
+                }
+            }
+        }
+
+        gloo_timers::future::TimeoutFuture::new((interval_secs * 1000) as u32).await;
+    }
 }
 
 #[cfg(not(target_arch = "wasm32"))]
@@ -228,6 +432,251 @@ pub async fn aggregate(
     crate::formatter::output(cfg, &data)
 }
 
+/// Page size used when walking the full result set for `export`.
+const EXPORT_PAGE_LIMIT: i32 = 1000;
+
+#[cfg(not(target_arch = "wasm32"))]
+pub async fn export(
+    cfg: &Config,
+    query: String,
+    from: String,
+    to: String,
+    out: String,
+    format: Option<String>,
+    max: Option<u64>,
+    start_cursor: Option<String>,
+) -> Result<()> {
+    use std::io::Write;
+
+    if !cfg.has_api_keys() {
+        bail!(
+            "logs export requires API+APP key authentication (DD_API_KEY + DD_APP_KEY).\n\
+             This endpoint does not support bearer token auth."
+        );
+    }
+
+    let dd_cfg = client::make_dd_config(cfg);
+    let api = LogsAPI::with_config(dd_cfg);
+
+    let from_ms = util::parse_time_to_unix_millis(&from)?;
+    let to_ms = util::parse_time_to_unix_millis(&to)?;
+
+    let filter = LogsQueryFilter::new()
+        .query(query)
+        .from(from_ms.to_string())
+        .to(to_ms.to_string());
+
+    let is_parquet = format.as_deref() == Some("parquet");
+    let mut writer = ExportWriter::new(&out, is_parquet)?;
+
+    let mut cursor = start_cursor;
+    let mut written: u64 = 0;
+
+    loop {
+        let mut page = LogsListRequestPage::new().limit(EXPORT_PAGE_LIMIT);
+        if let Some(c) = &cursor {
+            page = page.cursor(c.clone());
+        }
+
+        let body = LogsListRequest::new()
+            .filter(filter.clone())
+            .page(page)
+            .sort(LogsSort::TIMESTAMP_ASCENDING);
+
+        let params = ListLogsOptionalParams::default().body(body);
+
+        let resp = api
+            .list_logs(params)
+            .await
+            .map_err(|e| anyhow::anyhow!("failed to export logs: {:?}", e))?;
+
+        let events = resp.data.unwrap_or_default();
+        if events.is_empty() {
+            break;
+        }
+
+        for event in &events {
+            writer.write_event(event)?;
+            written += 1;
+            if let Some(max) = max {
+                if written >= max {
+                    writer.finish()?;
+                    eprintln!("Exported {written} events (reached --max). Wrote to {out}");
+                    return Ok(());
+                }
+            }
+        }
+
+        cursor = resp
+            .meta
+            .as_ref()
+            .and_then(|m| m.page.as_ref())
+            .and_then(|p| p.after.clone());
+
+        eprintln!(
+            "... {written} events written, last cursor: {}",
+            cursor.as_deref().unwrap_or("(none)")
+        );
+
+        if cursor.is_none() {
+            break;
+        }
+    }
+
+    writer.finish()?;
+    eprintln!("Exported {written} events. Wrote to {out}");
+    Ok(())
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+enum ExportWriter {
+    Ndjson(std::io::BufWriter<std::fs::File>),
+    Parquet(ParquetLogWriter),
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl ExportWriter {
+    fn new(path: &str, parquet: bool) -> Result<Self> {
+        if parquet {
+            Ok(ExportWriter::Parquet(ParquetLogWriter::new(path)?))
+        } else {
+            use std::io::Write as _;
+            let file = std::fs::File::create(path)
+                .with_context(|| format!("failed to create export file: {path}"))?;
+            let mut w = std::io::BufWriter::new(file);
+            w.flush()?;
+            Ok(ExportWriter::Ndjson(w))
+        }
+    }
+
+    fn write_event(
+        &mut self,
+        event: &datadog_api_client::datadogV2::model::Log,
+    ) -> Result<()> {
+        match self {
+            ExportWriter::Ndjson(w) => {
+                use std::io::Write as _;
+                let line = serde_json::to_string(event)?;
+                writeln!(w, "{line}")?;
+                Ok(())
+            }
+            ExportWriter::Parquet(w) => w.write_event(event),
+        }
+    }
+
+    fn finish(self) -> Result<()> {
+        match self {
+            ExportWriter::Ndjson(mut w) => {
+                use std::io::Write as _;
+                w.flush()?;
+                Ok(())
+            }
+            ExportWriter::Parquet(w) => w.finish(),
+        }
+    }
+}
+
+/// Columnar writer keyed on the common log fields, with the rest of the
+/// event's attributes flattened into a single JSON-encoded `attributes` column.
+#[cfg(not(target_arch = "wasm32"))]
+struct ParquetLogWriter {
+    path: String,
+    timestamps: Vec<i64>,
+    services: Vec<String>,
+    statuses: Vec<String>,
+    hosts: Vec<String>,
+    messages: Vec<String>,
+    attributes: Vec<String>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl ParquetLogWriter {
+    fn new(path: &str) -> Result<Self> {
+        Ok(Self {
+            path: path.to_string(),
+            timestamps: Vec::new(),
+            services: Vec::new(),
+            statuses: Vec::new(),
+            hosts: Vec::new(),
+            messages: Vec::new(),
+            attributes: Vec::new(),
+        })
+    }
+
+    fn write_event(&mut self, event: &datadog_api_client::datadogV2::model::Log) -> Result<()> {
+        let attrs = event.attributes.as_ref();
+        self.timestamps.push(
+            attrs
+                .and_then(|a| a.timestamp.as_ref())
+                .map(|t| t.timestamp_millis())
+                .unwrap_or(0),
+        );
+        self.services
+            .push(attrs.and_then(|a| a.service.clone()).unwrap_or_default());
+        self.statuses.push(
+            attrs
+                .and_then(|a| a.status.clone())
+                .unwrap_or_default(),
+        );
+        self.hosts
+            .push(attrs.and_then(|a| a.host.clone()).unwrap_or_default());
+        self.messages
+            .push(attrs.and_then(|a| a.message.clone()).unwrap_or_default());
+        self.attributes
+            .push(serde_json::to_string(&attrs.and_then(|a| a.attributes.clone())).unwrap_or_default());
+        Ok(())
+    }
+
+    fn finish(self) -> Result<()> {
+        use arrow::array::{Int64Array, StringArray};
+        use arrow::datatypes::{DataType, Field, Schema};
+        use arrow::record_batch::RecordBatch;
+        use parquet::arrow::ArrowWriter;
+
+        let schema = std::sync::Arc::new(Schema::new(vec![
+            Field::new("timestamp", DataType::Int64, false),
+            Field::new("service", DataType::Utf8, true),
+            Field::new("status", DataType::Utf8, true),
+            Field::new("host", DataType::Utf8, true),
+            Field::new("message", DataType::Utf8, true),
+            Field::new("attributes", DataType::Utf8, true),
+        ]));
+
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                std::sync::Arc::new(Int64Array::from(self.timestamps)),
+                std::sync::Arc::new(StringArray::from(self.services)),
+                std::sync::Arc::new(StringArray::from(self.statuses)),
+                std::sync::Arc::new(StringArray::from(self.hosts)),
+                std::sync::Arc::new(StringArray::from(self.messages)),
+                std::sync::Arc::new(StringArray::from(self.attributes)),
+            ],
+        )?;
+
+        let file = std::fs::File::create(&self.path)
+            .with_context(|| format!("failed to create parquet file: {}", self.path))?;
+        let mut writer = ArrowWriter::try_new(file, schema, None)?;
+        writer.write(&batch)?;
+        writer.close()?;
+        Ok(())
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+pub async fn export(
+    _cfg: &Config,
+    _query: String,
+    _from: String,
+    _to: String,
+    _out: String,
+    _format: Option<String>,
+    _max: Option<u64>,
+    _start_cursor: Option<String>,
+) -> Result<()> {
+    bail!("logs export is not available in WASM builds — it writes to the local filesystem")
+}
+
 #[cfg(not(target_arch = "wasm32"))]
 pub async fn archives_list(cfg: &Config) -> Result<()> {
     if !cfg.has_api_keys() {
@@ -283,6 +732,260 @@ pub async fn archives_get(cfg: &Config, archive_id: &str) -> Result<()> {
     crate::formatter::output(cfg, &data)
 }
 
+/// Flags describing an archive destination, shared by `archives_create` and
+/// `archives_update`. Mirrors the subset of `LogsArchiveDestination` variants
+/// `pup` supports provisioning from the CLI.
+pub struct ArchiveDestinationArgs {
+    pub dest_type: String,
+    pub bucket: String,
+    pub path_prefix: Option<String>,
+    pub integration_id: Option<String>,
+    pub client_email: Option<String>,
+    pub project_id: Option<String>,
+    pub tenant_id: Option<String>,
+    pub client_id: Option<String>,
+    pub storage_account: Option<String>,
+    pub container: Option<String>,
+}
+
+/// Load an archive definition from a YAML/JSON file, same approach as the
+/// aliases module's `import`: JSON if the extension says so, YAML otherwise.
+#[cfg(not(target_arch = "wasm32"))]
+fn read_archive_def<T: serde::de::DeserializeOwned>(path: &str) -> Result<T> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read archive definition: {path}"))?;
+    if path.ends_with(".json") {
+        serde_json::from_str(&contents)
+            .with_context(|| format!("failed to parse JSON from {path}"))
+    } else {
+        serde_yaml::from_str(&contents)
+            .with_context(|| format!("failed to parse YAML from {path}"))
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn build_archive_request(
+    name: &str,
+    query: Option<String>,
+    include_tags: bool,
+    rehydration_tags: Vec<String>,
+    dest: &ArchiveDestinationArgs,
+) -> Result<datadog_api_client::datadogV2::model::LogsArchiveCreateRequest> {
+    use datadog_api_client::datadogV2::model::{
+        LogsArchiveCreateAttributes, LogsArchiveCreateRequest, LogsArchiveDestination,
+        LogsArchiveDestinationAzure, LogsArchiveDestinationAzureIntegration,
+        LogsArchiveDestinationGCS, LogsArchiveDestinationGCSIntegration,
+        LogsArchiveDestinationS3, LogsArchiveDestinationS3Integration, LogsArchiveDestinationType,
+    };
+
+    let destination = match dest.dest_type.as_str() {
+        "s3" => {
+            let account_id = dest
+                .integration_id
+                .clone()
+                .ok_or_else(|| anyhow::anyhow!("--integration-id (AWS account/role) is required for --type s3"))?;
+            LogsArchiveDestination::LogsArchiveDestinationS3(Box::new(
+                LogsArchiveDestinationS3::new(
+                    dest.bucket.clone(),
+                    LogsArchiveDestinationS3Integration::new(account_id),
+                    LogsArchiveDestinationType::S3,
+                )
+                .path(dest.path_prefix.clone().unwrap_or_default()),
+            ))
+        }
+        "gcs" => {
+            let client_email = dest
+                .client_email
+                .clone()
+                .ok_or_else(|| anyhow::anyhow!("--client-email is required for --type gcs"))?;
+            let project_id = dest
+                .project_id
+                .clone()
+                .ok_or_else(|| anyhow::anyhow!("--project-id is required for --type gcs"))?;
+            LogsArchiveDestination::LogsArchiveDestinationGCS(Box::new(
+                LogsArchiveDestinationGCS::new(
+                    dest.bucket.clone(),
+                    LogsArchiveDestinationGCSIntegration::new(client_email, project_id),
+                    LogsArchiveDestinationType::GCS,
+                )
+                .path(dest.path_prefix.clone().unwrap_or_default()),
+            ))
+        }
+        "azure" => {
+            let tenant = dest
+                .tenant_id
+                .clone()
+                .ok_or_else(|| anyhow::anyhow!("--tenant-id is required for --type azure"))?;
+            let client_id = dest
+                .client_id
+                .clone()
+                .ok_or_else(|| anyhow::anyhow!("--client-id is required for --type azure"))?;
+            let storage_account = dest
+                .storage_account
+                .clone()
+                .ok_or_else(|| anyhow::anyhow!("--storage-account is required for --type azure"))?;
+            let container = dest
+                .container
+                .clone()
+                .unwrap_or_else(|| dest.bucket.clone());
+            LogsArchiveDestination::LogsArchiveDestinationAzure(Box::new(
+                LogsArchiveDestinationAzure::new(
+                    container,
+                    LogsArchiveDestinationAzureIntegration::new(client_id, tenant),
+                    storage_account,
+                    LogsArchiveDestinationType::AZURE,
+                )
+                .path(dest.path_prefix.clone().unwrap_or_default()),
+            ))
+        }
+        other => bail!("unknown --type {other:?}; expected one of: s3, gcs, azure"),
+    };
+
+    let mut attrs = LogsArchiveCreateAttributes::new(destination, name.to_string())
+        .include_tags(include_tags)
+        .rehydration_tags(rehydration_tags);
+    if let Some(q) = query {
+        attrs = attrs.query(q);
+    }
+
+    Ok(LogsArchiveCreateRequest::new(attrs))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub async fn archives_create(
+    cfg: &Config,
+    name: &str,
+    query: Option<String>,
+    include_tags: bool,
+    rehydration_tags: Vec<String>,
+    dest: &ArchiveDestinationArgs,
+    from_file: Option<&str>,
+) -> Result<()> {
+    if !cfg.has_api_keys() {
+        bail!(
+            "logs archives create requires API key authentication (DD_API_KEY + DD_APP_KEY).\n\
+             This endpoint does not support bearer token auth."
+        );
+    }
+
+    let dd_cfg = client::make_dd_config(cfg);
+    let api = LogsArchivesAPI::with_config(dd_cfg);
+
+    let body = match from_file {
+        // Reuse the aliases module's pattern: version-controllable YAML/JSON definitions.
+        Some(path) => read_archive_def(path)?,
+        None => build_archive_request(name, query, include_tags, rehydration_tags, dest)?,
+    };
+
+    let resp = api
+        .create_logs_archive(body)
+        .await
+        .map_err(|e| anyhow::anyhow!("failed to create log archive: {:?}", e))?;
+
+    formatter::output(cfg, &resp)
+}
+
+#[cfg(target_arch = "wasm32")]
+pub async fn archives_create(
+    cfg: &Config,
+    name: &str,
+    query: Option<String>,
+    include_tags: bool,
+    rehydration_tags: Vec<String>,
+    from_file: Option<&str>,
+) -> Result<()> {
+    let mut body = match from_file {
+        Some(path) => util::read_json_file(path)?,
+        None => serde_json::json!({
+            "data": {
+                "type": "archives",
+                "attributes": {
+                    "name": name,
+                    "include_tags": include_tags,
+                    "rehydration_tags": rehydration_tags,
+                }
+            }
+        }),
+    };
+    if let Some(q) = query {
+        body["data"]["attributes"]["query"] = serde_json::Value::String(q);
+    }
+    let data = crate::api::post(cfg, "/api/v2/logs/config/archives", &body).await?;
+    crate::formatter::output(cfg, &data)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub async fn archives_update(
+    cfg: &Config,
+    archive_id: &str,
+    name: &str,
+    query: Option<String>,
+    include_tags: bool,
+    rehydration_tags: Vec<String>,
+    dest: &ArchiveDestinationArgs,
+    from_file: Option<&str>,
+) -> Result<()> {
+    use datadog_api_client::datadogV2::model::LogsArchive;
+
+    if !cfg.has_api_keys() {
+        bail!(
+            "logs archives update requires API key authentication (DD_API_KEY + DD_APP_KEY).\n\
+             This endpoint does not support bearer token auth."
+        );
+    }
+
+    let dd_cfg = client::make_dd_config(cfg);
+    let api = LogsArchivesAPI::with_config(dd_cfg);
+
+    let body: LogsArchive = match from_file {
+        Some(path) => read_archive_def(path)?,
+        None => {
+            let create_req =
+                build_archive_request(name, query, include_tags, rehydration_tags, dest)?;
+            LogsArchive::new(*create_req.data)
+        }
+    };
+
+    let resp = api
+        .update_logs_archive(archive_id.to_string(), body)
+        .await
+        .map_err(|e| anyhow::anyhow!("failed to update log archive: {:?}", e))?;
+
+    formatter::output(cfg, &resp)
+}
+
+#[cfg(target_arch = "wasm32")]
+pub async fn archives_update(
+    cfg: &Config,
+    archive_id: &str,
+    name: &str,
+    query: Option<String>,
+    include_tags: bool,
+    rehydration_tags: Vec<String>,
+    from_file: Option<&str>,
+) -> Result<()> {
+    let mut body = match from_file {
+        Some(path) => util::read_json_file(path)?,
+        None => serde_json::json!({
+            "data": {
+                "type": "archives",
+                "id": archive_id,
+                "attributes": {
+                    "name": name,
+                    "include_tags": include_tags,
+                    "rehydration_tags": rehydration_tags,
+                }
+            }
+        }),
+    };
+    if let Some(q) = query {
+        body["data"]["attributes"]["query"] = serde_json::Value::String(q);
+    }
+    let path = format!("/api/v2/logs/config/archives/{archive_id}");
+    let data = crate::api::put(cfg, &path, &body).await?;
+    crate::formatter::output(cfg, &data)
+}
+
 #[cfg(not(target_arch = "wasm32"))]
 pub async fn archives_delete(cfg: &Config, archive_id: &str) -> Result<()> {
     if !cfg.has_api_keys() {
@@ -449,6 +1152,260 @@ pub async fn metrics_delete(cfg: &Config, metric_id: &str) -> Result<()> {
     Ok(())
 }
 
+// ---------------------------------------------------------------------------
+// Batch delete — glob-match against a list_* endpoint, confirm, then delete each
+// ---------------------------------------------------------------------------
+
+/// Minimal glob matcher supporting `*` (any run of characters) and `?` (single
+/// character). Good enough for name/ID matching without pulling in a crate.
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let p: Vec<char> = pattern.chars().collect();
+    let t: Vec<char> = text.chars().collect();
+    fn go(p: &[char], t: &[char]) -> bool {
+        match p.first() {
+            None => t.is_empty(),
+            Some('*') => go(&p[1..], t) || (!t.is_empty() && go(p, &t[1..])),
+            Some('?') => !t.is_empty() && go(&p[1..], &t[1..]),
+            Some(&c) => t.first() == Some(&c) && go(&p[1..], &t[1..]),
+        }
+    }
+    go(&p, &t)
+}
+
+/// Summary of a batch-delete run across many items of the same resource kind.
+#[derive(Debug, Serialize)]
+pub struct BatchDeleteSummary {
+    pub matched: usize,
+    pub deleted: Vec<String>,
+    pub failed: Vec<(String, String)>,
+    pub dry_run: bool,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub async fn metrics_delete_matching(
+    cfg: &Config,
+    name_glob: &str,
+    yes: bool,
+    dry_run: bool,
+) -> Result<BatchDeleteSummary> {
+    if !cfg.has_api_keys() {
+        bail!(
+            "logs metrics delete requires API key authentication (DD_API_KEY + DD_APP_KEY).\n\
+             This endpoint does not support bearer token auth."
+        );
+    }
+
+    let dd_cfg = client::make_dd_config(cfg);
+    let api = LogsMetricsAPI::with_config(dd_cfg);
+
+    let resp = api
+        .list_logs_metrics()
+        .await
+        .map_err(|e| anyhow::anyhow!("failed to list log-based metrics: {:?}", e))?;
+
+    let ids: Vec<String> = resp
+        .data
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|m| m.id)
+        .filter(|id| glob_match(name_glob, id))
+        .collect();
+
+    println!("Matched {} log-based metric(s):", ids.len());
+    for id in &ids {
+        println!("  - {id}");
+    }
+
+    if dry_run || ids.is_empty() {
+        return Ok(BatchDeleteSummary {
+            matched: ids.len(),
+            deleted: vec![],
+            failed: vec![],
+            dry_run,
+        });
+    }
+
+    if !yes {
+        bail!("refusing to delete {} metric(s) without --yes (or pass --dry-run to preview)", ids.len());
+    }
+
+    let dd_cfg = client::make_dd_config(cfg);
+    let api = LogsMetricsAPI::with_config(dd_cfg);
+
+    let mut deleted = Vec::new();
+    let mut failed = Vec::new();
+    for id in ids {
+        match api.delete_logs_metric(id.clone()).await {
+            Ok(_) => deleted.push(id),
+            Err(e) => failed.push((id, format!("{e:?}"))),
+        }
+    }
+
+    println!("Deleted {} metric(s), {} failed.", deleted.len(), failed.len());
+    Ok(BatchDeleteSummary {
+        matched: deleted.len() + failed.len(),
+        deleted,
+        failed,
+        dry_run,
+    })
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub async fn archives_delete_matching(
+    cfg: &Config,
+    name_glob: &str,
+    yes: bool,
+    dry_run: bool,
+) -> Result<BatchDeleteSummary> {
+    if !cfg.has_api_keys() {
+        bail!(
+            "logs archives delete requires API key authentication (DD_API_KEY + DD_APP_KEY).\n\
+             This endpoint does not support bearer token auth."
+        );
+    }
+
+    let dd_cfg = client::make_dd_config(cfg);
+    let api = LogsArchivesAPI::with_config(dd_cfg);
+
+    let resp = api
+        .list_logs_archives()
+        .await
+        .map_err(|e| anyhow::anyhow!("failed to list log archives: {:?}", e))?;
+
+    let matches: Vec<(String, String)> = resp
+        .data
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|a| {
+            let id = a.id?;
+            let name = a.attributes.and_then(|attrs| attrs.name).unwrap_or_default();
+            Some((id, name))
+        })
+        .filter(|(id, name)| glob_match(name_glob, name) || glob_match(name_glob, id))
+        .collect();
+
+    println!("Matched {} archive(s):", matches.len());
+    for (id, name) in &matches {
+        println!("  - {name} ({id})");
+    }
+
+    if dry_run || matches.is_empty() {
+        return Ok(BatchDeleteSummary {
+            matched: matches.len(),
+            deleted: vec![],
+            failed: vec![],
+            dry_run,
+        });
+    }
+
+    if !yes {
+        bail!(
+            "refusing to delete {} archive(s) without --yes (or pass --dry-run to preview)",
+            matches.len()
+        );
+    }
+
+    let dd_cfg = client::make_dd_config(cfg);
+    let api = LogsArchivesAPI::with_config(dd_cfg);
+
+    let mut deleted = Vec::new();
+    let mut failed = Vec::new();
+    for (id, _name) in matches {
+        match api.delete_logs_archive(id.clone()).await {
+            Ok(_) => deleted.push(id),
+            Err(e) => failed.push((id, format!("{e:?}"))),
+        }
+    }
+
+    println!("Deleted {} archive(s), {} failed.", deleted.len(), failed.len());
+    Ok(BatchDeleteSummary {
+        matched: deleted.len() + failed.len(),
+        deleted,
+        failed,
+        dry_run,
+    })
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub async fn custom_destinations_delete_matching(
+    cfg: &Config,
+    name_glob: &str,
+    yes: bool,
+    dry_run: bool,
+) -> Result<BatchDeleteSummary> {
+    if !cfg.has_api_keys() {
+        bail!(
+            "logs custom-destinations delete requires API key authentication (DD_API_KEY + DD_APP_KEY).\n\
+             This endpoint does not support bearer token auth."
+        );
+    }
+
+    let dd_cfg = client::make_dd_config(cfg);
+    let api = LogsCustomDestinationsAPI::with_config(dd_cfg);
+
+    let resp = api
+        .list_logs_custom_destinations()
+        .await
+        .map_err(|e| anyhow::anyhow!("failed to list custom destinations: {:?}", e))?;
+
+    let matches: Vec<(String, String)> = resp
+        .data
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|d| {
+            let id = d.id?;
+            let name = d.attributes.and_then(|attrs| attrs.name).unwrap_or_default();
+            Some((id, name))
+        })
+        .filter(|(id, name)| glob_match(name_glob, name) || glob_match(name_glob, id))
+        .collect();
+
+    println!("Matched {} custom destination(s):", matches.len());
+    for (id, name) in &matches {
+        println!("  - {name} ({id})");
+    }
+
+    if dry_run || matches.is_empty() {
+        return Ok(BatchDeleteSummary {
+            matched: matches.len(),
+            deleted: vec![],
+            failed: vec![],
+            dry_run,
+        });
+    }
+
+    if !yes {
+        bail!(
+            "refusing to delete {} custom destination(s) without --yes (or pass --dry-run to preview)",
+            matches.len()
+        );
+    }
+
+    let dd_cfg = client::make_dd_config(cfg);
+    let api = LogsCustomDestinationsAPI::with_config(dd_cfg);
+
+    let mut deleted = Vec::new();
+    let mut failed = Vec::new();
+    for (id, _name) in matches {
+        match api.delete_logs_custom_destination(id.clone()).await {
+            Ok(_) => deleted.push(id),
+            Err(e) => failed.push((id, format!("{e:?}"))),
+        }
+    }
+
+    println!(
+        "Deleted {} custom destination(s), {} failed.",
+        deleted.len(),
+        failed.len()
+    );
+    Ok(BatchDeleteSummary {
+        matched: deleted.len() + failed.len(),
+        deleted,
+        failed,
+        dry_run,
+    })
+}
+
 // ---------------------------------------------------------------------------
 // Restriction Queries (raw HTTP - not available in typed client)
 // ---------------------------------------------------------------------------
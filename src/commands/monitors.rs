@@ -0,0 +1,281 @@
+//! `pup monitors`: read and, since this chunk, incident-response-usable
+//! write commands for Datadog V1 monitors.
+
+use anyhow::{bail, Context, Result};
+#[cfg(not(target_arch = "wasm32"))]
+use datadog_api_client::datadogV1::api_monitors::{ListMonitorsOptionalParams, MonitorsAPI};
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::client;
+use crate::config::Config;
+use crate::formatter;
+
+// ---------------------------------------------------------------------------
+// List monitors
+// ---------------------------------------------------------------------------
+
+#[cfg(not(target_arch = "wasm32"))]
+pub async fn list(cfg: &Config, name: Option<String>, tags: Option<String>, limit: i32, all: bool) -> Result<()> {
+    let dd_cfg = client::make_dd_config(cfg);
+
+    let api = if let Some(http_client) = client::make_bearer_client(cfg) {
+        MonitorsAPI::with_client_and_config(dd_cfg, http_client)
+    } else {
+        MonitorsAPI::with_config(dd_cfg)
+    };
+
+    let limit = limit.clamp(1, 1000);
+
+    if all {
+        let budget = crate::ops::pagination::RequestBudget::unbounded();
+        let outcome = crate::ops::pagination::paginate_pages(
+            &budget,
+            limit as i64,
+            crate::ops::pagination::DEFAULT_MAX_PAGES,
+            |monitor| monitor.id,
+            |page| {
+                let mut params = ListMonitorsOptionalParams::default();
+                if let Some(name) = name.clone() {
+                    params = params.name(name);
+                }
+                if let Some(tags) = tags.clone() {
+                    params = params.monitor_tags(tags);
+                }
+                params = params.page_size(limit).page(page as i32);
+                let api = &api;
+                async move {
+                    api.list_monitors(params)
+                        .await
+                        .map_err(|e| formatter::datadog_error("failed to list monitors", e))
+                }
+            },
+        )
+        .await?;
+        return formatter::output(cfg, serde_json::json!({ "data": outcome.items, "truncated": outcome.truncated }));
+    }
+
+    let mut params = ListMonitorsOptionalParams::default();
+    if let Some(name) = name {
+        params = params.name(name);
+    }
+    if let Some(tags) = tags {
+        params = params.monitor_tags(tags);
+    }
+    params = params.page_size(limit).page(0);
+
+    let monitors = api
+        .list_monitors(params)
+        .await
+        .map_err(|e| formatter::datadog_error("failed to list monitors", e))?;
+
+    let monitors: Vec<_> = monitors.into_iter().take(limit as usize).collect();
+    formatter::output(cfg, &monitors)
+}
+
+#[cfg(target_arch = "wasm32")]
+pub async fn list(cfg: &Config, name: Option<String>, tags: Option<String>, limit: i32, all: bool) -> Result<()> {
+    let limit = limit.clamp(1, 1000);
+
+    if all {
+        let budget = crate::ops::pagination::RequestBudget::unbounded();
+        let outcome = crate::ops::pagination::fetch_all(&budget, None, |cursor| {
+            let offset = match cursor {
+                Some(crate::ops::pagination::PageCursor::Offset(o)) => o,
+                _ => 0,
+            };
+            let page_number = offset / (limit.max(1) as u64) + 1;
+            let mut query: Vec<(&str, String)> =
+                vec![("page[size]", limit.to_string()), ("page[number]", page_number.to_string())];
+            if let Some(name) = name.clone() {
+                query.push(("name", name));
+            }
+            if let Some(tags) = tags.clone() {
+                query.push(("monitor_tags", tags));
+            }
+            async move { crate::api::get(cfg, "/api/v1/monitor", &query).await }
+        })
+        .await?;
+        return crate::formatter::output(cfg, serde_json::json!({ "data": outcome.items, "truncated": outcome.truncated }));
+    }
+
+    let mut query: Vec<(&str, String)> = vec![("page[size]".into(), limit.to_string()).into()];
+    if let Some(name) = name {
+        query.push(("name", name));
+    }
+    if let Some(tags) = tags {
+        query.push(("monitor_tags", tags));
+    }
+    let data = crate::api::get(cfg, "/api/v1/monitor", &query).await?;
+    crate::formatter::output(cfg, &data)
+}
+
+// ---------------------------------------------------------------------------
+// Get a single monitor's full definition
+// ---------------------------------------------------------------------------
+
+#[cfg(not(target_arch = "wasm32"))]
+pub async fn get(cfg: &Config, monitor_id: i64) -> Result<()> {
+    let dd_cfg = client::make_dd_config(cfg);
+    let api = if let Some(http_client) = client::make_bearer_client(cfg) {
+        MonitorsAPI::with_client_and_config(dd_cfg, http_client)
+    } else {
+        MonitorsAPI::with_config(dd_cfg)
+    };
+
+    let monitor = api
+        .get_monitor(monitor_id, Default::default())
+        .await
+        .map_err(|e| formatter::datadog_error(&format!("failed to get monitor {monitor_id}"), e))?;
+    formatter::output(cfg, &monitor)
+}
+
+#[cfg(target_arch = "wasm32")]
+pub async fn get(cfg: &Config, monitor_id: i64) -> Result<()> {
+    let data = crate::api::get(cfg, &format!("/api/v1/monitor/{monitor_id}"), &[]).await?;
+    crate::formatter::output(cfg, &data)
+}
+
+// ---------------------------------------------------------------------------
+// Mute / unmute / resolve — incident-response state changes
+// ---------------------------------------------------------------------------
+
+/// A `pup monitors control <id> <action>` action. `MuteUntil` carries the
+/// silence expiry as a unix timestamp, the same shape the V1 mute endpoint's
+/// `end` field expects.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MonitorAction {
+    Mute,
+    Unmute,
+    Resolve,
+    MuteUntil(i64),
+}
+
+impl MonitorAction {
+    /// Parse `action` plus an optional trailing `<timestamp>` the same way
+    /// `monitors control <id> mute-until <timestamp>` is typed.
+    pub fn parse(action: &str, arg: Option<&str>) -> Result<MonitorAction> {
+        match action {
+            "mute" => Ok(MonitorAction::Mute),
+            "unmute" => Ok(MonitorAction::Unmute),
+            "resolve" => Ok(MonitorAction::Resolve),
+            "mute-until" => {
+                let ts = arg
+                    .context("mute-until requires a unix timestamp argument")?
+                    .parse::<i64>()
+                    .context("mute-until timestamp must be an integer unix timestamp")?;
+                Ok(MonitorAction::MuteUntil(ts))
+            }
+            other => bail!("unknown monitor action '{other}' (expected mute, unmute, resolve, or mute-until)"),
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            MonitorAction::Mute => "mute",
+            MonitorAction::Unmute => "unmute",
+            MonitorAction::Resolve => "resolve",
+            MonitorAction::MuteUntil(_) => "mute-until",
+        }
+    }
+}
+
+/// Apply `action` to `monitor_id`, requiring `cfg.auto_approve` first —
+/// every variant changes the monitor's alerting state, so all of them get
+/// the same confirmation gate a destructive command would.
+pub async fn control(cfg: &Config, monitor_id: i64, action: MonitorAction) -> Result<()> {
+    if !cfg.auto_approve {
+        bail!(
+            "refusing to {} monitor {monitor_id} without --yes (or DD_AUTO_APPROVE/agent mode)",
+            action.label()
+        );
+    }
+    control_impl(cfg, monitor_id, action).await
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+async fn control_impl(cfg: &Config, monitor_id: i64, action: MonitorAction) -> Result<()> {
+    use datadog_api_client::datadogV1::api_monitors::{
+        MuteMonitorOptionalParams, ResolveMonitorOptionalParams,
+    };
+    use datadog_api_client::datadogV1::model::MonitorMuteFields;
+
+    let dd_cfg = client::make_dd_config(cfg);
+    let api = if let Some(http_client) = client::make_bearer_client(cfg) {
+        MonitorsAPI::with_client_and_config(dd_cfg, http_client)
+    } else {
+        MonitorsAPI::with_config(dd_cfg)
+    };
+
+    let monitor = match action {
+        MonitorAction::Mute => api
+            .mute_monitor(monitor_id, MuteMonitorOptionalParams::default())
+            .await
+            .map_err(|e| formatter::datadog_error(&format!("failed to mute monitor {monitor_id}"), e))?,
+        MonitorAction::MuteUntil(end) => api
+            .mute_monitor(
+                monitor_id,
+                MuteMonitorOptionalParams::default().body(MonitorMuteFields::new().end(end)),
+            )
+            .await
+            .map_err(|e| formatter::datadog_error(&format!("failed to mute monitor {monitor_id} until {end}"), e))?,
+        MonitorAction::Unmute => api
+            .unmute_monitor(monitor_id, Default::default())
+            .await
+            .map_err(|e| formatter::datadog_error(&format!("failed to unmute monitor {monitor_id}"), e))?,
+        MonitorAction::Resolve => api
+            .resolve_monitor(monitor_id, ResolveMonitorOptionalParams::default())
+            .await
+            .map_err(|e| formatter::datadog_error(&format!("failed to resolve monitor {monitor_id}"), e))?,
+    };
+    formatter::output(cfg, &monitor)
+}
+
+#[cfg(target_arch = "wasm32")]
+async fn control_impl(cfg: &Config, monitor_id: i64, action: MonitorAction) -> Result<()> {
+    let data = match action {
+        MonitorAction::Mute => {
+            crate::api::post(cfg, &format!("/api/v1/monitor/{monitor_id}/mute"), &serde_json::json!({})).await?
+        }
+        MonitorAction::MuteUntil(end) => {
+            crate::api::post(
+                cfg,
+                &format!("/api/v1/monitor/{monitor_id}/mute"),
+                &serde_json::json!({ "end": end }),
+            )
+            .await?
+        }
+        MonitorAction::Unmute => {
+            crate::api::post(cfg, &format!("/api/v1/monitor/{monitor_id}/unmute"), &serde_json::json!({})).await?
+        }
+        MonitorAction::Resolve => {
+            crate::api::post(cfg, &format!("/api/v1/monitor/{monitor_id}/resolve"), &serde_json::json!({})).await?
+        }
+    };
+    crate::formatter::output(cfg, &data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_simple_actions() {
+        assert_eq!(MonitorAction::parse("mute", None).unwrap(), MonitorAction::Mute);
+        assert_eq!(MonitorAction::parse("unmute", None).unwrap(), MonitorAction::Unmute);
+        assert_eq!(MonitorAction::parse("resolve", None).unwrap(), MonitorAction::Resolve);
+    }
+
+    #[test]
+    fn test_parse_mute_until_requires_timestamp() {
+        assert!(MonitorAction::parse("mute-until", None).is_err());
+        assert_eq!(
+            MonitorAction::parse("mute-until", Some("1700000000")).unwrap(),
+            MonitorAction::MuteUntil(1700000000)
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_unknown_action() {
+        assert!(MonitorAction::parse("snooze", None).is_err());
+    }
+}
@@ -0,0 +1,79 @@
+use anyhow::Result;
+
+use crate::config::Config;
+use crate::formatter;
+use crate::ops::plugins;
+use crate::ops::wasm_plugins;
+
+/// `pup plugins list`: discover installed `pup-*` binaries and their
+/// self-reported descriptions.
+pub fn list(cfg: &Config) -> Result<()> {
+    let found = plugins::discover();
+
+    if cfg.agent_mode {
+        return formatter::output(cfg, &found);
+    }
+
+    if found.is_empty() {
+        println!("No plugins found on PATH or PUP_PLUGIN_DIR.");
+        return Ok(());
+    }
+    for plugin in &found {
+        match &plugin.description {
+            Some(desc) => println!("{}  {}  ({})", plugin.name, plugin.path.display(), desc),
+            None => println!("{}  {}", plugin.name, plugin.path.display()),
+        }
+    }
+    Ok(())
+}
+
+/// `pup plugins which <name>`: resolve the path `pup <name>` would dispatch to.
+pub fn which(cfg: &Config, name: &str) -> Result<()> {
+    let Some(path) = plugins::which(name) else {
+        anyhow::bail!("no plugin named \"pup-{name}\" found on PATH or PUP_PLUGIN_DIR");
+    };
+
+    if cfg.agent_mode {
+        return formatter::output(cfg, &serde_json::json!({"name": name, "path": path}));
+    }
+    println!("{}", path.display());
+    Ok(())
+}
+
+/// `pup plugins wasm list`: discover installed `.wasm` modules and the
+/// subcommand each describes via its guest `describe()` export.
+pub fn wasm_list(cfg: &Config) -> Result<()> {
+    let found = wasm_plugins::discover(cfg)?;
+
+    if cfg.agent_mode {
+        return formatter::output(cfg, &found.iter().map(|p| &p.descriptor).collect::<Vec<_>>());
+    }
+
+    if found.is_empty() {
+        println!("No wasm plugins found in PUP_WASM_PLUGIN_DIR.");
+        return Ok(());
+    }
+    for plugin in &found {
+        println!(
+            "{}  {}  ({}){}",
+            plugin.descriptor.name,
+            plugin.path.display(),
+            plugin.descriptor.description,
+            if plugin.descriptor.read_only { " [read-only]" } else { "" }
+        );
+    }
+    Ok(())
+}
+
+/// `pup plugins wasm invoke <name> --args '{...}'`: run a discovered wasm
+/// plugin's `invoke()` with `args` as the parsed CLI arguments.
+pub fn wasm_invoke(cfg: &Config, name: &str, args: serde_json::Value) -> Result<()> {
+    let found = wasm_plugins::discover(cfg)?;
+    let plugin = found
+        .into_iter()
+        .find(|p| p.descriptor.name == name)
+        .ok_or_else(|| anyhow::anyhow!("no wasm plugin named {name:?} found in PUP_WASM_PLUGIN_DIR"))?;
+
+    let result = wasm_plugins::invoke(cfg, &plugin, &args)?;
+    formatter::output(cfg, &result)
+}
@@ -8,6 +8,9 @@ use datadog_api_client::datadogV2::model::ProductAnalyticsServerSideEventItem;
 use crate::client;
 use crate::config::Config;
 use crate::formatter;
+use crate::ops::analytics_query::{self, QueryArgs};
+use crate::ops::events_batch;
+use crate::ops::segment_static::{self, IdColumn};
 use crate::util;
 
 #[cfg(not(target_arch = "wasm32"))]
@@ -32,16 +35,85 @@ pub async fn events_send(cfg: &Config, file: &str) -> Result<()> {
     crate::formatter::output(cfg, &data)
 }
 
+/// `events_send`'s streaming sibling: read one event per line of `path`
+/// (`-` for stdin) instead of one event per file, and submit them with
+/// bounded concurrency instead of one `pup` invocation per event. Prints a
+/// summary and returns an error (for a nonzero exit) if any line failed,
+/// while still reporting every other line's outcome.
+#[cfg(not(target_arch = "wasm32"))]
+pub async fn events_send_batch(cfg: &Config, path: &str, chunk_size: usize, concurrency: usize) -> Result<()> {
+    let lines = events_batch::read_lines(path)?;
+    let owned_cfg = cfg.clone();
+    let summary = events_batch::run(
+        &lines,
+        chunk_size,
+        concurrency,
+        move |event: ProductAnalyticsServerSideEventItem| {
+            let cfg = owned_cfg.clone();
+            async move {
+                let dd_cfg = client::make_dd_config(&cfg);
+                let api = match client::make_bearer_client(&cfg) {
+                    Some(c) => ProductAnalyticsAPI::with_client_and_config(dd_cfg, c),
+                    None => ProductAnalyticsAPI::with_config(dd_cfg),
+                };
+                api.submit_product_analytics_event(event)
+                    .await
+                    .map_err(|e| anyhow::anyhow!("failed to send product analytics event: {e:?}"))?;
+                Ok(())
+            }
+        },
+    )
+    .await;
+
+    print_batch_summary(cfg, &summary)?;
+    check_batch_outcome(&summary)
+}
+
+#[cfg(target_arch = "wasm32")]
+pub async fn events_send_batch(cfg: &Config, path: &str, chunk_size: usize, concurrency: usize) -> Result<()> {
+    let lines = events_batch::read_lines(path)?;
+    let owned_cfg = cfg.clone();
+    let summary = events_batch::run(&lines, chunk_size, concurrency, move |event: serde_json::Value| {
+        let cfg = owned_cfg.clone();
+        async move {
+            crate::api::post(&cfg, "/api/v2/product-analytics/events", &event).await?;
+            Ok(())
+        }
+    })
+    .await;
+
+    print_batch_summary(cfg, &summary)?;
+    check_batch_outcome(&summary)
+}
+
+fn print_batch_summary(cfg: &Config, summary: &events_batch::BatchSummary) -> Result<()> {
+    if cfg.agent_mode {
+        return formatter::output(cfg, summary);
+    }
+    println!("Sent {} event(s), {} failed.", summary.sent, summary.failed);
+    for failure in &summary.failures {
+        println!("  - line {}: {}", failure.line, failure.error);
+    }
+    Ok(())
+}
+
+fn check_batch_outcome(summary: &events_batch::BatchSummary) -> Result<()> {
+    if summary.is_ok() {
+        return Ok(());
+    }
+    anyhow::bail!("{} of {} event(s) failed to send", summary.failed, summary.sent + summary.failed)
+}
+
 // ---- Analytics ----
 
-pub async fn analytics_scalar(cfg: &Config, file: &str) -> Result<()> {
-    let body: serde_json::Value = util::read_json_file(file)?;
+pub async fn analytics_scalar(cfg: &Config, file: Option<&str>, args: QueryArgs) -> Result<()> {
+    let body = analytics_query::build_body(file, &args)?;
     let data = crate::api::post(cfg, "/api/v2/product-analytics/analytics/scalar", &body).await?;
     formatter::output(cfg, &data)
 }
 
-pub async fn analytics_timeseries(cfg: &Config, file: &str) -> Result<()> {
-    let body: serde_json::Value = util::read_json_file(file)?;
+pub async fn analytics_timeseries(cfg: &Config, file: Option<&str>, args: QueryArgs) -> Result<()> {
+    let body = analytics_query::build_body(file, &args)?;
     let data =
         crate::api::post(cfg, "/api/v2/product-analytics/analytics/timeseries", &body).await?;
     formatter::output(cfg, &data)
@@ -49,20 +121,20 @@ pub async fn analytics_timeseries(cfg: &Config, file: &str) -> Result<()> {
 
 // ---- Journey ----
 
-pub async fn journey_funnel(cfg: &Config, file: &str) -> Result<()> {
-    let body: serde_json::Value = util::read_json_file(file)?;
+pub async fn journey_funnel(cfg: &Config, file: Option<&str>, args: QueryArgs) -> Result<()> {
+    let body = analytics_query::build_body(file, &args)?;
     let data = crate::api::post(cfg, "/api/v2/product-analytics/journey/funnel", &body).await?;
     formatter::output(cfg, &data)
 }
 
-pub async fn journey_timeseries(cfg: &Config, file: &str) -> Result<()> {
-    let body: serde_json::Value = util::read_json_file(file)?;
+pub async fn journey_timeseries(cfg: &Config, file: Option<&str>, args: QueryArgs) -> Result<()> {
+    let body = analytics_query::build_body(file, &args)?;
     let data = crate::api::post(cfg, "/api/v2/product-analytics/journey/timeseries", &body).await?;
     formatter::output(cfg, &data)
 }
 
-pub async fn journey_scalar(cfg: &Config, file: &str) -> Result<()> {
-    let body: serde_json::Value = util::read_json_file(file)?;
+pub async fn journey_scalar(cfg: &Config, file: Option<&str>, args: QueryArgs) -> Result<()> {
+    let body = analytics_query::build_body(file, &args)?;
     let data = crate::api::post(cfg, "/api/v2/product-analytics/journey/scalar", &body).await?;
     formatter::output(cfg, &data)
 }
@@ -73,8 +145,12 @@ pub async fn journey_list(cfg: &Config, file: &str) -> Result<()> {
     formatter::output(cfg, &data)
 }
 
-pub async fn journey_drop_off_analysis(cfg: &Config, file: &str) -> Result<()> {
-    let body: serde_json::Value = util::read_json_file(file)?;
+pub async fn journey_drop_off_analysis(
+    cfg: &Config,
+    file: Option<&str>,
+    args: QueryArgs,
+) -> Result<()> {
+    let body = analytics_query::build_body(file, &args)?;
     let data = crate::api::post(
         cfg,
         "/api/v2/product-analytics/journey/drop_off_analysis",
@@ -86,21 +162,21 @@ pub async fn journey_drop_off_analysis(cfg: &Config, file: &str) -> Result<()> {
 
 // ---- Retention ----
 
-pub async fn retention_grid(cfg: &Config, file: &str) -> Result<()> {
-    let body: serde_json::Value = util::read_json_file(file)?;
+pub async fn retention_grid(cfg: &Config, file: Option<&str>, args: QueryArgs) -> Result<()> {
+    let body = analytics_query::build_body(file, &args)?;
     let data = crate::api::post(cfg, "/api/v2/product-analytics/retention/grid", &body).await?;
     formatter::output(cfg, &data)
 }
 
-pub async fn retention_timeseries(cfg: &Config, file: &str) -> Result<()> {
-    let body: serde_json::Value = util::read_json_file(file)?;
+pub async fn retention_timeseries(cfg: &Config, file: Option<&str>, args: QueryArgs) -> Result<()> {
+    let body = analytics_query::build_body(file, &args)?;
     let data =
         crate::api::post(cfg, "/api/v2/product-analytics/retention/timeseries", &body).await?;
     formatter::output(cfg, &data)
 }
 
-pub async fn retention_scalar(cfg: &Config, file: &str) -> Result<()> {
-    let body: serde_json::Value = util::read_json_file(file)?;
+pub async fn retention_scalar(cfg: &Config, file: Option<&str>, args: QueryArgs) -> Result<()> {
+    let body = analytics_query::build_body(file, &args)?;
     let data = crate::api::post(cfg, "/api/v2/product-analytics/retention/scalar", &body).await?;
     formatter::output(cfg, &data)
 }
@@ -119,8 +195,8 @@ pub async fn retention_meta(cfg: &Config, file: &str) -> Result<()> {
 
 // ---- Sankey ----
 
-pub async fn sankey(cfg: &Config, file: &str) -> Result<()> {
-    let body: serde_json::Value = util::read_json_file(file)?;
+pub async fn sankey(cfg: &Config, file: Option<&str>, args: QueryArgs) -> Result<()> {
+    let body = analytics_query::build_body(file, &args)?;
     let data = crate::api::post(cfg, "/api/v2/product-analytics/sankey", &body).await?;
     formatter::output(cfg, &data)
 }
@@ -144,6 +220,41 @@ pub async fn segment_create_static(cfg: &Config, file: &str) -> Result<()> {
     formatter::output(cfg, &data)
 }
 
+/// `segment_create_static`'s convenience sibling: build the static-segment
+/// payload from a plain CSV/newline-delimited identifier list instead of a
+/// pre-built JSON body. There's no incremental-append endpoint in this API
+/// surface, so a member list over `chunk_size` is still split across
+/// multiple creation POSTs (all under `name`) rather than one oversized
+/// request; the reported upload count covers every chunk sent.
+pub async fn segment_create_static_from_ids(
+    cfg: &Config,
+    path: &str,
+    name: &str,
+    id_column: &str,
+    header: bool,
+    chunk_size: usize,
+) -> Result<()> {
+    let column = IdColumn::parse(id_column);
+    let members = segment_static::read_member_ids(path, &column, header)?;
+    if members.is_empty() {
+        anyhow::bail!("no member IDs found in {path}");
+    }
+
+    let mut uploaded = 0usize;
+    let mut last_response = serde_json::Value::Null;
+    for chunk in members.chunks(chunk_size.max(1)) {
+        let body = segment_static::build_payload(name, chunk);
+        last_response = crate::api::post(cfg, "/api/v2/product-analytics/segment/static", &body).await?;
+        uploaded += chunk.len();
+    }
+
+    if cfg.agent_mode {
+        return formatter::output(cfg, &last_response);
+    }
+    println!("Uploaded {uploaded} member(s) to static segment {name:?}.");
+    Ok(())
+}
+
 pub async fn segment_get(cfg: &Config, id: &str) -> Result<()> {
     let path = format!("/api/v2/product-analytics/segment/{id}");
     let data = crate::api::get(cfg, &path, &[]).await?;
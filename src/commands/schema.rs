@@ -0,0 +1,56 @@
+use anyhow::{bail, Context, Result};
+use clap::CommandFactory;
+
+use crate::cli::Cli;
+use crate::config::Config;
+use crate::ops::schema;
+
+/// `pup schema [--format json|yaml]`: walk the real clap command tree
+/// (`Cli::command()`) and print the generated manifest.
+pub fn export(cfg: &Config, format: &str) -> Result<()> {
+    let manifest = schema::manifest(&Cli::command());
+    print_manifest(cfg, &manifest, format)
+}
+
+/// `pup schema --diff <old-manifest.json>`: compare the current manifest
+/// against a previously-exported one and report added/removed/renamed
+/// command paths, for use as a CI contract check against accidental
+/// breaking CLI changes.
+pub fn diff(cfg: &Config, old_manifest_file: &str, format: &str) -> Result<()> {
+    let raw = std::fs::read_to_string(old_manifest_file)
+        .with_context(|| format!("failed to read manifest {old_manifest_file}"))?;
+    let old: serde_json::Value = if old_manifest_file.ends_with(".yaml") || old_manifest_file.ends_with(".yml") {
+        serde_yaml::from_str(&raw)?
+    } else {
+        serde_json::from_str(&raw)?
+    };
+
+    let new = schema::manifest(&Cli::command());
+    let result = schema::diff(&old, &new);
+
+    if result.added.is_empty() && result.removed.is_empty() && result.renamed.is_empty() {
+        println!("No command path changes.");
+        return Ok(());
+    }
+
+    print_manifest(cfg, &serde_json::to_value(&result)?, format)?;
+
+    if !result.removed.is_empty() || !result.renamed.is_empty() {
+        bail!(
+            "{} command path(s) removed, {} renamed — breaking CLI change detected",
+            result.removed.len(),
+            result.renamed.len()
+        );
+    }
+    Ok(())
+}
+
+fn print_manifest(cfg: &Config, value: &serde_json::Value, format: &str) -> Result<()> {
+    match format {
+        "yaml" => println!("{}", serde_yaml::to_string(value)?),
+        "json" => println!("{}", serde_json::to_string_pretty(value)?),
+        other => bail!("unknown --format {other:?}\nExpected one of: json, yaml"),
+    }
+    let _ = cfg;
+    Ok(())
+}
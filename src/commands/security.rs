@@ -0,0 +1,42 @@
+use anyhow::Result;
+use serde_json::Value;
+
+use crate::api;
+use crate::config::Config;
+use crate::formatter;
+use crate::ops::sarif;
+
+/// `pup security findings search [--query ...] [--output sarif]`: the
+/// `SecurityActions::Findings { Search }` branch. `--output sarif` formats
+/// the result as a SARIF 2.1.0 log instead of the usual JSON/table output,
+/// for piping into GitHub code scanning or any other SARIF consumer.
+pub async fn findings_search(cfg: &Config, query: Option<&str>, sarif_output: bool) -> Result<()> {
+    let params: Vec<(&str, &str)> = query.map(|q| vec![("filter[query]", q)]).unwrap_or_default();
+    let raw = api::get(cfg, "/api/v2/security_monitoring/findings", &params).await?;
+
+    if sarif_output {
+        return print_sarif(&raw);
+    }
+    formatter::output(cfg, &raw)
+}
+
+/// `pup security signals search [--query ...] [--output sarif]`: the
+/// `SecuritySignalActions::Search` branch, with the same SARIF output mode.
+pub async fn signals_search(cfg: &Config, query: Option<&str>, sarif_output: bool) -> Result<()> {
+    let body = serde_json::json!({
+        "filter": { "query": query.unwrap_or("*") },
+    });
+    let raw = api::post(cfg, "/api/v2/security_monitoring/signals/search", &body).await?;
+
+    if sarif_output {
+        return print_sarif(&raw);
+    }
+    formatter::output(cfg, &raw)
+}
+
+fn print_sarif(raw: &Value) -> Result<()> {
+    let findings = sarif::from_value(raw);
+    let log = sarif::build_sarif(&findings);
+    println!("{}", serde_json::to_string_pretty(&log)?);
+    Ok(())
+}
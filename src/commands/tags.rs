@@ -12,108 +12,179 @@ use crate::client;
 use crate::config::Config;
 use crate::formatter;
 
+/// `source=...` query-string entry for the wasm32 `crate::api::*` path,
+/// matching the native path's `.source(...)` optional-params builder call.
+#[cfg(target_arch = "wasm32")]
+fn source_query(source: &Option<String>) -> Vec<(&'static str, String)> {
+    source.as_ref().map(|s| vec![("source", s.clone())]).unwrap_or_default()
+}
+
 #[cfg(not(target_arch = "wasm32"))]
-pub async fn list(cfg: &Config) -> Result<()> {
+pub async fn list(cfg: &Config, source: Option<String>) -> Result<()> {
     let dd_cfg = client::make_dd_config(cfg);
     let dd_client = client::make_dd_client(cfg);
     let api = TagsAPI::with_client_and_config(dd_cfg, dd_client);
-    let resp = api
-        .list_host_tags(ListHostTagsOptionalParams::default())
+    let mut params = ListHostTagsOptionalParams::default();
+    if let Some(s) = source {
+        params = params.source(s);
+    }
+    let resp = crate::ops::retry::with_retry(cfg.max_retries, || api.list_host_tags(params.clone()))
         .await
-        .map_err(|e| anyhow::anyhow!("failed to list tags: {e:?}"))?;
+        .map_err(crate::ops::tags::classify_error)?;
     formatter::output(cfg, &resp)
 }
 
 #[cfg(target_arch = "wasm32")]
-pub async fn list(cfg: &Config) -> Result<()> {
-    let data = crate::api::get(cfg, "/api/v1/tags/hosts", &[]).await?;
+pub async fn list(cfg: &Config, source: Option<String>) -> Result<()> {
+    let data = crate::api::get(cfg, "/api/v1/tags/hosts", &source_query(&source)).await?;
     crate::formatter::output(cfg, &data)
 }
 
 #[cfg(not(target_arch = "wasm32"))]
-pub async fn get(cfg: &Config, hostname: &str) -> Result<()> {
+pub async fn get(cfg: &Config, hostname: &str, source: Option<String>) -> Result<()> {
     let dd_cfg = client::make_dd_config(cfg);
     let dd_client = client::make_dd_client(cfg);
     let api = TagsAPI::with_client_and_config(dd_cfg, dd_client);
-    let resp = api
-        .get_host_tags(hostname.to_string(), GetHostTagsOptionalParams::default())
+    let mut params = GetHostTagsOptionalParams::default();
+    if let Some(s) = source {
+        params = params.source(s);
+    }
+    let resp = crate::ops::retry::with_retry(cfg.max_retries, || api.get_host_tags(hostname.to_string(), params.clone()))
         .await
-        .map_err(|e| anyhow::anyhow!("failed to get tags: {e:?}"))?;
+        .map_err(crate::ops::tags::classify_error)?;
     formatter::output(cfg, &resp)
 }
 
 #[cfg(target_arch = "wasm32")]
-pub async fn get(cfg: &Config, hostname: &str) -> Result<()> {
-    let data = crate::api::get(cfg, &format!("/api/v1/tags/hosts/{hostname}"), &[]).await?;
+pub async fn get(cfg: &Config, hostname: &str, source: Option<String>) -> Result<()> {
+    let data = crate::api::get(cfg, &format!("/api/v1/tags/hosts/{hostname}"), &source_query(&source)).await?;
     crate::formatter::output(cfg, &data)
 }
 
 #[cfg(not(target_arch = "wasm32"))]
-pub async fn add(cfg: &Config, hostname: &str, tags: Vec<String>) -> Result<()> {
+pub async fn add(cfg: &Config, hostname: &str, tags: Vec<String>, source: Option<String>) -> Result<()> {
     let dd_cfg = client::make_dd_config(cfg);
     let dd_client = client::make_dd_client(cfg);
     let api = TagsAPI::with_client_and_config(dd_cfg, dd_client);
     let body = HostTags::new().tags(tags);
-    let resp = api
-        .create_host_tags(
-            hostname.to_string(),
-            body,
-            CreateHostTagsOptionalParams::default(),
-        )
-        .await
-        .map_err(|e| anyhow::anyhow!("failed to add tags: {e:?}"))?;
+    let mut params = CreateHostTagsOptionalParams::default();
+    if let Some(s) = source {
+        params = params.source(s);
+    }
+    let resp = crate::ops::retry::with_retry(cfg.max_retries, || {
+        api.create_host_tags(hostname.to_string(), body.clone(), params.clone())
+    })
+    .await
+    .map_err(crate::ops::tags::classify_error)?;
     formatter::output(cfg, &resp)
 }
 
 #[cfg(target_arch = "wasm32")]
-pub async fn add(cfg: &Config, hostname: &str, tags: Vec<String>) -> Result<()> {
+pub async fn add(cfg: &Config, hostname: &str, tags: Vec<String>, source: Option<String>) -> Result<()> {
     let body = serde_json::json!({ "tags": tags });
-    let data = crate::api::post(cfg, &format!("/api/v1/tags/hosts/{hostname}"), &body).await?;
+    let data = crate::api::post(cfg, &with_source_query(&format!("/api/v1/tags/hosts/{hostname}"), &source), &body).await?;
     crate::formatter::output(cfg, &data)
 }
 
 #[cfg(not(target_arch = "wasm32"))]
-pub async fn update(cfg: &Config, hostname: &str, tags: Vec<String>) -> Result<()> {
+pub async fn update(cfg: &Config, hostname: &str, tags: Vec<String>, source: Option<String>) -> Result<()> {
     let dd_cfg = client::make_dd_config(cfg);
     let dd_client = client::make_dd_client(cfg);
     let api = TagsAPI::with_client_and_config(dd_cfg, dd_client);
     let body = HostTags::new().tags(tags);
-    let resp = api
-        .update_host_tags(
-            hostname.to_string(),
-            body,
-            UpdateHostTagsOptionalParams::default(),
-        )
-        .await
-        .map_err(|e| anyhow::anyhow!("failed to update tags: {e:?}"))?;
+    let mut params = UpdateHostTagsOptionalParams::default();
+    if let Some(s) = source {
+        params = params.source(s);
+    }
+    let resp = crate::ops::retry::with_retry(cfg.max_retries, || {
+        api.update_host_tags(hostname.to_string(), body.clone(), params.clone())
+    })
+    .await
+    .map_err(crate::ops::tags::classify_error)?;
     formatter::output(cfg, &resp)
 }
 
 #[cfg(target_arch = "wasm32")]
-pub async fn update(cfg: &Config, hostname: &str, tags: Vec<String>) -> Result<()> {
+pub async fn update(cfg: &Config, hostname: &str, tags: Vec<String>, source: Option<String>) -> Result<()> {
     let body = serde_json::json!({ "tags": tags });
-    let data = crate::api::put(cfg, &format!("/api/v1/tags/hosts/{hostname}"), &body).await?;
+    let data = crate::api::put(cfg, &with_source_query(&format!("/api/v1/tags/hosts/{hostname}"), &source), &body).await?;
     crate::formatter::output(cfg, &data)
 }
 
 #[cfg(not(target_arch = "wasm32"))]
-pub async fn delete(cfg: &Config, hostname: &str) -> Result<()> {
+pub async fn delete(cfg: &Config, hostname: &str, source: Option<String>) -> Result<()> {
     let dd_cfg = client::make_dd_config(cfg);
     let dd_client = client::make_dd_client(cfg);
     let api = TagsAPI::with_client_and_config(dd_cfg, dd_client);
-    api.delete_host_tags(
-        hostname.to_string(),
-        DeleteHostTagsOptionalParams::default(),
-    )
-    .await
-    .map_err(|e| anyhow::anyhow!("failed to delete tags: {e:?}"))?;
+    let mut params = DeleteHostTagsOptionalParams::default();
+    if let Some(s) = source {
+        params = params.source(s);
+    }
+    crate::ops::retry::with_retry(cfg.max_retries, || api.delete_host_tags(hostname.to_string(), params.clone()))
+        .await
+        .map_err(crate::ops::tags::classify_error)?;
     println!("Successfully deleted all tags from host {hostname}");
     Ok(())
 }
 
 #[cfg(target_arch = "wasm32")]
-pub async fn delete(cfg: &Config, hostname: &str) -> Result<()> {
-    crate::api::delete(cfg, &format!("/api/v1/tags/hosts/{hostname}")).await?;
+pub async fn delete(cfg: &Config, hostname: &str, source: Option<String>) -> Result<()> {
+    crate::api::delete(cfg, &with_source_query(&format!("/api/v1/tags/hosts/{hostname}"), &source)).await?;
     println!("Successfully deleted all tags from host {hostname}");
     Ok(())
 }
+
+/// `crate::api::post`/`put`/`delete` take a bare path, not a query slice, so
+/// append `?source=...` to the URL directly for the wasm32 write paths.
+#[cfg(target_arch = "wasm32")]
+fn with_source_query(path: &str, source: &Option<String>) -> String {
+    match source {
+        Some(s) => format!("{path}?source={}", urlencoding::encode(s)),
+        None => path.to_string(),
+    }
+}
+
+/// `pup tags sync <hostname> --tag k:v [--tag k:v...] [--dry-run]`: converge
+/// a host's tags to exactly the given set instead of blindly `update`-ing
+/// (which would also be a full replace, but without showing what changed).
+/// `--dry-run` reports the computed add/remove diff through
+/// `formatter::output` and makes no write.
+pub async fn sync(cfg: &Config, hostname: &str, tags: Vec<String>, dry_run: bool) -> Result<()> {
+    let diff = crate::ops::tags::sync(cfg, hostname, tags, dry_run).await?;
+
+    if cfg.agent_mode || dry_run {
+        return formatter::output(cfg, &diff);
+    }
+
+    if diff.is_noop() {
+        println!("{hostname} already matches the desired tag set; no changes made.");
+        return Ok(());
+    }
+
+    println!("Synced tags for {hostname}:");
+    for tag in &diff.to_add {
+        println!("  + {tag}");
+    }
+    for tag in &diff.to_remove {
+        println!("  - {tag}");
+    }
+    Ok(())
+}
+
+/// `pup tags bulk-apply --file manifest.(yaml|json) [--concurrency N]`: apply
+/// tags to many hosts at once from a `{hostname: [tag, ...]}` manifest,
+/// printing a succeeded/failed report instead of aborting on the first
+/// host's failure.
+pub async fn bulk_apply(cfg: &Config, path: &str, concurrency: usize) -> Result<()> {
+    let summary = crate::ops::tags::bulk_apply(cfg, path, concurrency).await?;
+
+    if cfg.agent_mode {
+        return formatter::output(cfg, &summary);
+    }
+
+    println!("Applied tags to {} host(s), {} failed.", summary.succeeded.len(), summary.failed.len());
+    for (hostname, err) in &summary.failed {
+        println!("  - {hostname}: {err}");
+    }
+    Ok(())
+}
@@ -0,0 +1,32 @@
+use anyhow::Result;
+
+use crate::config::Config;
+use crate::formatter;
+use crate::ops::scorecard;
+
+/// `pup test [--json]`: run the read-only health scorecard and print a
+/// grouped pass/fail summary with a grand-total percentage, e.g.
+/// "logs: 2/2 reachable" ... "Grand total: 92.0/100". `--json` (and agent
+/// mode) print the full `ScorecardResult` instead.
+pub async fn run(cfg: &Config, json: bool) -> Result<()> {
+    let result = scorecard::run(cfg).await?;
+
+    if json || cfg.agent_mode {
+        return formatter::output(cfg, &result);
+    }
+
+    for group in &result.groups {
+        println!("{}: {}/{} reachable", group.group, group.passed, group.total);
+        for check in result.checks.iter().filter(|c| c.group == group.group) {
+            let status = if check.passed { "ok" } else { "FAIL" };
+            print!("  [{status}] {} ({}ms)", check.name, check.latency_ms);
+            match &check.error {
+                Some(err) => println!(" - {err}"),
+                None => println!(),
+            }
+        }
+    }
+
+    println!("\nGrand total: {:.1}/100", result.grand_total);
+    Ok(())
+}
@@ -0,0 +1,41 @@
+use anyhow::Result;
+
+use crate::config::Config;
+use crate::formatter;
+use crate::ops::upgrade;
+
+/// `pup upgrade [--check-only] [--version X.Y.Z]`: compare the embedded
+/// build version against GitHub releases and, unless `check_only`,
+/// download and install the newer build in place of the running binary.
+pub async fn run(cfg: &Config, check_only: bool, version: Option<&str>, yes: bool) -> Result<()> {
+    if check_only {
+        let check = upgrade::check(version).await?;
+
+        if cfg.agent_mode {
+            return formatter::output(cfg, &check);
+        }
+
+        if check.update_available {
+            println!(
+                "A new version is available: {} -> {}",
+                check.current, check.latest
+            );
+        } else {
+            println!("Already up to date ({}).", check.current);
+        }
+        return Ok(());
+    }
+
+    let outcome = upgrade::perform(version, yes).await?;
+
+    if cfg.agent_mode {
+        return formatter::output(cfg, &outcome);
+    }
+
+    if outcome.upgraded {
+        println!("Upgraded {} -> {}", outcome.from, outcome.to);
+    } else {
+        println!("Already up to date ({}).", outcome.from);
+    }
+    Ok(())
+}
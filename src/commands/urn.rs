@@ -0,0 +1,94 @@
+use anyhow::{bail, Context, Result};
+
+use crate::api;
+use crate::commands::{app_keys, logs, product_analytics, tags};
+use crate::config::Config;
+use crate::ops::backup::ResourceKind;
+use crate::ops::urn::{self, Urn};
+
+/// `urn.ids` as a single id, for the (overwhelmingly common) resource types
+/// addressed by exactly one.
+fn single_id(urn: &Urn) -> Result<&str> {
+    match urn.ids.as_slice() {
+        [id] => Ok(id.as_str()),
+        other => bail!(
+            "{} expects exactly 1 id segment, got {}",
+            urn,
+            other.len()
+        ),
+    }
+}
+
+/// `pup get <urn>`: fetch one object addressed by its `dd:<service>:<type>/<id>`
+/// URN and print it, routing to whichever `commands::*`/`ops::backup` getter
+/// already knows that service/type's API shape. Any `ResourceKind` that
+/// `export`/`import`/`apply` already understands (`monitors`, `slos`, ...) is
+/// addressable by using the kind name as the service, e.g. `dd:monitors:object/123`.
+pub async fn get(cfg: &Config, urn_str: &str) -> Result<()> {
+    let urn = urn::parse(urn_str)?;
+    if urn.is_wildcard() {
+        bail!("{urn} has a wildcard service or type; `pup get` needs a fully-qualified urn naming one object");
+    }
+
+    match (urn.service.as_str(), urn.resource_type.as_str()) {
+        ("logs", "archive") => logs::archives_get(cfg, single_id(&urn)?).await,
+        ("logs", "metric") => logs::metrics_get(cfg, single_id(&urn)?).await,
+        ("logs", "custom-destination") => logs::custom_destinations_get(cfg, single_id(&urn)?).await,
+        ("logs", "restriction-query") => logs::restriction_queries_get(cfg, single_id(&urn)?).await,
+        ("app-keys", "key") => app_keys::get(cfg, single_id(&urn)?).await,
+        ("tags", "host") => tags::get(cfg, single_id(&urn)?, None).await,
+        ("product-analytics", "segment") => product_analytics::segment_get(cfg, single_id(&urn)?).await,
+        (service, _) if ResourceKind::parse(service).is_ok() => {
+            crate::commands::backup::show(cfg, service, single_id(&urn)?).await
+        }
+        _ => bail!("no `pup get` handler registered for {}:{}", urn.service, urn.resource_type),
+    }
+    .with_context(|| format!("failed to get {urn}"))
+}
+
+/// `pup delete <urn>`: the `delete` counterpart to [`get`], routed the same
+/// way. `ResourceKind`s without a dedicated `commands::*` delete entry point
+/// fall through to a direct `api::delete` against `kind.object_path(id)`, the
+/// same call `ops::apply`'s prune step makes.
+pub async fn delete(cfg: &Config, urn_str: &str) -> Result<()> {
+    let urn = urn::parse(urn_str)?;
+    if urn.is_wildcard() {
+        bail!("{urn} has a wildcard service or type; `pup delete` needs a fully-qualified urn naming one object");
+    }
+
+    match (urn.service.as_str(), urn.resource_type.as_str()) {
+        ("logs", "archive") => return logs::archives_delete(cfg, single_id(&urn)?).await,
+        ("logs", "metric") => return logs::metrics_delete(cfg, single_id(&urn)?).await,
+        ("app-keys", "key") => return app_keys::delete(cfg, single_id(&urn)?).await,
+        ("tags", "host") => return tags::delete(cfg, single_id(&urn)?, None).await,
+        ("product-analytics", "segment") => return product_analytics::segment_delete(cfg, single_id(&urn)?).await,
+        (service, _) if ResourceKind::parse(service).is_ok() => {
+            let kind = ResourceKind::parse(service)?;
+            let id = single_id(&urn)?;
+            api::delete(cfg, &kind.object_path(id))
+                .await
+                .with_context(|| format!("failed to delete {urn}"))?;
+            println!("{urn} deleted.");
+            return Ok(());
+        }
+        _ => {}
+    }
+    bail!("no `pup delete` handler registered for {}:{}", urn.service, urn.resource_type)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_single_id_rejects_compound_urn() {
+        let urn = urn::parse("dd:status-pages:component/abc/def").unwrap();
+        assert!(single_id(&urn).is_err());
+    }
+
+    #[test]
+    fn test_single_id_accepts_one_segment() {
+        let urn = urn::parse("dd:fleet:deployment/dep-789").unwrap();
+        assert_eq!(single_id(&urn).unwrap(), "dep-789");
+    }
+}
@@ -0,0 +1,39 @@
+use anyhow::Result;
+
+use crate::config::{Config, OutputFormat};
+use crate::formatter;
+
+/// `pup version [--remote]`: print the local build string, and — with
+/// `--remote` — also negotiate capabilities against `cfg.site` via
+/// `ops::api_compat::check_api_compat`, so users on GovCloud/EU/self-managed
+/// sites get a compatibility readout before a feature silently 404s.
+pub async fn show(cfg: &Config, remote: bool) -> Result<()> {
+    let info = crate::version::build_info();
+
+    if !remote {
+        return match cfg.output_format {
+            OutputFormat::Table => {
+                println!("{info}");
+                Ok(())
+            }
+            _ => formatter::output(cfg, serde_json::json!({ "build": info })),
+        };
+    }
+
+    let compat = crate::ops::api_compat::check_api_compat(cfg).await?;
+
+    match cfg.output_format {
+        OutputFormat::Table => {
+            println!("{info}");
+            println!(
+                "site: {} (credentials valid: {})",
+                compat.site, compat.credentials_valid
+            );
+            for warning in &compat.warnings {
+                println!("warning: {warning}");
+            }
+            Ok(())
+        }
+        _ => formatter::output(cfg, serde_json::json!({ "build": info, "compat": compat })),
+    }
+}
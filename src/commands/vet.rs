@@ -2,14 +2,83 @@ use anyhow::Result;
 
 use crate::config::Config;
 use crate::ops::vet;
+use crate::ops::vet_notify::{self, NotifyTarget};
+use crate::ops::vet_offline;
+use crate::ops::vet_prometheus::{self, ServeParams};
 
+#[allow(clippy::too_many_arguments)]
 pub async fn run(
     cfg: &Config,
     tags: Option<String>,
     check: Option<String>,
     severity: Option<String>,
+    rules: Option<String>,
+    notify: Option<String>,
+    dump: Option<String>,
+    from: Option<String>,
+    events: Option<String>,
+    baseline: Option<String>,
+    format: Option<String>,
+    output: Option<String>,
+    serve: Option<String>,
 ) -> Result<()> {
-    let result = vet::run(cfg, tags, check, severity).await?;
+    // `--serve <addr>` never runs `vet::run` itself — it hands off to a loop
+    // that re-runs it on every scrape, so it bypasses the one-shot path below.
+    if let Some(addr) = &serve {
+        let params = ServeParams {
+            tags,
+            check,
+            severity_filter: severity,
+            rules_path: rules,
+        };
+        return vet_prometheus::serve(cfg, addr, params).await;
+    }
+
+    let result = vet::run(cfg, tags, check, severity, rules, dump, from, events).await?;
+
+    if format.as_deref() == Some("prometheus") {
+        vet_prometheus::write_textfile(&result, output.as_deref())?;
+        return Ok(());
+    }
+
+    if let Some(baseline_path) = &baseline {
+        let baseline = vet_offline::load_baseline(baseline_path)?;
+        let diff = vet_offline::diff_against_baseline(&baseline, &result);
+
+        if diff.checks.is_empty() {
+            println!("No change vs baseline {baseline_path}.");
+        } else {
+            for check_diff in &diff.checks {
+                println!("\n{}:", check_diff.check);
+                for r in &check_diff.newly_introduced {
+                    println!("  + #{} \"{}\" ({}) [new]", r.id, r.name, r.detail);
+                }
+                for r in &check_diff.resolved {
+                    println!("  - #{} \"{}\" ({}) [resolved]", r.id, r.name, r.detail);
+                }
+            }
+        }
+
+        println!("\nNew critical findings vs baseline: {}", diff.new_critical_count);
+        if diff.new_critical_count > 0 {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    if let Some(target) = notify {
+        let target = NotifyTarget::parse(&target)?;
+        let summary = vet_notify::notify(cfg, &result, target, cfg.pagerduty_routing_key.as_deref()).await?;
+        println!(
+            "Notified {}: {} triggered, {} resolved",
+            match target {
+                NotifyTarget::DatadogEvents => "Datadog Events",
+                NotifyTarget::PagerDuty => "PagerDuty",
+            },
+            summary.triggered,
+            summary.resolved
+        );
+    }
 
     if cfg.agent_mode {
         let meta = crate::formatter::Metadata {
@@ -0,0 +1,26 @@
+use anyhow::{Context, Result};
+
+use crate::config::Config;
+use crate::formatter;
+use crate::ops::workflow::{self, WorkflowFile};
+
+/// `pup run --file workflow.(yaml|json) [--dry-run]`: execute an ordered
+/// list of steps chaining `ops::batch`'s command registry, resolving
+/// `${capture}` placeholders from earlier steps' output before each later
+/// step is dispatched. With `--dry-run`, no step is actually called — the
+/// resolved plan for every step is printed instead.
+pub async fn run(cfg: &Config, file: &str, dry_run: bool) -> Result<()> {
+    let raw = std::fs::read_to_string(file).with_context(|| format!("failed to read workflow file {file}"))?;
+    let workflow: WorkflowFile = if file.ends_with(".json") {
+        serde_json::from_str(&raw).with_context(|| format!("failed to parse {file} as JSON"))?
+    } else {
+        serde_yaml::from_str(&raw).with_context(|| format!("failed to parse {file} as YAML"))?
+    };
+
+    let results = workflow::run(cfg, &workflow, dry_run).await?;
+    formatter::output(cfg, &results)?;
+    if dry_run {
+        return Ok(());
+    }
+    workflow::check_outcome(&results)
+}
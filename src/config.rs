@@ -0,0 +1,353 @@
+//! Runtime configuration with precedence: flag > env > file > default.
+//!
+//! `Config` is built once per invocation by [`Config::from_env`] and threaded
+//! through every command as `&Config`; CLI flags override whatever this
+//! produces, applied by the caller after `from_env` returns.
+
+use std::path::PathBuf;
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+pub mod crypto;
+
+#[derive(Clone, Debug)]
+pub struct Config {
+    pub api_key: Option<String>,
+    pub app_key: Option<String>,
+    pub access_token: Option<String>,
+    pub site: String,
+    pub org: Option<String>,
+    pub output_format: OutputFormat,
+    pub auto_approve: bool,
+    pub agent_mode: bool,
+
+    /// `"keyring"` / `"file"` / `"sqlite"` / `"age"`; `None` to auto-detect.
+    /// See [`crate::auth::storage::get_storage`].
+    pub storage_backend: Option<String>,
+    /// Retry budget [`crate::ops::retry::with_retry`] applies to transient
+    /// (429/5xx/timeout) API errors.
+    pub max_retries: u32,
+    pub pagerduty_routing_key: Option<String>,
+    pub vet_rules_path: Option<String>,
+
+    /// Request the `offline_access` scope during OAuth2 login so the
+    /// authorization server issues a `refresh_token`.
+    pub offline_access: bool,
+    /// Extra `key=value` pairs appended to the browser-flow authorization URL.
+    pub extra_auth_params: Vec<(String, String)>,
+
+    /// OIDC gateway login (`--gateway`): pre-registered client credentials
+    /// and discovery/verification overrides. See `commands::auth::login_gateway`.
+    pub oidc_token_endpoint: Option<String>,
+    pub oidc_client_id: Option<String>,
+    pub oidc_client_secret: Option<String>,
+    pub oidc_issuer: Option<String>,
+    pub oidc_audience: Option<String>,
+    pub oidc_scope: Option<String>,
+    pub oidc_jwks_uri: Option<String>,
+    pub oidc_userinfo_uri: Option<String>,
+    pub oidc_required_claims: Vec<String>,
+    pub oidc_id_token_hint: Option<String>,
+    /// The only signing algorithm accepted for ID token verification
+    /// (`RS256`, `ES256`, ...). Pinned here rather than trusted from the
+    /// token header, since the header is attacker-controlled. Defaults to
+    /// `RS256` when unset. See `TokenChecker::verify_id_token`.
+    pub oidc_signing_alg: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    #[default]
+    Json,
+    Table,
+    Yaml,
+}
+
+impl std::fmt::Display for OutputFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OutputFormat::Json => write!(f, "json"),
+            OutputFormat::Table => write!(f, "table"),
+            OutputFormat::Yaml => write!(f, "yaml"),
+        }
+    }
+}
+
+impl std::str::FromStr for OutputFormat {
+    type Err = anyhow::Error;
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "json" => Ok(OutputFormat::Json),
+            "table" => Ok(OutputFormat::Table),
+            "yaml" => Ok(OutputFormat::Yaml),
+            _ => bail!("invalid output format: {s:?} (expected json, table, or yaml)"),
+        }
+    }
+}
+
+/// Config file structure (`~/.config/pup/config.yaml`). Plaintext
+/// credential fields and [`EncryptedBlock`] are mutually exclusive in
+/// practice — `pup auth lock` rewrites the file to carry only the latter —
+/// but both are left `Option` so a half-migrated file still parses.
+#[derive(Deserialize, Default)]
+struct FileConfig {
+    api_key: Option<String>,
+    app_key: Option<String>,
+    access_token: Option<String>,
+    site: Option<String>,
+    org: Option<String>,
+    output: Option<String>,
+    auto_approve: Option<bool>,
+    storage_backend: Option<String>,
+    max_retries: Option<u32>,
+    pagerduty_routing_key: Option<String>,
+    vet_rules_path: Option<String>,
+    offline_access: Option<bool>,
+    oidc_token_endpoint: Option<String>,
+    oidc_client_id: Option<String>,
+    oidc_client_secret: Option<String>,
+    oidc_issuer: Option<String>,
+    oidc_audience: Option<String>,
+    oidc_scope: Option<String>,
+    oidc_jwks_uri: Option<String>,
+    oidc_userinfo_uri: Option<String>,
+    oidc_id_token_hint: Option<String>,
+    oidc_signing_alg: Option<String>,
+
+    /// Sealed `api_key`/`app_key`/`access_token`, written by `pup auth lock`.
+    /// When present, [`load_config_file`] decrypts it and slots the results
+    /// into the plaintext fields above before `from_env` reads them, so
+    /// precedence and every downstream call site are unaffected.
+    encrypted: Option<crypto::EncryptedBlock>,
+}
+
+impl Config {
+    /// Load configuration with precedence: flag overrides > env > file > defaults.
+    /// Flag overrides are applied by the caller after this returns.
+    pub fn from_env() -> Result<Self> {
+        let file_cfg = load_config_file().unwrap_or_default();
+
+        let cfg = Config {
+            api_key: env_or("DD_API_KEY", file_cfg.api_key),
+            app_key: env_or("DD_APP_KEY", file_cfg.app_key),
+            access_token: env_or("DD_ACCESS_TOKEN", file_cfg.access_token),
+            site: env_or("DD_SITE", file_cfg.site).unwrap_or_else(|| "datadoghq.com".into()),
+            org: env_or("DD_ORG", file_cfg.org),
+            output_format: env_or("DD_OUTPUT", file_cfg.output)
+                .and_then(|s| s.parse().ok())
+                .unwrap_or_default(),
+            auto_approve: env_bool("DD_AUTO_APPROVE")
+                || env_bool("DD_CLI_AUTO_APPROVE")
+                || file_cfg.auto_approve.unwrap_or(false),
+            agent_mode: false, // set by caller from --agent flag or useragent detection
+            storage_backend: env_or("DD_TOKEN_STORAGE", file_cfg.storage_backend),
+            max_retries: std::env::var("DD_MAX_RETRIES")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .or(file_cfg.max_retries)
+                .unwrap_or(3),
+            pagerduty_routing_key: env_or("DD_PAGERDUTY_ROUTING_KEY", file_cfg.pagerduty_routing_key),
+            vet_rules_path: env_or("DD_VET_RULES_PATH", file_cfg.vet_rules_path),
+            offline_access: env_bool("DD_OFFLINE_ACCESS") || file_cfg.offline_access.unwrap_or(false),
+            extra_auth_params: Vec::new(), // populated by the caller from repeated --auth-param flags
+            oidc_token_endpoint: env_or("DD_OIDC_TOKEN_ENDPOINT", file_cfg.oidc_token_endpoint),
+            oidc_client_id: env_or("DD_OIDC_CLIENT_ID", file_cfg.oidc_client_id),
+            oidc_client_secret: env_or("DD_OIDC_CLIENT_SECRET", file_cfg.oidc_client_secret),
+            oidc_issuer: env_or("DD_OIDC_ISSUER", file_cfg.oidc_issuer),
+            oidc_audience: env_or("DD_OIDC_AUDIENCE", file_cfg.oidc_audience),
+            oidc_scope: env_or("DD_OIDC_SCOPE", file_cfg.oidc_scope),
+            oidc_jwks_uri: env_or("DD_OIDC_JWKS_URI", file_cfg.oidc_jwks_uri),
+            oidc_userinfo_uri: env_or("DD_OIDC_USERINFO_URI", file_cfg.oidc_userinfo_uri),
+            oidc_required_claims: Vec::new(),
+            oidc_id_token_hint: env_or("DD_OIDC_ID_TOKEN_HINT", file_cfg.oidc_id_token_hint),
+            oidc_signing_alg: env_or("DD_OIDC_SIGNING_ALG", file_cfg.oidc_signing_alg),
+        };
+
+        Ok(cfg)
+    }
+
+    /// Validate that sufficient auth credentials are configured.
+    pub fn validate_auth(&self) -> Result<()> {
+        if self.access_token.is_none() && (self.api_key.is_none() || self.app_key.is_none()) {
+            bail!(
+                "authentication required: set DD_ACCESS_TOKEN for bearer auth, \
+                 run 'pup auth login' for OAuth2, \
+                 or set DD_API_KEY and DD_APP_KEY for API key auth"
+            );
+        }
+        Ok(())
+    }
+
+    pub fn has_api_keys(&self) -> bool {
+        self.api_key.is_some() && self.app_key.is_some()
+    }
+
+    pub fn has_bearer_token(&self) -> bool {
+        self.access_token.is_some()
+    }
+
+    /// Returns the API host (e.g., "api.datadoghq.com").
+    pub fn api_host(&self) -> String {
+        if self.site.contains("oncall") {
+            self.site.clone()
+        } else {
+            format!("api.{}", self.site)
+        }
+    }
+}
+
+/// Config file path: ~/.config/pup/config.yaml
+pub fn config_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|d| d.join("pup"))
+}
+
+fn config_file_path() -> Option<PathBuf> {
+    config_dir().map(|d| d.join("config.yaml"))
+}
+
+fn load_config_file() -> Option<FileConfig> {
+    let path = config_file_path()?;
+    let contents = std::fs::read_to_string(path).ok()?;
+    let mut file_cfg: FileConfig = serde_yaml::from_str(&contents).ok()?;
+
+    if let Some(block) = file_cfg.encrypted.take() {
+        match crypto::unseal_credentials(&block) {
+            Ok(creds) => {
+                file_cfg.api_key = file_cfg.api_key.or(creds.api_key);
+                file_cfg.app_key = file_cfg.app_key.or(creds.app_key);
+                file_cfg.access_token = file_cfg.access_token.or(creds.access_token);
+            }
+            Err(e) => {
+                eprintln!("warning: failed to decrypt stored credentials: {e:#}");
+            }
+        }
+    }
+
+    Some(file_cfg)
+}
+
+/// Rewrite `~/.config/pup/config.yaml` so `api_key`/`app_key`/`access_token`
+/// are replaced by a single sealed `encrypted:` block. Called by
+/// `pup auth lock`; the plaintext fields are never written back.
+pub fn write_encrypted_credentials(block: &crypto::EncryptedBlock) -> Result<()> {
+    let path = config_file_path().context("could not determine config directory")?;
+    let mut file_cfg: FileConfig = match std::fs::read_to_string(&path) {
+        Ok(contents) => serde_yaml::from_str(&contents).unwrap_or_default(),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => FileConfig::default(),
+        Err(e) => return Err(e.into()),
+    };
+
+    file_cfg.api_key = None;
+    file_cfg.app_key = None;
+    file_cfg.access_token = None;
+    file_cfg.encrypted = Some(block.clone());
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let yaml = serde_yaml::to_string(&SerializableFileConfig::from(&file_cfg))?;
+    std::fs::write(&path, yaml)?;
+    Ok(())
+}
+
+/// `serde_yaml::to_string` needs `Serialize`, but [`FileConfig`] only derives
+/// `Deserialize` (it's read-only everywhere else) — this mirrors its shape
+/// just for the one write path `write_encrypted_credentials` uses.
+#[derive(Serialize)]
+struct SerializableFileConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    api_key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    app_key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    access_token: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    site: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    org: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    output: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    auto_approve: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    storage_backend: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_retries: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pagerduty_routing_key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    vet_rules_path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    offline_access: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    oidc_token_endpoint: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    oidc_client_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    oidc_client_secret: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    oidc_issuer: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    oidc_audience: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    oidc_scope: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    oidc_jwks_uri: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    oidc_userinfo_uri: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    oidc_id_token_hint: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    oidc_signing_alg: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    encrypted: Option<crypto::EncryptedBlock>,
+}
+
+impl From<&FileConfig> for SerializableFileConfig {
+    fn from(f: &FileConfig) -> Self {
+        Self {
+            api_key: f.api_key.clone(),
+            app_key: f.app_key.clone(),
+            access_token: f.access_token.clone(),
+            site: f.site.clone(),
+            org: f.org.clone(),
+            output: f.output.clone(),
+            auto_approve: f.auto_approve,
+            storage_backend: f.storage_backend.clone(),
+            max_retries: f.max_retries,
+            pagerduty_routing_key: f.pagerduty_routing_key.clone(),
+            vet_rules_path: f.vet_rules_path.clone(),
+            offline_access: f.offline_access,
+            oidc_token_endpoint: f.oidc_token_endpoint.clone(),
+            oidc_client_id: f.oidc_client_id.clone(),
+            oidc_client_secret: f.oidc_client_secret.clone(),
+            oidc_issuer: f.oidc_issuer.clone(),
+            oidc_audience: f.oidc_audience.clone(),
+            oidc_scope: f.oidc_scope.clone(),
+            oidc_jwks_uri: f.oidc_jwks_uri.clone(),
+            oidc_userinfo_uri: f.oidc_userinfo_uri.clone(),
+            oidc_id_token_hint: f.oidc_id_token_hint.clone(),
+            oidc_signing_alg: f.oidc_signing_alg.clone(),
+            encrypted: f.encrypted.clone(),
+        }
+    }
+}
+
+fn env_or(key: &str, fallback: Option<String>) -> Option<String> {
+    std::env::var(key)
+        .ok()
+        .filter(|s| !s.is_empty())
+        .or(fallback)
+}
+
+fn env_bool(key: &str) -> bool {
+    matches!(
+        std::env::var(key)
+            .unwrap_or_default()
+            .to_lowercase()
+            .as_str(),
+        "true" | "1"
+    )
+}
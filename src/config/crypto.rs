@@ -0,0 +1,206 @@
+//! At-rest encryption for the `api_key`/`app_key`/`access_token` fields
+//! `pup auth lock` seals into the config file's `encrypted:` block.
+//!
+//! Mirrors [`crate::auth::storage`]'s Argon2id + XChaCha20-Poly1305 framing
+//! for the session-token store (same primitives, same `salt || nonce ||
+//! ciphertext` layout) rather than inventing a second scheme — the two
+//! differ only in what they protect and where the key comes from. Unlike
+//! that store, the derived key can also be parked in the OS keychain
+//! ([`KeySource::Keyring`]) so `from_env` can decrypt non-interactively.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 24;
+const KEY_LEN: usize = 32;
+
+/// Argon2id cost parameters: ~64 MiB memory, 3 iterations, single lane.
+/// Deliberately heavier than `auth::storage`'s defaults (session tokens are
+/// decrypted far more often than this config block is) but still cheap
+/// enough for an interactive `pup` invocation.
+const ARGON2_MEM_COST_KIB: u32 = 64 * 1024;
+const ARGON2_TIME_COST: u32 = 3;
+const ARGON2_LANES: u32 = 1;
+
+const SERVICE_NAME: &str = "pup";
+const KEYRING_KEY_ENTRY: &str = "config-credentials-key";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KeySource {
+    /// Key is derived from a passphrase (`DD_CONFIG_PASSPHRASE` or an
+    /// interactive prompt) plus the salt carried alongside the ciphertext.
+    Passphrase,
+    /// The derived key itself is stored in the OS keychain, so no
+    /// passphrase is needed at decrypt time.
+    Keyring,
+}
+
+/// The sealed form persisted under `encrypted:` in `~/.config/pup/config.yaml`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EncryptedBlock {
+    pub key_source: KeySource,
+    /// base64 of `salt || nonce || ciphertext` (`Passphrase`) or
+    /// `nonce || ciphertext` (`Keyring`, which has no salt to carry).
+    pub data: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DecryptedCredentials {
+    pub api_key: Option<String>,
+    pub app_key: Option<String>,
+    pub access_token: Option<String>,
+}
+
+/// Seal `creds` as JSON, using a freshly generated random salt (`Passphrase`)
+/// or the key already stashed in the OS keychain (`Keyring`, generating and
+/// storing one on first use).
+pub fn seal_credentials(creds: &DecryptedCredentials, source: KeySource) -> Result<EncryptedBlock> {
+    let plaintext = serde_json::to_vec(creds).context("failed to serialize credentials")?;
+
+    let mut nonce = [0u8; NONCE_LEN];
+    rand::RngCore::fill_bytes(&mut rand::thread_rng(), &mut nonce);
+
+    let data = match source {
+        KeySource::Passphrase => {
+            let mut salt = [0u8; SALT_LEN];
+            rand::RngCore::fill_bytes(&mut rand::thread_rng(), &mut salt);
+            let passphrase = config_passphrase()?;
+            let key = derive_key(&passphrase, &salt)?;
+            let ciphertext = encrypt(&key, &nonce, &plaintext)?;
+
+            let mut out = Vec::with_capacity(salt.len() + nonce.len() + ciphertext.len());
+            out.extend_from_slice(&salt);
+            out.extend_from_slice(&nonce);
+            out.extend_from_slice(&ciphertext);
+            out
+        }
+        KeySource::Keyring => {
+            let key = keyring_key(true)?;
+            let ciphertext = encrypt(&key, &nonce, &plaintext)?;
+
+            let mut out = Vec::with_capacity(nonce.len() + ciphertext.len());
+            out.extend_from_slice(&nonce);
+            out.extend_from_slice(&ciphertext);
+            out
+        }
+    };
+
+    Ok(EncryptedBlock { key_source: source, data: base64_encode(&data) })
+}
+
+/// Reverse of [`seal_credentials`]: re-derive (or fetch) the key and open
+/// the blob, returning the decrypted credentials for `from_env` to slot
+/// into `FileConfig` where plaintext values would otherwise sit.
+pub fn unseal_credentials(block: &EncryptedBlock) -> Result<DecryptedCredentials> {
+    let raw = base64_decode(&block.data).context("encrypted block is not valid base64")?;
+
+    let plaintext = match block.key_source {
+        KeySource::Passphrase => {
+            if raw.len() < SALT_LEN + NONCE_LEN {
+                anyhow::bail!("encrypted block is truncated");
+            }
+            let (salt, rest) = raw.split_at(SALT_LEN);
+            let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+            let passphrase = config_passphrase()?;
+            let key = derive_key(&passphrase, salt)?;
+            decrypt(&key, nonce, ciphertext)?
+        }
+        KeySource::Keyring => {
+            if raw.len() < NONCE_LEN {
+                anyhow::bail!("encrypted block is truncated");
+            }
+            let (nonce, ciphertext) = raw.split_at(NONCE_LEN);
+            let key = keyring_key(false)?;
+            decrypt(&key, nonce, ciphertext)?
+        }
+    };
+
+    serde_json::from_slice(&plaintext).context("decrypted credentials are not valid JSON")
+}
+
+/// `DD_CONFIG_PASSPHRASE` if set; otherwise an interactive masked prompt.
+fn config_passphrase() -> Result<String> {
+    if let Ok(p) = std::env::var("DD_CONFIG_PASSPHRASE") {
+        return Ok(p);
+    }
+    rpassword::prompt_password("pup config passphrase: ")
+        .map_err(|e| anyhow::anyhow!("failed to read passphrase: {e}"))
+}
+
+/// Load the 32-byte key from the OS keychain, generating and storing a
+/// fresh random one on first use when `create_if_missing` is set (the
+/// `pup auth lock --keyring` path); otherwise a missing entry is an error
+/// (decrypting should never silently invent a new key).
+fn keyring_key(create_if_missing: bool) -> Result<[u8; KEY_LEN]> {
+    let entry = keyring::Entry::new(SERVICE_NAME, KEYRING_KEY_ENTRY)
+        .context("failed to open OS keychain entry")?;
+
+    match entry.get_password() {
+        Ok(encoded) => {
+            let bytes = base64_decode(&encoded).context("keychain key is not valid base64")?;
+            bytes
+                .try_into()
+                .map_err(|_| anyhow::anyhow!("keychain key has unexpected length"))
+        }
+        Err(keyring::Error::NoEntry) if create_if_missing => {
+            let mut key = [0u8; KEY_LEN];
+            rand::RngCore::fill_bytes(&mut rand::thread_rng(), &mut key);
+            entry
+                .set_password(&base64_encode(&key))
+                .context("failed to store key in OS keychain")?;
+            Ok(key)
+        }
+        Err(keyring::Error::NoEntry) => {
+            anyhow::bail!("no config-credentials key found in the OS keychain; run `pup auth lock --keyring` first")
+        }
+        Err(e) => Err(e).context("failed to read OS keychain entry"),
+    }
+}
+
+/// Derive a 32-byte key from `passphrase` and `salt` using Argon2id with
+/// this module's fixed cost parameters.
+fn derive_key(passphrase: &str, salt: &[u8]) -> Result<[u8; KEY_LEN]> {
+    use argon2::{Algorithm, Argon2, Params, Version};
+
+    let params = Params::new(ARGON2_MEM_COST_KIB, ARGON2_TIME_COST, ARGON2_LANES, Some(KEY_LEN))
+        .map_err(|e| anyhow::anyhow!("invalid argon2 parameters: {e}"))?;
+    let argon2 = Argon2::new(Algorithm::Argon2id, Version::V0x13, params);
+
+    let mut key = [0u8; KEY_LEN];
+    argon2
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| anyhow::anyhow!("key derivation failed: {e}"))?;
+    Ok(key)
+}
+
+fn encrypt(key: &[u8; KEY_LEN], nonce: &[u8; NONCE_LEN], plaintext: &[u8]) -> Result<Vec<u8>> {
+    use chacha20poly1305::aead::{Aead, KeyInit};
+    use chacha20poly1305::XChaCha20Poly1305;
+
+    let cipher = XChaCha20Poly1305::new(key.into());
+    cipher
+        .encrypt(nonce.into(), plaintext)
+        .map_err(|_| anyhow::anyhow!("failed to encrypt credentials"))
+}
+
+fn decrypt(key: &[u8; KEY_LEN], nonce: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>> {
+    use chacha20poly1305::aead::{Aead, KeyInit};
+    use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+
+    let cipher = XChaCha20Poly1305::new(key.into());
+    cipher
+        .decrypt(XNonce::from_slice(nonce), ciphertext)
+        .map_err(|_| anyhow::anyhow!("wrong passphrase (or keychain key) or corrupted credentials"))
+}
+
+fn base64_encode(bytes: &[u8]) -> String {
+    use base64::{engine::general_purpose::STANDARD, Engine as _};
+    STANDARD.encode(bytes)
+}
+
+fn base64_decode(s: &str) -> Result<Vec<u8>> {
+    use base64::{engine::general_purpose::STANDARD, Engine as _};
+    STANDARD.decode(s).context("invalid base64")
+}
@@ -0,0 +1,160 @@
+//! Output formatting for command results: renders a `Serialize` value as
+//! `table` (best-effort human summary via `serde_json::Value`), `json`, or
+//! `yaml`, per `cfg.output_format`. Agent-mode additionally wraps JSON/YAML
+//! output in an envelope carrying [`Metadata`] — counts and a suggested
+//! next action — so an LLM driving `pup` doesn't have to re-derive those
+//! from the raw payload.
+
+use anyhow::Result;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::config::{Config, OutputFormat};
+
+/// Agent-mode envelope metadata: how many items came back, whether the
+/// result was truncated, which command produced it, and (when truncated or
+/// otherwise actionable) a one-line suggestion for what to do next.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Metadata {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub count: Option<usize>,
+    pub truncated: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_action: Option<String>,
+}
+
+/// Render `value` per `cfg.output_format` with no metadata envelope — the
+/// common case every read-only command and most mutations use.
+pub fn output<T: Serialize>(cfg: &Config, value: T) -> Result<()> {
+    format_and_print(&value, &cfg.output_format, cfg.agent_mode, None)
+}
+
+/// Render `value` per `format`, wrapping it in a `{"data": ..., "meta": ...}`
+/// envelope when `meta` is `Some` (agent mode) and the format is JSON/YAML;
+/// `table` format ignores `meta` since it's meant for a human already
+/// looking at the terminal.
+pub fn format_and_print<T: Serialize>(
+    value: &T,
+    format: &OutputFormat,
+    agent_mode: bool,
+    meta: Option<&Metadata>,
+) -> Result<()> {
+    match format {
+        OutputFormat::Table => print_table(value),
+        OutputFormat::Json => {
+            let rendered = envelope(value, agent_mode, meta)?;
+            println!("{}", serde_json::to_string_pretty(&rendered)?);
+            Ok(())
+        }
+        OutputFormat::Yaml => {
+            let rendered = envelope(value, agent_mode, meta)?;
+            print!("{}", serde_yaml::to_string(&rendered)?);
+            Ok(())
+        }
+    }
+}
+
+fn envelope<T: Serialize>(value: &T, agent_mode: bool, meta: Option<&Metadata>) -> Result<Value> {
+    let data = serde_json::to_value(value)?;
+    Ok(match (agent_mode, meta) {
+        (true, Some(meta)) => serde_json::json!({ "data": data, "meta": meta }),
+        _ => data,
+    })
+}
+
+/// `table` format is best-effort: pretty-print whatever shape the JSON takes
+/// rather than pretending every command result maps onto real columns.
+fn print_table<T: Serialize>(value: &T) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
+
+/// Print `value` as JSON regardless of `cfg.output_format` — for the
+/// handful of call sites (batch results, `vet` summaries) that decide their
+/// own envelope shape rather than deferring to [`format_and_print`].
+pub fn print_json<T: Serialize>(value: &T) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Error output: keep `--output json|yaml` machine-parseable on failure
+// ---------------------------------------------------------------------------
+
+/// An HTTP status code a command can attach to its `anyhow::Error` via
+/// `.context(DatadogStatus(403))`, read back out by [`output_error`]'s
+/// downcast walk without changing every command's error type to carry one.
+#[derive(Debug, Clone, Copy)]
+pub struct DatadogStatus(pub u16);
+
+impl std::fmt::Display for DatadogStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "datadog status {}", self.0)
+    }
+}
+
+impl std::error::Error for DatadogStatus {}
+
+fn find_datadog_status(err: &anyhow::Error) -> Option<u16> {
+    err.chain().find_map(|cause| cause.downcast_ref::<DatadogStatus>()).map(|s| s.0)
+}
+
+/// Wrap a generated Datadog SDK call's error with `prefix`, the way every
+/// command's `.map_err(|e| anyhow::anyhow!("failed to ...: {e:?}"))` already
+/// does — except this also attaches the response's HTTP status via
+/// [`DatadogStatus`] when the failure came back as an API response (as
+/// opposed to a transport/serialization error), so [`output_error`] has
+/// something to surface instead of always reporting `datadog_status: null`.
+pub fn datadog_error<T: std::fmt::Debug>(prefix: &str, err: datadog_api_client::datadog::Error<T>) -> anyhow::Error {
+    let status = match &err {
+        datadog_api_client::datadog::Error::ResponseError(resp) => Some(resp.status.as_u16()),
+        _ => None,
+    };
+    let wrapped = anyhow::anyhow!("{prefix}: {err:?}");
+    match status {
+        Some(code) => wrapped.context(DatadogStatus(code)),
+        None => wrapped,
+    }
+}
+
+/// The top-level error handler `main` calls instead of printing `err`
+/// directly: for `table` format this is just `Error: {err:#}` on stderr,
+/// unchanged from before. For `json`/`yaml`, emit a structured
+/// `{"error": {"message", "operation", "datadog_status"}}` envelope on
+/// stderr instead, so scripted/agent callers get the same shape on failure
+/// they get on success.
+pub fn output_error(cfg: &Config, operation: &str, err: &anyhow::Error) {
+    match cfg.output_format {
+        OutputFormat::Table => eprintln!("Error: {err:#}"),
+        OutputFormat::Json | OutputFormat::Yaml => {
+            let body = serde_json::json!({
+                "error": {
+                    "message": err.to_string(),
+                    "operation": operation,
+                    "datadog_status": find_datadog_status(err),
+                }
+            });
+            let rendered = match cfg.output_format {
+                OutputFormat::Yaml => serde_yaml::to_string(&body).unwrap_or_else(|_| body.to_string()),
+                _ => serde_json::to_string_pretty(&body).unwrap_or_else(|_| body.to_string()),
+            };
+            eprintln!("{rendered}");
+        }
+    }
+}
+
+/// Render a `delete`-style success message — `{"status":"deleted","id":...}`
+/// for JSON/YAML, a human sentence for `table` — so destructive commands
+/// stop hardcoding `println!` and honor `cfg.output_format` like every read
+/// path already does via [`output`].
+pub fn output_deleted(cfg: &Config, resource: &str, id: &str) -> Result<()> {
+    match cfg.output_format {
+        OutputFormat::Table => {
+            println!("Successfully deleted {resource} {id}");
+            Ok(())
+        }
+        _ => output(cfg, serde_json::json!({ "status": "deleted", "id": id })),
+    }
+}
@@ -0,0 +1,460 @@
+//! A CLI query-builder + filter DSL for product-analytics commands, so
+//! common analytics/journey/retention/sankey queries need no hand-authored
+//! JSON request body. `build_body` merges flag-derived fields onto an
+//! optional `--file` JSON body (flags win on conflict), and `parse_filter`
+//! implements a recursive-descent parser for `--filter "EXPR"`: tokens are
+//! `field OP value` (`OP` in `= != > >= < <= in`), combined with `AND`/`OR`
+//! and parenthesized groups, serialized into Datadog's nested
+//! `{"and":[...]}` / `{"or":[...]}` / `{"operator":..,"field":..,"value":..}`
+//! shape.
+
+use anyhow::{bail, Context, Result};
+use serde_json::{json, Value};
+
+/// Structured flags for one query — the alternative to hand-authoring the
+/// full JSON request body in a `--file`.
+#[derive(Debug, Default, Clone)]
+pub struct QueryArgs {
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub metric: Option<String>,
+    pub group_by: Vec<String>,
+    pub steps: Vec<String>,
+    pub filters: Vec<String>,
+}
+
+impl QueryArgs {
+    fn is_empty(&self) -> bool {
+        self.from.is_none()
+            && self.to.is_none()
+            && self.metric.is_none()
+            && self.group_by.is_empty()
+            && self.steps.is_empty()
+            && self.filters.is_empty()
+    }
+}
+
+/// Build the request body for a query command: `file` read as JSON if
+/// given (an empty object otherwise), then `args`' structured flags merged
+/// on top — flags override matching keys already in the file body.
+pub fn build_body(file: Option<&str>, args: &QueryArgs) -> Result<Value> {
+    let mut body = match file {
+        Some(path) => crate::util::read_json_file(path)?,
+        None => json!({}),
+    };
+
+    if args.is_empty() {
+        return Ok(body);
+    }
+
+    let obj = body
+        .as_object_mut()
+        .context("request body must be a JSON object to merge query-builder flags into")?;
+
+    if let Some(from) = &args.from {
+        obj.insert("from".to_string(), json!(resolve_time(from)?));
+    }
+    if let Some(to) = &args.to {
+        obj.insert("to".to_string(), json!(resolve_time(to)?));
+    }
+    if let Some(metric) = &args.metric {
+        obj.insert("metric".to_string(), json!(metric));
+    }
+    if !args.group_by.is_empty() {
+        obj.insert("group_by".to_string(), json!(args.group_by));
+    }
+    if !args.steps.is_empty() {
+        obj.insert("steps".to_string(), json!(args.steps));
+    }
+    if !args.filters.is_empty() {
+        let trees = args
+            .filters
+            .iter()
+            .map(|f| parse_filter(f))
+            .collect::<Result<Vec<Value>>>()?;
+        let filter = if trees.len() == 1 {
+            trees.into_iter().next().unwrap()
+        } else {
+            json!({ "and": trees })
+        };
+        obj.insert("filter".to_string(), filter);
+    }
+
+    Ok(body)
+}
+
+/// Resolve a `now`/`now-<n><unit>` relative timestamp (unit in `s m h d w`)
+/// to an RFC3339 string; anything else (an already-absolute RFC3339
+/// timestamp) passes through unchanged.
+fn resolve_time(spec: &str) -> Result<String> {
+    let Some(rest) = spec.strip_prefix("now") else {
+        return Ok(spec.to_string());
+    };
+    if rest.is_empty() {
+        return Ok(chrono::Utc::now().to_rfc3339());
+    }
+    let rest = rest
+        .strip_prefix('-')
+        .with_context(|| format!("invalid relative time '{spec}' (expected 'now' or 'now-<n><unit>')"))?;
+    let unit_pos = rest
+        .find(|c: char| !c.is_ascii_digit())
+        .with_context(|| format!("invalid relative time '{spec}' (expected 'now-<n><unit>')"))?;
+    let (amount, unit) = rest.split_at(unit_pos);
+    let amount: i64 = amount
+        .parse()
+        .with_context(|| format!("invalid relative time '{spec}'"))?;
+    let secs = match unit {
+        "s" => amount,
+        "m" => amount * 60,
+        "h" => amount * 3600,
+        "d" => amount * 86400,
+        "w" => amount * 7 * 86400,
+        other => bail!("unknown relative time unit '{other}' in '{spec}' (expected s/m/h/d/w)"),
+    };
+    Ok((chrono::Utc::now() - chrono::Duration::seconds(secs)).to_rfc3339())
+}
+
+// ---- Filter DSL ----
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Op(String),
+    Number(f64),
+    Bool(bool),
+    Str(String),
+    LParen,
+    RParen,
+    LBracket,
+    RBracket,
+    Comma,
+    And,
+    Or,
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+        match c {
+            '(' => {
+                tokens.push(Token::LParen);
+                i += 1;
+            }
+            ')' => {
+                tokens.push(Token::RParen);
+                i += 1;
+            }
+            '[' => {
+                tokens.push(Token::LBracket);
+                i += 1;
+            }
+            ']' => {
+                tokens.push(Token::RBracket);
+                i += 1;
+            }
+            ',' => {
+                tokens.push(Token::Comma);
+                i += 1;
+            }
+            '"' | '\'' => {
+                let quote = c;
+                let start = i + 1;
+                let mut j = start;
+                while j < chars.len() && chars[j] != quote {
+                    j += 1;
+                }
+                if j >= chars.len() {
+                    bail!("unterminated string literal in filter expression '{input}'");
+                }
+                tokens.push(Token::Str(chars[start..j].iter().collect()));
+                i = j + 1;
+            }
+            '=' => {
+                tokens.push(Token::Op("=".to_string()));
+                i += 1;
+            }
+            '!' if chars.get(i + 1) == Some(&'=') => {
+                tokens.push(Token::Op("!=".to_string()));
+                i += 2;
+            }
+            '>' if chars.get(i + 1) == Some(&'=') => {
+                tokens.push(Token::Op(">=".to_string()));
+                i += 2;
+            }
+            '>' => {
+                tokens.push(Token::Op(">".to_string()));
+                i += 1;
+            }
+            '<' if chars.get(i + 1) == Some(&'=') => {
+                tokens.push(Token::Op("<=".to_string()));
+                i += 2;
+            }
+            '<' => {
+                tokens.push(Token::Op("<".to_string()));
+                i += 1;
+            }
+            _ if c.is_ascii_digit() || (c == '-' && chars.get(i + 1).is_some_and(char::is_ascii_digit)) => {
+                let start = i;
+                i += 1;
+                while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                    i += 1;
+                }
+                let raw: String = chars[start..i].iter().collect();
+                let num: f64 = raw
+                    .parse()
+                    .with_context(|| format!("invalid number '{raw}' in filter expression '{input}'"))?;
+                tokens.push(Token::Number(num));
+            }
+            _ if c.is_alphabetic() || c == '_' => {
+                let start = i;
+                while i < chars.len()
+                    && (chars[i].is_alphanumeric() || chars[i] == '_' || chars[i] == '.' || chars[i] == ':')
+                {
+                    i += 1;
+                }
+                let word: String = chars[start..i].iter().collect();
+                match word.to_uppercase().as_str() {
+                    "AND" => tokens.push(Token::And),
+                    "OR" => tokens.push(Token::Or),
+                    "IN" => tokens.push(Token::Op("in".to_string())),
+                    "TRUE" => tokens.push(Token::Bool(true)),
+                    "FALSE" => tokens.push(Token::Bool(false)),
+                    _ => tokens.push(Token::Ident(word)),
+                }
+            }
+            other => bail!("unexpected character '{other}' in filter expression '{input}'"),
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn advance(&mut self) -> Option<Token> {
+        let t = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        t
+    }
+
+    fn expect(&mut self, tok: &Token) -> Result<()> {
+        match self.advance() {
+            Some(ref t) if t == tok => Ok(()),
+            other => bail!("expected {tok:?}, found {other:?} in filter expression"),
+        }
+    }
+
+    /// expr := and_expr (OR and_expr)*
+    fn parse_expr(&mut self) -> Result<Value> {
+        let first = self.parse_and()?;
+        let mut rest = Vec::new();
+        while matches!(self.peek(), Some(Token::Or)) {
+            self.advance();
+            rest.push(self.parse_and()?);
+        }
+        if rest.is_empty() {
+            return Ok(first);
+        }
+        let mut all = vec![first];
+        all.extend(rest);
+        Ok(json!({ "or": all }))
+    }
+
+    /// and_expr := term (AND term)*
+    fn parse_and(&mut self) -> Result<Value> {
+        let first = self.parse_term()?;
+        let mut rest = Vec::new();
+        while matches!(self.peek(), Some(Token::And)) {
+            self.advance();
+            rest.push(self.parse_term()?);
+        }
+        if rest.is_empty() {
+            return Ok(first);
+        }
+        let mut all = vec![first];
+        all.extend(rest);
+        Ok(json!({ "and": all }))
+    }
+
+    /// term := '(' expr ')' | comparison
+    fn parse_term(&mut self) -> Result<Value> {
+        if matches!(self.peek(), Some(Token::LParen)) {
+            self.advance();
+            let inner = self.parse_expr()?;
+            self.expect(&Token::RParen)?;
+            return Ok(inner);
+        }
+        self.parse_comparison()
+    }
+
+    /// comparison := field OP value
+    fn parse_comparison(&mut self) -> Result<Value> {
+        let field = match self.advance() {
+            Some(Token::Ident(f)) => f,
+            other => bail!("expected a field name, found {other:?} in filter expression"),
+        };
+        let op = match self.advance() {
+            Some(Token::Op(op)) => op,
+            other => bail!("expected an operator (= != > >= < <= in), found {other:?} in filter expression"),
+        };
+        let value = self.parse_value()?;
+        Ok(json!({ "operator": op, "field": field, "value": value }))
+    }
+
+    fn parse_value(&mut self) -> Result<Value> {
+        match self.advance() {
+            Some(Token::Number(n)) => Ok(json!(n)),
+            Some(Token::Bool(b)) => Ok(json!(b)),
+            Some(Token::Str(s)) => Ok(json!(s)),
+            Some(Token::LBracket) => {
+                let mut items = Vec::new();
+                if !matches!(self.peek(), Some(Token::RBracket)) {
+                    loop {
+                        items.push(self.parse_value()?);
+                        if matches!(self.peek(), Some(Token::Comma)) {
+                            self.advance();
+                        } else {
+                            break;
+                        }
+                    }
+                }
+                self.expect(&Token::RBracket)?;
+                Ok(json!(items))
+            }
+            other => bail!("expected a value (number, boolean, quoted string, or [list]), found {other:?} in filter expression"),
+        }
+    }
+}
+
+/// Parse one `--filter "EXPR"` into Datadog's nested filter-tree JSON shape.
+pub fn parse_filter(expr: &str) -> Result<Value> {
+    let tokens = tokenize(expr)?;
+    if tokens.is_empty() {
+        bail!("empty filter expression");
+    }
+    let mut parser = Parser { tokens, pos: 0 };
+    let tree = parser.parse_expr()?;
+    if parser.pos != parser.tokens.len() {
+        bail!("unexpected trailing tokens in filter expression '{expr}'");
+    }
+    Ok(tree)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_filter_simple_comparison() {
+        let tree = parse_filter("status = \"active\"").unwrap();
+        assert_eq!(tree, json!({"operator": "=", "field": "status", "value": "active"}));
+    }
+
+    #[test]
+    fn parse_filter_numeric_and_boolean_values() {
+        let tree = parse_filter("count >= 5").unwrap();
+        assert_eq!(tree, json!({"operator": ">=", "field": "count", "value": 5.0}));
+
+        let tree = parse_filter("active = true").unwrap();
+        assert_eq!(tree, json!({"operator": "=", "field": "active", "value": true}));
+    }
+
+    #[test]
+    fn parse_filter_in_list() {
+        let tree = parse_filter("env in [\"prod\", \"staging\"]").unwrap();
+        assert_eq!(
+            tree,
+            json!({"operator": "in", "field": "env", "value": ["prod", "staging"]})
+        );
+    }
+
+    #[test]
+    fn parse_filter_and_or_precedence() {
+        // AND binds tighter than OR: a OR (b AND c)
+        let tree = parse_filter("a = 1 OR b = 2 AND c = 3").unwrap();
+        assert_eq!(
+            tree,
+            json!({"or": [
+                {"operator": "=", "field": "a", "value": 1.0},
+                {"and": [
+                    {"operator": "=", "field": "b", "value": 2.0},
+                    {"operator": "=", "field": "c", "value": 3.0},
+                ]},
+            ]})
+        );
+    }
+
+    #[test]
+    fn parse_filter_parenthesized_group() {
+        let tree = parse_filter("(a = 1 OR b = 2) AND c = 3").unwrap();
+        assert_eq!(
+            tree,
+            json!({"and": [
+                {"or": [
+                    {"operator": "=", "field": "a", "value": 1.0},
+                    {"operator": "=", "field": "b", "value": 2.0},
+                ]},
+                {"operator": "=", "field": "c", "value": 3.0},
+            ]})
+        );
+    }
+
+    #[test]
+    fn parse_filter_rejects_trailing_garbage() {
+        assert!(parse_filter("a = 1 b = 2").is_err());
+    }
+
+    #[test]
+    fn build_body_merges_flags_onto_file_body() {
+        let args = QueryArgs {
+            metric: Some("clicks".to_string()),
+            group_by: vec!["country".to_string()],
+            filters: vec!["env = \"prod\"".to_string()],
+            ..Default::default()
+        };
+        let body = build_body(None, &args).unwrap();
+        assert_eq!(body["metric"], json!("clicks"));
+        assert_eq!(body["group_by"], json!(["country"]));
+        assert_eq!(body["filter"], json!({"operator": "=", "field": "env", "value": "prod"}));
+    }
+
+    #[test]
+    fn build_body_combines_multiple_filters_with_and() {
+        let args = QueryArgs {
+            filters: vec!["a = 1".to_string(), "b = 2".to_string()],
+            ..Default::default()
+        };
+        let body = build_body(None, &args).unwrap();
+        assert_eq!(
+            body["filter"],
+            json!({"and": [
+                {"operator": "=", "field": "a", "value": 1.0},
+                {"operator": "=", "field": "b", "value": 2.0},
+            ]})
+        );
+    }
+
+    #[test]
+    fn resolve_time_passes_through_absolute_timestamps() {
+        assert_eq!(resolve_time("2024-01-01T00:00:00Z").unwrap(), "2024-01-01T00:00:00Z");
+    }
+
+    #[test]
+    fn resolve_time_rejects_unknown_unit() {
+        assert!(resolve_time("now-7x").is_err());
+    }
+}
@@ -0,0 +1,95 @@
+//! Datadog API/site compatibility probing.
+//!
+//! `pup` is built against a specific `datadog-api-client` model version,
+//! but not every site edition (GovCloud, EU, self-managed) exposes every
+//! feature that version models — a scoped application key, say, may not
+//! exist yet on an older on-prem deployment. [`check_api_compat`] hits the
+//! cheap, universally-supported `/api/v1/validate` endpoint to confirm the
+//! configured credentials work against `cfg.site` at all, then reports
+//! what it can infer about the target's capabilities so a later command
+//! fails with a clear warning up front instead of a confusing 404.
+
+use anyhow::Result;
+use serde::Serialize;
+
+use crate::config::Config;
+
+/// A capability pup's command surface relies on that not every site
+/// edition supports yet.
+const SCOPED_APP_KEY_FEATURE: &str = "application-key-scopes (api-keys create/update --scopes)";
+
+#[derive(Debug, Serialize)]
+pub struct ApiCompat {
+    pub site: String,
+    /// Whether `cfg`'s credentials validated successfully against `site`.
+    pub credentials_valid: bool,
+    /// The `datadog-api-client` model version `pup` was built against.
+    pub client_model_version: &'static str,
+    /// Features `pup` exposes that may be unavailable on this site's
+    /// edition, inferred from the site hostname (GovCloud, EU, ...) since
+    /// `/api/v1/validate` doesn't itself report a capability list.
+    pub warnings: Vec<String>,
+}
+
+fn capability_warnings(site: &str) -> Vec<String> {
+    let mut warnings = Vec::new();
+    if site.contains("gov") {
+        warnings.push(format!(
+            "{site} looks like a GovCloud site: {SCOPED_APP_KEY_FEATURE} may not be available there yet"
+        ));
+    }
+    warnings
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub async fn check_api_compat(cfg: &Config) -> Result<ApiCompat> {
+    use datadog_api_client::datadogV1::api_authentication::AuthenticationAPI;
+
+    let dd_cfg = crate::client::make_dd_config(cfg);
+    let api = match crate::client::make_bearer_client(cfg) {
+        Some(c) => AuthenticationAPI::with_client_and_config(dd_cfg, c),
+        None => AuthenticationAPI::with_config(dd_cfg),
+    };
+    let resp = api
+        .validate()
+        .await
+        .map_err(|e| anyhow::anyhow!("failed to validate credentials against {}: {e:?}", cfg.site))?;
+
+    Ok(ApiCompat {
+        site: cfg.site.clone(),
+        credentials_valid: resp.valid.unwrap_or(false),
+        client_model_version: env!("CARGO_PKG_VERSION"),
+        warnings: capability_warnings(&cfg.site),
+    })
+}
+
+#[cfg(target_arch = "wasm32")]
+pub async fn check_api_compat(cfg: &Config) -> Result<ApiCompat> {
+    let data = crate::api::get(cfg, "/api/v1/validate", &[]).await?;
+    let credentials_valid = data.get("valid").and_then(serde_json::Value::as_bool).unwrap_or(false);
+
+    Ok(ApiCompat {
+        site: cfg.site.clone(),
+        credentials_valid,
+        client_model_version: env!("CARGO_PKG_VERSION"),
+        warnings: capability_warnings(&cfg.site),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capability_warnings_flags_gov_sites() {
+        let warnings = capability_warnings("ddog-gov.com");
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("GovCloud"));
+    }
+
+    #[test]
+    fn test_capability_warnings_silent_on_commercial_sites() {
+        assert!(capability_warnings("datadoghq.com").is_empty());
+        assert!(capability_warnings("datadoghq.eu").is_empty());
+    }
+}
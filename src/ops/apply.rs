@@ -0,0 +1,247 @@
+//! Declarative `apply`: reconcile a manifest of resources against the live
+//! account, like an operator reconciling desired state. Today this spans
+//! the resource kinds `ops::backup::ResourceKind` already knows how to
+//! list/create/update (monitors, SLOs, dashboards, downtime, notebooks,
+//! synthetics tests, security rules) — the other CRUD subsystems
+//! (status pages, RUM retention filters, Jira templates, flaky tests) this
+//! was modeled on don't exist in this build yet, so `ResourceKind::parse`
+//! is the single source of truth for which `kind`s a manifest can use.
+
+use std::collections::HashMap;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::api;
+use crate::config::Config;
+use crate::ops::backup::ResourceKind;
+
+/// One entry in an `apply` manifest: `kind` + a stable `name` identify the
+/// resource across runs (so repeated applies are idempotent), `spec` is the
+/// desired object body merged onto (or compared against) the live object.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ManifestResource {
+    pub kind: String,
+    pub name: String,
+    #[serde(flatten)]
+    pub spec: Value,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Manifest {
+    pub resources: Vec<ManifestResource>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Action {
+    Create,
+    Update,
+    Delete,
+    Unchanged,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PlanItem {
+    pub kind: String,
+    pub name: String,
+    pub id: Option<String>,
+    pub action: Action,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ApplySummary {
+    pub created: Vec<String>,
+    pub updated: Vec<String>,
+    pub deleted: Vec<String>,
+    pub unchanged: usize,
+    pub plan: Vec<PlanItem>,
+}
+
+/// Compare the manifest's desired resources against the live account and
+/// decide create/update/delete/unchanged for each, without mutating
+/// anything. `prune` controls whether live objects of a manifest's kinds
+/// that have no matching manifest entry get planned for deletion.
+pub async fn plan(cfg: &Config, manifest: &Manifest, prune: bool) -> Result<Vec<PlanItem>> {
+    let kinds: Vec<ResourceKind> = {
+        let mut kinds = manifest
+            .resources
+            .iter()
+            .map(|r| ResourceKind::parse(&r.kind))
+            .collect::<Result<Vec<_>>>()?;
+        kinds.sort_by_key(|k| k.as_str());
+        kinds.dedup();
+        kinds
+    };
+
+    let mut live_by_kind: HashMap<&'static str, HashMap<String, Value>> = HashMap::new();
+    for kind in &kinds {
+        let resp = api::get(cfg, kind.collection_path(), &[])
+            .await
+            .with_context(|| format!("failed to list {} while planning apply", kind.as_str()))?;
+        let by_name: HashMap<String, Value> = kind
+            .list_items(resp)?
+            .into_iter()
+            .filter_map(|obj| kind.name_of(&obj).map(|name| (name, obj)))
+            .collect();
+        live_by_kind.insert(kind.as_str(), by_name);
+    }
+
+    let mut items = Vec::new();
+    let mut claimed: HashMap<&'static str, std::collections::HashSet<String>> = HashMap::new();
+
+    for resource in &manifest.resources {
+        let kind = ResourceKind::parse(&resource.kind)?;
+        let live = live_by_kind.get(kind.as_str()).and_then(|m| m.get(&resource.name));
+
+        claimed
+            .entry(kind.as_str())
+            .or_default()
+            .insert(resource.name.clone());
+
+        let (action, id) = match live {
+            None => (Action::Create, None),
+            Some(obj) => {
+                let id = kind.id_of(obj);
+                if spec_matches(&resource.spec, obj) {
+                    (Action::Unchanged, id)
+                } else {
+                    (Action::Update, id)
+                }
+            }
+        };
+
+        items.push(PlanItem {
+            kind: kind.as_str().to_string(),
+            name: resource.name.clone(),
+            id,
+            action,
+        });
+    }
+
+    if prune {
+        for kind in &kinds {
+            let Some(live) = live_by_kind.get(kind.as_str()) else {
+                continue;
+            };
+            let claimed_names = claimed.get(kind.as_str());
+            for (name, obj) in live {
+                if claimed_names.is_some_and(|c| c.contains(name)) {
+                    continue;
+                }
+                items.push(PlanItem {
+                    kind: kind.as_str().to_string(),
+                    name: name.clone(),
+                    id: kind.id_of(obj),
+                    action: Action::Delete,
+                });
+            }
+        }
+    }
+
+    Ok(items)
+}
+
+/// True if every field in `spec` is already present with the same value on
+/// `live` — a subset comparison, since `live` carries server-managed fields
+/// (id, timestamps, ...) the manifest never specifies.
+fn spec_matches(spec: &Value, live: &Value) -> bool {
+    let Some(spec_obj) = spec.as_object() else {
+        return false;
+    };
+    let Some(live_obj) = live.as_object() else {
+        return false;
+    };
+    spec_obj
+        .iter()
+        .all(|(k, v)| k == "kind" || k == "name" || live_obj.get(k) == Some(v))
+}
+
+/// Execute `plan`'s decisions against the account: create, update, or (if
+/// `prune`) delete resources so they match the manifest. `dry_run` computes
+/// and returns the plan without mutating anything.
+pub async fn run(cfg: &Config, manifest: &Manifest, dry_run: bool, prune: bool) -> Result<ApplySummary> {
+    let items = plan(cfg, manifest, prune).await?;
+
+    if dry_run {
+        return Ok(ApplySummary {
+            created: vec![],
+            updated: vec![],
+            deleted: vec![],
+            unchanged: items.iter().filter(|i| i.action == Action::Unchanged).count(),
+            plan: items,
+        });
+    }
+
+    let specs: HashMap<(String, String), &Value> = manifest
+        .resources
+        .iter()
+        .map(|r| ((r.kind.clone(), r.name.clone()), &r.spec))
+        .collect();
+
+    let mut created = Vec::new();
+    let mut updated = Vec::new();
+    let mut deleted = Vec::new();
+
+    for item in &items {
+        let kind = ResourceKind::parse(&item.kind)?;
+        match item.action {
+            Action::Unchanged => {}
+            Action::Create => {
+                let spec = specs
+                    .get(&(item.kind.clone(), item.name.clone()))
+                    .with_context(|| format!("no spec found for {} {}", item.kind, item.name))?;
+                let resp = api::post(cfg, kind.collection_path(), spec).await?;
+                created.push(kind.id_of(&resp).unwrap_or_else(|| item.name.clone()));
+            }
+            Action::Update => {
+                let spec = specs
+                    .get(&(item.kind.clone(), item.name.clone()))
+                    .with_context(|| format!("no spec found for {} {}", item.kind, item.name))?;
+                let id = item
+                    .id
+                    .as_ref()
+                    .with_context(|| format!("missing id for update of {} {}", item.kind, item.name))?;
+                api::patch(cfg, &kind.object_path(id), spec).await?;
+                updated.push(id.clone());
+            }
+            Action::Delete => {
+                let id = item
+                    .id
+                    .as_ref()
+                    .with_context(|| format!("missing id for delete of {} {}", item.kind, item.name))?;
+                api::delete(cfg, &kind.object_path(id)).await?;
+                deleted.push(id.clone());
+            }
+        }
+    }
+
+    Ok(ApplySummary {
+        unchanged: items.iter().filter(|i| i.action == Action::Unchanged).count(),
+        created,
+        updated,
+        deleted,
+        plan: items,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_spec_matches_ignores_server_fields() {
+        let spec = json!({"kind": "monitors", "name": "x", "query": "avg:x{*}"});
+        let live = json!({"id": "1", "name": "x", "query": "avg:x{*}", "created": "now"});
+        assert!(spec_matches(&spec, &live));
+    }
+
+    #[test]
+    fn test_spec_matches_detects_drift() {
+        let spec = json!({"kind": "monitors", "name": "x", "query": "avg:y{*}"});
+        let live = json!({"id": "1", "name": "x", "query": "avg:x{*}"});
+        assert!(!spec_matches(&spec, &live));
+    }
+}
@@ -0,0 +1,437 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::api;
+use crate::config::Config;
+
+// ---------------------------------------------------------------------------
+// Resource kinds
+// ---------------------------------------------------------------------------
+
+/// A resource kind that can be snapshotted by `export` and recreated by
+/// `import`. `ALL_KINDS` below is both the export order and (via
+/// `import_order`) the import order, so that references (e.g. a SLO's
+/// monitor ids) resolve to already-imported objects.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ResourceKind {
+    Monitors,
+    Slos,
+    Dashboards,
+    Downtime,
+    Notebooks,
+    SyntheticsTests,
+    SecurityRules,
+}
+
+/// All kinds `export`/`import` know about, in a safe dependency order:
+/// monitors have no dependencies on the other kinds, SLOs and dashboards
+/// commonly embed monitor ids, downtime can reference monitors, and
+/// notebooks can embed both dashboard and monitor widgets, so they go last.
+/// Synthetics tests and security rules are self-contained and export/import
+/// cleanly at the end alongside notebooks.
+pub const ALL_KINDS: &[ResourceKind] = &[
+    ResourceKind::Monitors,
+    ResourceKind::Slos,
+    ResourceKind::Dashboards,
+    ResourceKind::Downtime,
+    ResourceKind::Notebooks,
+    ResourceKind::SyntheticsTests,
+    ResourceKind::SecurityRules,
+];
+
+impl ResourceKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ResourceKind::Monitors => "monitors",
+            ResourceKind::Slos => "slos",
+            ResourceKind::Dashboards => "dashboards",
+            ResourceKind::Downtime => "downtime",
+            ResourceKind::Notebooks => "notebooks",
+            ResourceKind::SyntheticsTests => "synthetics-tests",
+            ResourceKind::SecurityRules => "security-rules",
+        }
+    }
+
+    pub fn parse(s: &str) -> Result<ResourceKind> {
+        ALL_KINDS
+            .iter()
+            .find(|k| k.as_str() == s)
+            .copied()
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "unknown resource type {s:?}\nExpected one of: {}",
+                    ALL_KINDS
+                        .iter()
+                        .map(|k| k.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+            })
+    }
+
+    /// Path (relative to the API root) used to list/create objects of this kind.
+    pub(crate) fn collection_path(&self) -> &'static str {
+        match self {
+            ResourceKind::Monitors => "/api/v1/monitor",
+            ResourceKind::Slos => "/api/v1/slo",
+            ResourceKind::Dashboards => "/api/v1/dashboard",
+            ResourceKind::Downtime => "/api/v2/downtime",
+            ResourceKind::Notebooks => "/api/v1/notebooks",
+            ResourceKind::SyntheticsTests => "/api/v1/synthetics/tests",
+            ResourceKind::SecurityRules => "/api/v2/security_monitoring/rules",
+        }
+    }
+
+    /// Path used to get or update a single object of this kind once it has an id.
+    pub(crate) fn object_path(&self, id: &str) -> String {
+        format!("{}/{id}", self.collection_path())
+    }
+
+    /// Where, within the response envelope of a list call, the array of
+    /// objects lives. Datadog v1 endpoints return a bare array under a
+    /// type-named key; v2 endpoints wrap it in a JSON:API `data` array.
+    fn list_key(&self) -> Option<&'static str> {
+        match self {
+            ResourceKind::Slos => Some("data"),
+            ResourceKind::Notebooks => Some("data"),
+            ResourceKind::Downtime => Some("data"),
+            ResourceKind::SecurityRules => Some("data"),
+            ResourceKind::SyntheticsTests => Some("tests"),
+            ResourceKind::Monitors | ResourceKind::Dashboards => None,
+        }
+    }
+
+    pub(crate) fn list_items(&self, resp: Value) -> Result<Vec<Value>> {
+        match self.list_key() {
+            Some(key) => Ok(resp
+                .get(key)
+                .and_then(|v| v.as_array())
+                .cloned()
+                .unwrap_or_default()),
+            None => Ok(resp.as_array().cloned().unwrap_or_default()),
+        }
+    }
+
+    /// Best-effort extraction of a stable identifier from a freshly listed
+    /// or created object, independent of whether it is a bare v1 object or
+    /// a JSON:API v2 resource.
+    pub(crate) fn id_of(&self, obj: &Value) -> Option<String> {
+        obj.get("id")
+            .or_else(|| obj.pointer("/data/id"))
+            .map(|v| match v {
+                Value::String(s) => s.clone(),
+                other => other.to_string(),
+            })
+    }
+
+    /// Best-effort extraction of the display name used for idempotent
+    /// re-import matching (see `find_existing_by_name`), and for labeling
+    /// Terraform resource blocks (see `ops::terraform`).
+    pub(crate) fn name_of(&self, obj: &Value) -> Option<String> {
+        obj.get("name")
+            .or_else(|| obj.pointer("/attributes/name"))
+            .or_else(|| obj.pointer("/data/attributes/name"))
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+    }
+
+    /// Field names within this kind's object body that hold ids of other
+    /// exported objects and must be rewritten through the id-remap table
+    /// on import.
+    fn reference_fields(&self) -> &'static [&'static str] {
+        match self {
+            ResourceKind::Slos => &["monitor_ids"],
+            ResourceKind::Downtime => &["monitor_id", "monitor_ids"],
+            ResourceKind::Notebooks => &["monitor_id", "dashboard_id"],
+            ResourceKind::Monitors
+            | ResourceKind::Dashboards
+            | ResourceKind::SyntheticsTests
+            | ResourceKind::SecurityRules => &[],
+        }
+    }
+}
+
+/// Kinds are recreated on import in the same order `ALL_KINDS` exports them,
+/// so that a later kind's `reference_fields` always resolve against an
+/// already-populated id-remap table.
+fn import_order(selected: &[ResourceKind]) -> Vec<ResourceKind> {
+    ALL_KINDS
+        .iter()
+        .copied()
+        .filter(|k| selected.contains(k))
+        .collect()
+}
+
+// ---------------------------------------------------------------------------
+// Manifest
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Serialize, serde::Deserialize)]
+pub struct ManifestEntry {
+    pub kind: String,
+    pub source_id: String,
+    pub path: String,
+}
+
+#[derive(Debug, Serialize, serde::Deserialize)]
+pub struct Manifest {
+    pub exported_at: String,
+    pub entries: Vec<ManifestEntry>,
+}
+
+const MANIFEST_FILE: &str = "manifest.json";
+
+// ---------------------------------------------------------------------------
+// Export
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Serialize)]
+pub struct ExportSummary {
+    pub dir: String,
+    pub counts: Vec<(String, usize)>,
+    pub total: usize,
+}
+
+/// Snapshot the selected resource kinds (all kinds, if `types` is `None`)
+/// to `dir/<kind>/<id>.json`, plus a top-level `manifest.json` recording
+/// what was exported and when.
+pub async fn export(cfg: &Config, dir: &str, types: Option<Vec<String>>) -> Result<ExportSummary> {
+    let kinds = match types {
+        Some(names) => names
+            .iter()
+            .map(|n| ResourceKind::parse(n))
+            .collect::<Result<Vec<_>>>()?,
+        None => ALL_KINDS.to_vec(),
+    };
+
+    let exported_at = chrono::Utc::now().to_rfc3339();
+    let mut entries = Vec::new();
+    let mut counts = Vec::new();
+
+    for kind in &kinds {
+        let resp = api::get(cfg, kind.collection_path(), &[])
+            .await
+            .with_context(|| format!("failed to list {}", kind.as_str()))?;
+        let items = kind.list_items(resp)?;
+
+        let kind_dir = Path::new(dir).join(kind.as_str());
+        std::fs::create_dir_all(&kind_dir)
+            .with_context(|| format!("failed to create {}", kind_dir.display()))?;
+
+        for item in &items {
+            let Some(id) = kind.id_of(item) else {
+                continue;
+            };
+            let rel_path = format!("{}/{id}.json", kind.as_str());
+            let file_path = Path::new(dir).join(&rel_path);
+            let body = serde_json::to_vec_pretty(item)?;
+            std::fs::write(&file_path, body)
+                .with_context(|| format!("failed to write {}", file_path.display()))?;
+            entries.push(ManifestEntry {
+                kind: kind.as_str().to_string(),
+                source_id: id,
+                path: rel_path,
+            });
+        }
+
+        counts.push((kind.as_str().to_string(), items.len()));
+    }
+
+    let manifest = Manifest {
+        exported_at,
+        entries,
+    };
+    let manifest_path = Path::new(dir).join(MANIFEST_FILE);
+    std::fs::write(&manifest_path, serde_json::to_vec_pretty(&manifest)?)
+        .with_context(|| format!("failed to write {}", manifest_path.display()))?;
+
+    Ok(ExportSummary {
+        dir: dir.to_string(),
+        total: counts.iter().map(|(_, n)| n).sum(),
+        counts,
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Import
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Serialize)]
+pub struct ImportSummary {
+    pub dir: String,
+    pub created: Vec<String>,
+    pub updated: Vec<String>,
+    pub failed: Vec<(String, String)>,
+    pub dry_run: bool,
+}
+
+/// Recreate objects from a directory previously written by `export`, in
+/// dependency order, rewriting cross-object references through an id-remap
+/// table as new ids are assigned. Re-running against the same org is
+/// idempotent: an object whose name matches an existing one is updated in
+/// place instead of duplicated.
+pub async fn import(cfg: &Config, dir: &str, dry_run: bool, yes: bool) -> Result<ImportSummary> {
+    let manifest_path = Path::new(dir).join(MANIFEST_FILE);
+    let raw = std::fs::read_to_string(&manifest_path)
+        .with_context(|| format!("failed to read {}", manifest_path.display()))?;
+    let manifest: Manifest =
+        serde_json::from_str(&raw).context("failed to parse manifest.json")?;
+
+    if manifest.entries.is_empty() {
+        return Ok(ImportSummary {
+            dir: dir.to_string(),
+            created: vec![],
+            updated: vec![],
+            failed: vec![],
+            dry_run,
+        });
+    }
+
+    if !dry_run && !yes {
+        bail!(
+            "refusing to import {} object(s) without --yes (or pass --dry-run to preview)",
+            manifest.entries.len()
+        );
+    }
+
+    let selected: Vec<ResourceKind> = {
+        let mut kinds: Vec<ResourceKind> = manifest
+            .entries
+            .iter()
+            .filter_map(|e| ResourceKind::parse(&e.kind).ok())
+            .collect();
+        kinds.sort_by_key(|k| k.as_str());
+        kinds.dedup();
+        kinds
+    };
+
+    let mut id_remap: HashMap<String, String> = HashMap::new();
+    let mut created = Vec::new();
+    let mut updated = Vec::new();
+    let mut failed = Vec::new();
+
+    for kind in import_order(&selected) {
+        for entry in manifest.entries.iter().filter(|e| e.kind == kind.as_str()) {
+            let file_path = Path::new(dir).join(&entry.path);
+            let raw = match std::fs::read_to_string(&file_path) {
+                Ok(raw) => raw,
+                Err(e) => {
+                    failed.push((entry.source_id.clone(), e.to_string()));
+                    continue;
+                }
+            };
+            let mut body: Value = match serde_json::from_str(&raw) {
+                Ok(v) => v,
+                Err(e) => {
+                    failed.push((entry.source_id.clone(), e.to_string()));
+                    continue;
+                }
+            };
+
+            rewrite_references(&mut body, kind.reference_fields(), &id_remap);
+
+            if dry_run {
+                let label = kind.name_of(&body).unwrap_or_else(|| entry.source_id.clone());
+                println!("Would import {} {} ({label})", kind.as_str(), entry.source_id);
+                continue;
+            }
+
+            let name = kind.name_of(&body);
+            let existing_id = match &name {
+                Some(name) => find_existing_by_name(cfg, kind, name).await?,
+                None => None,
+            };
+
+            let is_update = existing_id.is_some();
+            let result = match existing_id {
+                Some(existing_id) => api::patch(cfg, &kind.object_path(&existing_id), &body).await,
+                None => api::post(cfg, kind.collection_path(), &body).await,
+            };
+
+            match result {
+                Ok(resp) => {
+                    let new_id = kind.id_of(&resp).unwrap_or_else(|| entry.source_id.clone());
+                    id_remap.insert(entry.source_id.clone(), new_id.clone());
+                    if is_update {
+                        updated.push(new_id);
+                    } else {
+                        created.push(new_id);
+                    }
+                }
+                Err(e) => failed.push((entry.source_id.clone(), format!("{e:?}"))),
+            }
+        }
+    }
+
+    Ok(ImportSummary {
+        dir: dir.to_string(),
+        created,
+        updated,
+        failed,
+        dry_run,
+    })
+}
+
+/// Look up an existing object of `kind` by exact name match, so re-running
+/// `import` against an org that already has these objects updates them in
+/// place rather than creating duplicates.
+async fn find_existing_by_name(
+    cfg: &Config,
+    kind: ResourceKind,
+    name: &str,
+) -> Result<Option<String>> {
+    let resp = api::get(cfg, kind.collection_path(), &[])
+        .await
+        .with_context(|| format!("failed to list {} while checking for duplicates", kind.as_str()))?;
+    let items = kind.list_items(resp)?;
+    Ok(items
+        .iter()
+        .find(|item| kind.name_of(item).as_deref() == Some(name))
+        .and_then(|item| kind.id_of(item)))
+}
+
+/// Rewrite any of `fields` present on `body` from old (pre-import) ids to
+/// their freshly assigned new ids, leaving ids with no remap entry (e.g. a
+/// reference to an object that was not part of this export) untouched.
+fn rewrite_references(body: &mut Value, fields: &[&str], id_remap: &HashMap<String, String>) {
+    let Some(obj) = body.as_object_mut() else {
+        return;
+    };
+    for field in fields {
+        let Some(value) = obj.get_mut(*field) else {
+            continue;
+        };
+        match value {
+            Value::String(s) => {
+                if let Some(new_id) = id_remap.get(s.as_str()) {
+                    *s = new_id.clone();
+                }
+            }
+            Value::Number(_) => {
+                if let Some(new_id) = id_remap.get(&value.to_string()) {
+                    *value = Value::String(new_id.clone());
+                }
+            }
+            Value::Array(items) => {
+                for item in items {
+                    let old = match item {
+                        Value::String(s) => Some(s.clone()),
+                        Value::Number(n) => Some(n.to_string()),
+                        _ => None,
+                    };
+                    if let Some(old) = old {
+                        if let Some(new_id) = id_remap.get(&old) {
+                            *item = Value::String(new_id.clone());
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,200 @@
+//! `pup batch`: run an ordered list of operations — named the way a batch
+//! API endpoint names its items (`{ command, args }`) — against one resolved
+//! `Config`, and report a single JSON array of per-operation outcomes
+//! instead of N separate process launches.
+//!
+//! The dispatch table below only covers operations that already return a
+//! structured `serde_json::Value` (the `ops::*` functions backing
+//! `cicd`/`permissions`/`apply`) rather than `commands::*` entry points,
+//! most of which print their result straight to stdout via
+//! `formatter::output` and return `Result<()>` — there's no way to collect
+//! N of those into one JSON array without capturing process output. As more
+//! `ops::*` functions gain this shape, register them here.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::config::Config;
+use crate::ops::{apply, cicd, permissions};
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Operation {
+    pub command: String,
+    #[serde(default)]
+    pub args: Value,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BatchFile {
+    pub operations: Vec<Operation>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OpResult {
+    pub index: usize,
+    pub command: String,
+    pub status: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+pub(crate) type HandlerFuture<'a> = Pin<Box<dyn Future<Output = Result<Value>> + Send + 'a>>;
+pub(crate) type Handler = for<'a> fn(&'a Config, &'a Value) -> HandlerFuture<'a>;
+
+/// The `{command} -> ops::* fn` dispatch table, also reused as-is by
+/// `ops::workflow` so a workflow step and a batch operation name the exact
+/// same commands.
+pub(crate) fn registry() -> HashMap<&'static str, Handler> {
+    let mut m: HashMap<&'static str, Handler> = HashMap::new();
+    m.insert("permissions.grant", |cfg, args| Box::pin(h_permissions_grant(cfg, args)));
+    m.insert("permissions.revoke", |cfg, args| Box::pin(h_permissions_revoke(cfg, args)));
+    m.insert("cicd.pipelines.create", |cfg, args| Box::pin(h_cicd_submit_pipeline(cfg, args)));
+    m.insert("cicd.events.submit", |cfg, args| Box::pin(h_cicd_submit_event(cfg, args)));
+    m.insert("cicd.dora.create-deployment", |cfg, args| Box::pin(h_cicd_dora_deployment(cfg, args)));
+    m.insert("cicd.dora.create-failure", |cfg, args| Box::pin(h_cicd_dora_failure(cfg, args)));
+    m.insert("apply.run", |cfg, args| Box::pin(h_apply_run(cfg, args)));
+    m
+}
+
+fn arg_str<'a>(args: &'a Value, key: &str) -> Result<&'a str> {
+    args.get(key)
+        .and_then(Value::as_str)
+        .with_context(|| format!("batch operation args missing required string field {key:?}"))
+}
+
+async fn h_permissions_grant(cfg: &Config, args: &Value) -> Result<Value> {
+    permissions::grant(cfg, arg_str(args, "key_id")?, arg_str(args, "scope")?).await
+}
+
+async fn h_permissions_revoke(cfg: &Config, args: &Value) -> Result<Value> {
+    permissions::revoke(cfg, arg_str(args, "key_id")?, arg_str(args, "scope")?).await
+}
+
+async fn h_cicd_submit_pipeline(cfg: &Config, args: &Value) -> Result<Value> {
+    let body = args.get("body").cloned().context("batch cicd.pipelines.create requires an args.body event payload")?;
+    cicd::submit_pipeline(cfg, body).await
+}
+
+async fn h_cicd_submit_event(cfg: &Config, args: &Value) -> Result<Value> {
+    let body = args.get("body").cloned().context("batch cicd.events.submit requires an args.body event payload")?;
+    cicd::submit_event(cfg, body).await
+}
+
+async fn h_cicd_dora_deployment(cfg: &Config, args: &Value) -> Result<Value> {
+    let body = args.get("body").cloned().context("batch cicd.dora.create-deployment requires an args.body event payload")?;
+    cicd::dora_create_deployment(cfg, body).await
+}
+
+async fn h_cicd_dora_failure(cfg: &Config, args: &Value) -> Result<Value> {
+    let body = args.get("body").cloned().context("batch cicd.dora.create-failure requires an args.body event payload")?;
+    cicd::dora_create_failure(cfg, body).await
+}
+
+async fn h_apply_run(cfg: &Config, args: &Value) -> Result<Value> {
+    let manifest: apply::Manifest = args
+        .get("manifest")
+        .cloned()
+        .context("batch apply.run requires an args.manifest")
+        .and_then(|v| serde_json::from_value(v).context("failed to parse args.manifest"))?;
+    let dry_run = args.get("dry_run").and_then(Value::as_bool).unwrap_or(false);
+    let prune = args.get("prune").and_then(Value::as_bool).unwrap_or(false);
+    let summary = apply::run(cfg, &manifest, dry_run, prune).await?;
+    Ok(serde_json::to_value(summary)?)
+}
+
+/// Run every operation in `file` in order against `cfg`. Stops at the first
+/// error unless `continue_on_error` is set, in which case every remaining
+/// operation still runs and the caller is left to inspect `status` per
+/// entry. Returns the per-operation results either way; callers decide
+/// whether any `"error"` entries should turn into a nonzero exit.
+pub async fn run(cfg: &Config, file: &BatchFile, continue_on_error: bool) -> Result<Vec<OpResult>> {
+    let registry = registry();
+    let mut results = Vec::with_capacity(file.operations.len());
+
+    for (index, op) in file.operations.iter().enumerate() {
+        let Some(handler) = registry.get(op.command.as_str()) else {
+            let known: Vec<&str> = {
+                let mut keys: Vec<&str> = registry.keys().copied().collect();
+                keys.sort_unstable();
+                keys
+            };
+            let err = format!("unknown batch command {:?}\nExpected one of: {}", op.command, known.join(", "));
+            results.push(OpResult { index, command: op.command.clone(), status: "error", data: None, error: Some(err) });
+            if !continue_on_error {
+                break;
+            }
+            continue;
+        };
+
+        match handler(cfg, &op.args).await {
+            Ok(data) => results.push(OpResult {
+                index,
+                command: op.command.clone(),
+                status: "ok",
+                data: Some(data),
+                error: None,
+            }),
+            Err(e) => {
+                results.push(OpResult {
+                    index,
+                    command: op.command.clone(),
+                    status: "error",
+                    data: None,
+                    error: Some(e.to_string()),
+                });
+                if !continue_on_error {
+                    break;
+                }
+            }
+        }
+    }
+
+    Ok(results)
+}
+
+/// `bail!`s with a summary if any operation failed, so `pup batch` exits
+/// nonzero the way a multi-item batch endpoint reports partial failure.
+pub fn check_outcome(results: &[OpResult]) -> Result<()> {
+    let failed: Vec<&OpResult> = results.iter().filter(|r| r.status == "error").collect();
+    if failed.is_empty() {
+        return Ok(());
+    }
+    bail!(
+        "{} of {} batch operation(s) failed: {}",
+        failed.len(),
+        results.len(),
+        failed
+            .iter()
+            .map(|r| format!("#{} {}", r.index, r.command))
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_outcome_ok_when_no_failures() {
+        let results = vec![OpResult { index: 0, command: "x".into(), status: "ok", data: None, error: None }];
+        assert!(check_outcome(&results).is_ok());
+    }
+
+    #[test]
+    fn test_check_outcome_errors_when_any_failed() {
+        let results = vec![
+            OpResult { index: 0, command: "x".into(), status: "ok", data: None, error: None },
+            OpResult { index: 1, command: "y".into(), status: "error", data: None, error: Some("boom".into()) },
+        ];
+        let err = check_outcome(&results).unwrap_err();
+        assert!(err.to_string().contains("1 of 2"));
+    }
+}
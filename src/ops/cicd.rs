@@ -0,0 +1,233 @@
+//! CI Visibility ingestion: build and submit the pipeline/job/step and DORA
+//! events a build emits about itself. This is the *write* side of CI
+//! Visibility — the read side (`CicdActions`'s pipeline/test listing,
+//! `CicdDoraActions`'s `patch-deployment`) lives in the generated client and
+//! is dispatched straight from `commands::cicd` without going through here.
+
+use anyhow::Result;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::api;
+use crate::config::Config;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EventLevel {
+    Pipeline,
+    Stage,
+    Job,
+    Step,
+}
+
+impl EventLevel {
+    pub fn parse(s: &str) -> Result<EventLevel> {
+        match s {
+            "pipeline" => Ok(EventLevel::Pipeline),
+            "stage" => Ok(EventLevel::Stage),
+            "job" => Ok(EventLevel::Job),
+            "step" => Ok(EventLevel::Step),
+            other => anyhow::bail!("unknown CI event level {other:?}\nExpected one of: pipeline, stage, job, step"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EventStatus {
+    Success,
+    Failed,
+    Error,
+    Canceled,
+}
+
+impl EventStatus {
+    pub fn parse(s: &str) -> Result<EventStatus> {
+        match s {
+            "success" => Ok(EventStatus::Success),
+            "failed" => Ok(EventStatus::Failed),
+            "error" => Ok(EventStatus::Error),
+            "canceled" | "cancelled" => Ok(EventStatus::Canceled),
+            other => anyhow::bail!("unknown CI event status {other:?}\nExpected one of: success, failed, error, canceled"),
+        }
+    }
+}
+
+/// Common flags every `cicd pipelines create` / `cicd events submit`
+/// invocation in a build script needs, for the path that doesn't supply a
+/// full `--file` payload.
+#[derive(Debug, Clone, Default)]
+pub struct EventFlags {
+    pub name: String,
+    pub status: EventStatus,
+    pub service: Option<String>,
+    pub env: Option<String>,
+    pub git_sha: Option<String>,
+    pub start: Option<String>,
+    pub end: Option<String>,
+}
+
+impl Default for EventStatus {
+    fn default() -> Self {
+        EventStatus::Success
+    }
+}
+
+/// Build a pipeline/job/step event body from the common CI-script flags.
+/// `--file` is still the way to supply the full event schema (stage names,
+/// nested jobs/steps, parameters, tags) — this only covers the single-stage
+/// "report what just happened" path the flags are meant for.
+pub fn event_from_flags(level: EventLevel, flags: &EventFlags) -> Value {
+    let mut attributes = serde_json::json!({
+        "name": flags.name,
+        "level": level,
+        "status": flags.status,
+    });
+    let attrs = attributes.as_object_mut().unwrap();
+    if let Some(start) = &flags.start {
+        attrs.insert("start".to_string(), Value::String(start.clone()));
+    }
+    if let Some(end) = &flags.end {
+        attrs.insert("end".to_string(), Value::String(end.clone()));
+    }
+    if let Some(service) = &flags.service {
+        attrs.insert("service".to_string(), Value::String(service.clone()));
+    }
+    if let Some(env) = &flags.env {
+        attrs.insert("env".to_string(), Value::String(env.clone()));
+    }
+    if let Some(sha) = &flags.git_sha {
+        attrs.insert(
+            "git".to_string(),
+            serde_json::json!({ "sha": sha }),
+        );
+    }
+
+    serde_json::json!({
+        "data": {
+            "type": "ci_app_pipeline_event",
+            "attributes": attributes,
+        }
+    })
+}
+
+/// `pup cicd pipelines create`: submit a finished (or in-progress) pipeline
+/// event — the top-level `level: pipeline` event a multi-stage build reports
+/// once per run.
+pub async fn submit_pipeline(cfg: &Config, body: Value) -> Result<Value> {
+    api::post(cfg, "/api/v2/ci/pipeline", &body).await
+}
+
+/// `pup cicd events submit`: submit a stage/job/step event — the finer-
+/// grained events a build’s individual stages (build, build-image, test,
+/// upload, ...) report as they each complete.
+pub async fn submit_event(cfg: &Config, body: Value) -> Result<Value> {
+    api::post(cfg, "/api/v2/ci/events", &body).await
+}
+
+/// Flags for `pup cicd dora create-deployment`.
+#[derive(Debug, Clone, Default)]
+pub struct DeploymentFlags {
+    pub service: String,
+    pub env: String,
+    pub version: Option<String>,
+    pub git_sha: Option<String>,
+    pub started_at: Option<String>,
+    pub finished_at: Option<String>,
+}
+
+pub fn deployment_event_from_flags(flags: &DeploymentFlags) -> Value {
+    let mut data = serde_json::json!({
+        "service": flags.service,
+        "env": flags.env,
+    });
+    let obj = data.as_object_mut().unwrap();
+    if let Some(version) = &flags.version {
+        obj.insert("version".to_string(), Value::String(version.clone()));
+    }
+    if let Some(sha) = &flags.git_sha {
+        obj.insert("git_sha".to_string(), Value::String(sha.clone()));
+    }
+    if let Some(started) = &flags.started_at {
+        obj.insert("started_at".to_string(), Value::String(started.clone()));
+    }
+    if let Some(finished) = &flags.finished_at {
+        obj.insert("finished_at".to_string(), Value::String(finished.clone()));
+    }
+    serde_json::json!({ "data": { "type": "deployment_event", "attributes": data } })
+}
+
+/// `pup cicd dora create-deployment`: feed the "deployment frequency" and
+/// "lead time for changes" DORA keys.
+pub async fn dora_create_deployment(cfg: &Config, body: Value) -> Result<Value> {
+    api::post(cfg, "/api/v2/dora/deployment", &body).await
+}
+
+/// Flags for `pup cicd dora create-failure`.
+#[derive(Debug, Clone, Default)]
+pub struct FailureFlags {
+    pub service: String,
+    pub env: String,
+    pub git_sha: Option<String>,
+    pub deployment_name: Option<String>,
+    pub started_at: Option<String>,
+    pub finished_at: Option<String>,
+}
+
+pub fn failure_event_from_flags(flags: &FailureFlags) -> Value {
+    let mut data = serde_json::json!({
+        "service": flags.service,
+        "env": flags.env,
+    });
+    let obj = data.as_object_mut().unwrap();
+    if let Some(sha) = &flags.git_sha {
+        obj.insert("git_sha".to_string(), Value::String(sha.clone()));
+    }
+    if let Some(name) = &flags.deployment_name {
+        obj.insert("deployment_name".to_string(), Value::String(name.clone()));
+    }
+    if let Some(started) = &flags.started_at {
+        obj.insert("started_at".to_string(), Value::String(started.clone()));
+    }
+    if let Some(finished) = &flags.finished_at {
+        obj.insert("finished_at".to_string(), Value::String(finished.clone()));
+    }
+    serde_json::json!({ "data": { "type": "failure_event", "attributes": data } })
+}
+
+/// `pup cicd dora create-failure`: feed the "change failure rate" and "time
+/// to restore service" DORA keys — references a deployment (by name) or a
+/// commit (`git_sha`), plus the incident window.
+pub async fn dora_create_failure(cfg: &Config, body: Value) -> Result<Value> {
+    api::post(cfg, "/api/v2/dora/incident", &body).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_from_flags_includes_git_sha() {
+        let flags = EventFlags {
+            name: "test".to_string(),
+            status: EventStatus::Success,
+            git_sha: Some("abc123".to_string()),
+            ..Default::default()
+        };
+        let body = event_from_flags(EventLevel::Job, &flags);
+        assert_eq!(body["data"]["attributes"]["git"]["sha"], "abc123");
+        assert_eq!(body["data"]["attributes"]["level"], "job");
+    }
+
+    #[test]
+    fn test_deployment_event_from_flags_omits_absent_fields() {
+        let flags = DeploymentFlags {
+            service: "checkout".to_string(),
+            env: "prod".to_string(),
+            ..Default::default()
+        };
+        let body = deployment_event_from_flags(&flags);
+        assert_eq!(body["data"]["attributes"]["service"], "checkout");
+        assert!(body["data"]["attributes"].get("version").is_none());
+    }
+}
@@ -0,0 +1,218 @@
+//! Credential-type detection and named profiles for the `auth` subsystem.
+//!
+//! `Config` holds at most one of a Datadog API key, an application key, or
+//! an OAuth2 bearer token per invocation; [`classify`] inspects a secret's
+//! format to say which one it looks like, the same way other API clients
+//! route a request based on a token's prefix rather than requiring the
+//! caller to say up front which kind they're holding. [`REQUIREMENTS`]
+//! pairs that with a per-command table so `validate_auth` can name exactly
+//! which kind is missing instead of a generic "authentication required".
+//!
+//! Named profiles (`~/.config/pup/profiles.yaml`) let a user keep several
+//! credential sets — `prod`, `staging`, a read-only key — and select one
+//! per invocation with `--profile`, the same on-disk-map-of-named-things
+//! shape `commands::alias` uses for command aliases.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::config;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CredentialKind {
+    ApiKey,
+    AppKey,
+    Bearer,
+}
+
+impl CredentialKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            CredentialKind::ApiKey => "API key",
+            CredentialKind::AppKey => "application key",
+            CredentialKind::Bearer => "OAuth2 bearer token",
+        }
+    }
+}
+
+/// Classify a secret by format rather than by which flag/env var it arrived
+/// through: a bare 32-hex-char string is a Datadog API key, a bare
+/// 40-hex-char string is an application key (Datadog's two key types differ
+/// only in length), and anything else — a JWT, an opaque OAuth2 access
+/// token — is treated as a bearer token.
+pub fn classify(secret: &str) -> CredentialKind {
+    let is_hex = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_hexdigit());
+    match secret.len() {
+        32 if is_hex(secret) => CredentialKind::ApiKey,
+        40 if is_hex(secret) => CredentialKind::AppKey,
+        _ => CredentialKind::Bearer,
+    }
+}
+
+/// Which credential kind(s) a command path needs, for a `validate_auth`
+/// that can say exactly what's missing instead of a generic message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthRequirement {
+    /// Needs both an API key and an application key; no bearer-token endpoint exists.
+    ApiKeyAndAppKey,
+    /// Needs only an API key.
+    ApiKeyOnly,
+    /// A bearer token, or the API-key-and-app-key pair, both work.
+    BearerOrApiKeyAndAppKey,
+}
+
+impl AuthRequirement {
+    pub fn describe(&self) -> &'static str {
+        match self {
+            AuthRequirement::ApiKeyAndAppKey => {
+                "an API key and an application key (DD_API_KEY + DD_APP_KEY) — this endpoint does not support bearer token auth"
+            }
+            AuthRequirement::ApiKeyOnly => "an API key (DD_API_KEY)",
+            AuthRequirement::BearerOrApiKeyAndAppKey => {
+                "a bearer token (DD_ACCESS_TOKEN / `pup auth login`) or an API key + application key (DD_API_KEY + DD_APP_KEY)"
+            }
+        }
+    }
+
+    pub fn satisfied_by(&self, has_api_keys: bool, has_bearer_token: bool) -> bool {
+        match self {
+            AuthRequirement::ApiKeyAndAppKey | AuthRequirement::ApiKeyOnly => has_api_keys,
+            AuthRequirement::BearerOrApiKeyAndAppKey => has_api_keys || has_bearer_token,
+        }
+    }
+}
+
+/// Per-command auth requirement, for the command paths known to be
+/// API-key-only today (mirrors `ops::scopes::CATALOG`'s command naming).
+pub const REQUIREMENTS: &[(&str, AuthRequirement)] = &[
+    ("logs archives list", AuthRequirement::ApiKeyAndAppKey),
+    ("logs archives get", AuthRequirement::ApiKeyAndAppKey),
+    ("logs archives create", AuthRequirement::ApiKeyAndAppKey),
+    ("logs archives update", AuthRequirement::ApiKeyAndAppKey),
+    ("logs archives delete", AuthRequirement::ApiKeyAndAppKey),
+    ("logs custom-destinations list", AuthRequirement::ApiKeyAndAppKey),
+    ("logs custom-destinations get", AuthRequirement::ApiKeyAndAppKey),
+    ("logs metrics list", AuthRequirement::ApiKeyAndAppKey),
+    ("logs metrics get", AuthRequirement::ApiKeyAndAppKey),
+    ("logs metrics delete", AuthRequirement::ApiKeyAndAppKey),
+    ("logs export", AuthRequirement::ApiKeyAndAppKey),
+];
+
+/// Look up `command`'s requirement, defaulting to "bearer or API-key-pair"
+/// (the common case) for any command not listed in [`REQUIREMENTS`].
+pub fn requirement_for(command: &str) -> AuthRequirement {
+    REQUIREMENTS
+        .iter()
+        .find(|(c, _)| *c == command)
+        .map(|(_, req)| *req)
+        .unwrap_or(AuthRequirement::BearerOrApiKeyAndAppKey)
+}
+
+/// `cfg.validate_auth()`'s command-aware counterpart: fail with a message
+/// naming exactly which credential kind `command` needs, rather than the
+/// generic "authentication required".
+pub fn validate_for(command: &str, has_api_keys: bool, has_bearer_token: bool) -> Result<()> {
+    let requirement = requirement_for(command);
+    if requirement.satisfied_by(has_api_keys, has_bearer_token) {
+        return Ok(());
+    }
+    anyhow::bail!("`pup {command}` requires {}", requirement.describe());
+}
+
+// ---------------------------------------------------------------------------
+// Named profiles
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Profile {
+    #[serde(default)]
+    pub api_key: Option<String>,
+    #[serde(default)]
+    pub app_key: Option<String>,
+    #[serde(default)]
+    pub access_token: Option<String>,
+    #[serde(default)]
+    pub site: Option<String>,
+}
+
+fn profiles_path() -> Result<PathBuf> {
+    let dir = config::config_dir().context("could not determine config directory")?;
+    Ok(dir.join("profiles.yaml"))
+}
+
+fn load_profiles() -> Result<BTreeMap<String, Profile>> {
+    let path = profiles_path()?;
+    match std::fs::read_to_string(&path) {
+        Ok(contents) => Ok(serde_yaml::from_str(&contents).unwrap_or_default()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(BTreeMap::new()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn save_profiles(profiles: &BTreeMap<String, Profile>) -> Result<()> {
+    let path = profiles_path()?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let yaml = serde_yaml::to_string(profiles)?;
+    std::fs::write(&path, yaml)?;
+    Ok(())
+}
+
+pub fn list_profiles() -> Result<BTreeMap<String, Profile>> {
+    load_profiles()
+}
+
+pub fn set_profile(name: &str, profile: Profile) -> Result<()> {
+    let mut profiles = load_profiles()?;
+    profiles.insert(name.to_string(), profile);
+    save_profiles(&profiles)
+}
+
+/// Resolve `--profile <name>`'s credential set, for the caller to overlay
+/// onto the `Config` it already built from flags/env/file.
+pub fn resolve_profile(name: &str) -> Result<Profile> {
+    load_profiles()?
+        .remove(name)
+        .with_context(|| format!("no profile named {name:?} (see `pup auth profiles`)"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_32_hex_is_api_key() {
+        assert_eq!(classify(&"a".repeat(32)), CredentialKind::ApiKey);
+    }
+
+    #[test]
+    fn test_classify_40_hex_is_app_key() {
+        assert_eq!(classify(&"b".repeat(40)), CredentialKind::AppKey);
+    }
+
+    #[test]
+    fn test_classify_non_hex_is_bearer() {
+        assert_eq!(classify("eyJhbGciOiJIUzI1NiJ9.payload.sig"), CredentialKind::Bearer);
+    }
+
+    #[test]
+    fn test_requirement_defaults_to_bearer_or_api_key_pair() {
+        assert_eq!(requirement_for("monitors list"), AuthRequirement::BearerOrApiKeyAndAppKey);
+    }
+
+    #[test]
+    fn test_validate_for_names_missing_kind() {
+        let err = validate_for("logs archives list", false, true).unwrap_err();
+        assert!(err.to_string().contains("application key"));
+    }
+
+    #[test]
+    fn test_validate_for_passes_when_satisfied() {
+        assert!(validate_for("logs archives list", true, false).is_ok());
+    }
+}
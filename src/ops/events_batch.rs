@@ -0,0 +1,147 @@
+//! Streaming NDJSON batch ingestion for `events_send`: read one JSON event
+//! per line — from a file or, with `-`, from stdin — and submit them with
+//! bounded concurrency instead of requiring a single-event JSON file per
+//! invocation.
+//!
+//! There's no multi-event submission endpoint in this API surface, so
+//! "chunked batches" here means grouping `chunk_size` lines into one
+//! `futures::stream::buffer_unordered` wave of at most `concurrency`
+//! in-flight single-event submissions (the same bounded-concurrency shape
+//! [`crate::ops::pagination::buffered`] uses), not one HTTP request per
+//! chunk. A line that fails to parse or submit is recorded and skipped;
+//! the rest of the stream keeps going.
+
+use std::io::BufRead;
+
+use anyhow::{Context, Result};
+use futures::stream::{self, StreamExt};
+use serde::Serialize;
+
+/// Default lines grouped into one concurrency wave when the caller doesn't
+/// override it.
+pub const DEFAULT_CHUNK_SIZE: usize = 50;
+/// Default in-flight submit requests per wave when the caller doesn't
+/// override it.
+pub const DEFAULT_CONCURRENCY: usize = 8;
+
+#[derive(Debug, Serialize)]
+pub struct LineFailure {
+    pub line: usize,
+    pub error: String,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct BatchSummary {
+    pub sent: usize,
+    pub failed: usize,
+    pub failures: Vec<LineFailure>,
+}
+
+impl BatchSummary {
+    pub fn is_ok(&self) -> bool {
+        self.failed == 0
+    }
+}
+
+/// Read `path` as newline-delimited text, or stdin when `path == "-"`.
+/// Blank lines are kept (callers skip them) so 1-indexed line numbers in
+/// [`LineFailure`] still match the source file/stream.
+pub fn read_lines(path: &str) -> Result<Vec<String>> {
+    let reader: Box<dyn BufRead> = if path == "-" {
+        Box::new(std::io::BufReader::new(std::io::stdin()))
+    } else {
+        let file = std::fs::File::open(path).with_context(|| format!("failed to open {path}"))?;
+        Box::new(std::io::BufReader::new(file))
+    };
+    reader
+        .lines()
+        .collect::<std::io::Result<Vec<String>>>()
+        .with_context(|| format!("failed to read {path}"))
+}
+
+/// Parse and submit `lines` (1-indexed in [`LineFailure`]) through `submit`,
+/// which handles one already-deserialized event. Blank lines are skipped
+/// without affecting `sent`/`failed`. A parse or submit failure is recorded
+/// in the returned summary rather than aborting the remaining lines.
+pub async fn run<T, F, Fut>(lines: &[String], chunk_size: usize, concurrency: usize, submit: F) -> BatchSummary
+where
+    T: serde::de::DeserializeOwned,
+    F: Fn(T) -> Fut + Send + Sync,
+    Fut: std::future::Future<Output = Result<()>> + Send,
+{
+    let numbered: Vec<(usize, &str)> = lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.as_str()))
+        .filter(|(_, line)| !line.trim().is_empty())
+        .collect();
+
+    let mut summary = BatchSummary::default();
+
+    for chunk in numbered.chunks(chunk_size.max(1)) {
+        let outcomes: Vec<(usize, Result<()>)> = stream::iter(chunk.iter().copied().map(|(lineno, raw)| {
+            let submit = &submit;
+            async move {
+                let outcome = match serde_json::from_str::<T>(raw) {
+                    Ok(event) => submit(event).await,
+                    Err(e) => Err(anyhow::anyhow!("failed to parse JSON: {e}")),
+                };
+                (lineno, outcome)
+            }
+        }))
+        .buffer_unordered(concurrency.max(1))
+        .collect()
+        .await;
+
+        for (lineno, outcome) in outcomes {
+            match outcome {
+                Ok(()) => summary.sent += 1,
+                Err(e) => {
+                    summary.failed += 1;
+                    summary.failures.push(LineFailure { line: lineno, error: e.to_string() });
+                }
+            }
+        }
+    }
+
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn submit_even_only(n: u32) -> Result<()> {
+        if n % 2 == 0 {
+            Ok(())
+        } else {
+            anyhow::bail!("odd numbers rejected")
+        }
+    }
+
+    #[tokio::test]
+    async fn run_counts_sent_and_failed_and_records_failing_line_numbers() {
+        let lines: Vec<String> = vec!["2".into(), "3".into(), "4".into(), "not json".into()];
+        let summary = run::<u32, _, _>(&lines, 10, 4, submit_even_only).await;
+        assert_eq!(summary.sent, 2);
+        assert_eq!(summary.failed, 2);
+        assert_eq!(summary.failures.iter().map(|f| f.line).collect::<Vec<_>>(), vec![2, 4]);
+        assert!(!summary.is_ok());
+    }
+
+    #[tokio::test]
+    async fn run_skips_blank_lines_without_counting_them() {
+        let lines: Vec<String> = vec!["2".into(), "".into(), "   ".into(), "4".into()];
+        let summary = run::<u32, _, _>(&lines, 10, 4, submit_even_only).await;
+        assert_eq!(summary.sent, 2);
+        assert_eq!(summary.failed, 0);
+    }
+
+    #[tokio::test]
+    async fn run_respects_chunk_boundaries_across_multiple_waves() {
+        let lines: Vec<String> = vec!["2".into(), "4".into(), "6".into(), "8".into()];
+        let summary = run::<u32, _, _>(&lines, 2, 1, submit_even_only).await;
+        assert_eq!(summary.sent, 4);
+        assert!(summary.is_ok());
+    }
+}
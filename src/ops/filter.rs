@@ -0,0 +1,384 @@
+//! Client-side `--filter` expression language: a small boolean DSL applied
+//! to a JSON result set before it reaches the formatter, so `monitors list`,
+//! `logs list`, `incidents list`, `slos list`, and friends can be narrowed
+//! without each subcommand growing its own bespoke flags. Mirrors the
+//! server-side analytics/issue filtering DSLs users already know, without
+//! piping through `jq`.
+//!
+//! Grammar (loosest-binding first):
+//!   expr    := or
+//!   or      := and ("OR" and)*
+//!   and     := not ("AND" not)*
+//!   not     := "NOT" not | cmp
+//!   cmp     := "(" expr ")" | path op value
+//!   path    := IDENT ("." IDENT)*
+//!   op      := "=" | "!=" | "~" | ">" | "<" | ">=" | "<=" | "contains"
+//!   value   := STRING | NUMBER | BAREWORD
+
+use anyhow::{bail, Result};
+use regex::Regex;
+use serde_json::Value;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Eq,
+    Ne,
+    Match,
+    Gt,
+    Lt,
+    Ge,
+    Le,
+    Contains,
+}
+
+#[derive(Debug, Clone)]
+pub enum Expr {
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Cmp { path: Vec<String>, op: Op, value: String },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Str(String),
+    Op(Op),
+    And,
+    Or,
+    Not,
+    LParen,
+    RParen,
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+        match c {
+            '(' => {
+                tokens.push(Token::LParen);
+                i += 1;
+            }
+            ')' => {
+                tokens.push(Token::RParen);
+                i += 1;
+            }
+            '"' | '\'' => {
+                let quote = c;
+                let mut s = String::new();
+                i += 1;
+                while i < chars.len() && chars[i] != quote {
+                    s.push(chars[i]);
+                    i += 1;
+                }
+                if i >= chars.len() {
+                    bail!("unterminated string literal in filter expression");
+                }
+                i += 1; // closing quote
+                tokens.push(Token::Str(s));
+            }
+            '!' if chars.get(i + 1) == Some(&'=') => {
+                tokens.push(Token::Op(Op::Ne));
+                i += 2;
+            }
+            '=' => {
+                tokens.push(Token::Op(Op::Eq));
+                i += 1;
+            }
+            '~' => {
+                tokens.push(Token::Op(Op::Match));
+                i += 1;
+            }
+            '>' if chars.get(i + 1) == Some(&'=') => {
+                tokens.push(Token::Op(Op::Ge));
+                i += 2;
+            }
+            '<' if chars.get(i + 1) == Some(&'=') => {
+                tokens.push(Token::Op(Op::Le));
+                i += 2;
+            }
+            '>' => {
+                tokens.push(Token::Op(Op::Gt));
+                i += 1;
+            }
+            '<' => {
+                tokens.push(Token::Op(Op::Lt));
+                i += 1;
+            }
+            _ => {
+                let start = i;
+                while i < chars.len()
+                    && !chars[i].is_whitespace()
+                    && !"()=!~<>\"'".contains(chars[i])
+                {
+                    i += 1;
+                }
+                if i == start {
+                    bail!("unexpected character {c:?} in filter expression");
+                }
+                let word: String = chars[start..i].iter().collect();
+                tokens.push(match word.as_str() {
+                    "AND" => Token::And,
+                    "OR" => Token::Or,
+                    "NOT" => Token::Not,
+                    "contains" => Token::Op(Op::Contains),
+                    _ => Token::Ident(word),
+                });
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let t = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        t
+    }
+
+    fn parse_or(&mut self) -> Result<Expr> {
+        let mut lhs = self.parse_and()?;
+        while matches!(self.peek(), Some(Token::Or)) {
+            self.next();
+            let rhs = self.parse_and()?;
+            lhs = Expr::Or(Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn parse_and(&mut self) -> Result<Expr> {
+        let mut lhs = self.parse_not()?;
+        while matches!(self.peek(), Some(Token::And)) {
+            self.next();
+            let rhs = self.parse_not()?;
+            lhs = Expr::And(Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn parse_not(&mut self) -> Result<Expr> {
+        if matches!(self.peek(), Some(Token::Not)) {
+            self.next();
+            return Ok(Expr::Not(Box::new(self.parse_not()?)));
+        }
+        self.parse_atom()
+    }
+
+    fn parse_atom(&mut self) -> Result<Expr> {
+        if matches!(self.peek(), Some(Token::LParen)) {
+            self.next();
+            let inner = self.parse_or()?;
+            match self.next() {
+                Some(Token::RParen) => {}
+                _ => bail!("expected ')' in filter expression"),
+            }
+            return Ok(inner);
+        }
+
+        let path = match self.next() {
+            Some(Token::Ident(s)) => s,
+            other => bail!("expected a field path in filter expression, got {other:?}"),
+        };
+        let op = match self.next() {
+            Some(Token::Op(op)) => op,
+            other => bail!("expected an operator after {path:?} in filter expression, got {other:?}"),
+        };
+        let value = match self.next() {
+            Some(Token::Str(s)) => s,
+            Some(Token::Ident(s)) => s,
+            other => bail!("expected a value after operator in filter expression, got {other:?}"),
+        };
+
+        Ok(Expr::Cmp {
+            path: path.split('.').map(str::to_string).collect(),
+            op,
+            value,
+        })
+    }
+}
+
+pub fn parse(input: &str) -> Result<Expr> {
+    let tokens = tokenize(input)?;
+    let mut parser = Parser { tokens, pos: 0 };
+    let expr = parser.parse_or()?;
+    if parser.pos != parser.tokens.len() {
+        bail!("unexpected trailing tokens in filter expression: {input:?}");
+    }
+    Ok(expr)
+}
+
+/// Resolve a dot-path (e.g. `["tags", "env"]`) against a JSON value.
+/// A missing intermediate key, or indexing into a non-object, yields `None`
+/// — never an error — so the comparison that uses it evaluates to `false`.
+fn resolve<'a>(value: &'a Value, path: &[String]) -> Option<&'a Value> {
+    let mut current = value;
+    for segment in path {
+        current = current.as_object()?.get(segment)?;
+    }
+    Some(current)
+}
+
+fn values_as_f64(a: &Value, b: &str) -> Option<(f64, f64)> {
+    let a = a.as_f64().or_else(|| a.as_str().and_then(|s| s.parse().ok()))?;
+    let b = b.parse().ok()?;
+    Some((a, b))
+}
+
+fn compare(found: &Value, op: Op, expected: &str) -> bool {
+    match op {
+        Op::Eq => as_compare_string(found) == expected,
+        Op::Ne => as_compare_string(found) != expected,
+        Op::Match => Regex::new(expected)
+            .map(|re| re.is_match(&as_compare_string(found)))
+            .unwrap_or(false),
+        Op::Contains => match found {
+            Value::Array(items) => items.iter().any(|v| as_compare_string(v) == expected),
+            other => as_compare_string(other).contains(expected),
+        },
+        Op::Gt | Op::Lt | Op::Ge | Op::Le => {
+            if let Some((a, b)) = values_as_f64(found, expected) {
+                match op {
+                    Op::Gt => a > b,
+                    Op::Lt => a < b,
+                    Op::Ge => a >= b,
+                    Op::Le => a <= b,
+                    _ => unreachable!(),
+                }
+            } else {
+                let a = as_compare_string(found);
+                match op {
+                    Op::Gt => a.as_str() > expected,
+                    Op::Lt => a.as_str() < expected,
+                    Op::Ge => a.as_str() >= expected,
+                    Op::Le => a.as_str() <= expected,
+                    _ => unreachable!(),
+                }
+            }
+        }
+    }
+}
+
+fn as_compare_string(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+pub fn eval(expr: &Expr, value: &Value) -> bool {
+    match expr {
+        Expr::And(a, b) => eval(a, value) && eval(b, value),
+        Expr::Or(a, b) => eval(a, value) || eval(b, value),
+        Expr::Not(inner) => !eval(inner, value),
+        Expr::Cmp { path, op, value: expected } => match resolve(value, path) {
+            Some(found) => compare(found, *op, expected),
+            None => false,
+        },
+    }
+}
+
+/// Apply a `--filter` expression to an API result. Arrays are filtered
+/// element-wise; a single object is kept or dropped wholesale (represented
+/// as `null` when dropped, since there's no "absent" `Value`).
+pub fn apply(value: Value, expr_str: &str) -> Result<Value> {
+    let expr = parse(expr_str)?;
+    Ok(match value {
+        Value::Array(items) => {
+            Value::Array(items.into_iter().filter(|item| eval(&expr, item)).collect())
+        }
+        other => {
+            if eval(&expr, &other) {
+                other
+            } else {
+                Value::Null
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_simple_eq() {
+        let expr = parse("status = alert").unwrap();
+        assert!(eval(&expr, &json!({"status": "alert"})));
+        assert!(!eval(&expr, &json!({"status": "ok"})));
+    }
+
+    #[test]
+    fn test_dot_path() {
+        let expr = parse("tags.env = prod").unwrap();
+        assert!(eval(&expr, &json!({"tags": {"env": "prod"}})));
+        assert!(!eval(&expr, &json!({"tags": {"env": "staging"}})));
+    }
+
+    #[test]
+    fn test_missing_path_is_false_not_error() {
+        let expr = parse("attributes.status = alert").unwrap();
+        assert!(!eval(&expr, &json!({"other": 1})));
+    }
+
+    #[test]
+    fn test_and_or_not_with_parens() {
+        let expr = parse("(status = alert OR status = warn) AND NOT muted = true").unwrap();
+        assert!(eval(&expr, &json!({"status": "alert", "muted": "false"})));
+        assert!(!eval(&expr, &json!({"status": "ok", "muted": "false"})));
+        assert!(!eval(&expr, &json!({"status": "alert", "muted": "true"})));
+    }
+
+    #[test]
+    fn test_numeric_comparison() {
+        let expr = parse("count > 5").unwrap();
+        assert!(eval(&expr, &json!({"count": 10})));
+        assert!(!eval(&expr, &json!({"count": 3})));
+    }
+
+    #[test]
+    fn test_regex_match() {
+        let expr = parse("name ~ ^prod-").unwrap();
+        assert!(eval(&expr, &json!({"name": "prod-checkout"})));
+        assert!(!eval(&expr, &json!({"name": "staging-checkout"})));
+    }
+
+    #[test]
+    fn test_contains_on_array() {
+        let expr = parse("tags contains env:prod").unwrap();
+        assert!(eval(&expr, &json!({"tags": ["env:prod", "team:x"]})));
+        assert!(!eval(&expr, &json!({"tags": ["env:staging"]})));
+    }
+
+    #[test]
+    fn test_apply_filters_array() {
+        let value = json!([{"status": "alert"}, {"status": "ok"}]);
+        let filtered = apply(value, "status = alert").unwrap();
+        assert_eq!(filtered, json!([{"status": "alert"}]));
+    }
+
+    #[test]
+    fn test_apply_single_object() {
+        let value = json!({"status": "ok"});
+        assert_eq!(apply(value.clone(), "status = ok").unwrap(), value);
+        assert_eq!(apply(value, "status = alert").unwrap(), Value::Null);
+    }
+}
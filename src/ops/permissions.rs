@@ -0,0 +1,177 @@
+//! Datadog application-key permission catalog: the full set of valid
+//! `--scopes` strings, grouped by product area, plus client-side validation
+//! so a typo is caught before it ever reaches the API. Kept in sync with
+//! `ops::scopes`'s per-command catalog, which names the same scope strings.
+
+use anyhow::{bail, Context, Result};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::api;
+use crate::config::Config;
+
+#[derive(Debug, Serialize)]
+pub struct PermissionGroup {
+    pub area: &'static str,
+    pub scopes: &'static [&'static str],
+}
+
+/// Every valid app-key permission string, grouped the way Datadog's own
+/// docs group them, so `pup api-keys permissions list` reads like a
+/// product-area table rather than one flat alphabetical dump.
+pub const CATALOG: &[PermissionGroup] = &[
+    PermissionGroup { area: "monitors", scopes: &["monitors_read", "monitors_write", "monitors_downtime"] },
+    PermissionGroup { area: "dashboards", scopes: &["dashboards_read", "dashboards_write"] },
+    PermissionGroup { area: "slos", scopes: &["slos_read", "slos_write", "slos_corrections"] },
+    PermissionGroup { area: "downtime", scopes: &["downtime_read", "downtime_write"] },
+    PermissionGroup { area: "notebooks", scopes: &["notebooks_read", "notebooks_write"] },
+    PermissionGroup { area: "synthetics", scopes: &[
+        "synthetics_read", "synthetics_write",
+        "synthetics_global_variable_read", "synthetics_global_variable_write",
+        "synthetics_private_location_read", "synthetics_private_location_write",
+    ] },
+    PermissionGroup { area: "security_monitoring", scopes: &[
+        "security_monitoring_rules_read", "security_monitoring_rules_write",
+        "security_monitoring_signals_read", "security_monitoring_signals_write",
+    ] },
+    PermissionGroup { area: "logs", scopes: &[
+        "logs_read_data", "logs_read_index_data", "logs_write_index_data",
+        "logs_read_archive", "logs_write_archive",
+    ] },
+    PermissionGroup { area: "events", scopes: &["events_read"] },
+    PermissionGroup { area: "incidents", scopes: &["incident_read", "incident_write"] },
+    PermissionGroup { area: "apm", scopes: &["apm_read"] },
+    PermissionGroup { area: "keys", scopes: &["api_keys_read", "api_keys_write", "user_app_keys"] },
+    PermissionGroup { area: "hosts", scopes: &["hosts_read", "hosts_write"] },
+    PermissionGroup { area: "sensitive_data_scanner", scopes: &["sensitive_data_scanner_read", "sensitive_data_scanner_write"] },
+];
+
+pub fn all_scopes() -> impl Iterator<Item = &'static str> {
+    CATALOG.iter().flat_map(|g| g.scopes.iter().copied())
+}
+
+pub fn is_valid(scope: &str) -> bool {
+    all_scopes().any(|s| s == scope)
+}
+
+/// Cheapest useful "did you mean": the closest catalog entry by Levenshtein
+/// distance, if it's close enough to plausibly be a typo.
+fn suggest(scope: &str) -> Option<&'static str> {
+    all_scopes()
+        .map(|candidate| (candidate, levenshtein(scope, candidate)))
+        .min_by_key(|(_, dist)| *dist)
+        .filter(|(_, dist)| *dist <= 3)
+        .map(|(candidate, _)| candidate)
+}
+
+fn levenshtein(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for i in 1..=a.len() {
+        let mut prev = row[0];
+        row[0] = i;
+        for j in 1..=b.len() {
+            let cur = row[j];
+            row[j] = if a[i - 1] == b[j - 1] {
+                prev
+            } else {
+                1 + prev.min(row[j]).min(row[j - 1])
+            };
+            prev = cur;
+        }
+    }
+    row[b.len()]
+}
+
+/// Reject any scope string not present in `CATALOG`, with a "did you mean"
+/// suggestion per unknown entry, before a create/update call ever reaches
+/// the API.
+pub fn validate(scopes: &[String]) -> Result<()> {
+    let mut problems = Vec::new();
+    for scope in scopes {
+        if !is_valid(scope) {
+            match suggest(scope) {
+                Some(suggestion) => problems.push(format!("{scope:?} (did you mean {suggestion:?}?)")),
+                None => problems.push(format!("{scope:?}")),
+            }
+        }
+    }
+    if !problems.is_empty() {
+        bail!("unknown scope(s): {}", problems.join(", "));
+    }
+    Ok(())
+}
+
+const KEY_PATH_PREFIX: &str = "/api/v2/current_user/application_keys";
+
+fn scopes_of(key: &Value) -> Vec<String> {
+    key.pointer("/data/attributes/scopes")
+        .and_then(|v| v.as_array())
+        .map(|arr| arr.iter().filter_map(|v| v.as_str().map(str::to_string)).collect())
+        .unwrap_or_default()
+}
+
+/// Add `scope` to the key's scope list (a no-op if already granted) and
+/// persist it.
+pub async fn grant(cfg: &Config, key_id: &str, scope: &str) -> Result<Value> {
+    validate(&[scope.to_string()])?;
+
+    let key = api::get(cfg, &format!("{KEY_PATH_PREFIX}/{key_id}"), &[])
+        .await
+        .with_context(|| format!("failed to get application key {key_id}"))?;
+
+    let mut scopes = scopes_of(&key);
+    if !scopes.iter().any(|s| s == scope) {
+        scopes.push(scope.to_string());
+    }
+
+    update_scopes(cfg, key_id, scopes).await
+}
+
+/// Remove `scope` from the key's scope list (a no-op if not present) and
+/// persist it.
+pub async fn revoke(cfg: &Config, key_id: &str, scope: &str) -> Result<Value> {
+    let key = api::get(cfg, &format!("{KEY_PATH_PREFIX}/{key_id}"), &[])
+        .await
+        .with_context(|| format!("failed to get application key {key_id}"))?;
+
+    let scopes: Vec<String> = scopes_of(&key).into_iter().filter(|s| s != scope).collect();
+
+    update_scopes(cfg, key_id, scopes).await
+}
+
+async fn update_scopes(cfg: &Config, key_id: &str, scopes: Vec<String>) -> Result<Value> {
+    let body = serde_json::json!({
+        "data": {
+            "type": "application_keys",
+            "id": key_id,
+            "attributes": { "scopes": scopes },
+        }
+    });
+    api::patch(cfg, &format!("{KEY_PATH_PREFIX}/{key_id}"), &body)
+        .await
+        .with_context(|| format!("failed to update scopes for application key {key_id}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_valid() {
+        assert!(is_valid("monitors_read"));
+        assert!(!is_valid("monitors_reed"));
+    }
+
+    #[test]
+    fn test_validate_suggests_close_typo() {
+        let err = validate(&["monitors_reed".to_string()]).unwrap_err();
+        assert!(err.to_string().contains("monitors_read"), "{err}");
+    }
+
+    #[test]
+    fn test_validate_accepts_known_scopes() {
+        assert!(validate(&["monitors_read".to_string(), "dashboards_write".to_string()]).is_ok());
+    }
+}
@@ -0,0 +1,174 @@
+//! External-subcommand plugin dispatch: `pup foo ...` for any `foo` that
+//! isn't a built-in command looks up `pup-foo` on `PATH` (and in
+//! `PUP_PLUGIN_DIR`, if set), forwards the remaining args, and proxies the
+//! child's stdout/stderr/exit code. Modeled on the plugin architectures of
+//! other infra CLIs (`git`, `kubectl`, `cargo`) so teams can ship internal
+//! commands without forking pup itself.
+
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::config::Config;
+
+const PLUGIN_PREFIX: &str = "pup-";
+
+/// Env var naming the extra plugin search directory, checked in addition
+/// to `PATH`.
+const PLUGIN_DIR_ENV: &str = "PUP_PLUGIN_DIR";
+
+/// Convention a plugin can opt into: if it understands `--pup-describe`,
+/// it prints a one-line self-description to stdout and exits zero.
+const DESCRIBE_ARG: &str = "--pup-describe";
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct Plugin {
+    pub name: String,
+    pub path: PathBuf,
+    pub description: Option<String>,
+}
+
+fn search_dirs() -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = std::env::var_os("PATH")
+        .map(|p| std::env::split_paths(&p).collect())
+        .unwrap_or_default();
+    if let Some(extra) = std::env::var_os(PLUGIN_DIR_ENV) {
+        dirs.push(PathBuf::from(extra));
+    }
+    dirs
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::metadata(path)
+        .map(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+/// Find the first `pup-<name>` executable on `PATH`/`PUP_PLUGIN_DIR`,
+/// first match wins (same precedence as `PATH` lookup generally).
+pub fn which(name: &str) -> Option<PathBuf> {
+    let exe_name = format!("{PLUGIN_PREFIX}{name}");
+    search_dirs().into_iter().find_map(|dir| {
+        let candidate = dir.join(&exe_name);
+        is_executable(&candidate).then_some(candidate)
+    })
+}
+
+/// Discover every `pup-*` executable reachable on `PATH`/`PUP_PLUGIN_DIR`,
+/// deduplicated by name (first one found on the search path wins), each
+/// with its self-reported description if it supports `--pup-describe`.
+pub fn discover() -> Vec<Plugin> {
+    let mut seen = std::collections::HashSet::new();
+    let mut plugins = Vec::new();
+
+    for dir in search_dirs() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let Some(file_name) = path.file_name().and_then(|n| n.to_str()) else {
+                continue;
+            };
+            let Some(name) = file_name.strip_prefix(PLUGIN_PREFIX) else {
+                continue;
+            };
+            if name.is_empty() || !is_executable(&path) || !seen.insert(name.to_string()) {
+                continue;
+            }
+            plugins.push(Plugin {
+                name: name.to_string(),
+                description: describe(&path),
+                path,
+            });
+        }
+    }
+
+    plugins.sort_by(|a, b| a.name.cmp(&b.name));
+    plugins
+}
+
+/// Best-effort: ask the plugin to self-describe. Absence of support (exit
+/// error, hang, unknown flag) is not a failure — it just means no description.
+fn describe(path: &Path) -> Option<String> {
+    let output = Command::new(path).arg(DESCRIBE_ARG).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8(output.stdout)
+        .ok()?
+        .lines()
+        .next()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+}
+
+/// Credential/context env vars every plugin is handed, so a plugin author
+/// doesn't re-implement pup's own credential resolution (API/app keys or
+/// OAuth bearer token, whichever is configured).
+fn context_env(cfg: &Config, bearer_token: Option<&str>) -> Vec<(&'static str, String)> {
+    let mut env = vec![
+        ("PUP_SITE", cfg.site.clone()),
+        ("PUP_OUTPUT", format!("{:?}", cfg.output_format).to_lowercase()),
+        ("PUP_AGENT", cfg.agent_mode.to_string()),
+    ];
+
+    if let Ok(api_key) = std::env::var("DD_API_KEY") {
+        env.push(("PUP_API_KEY", api_key));
+    }
+    if let Ok(app_key) = std::env::var("DD_APP_KEY") {
+        env.push(("PUP_APP_KEY", app_key));
+    }
+    if let Some(token) = bearer_token {
+        env.push(("PUP_ACCESS_TOKEN", token.to_string()));
+    } else if let Ok(token) = std::env::var("DD_ACCESS_TOKEN") {
+        env.push(("PUP_ACCESS_TOKEN", token));
+    }
+
+    env
+}
+
+/// Run `pup-<name>` with `args` forwarded verbatim, plus the resolved
+/// credentials and global flags as `PUP_*` env vars, proxying its
+/// stdout/stderr and returning its exit code.
+pub fn dispatch(
+    cfg: &Config,
+    name: &str,
+    args: &[String],
+    bearer_token: Option<&str>,
+) -> Result<i32> {
+    let path = which(name).ok_or_else(|| {
+        anyhow::anyhow!(
+            "no built-in command or plugin named {name:?} (looked for `{PLUGIN_PREFIX}{name}` on PATH{})",
+            std::env::var(PLUGIN_DIR_ENV)
+                .map(|d| format!(" and in {d}"))
+                .unwrap_or_default()
+        )
+    })?;
+
+    let status = Command::new(&path)
+        .args(args)
+        .envs(context_env(cfg, bearer_token))
+        .status()
+        .with_context(|| format!("failed to run plugin {}", path.display()))?;
+
+    Ok(status.code().unwrap_or(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_which_finds_nothing_for_missing_prefix() {
+        assert!(which("definitely-not-a-real-plugin-xyz").is_none());
+    }
+}
@@ -0,0 +1,410 @@
+//! Local RBAC policy guard: a cross-cutting authorization layer meant to run
+//! in `main()`'s command dispatcher right after `cfg.validate_auth()`, gating
+//! each matched command branch against a user-supplied YAML policy before
+//! it's allowed to run.
+//!
+//! A policy maps dotted command paths (or glob-style patterns using `*` as a
+//! single path segment, e.g. `security.content-packs.*`) to a required-role
+//! boolean expression over `role:<name>` atoms (`and`/`or`/`not`, parens),
+//! evaluated against the authenticated principal's roles — fetched once via
+//! the users/roles API and cached for the rest of the invocation. Mutating
+//! verbs (`create`/`update`/`delete`/`archive`/`assign`/`register`)
+//! default-deny unless a rule explicitly grants a write-capable role; reads
+//! are ungated unless a rule says otherwise. No policy file configured means
+//! allow-all, so existing users are unaffected; a policy file present with
+//! no matching rule fails closed for mutating commands and allows reads
+//! through, so a policy written only to gate writes doesn't also have to
+//! enumerate every read command to avoid breaking them.
+
+use std::collections::HashSet;
+use std::sync::OnceLock;
+
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use tokio::sync::OnceCell;
+
+use crate::api;
+use crate::config::Config;
+
+/// Verbs (the last dotted segment of a command path, by convention) that
+/// mutate state and therefore default-deny without an explicit grant.
+const MUTATING_VERBS: &[&str] = &["create", "update", "delete", "archive", "assign", "register"];
+
+pub fn is_mutating(command_path: &str) -> bool {
+    command_path
+        .rsplit('.')
+        .next()
+        .map(|verb| MUTATING_VERBS.contains(&verb))
+        .unwrap_or(false)
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PolicyRule {
+    pub pattern: String,
+    pub requires: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Policy {
+    pub rules: Vec<PolicyRule>,
+}
+
+pub fn load(path: &str) -> Result<Policy> {
+    let raw = std::fs::read_to_string(path).with_context(|| format!("failed to read RBAC policy file {path}"))?;
+    serde_yaml::from_str(&raw).with_context(|| format!("failed to parse RBAC policy file {path}"))
+}
+
+/// A dotted command path matches a dotted pattern segment-by-segment, where
+/// `*` in the pattern matches exactly one segment (so `security.*` matches
+/// `security.findings` but not `security.findings.search`, while
+/// `security.*.*` matches the latter) — the same one-segment-at-a-time
+/// semantics as `commands::logs`'s name/ID glob matcher, just over `.`
+/// instead of characters.
+fn path_matches(pattern: &str, path: &str) -> bool {
+    let pattern_segments: Vec<&str> = pattern.split('.').collect();
+    let path_segments: Vec<&str> = path.split('.').collect();
+    if pattern_segments.len() != path_segments.len() {
+        return false;
+    }
+    pattern_segments
+        .iter()
+        .zip(path_segments.iter())
+        .all(|(p, s)| *p == "*" || p == s)
+}
+
+// ---------------------------------------------------------------------------
+// Role expression: `role:reader`, `(role:reader) or (role:admin)`, `not ...`
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone)]
+enum RoleExpr {
+    Role(String),
+    And(Box<RoleExpr>, Box<RoleExpr>),
+    Or(Box<RoleExpr>, Box<RoleExpr>),
+    Not(Box<RoleExpr>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Role(String),
+    And,
+    Or,
+    Not,
+    LParen,
+    RParen,
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    for word in input
+        .replace('(', " ( ")
+        .replace(')', " ) ")
+        .split_whitespace()
+        .map(str::to_string)
+        .collect::<Vec<_>>()
+    {
+        tokens.push(match word.as_str() {
+            "(" => Token::LParen,
+            ")" => Token::RParen,
+            "and" | "AND" => Token::And,
+            "or" | "OR" => Token::Or,
+            "not" | "NOT" => Token::Not,
+            other => {
+                let role = other
+                    .strip_prefix("role:")
+                    .with_context(|| format!("expected a role:<name> atom in RBAC rule, got {other:?}"))?;
+                Token::Role(role.to_string())
+            }
+        });
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let t = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        t
+    }
+
+    fn parse_or(&mut self) -> Result<RoleExpr> {
+        let mut lhs = self.parse_and()?;
+        while matches!(self.peek(), Some(Token::Or)) {
+            self.next();
+            lhs = RoleExpr::Or(Box::new(lhs), Box::new(self.parse_and()?));
+        }
+        Ok(lhs)
+    }
+
+    fn parse_and(&mut self) -> Result<RoleExpr> {
+        let mut lhs = self.parse_not()?;
+        while matches!(self.peek(), Some(Token::And)) {
+            self.next();
+            lhs = RoleExpr::And(Box::new(lhs), Box::new(self.parse_not()?));
+        }
+        Ok(lhs)
+    }
+
+    fn parse_not(&mut self) -> Result<RoleExpr> {
+        if matches!(self.peek(), Some(Token::Not)) {
+            self.next();
+            return Ok(RoleExpr::Not(Box::new(self.parse_not()?)));
+        }
+        self.parse_atom()
+    }
+
+    fn parse_atom(&mut self) -> Result<RoleExpr> {
+        match self.next() {
+            Some(Token::LParen) => {
+                let inner = self.parse_or()?;
+                match self.next() {
+                    Some(Token::RParen) => Ok(inner),
+                    _ => bail!("expected ')' in RBAC rule expression"),
+                }
+            }
+            Some(Token::Role(role)) => Ok(RoleExpr::Role(role)),
+            other => bail!("expected a role:<name> atom or '(' in RBAC rule expression, got {other:?}"),
+        }
+    }
+}
+
+fn parse_role_expr(input: &str) -> Result<RoleExpr> {
+    let tokens = tokenize(input)?;
+    let mut parser = Parser { tokens, pos: 0 };
+    let expr = parser.parse_or()?;
+    if parser.pos != parser.tokens.len() {
+        bail!("unexpected trailing tokens in RBAC rule expression: {input:?}");
+    }
+    Ok(expr)
+}
+
+fn eval_role_expr(expr: &RoleExpr, roles: &HashSet<String>) -> bool {
+    match expr {
+        RoleExpr::Role(name) => roles.contains(name),
+        RoleExpr::And(a, b) => eval_role_expr(a, roles) && eval_role_expr(b, roles),
+        RoleExpr::Or(a, b) => eval_role_expr(a, roles) || eval_role_expr(b, roles),
+        RoleExpr::Not(inner) => !eval_role_expr(inner, roles),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Principal roles: fetched once per invocation, cached.
+// ---------------------------------------------------------------------------
+
+static ROLE_CACHE: OnceLock<OnceCell<HashSet<String>>> = OnceLock::new();
+
+fn role_cache() -> &'static OnceCell<HashSet<String>> {
+    ROLE_CACHE.get_or_init(OnceCell::new)
+}
+
+async fn fetch_roles(cfg: &Config) -> Result<HashSet<String>> {
+    role_cache()
+        .get_or_try_init(|| async {
+            let resp = api::get(cfg, "/api/v2/current_user", &[("include", "roles")]).await?;
+            let roles = resp
+                .pointer("/included")
+                .and_then(|v| v.as_array())
+                .map(|items| {
+                    items
+                        .iter()
+                        .filter(|item| item.get("type").and_then(|t| t.as_str()) == Some("roles"))
+                        .filter_map(|item| item.pointer("/attributes/name").and_then(|n| n.as_str()))
+                        .map(str::to_lowercase)
+                        .collect()
+                })
+                .unwrap_or_default();
+            Ok::<_, anyhow::Error>(roles)
+        })
+        .await
+        .cloned()
+}
+
+// ---------------------------------------------------------------------------
+// Decision + enforcement
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone)]
+pub enum Decision {
+    Allowed { matched_rule: Option<String> },
+    Denied { matched_rule: Option<String>, reason: String },
+}
+
+impl Decision {
+    pub fn is_allowed(&self) -> bool {
+        matches!(self, Decision::Allowed { .. })
+    }
+}
+
+/// Evaluate `command_path` against `policy` (allow-all when `None`),
+/// honoring `global_read_only`'s hard override on mutating commands before
+/// the policy is even consulted.
+pub async fn evaluate(
+    cfg: &Config,
+    policy: Option<&Policy>,
+    command_path: &str,
+    global_read_only: bool,
+) -> Result<Decision> {
+    if global_read_only && is_mutating(command_path) {
+        return Ok(Decision::Denied {
+            matched_rule: None,
+            reason: "blocked by global --read-only flag".to_string(),
+        });
+    }
+
+    let Some(policy) = policy else {
+        return Ok(Decision::Allowed { matched_rule: None });
+    };
+
+    let Some(rule) = policy.rules.iter().find(|r| path_matches(&r.pattern, command_path)) else {
+        if is_mutating(command_path) {
+            return Ok(Decision::Denied {
+                matched_rule: None,
+                reason: format!("no policy rule matched {command_path:?} (failing closed)"),
+            });
+        }
+        return Ok(Decision::Allowed { matched_rule: None });
+    };
+
+    let expr = parse_role_expr(&rule.requires)?;
+    let roles = fetch_roles(cfg).await?;
+    if eval_role_expr(&expr, &roles) {
+        Ok(Decision::Allowed { matched_rule: Some(rule.pattern.clone()) })
+    } else {
+        let mut sorted_roles: Vec<&String> = roles.iter().collect();
+        sorted_roles.sort();
+        Ok(Decision::Denied {
+            matched_rule: Some(rule.pattern.clone()),
+            reason: format!(
+                "principal roles [{}] do not satisfy policy rule {:?} ({:?})",
+                sorted_roles.iter().map(|s| s.as_str()).collect::<Vec<_>>().join(", "),
+                rule.pattern,
+                rule.requires
+            ),
+        })
+    }
+}
+
+/// Print the `--dry-run` report (matched rule + allow/deny) and return
+/// without making any API call.
+pub fn print_dry_run(command_path: &str, decision: &Decision) {
+    match decision {
+        Decision::Allowed { matched_rule } => println!(
+            "ALLOW  {command_path}  (rule: {})",
+            matched_rule.as_deref().unwrap_or("<no policy configured>")
+        ),
+        Decision::Denied { matched_rule, reason } => println!(
+            "DENY   {command_path}  (rule: {})  {reason}",
+            matched_rule.as_deref().unwrap_or("<none>")
+        ),
+    }
+}
+
+/// Evaluate and enforce: `bail!`s with the denial reason when not allowed.
+/// `main()`'s dispatcher calls this right after `cfg.validate_auth()`, ahead
+/// of every command branch.
+pub async fn guard(cfg: &Config, policy: Option<&Policy>, command_path: &str, global_read_only: bool) -> Result<()> {
+    let decision = evaluate(cfg, policy, command_path, global_read_only).await?;
+    match &decision {
+        Decision::Allowed { .. } => Ok(()),
+        Decision::Denied { reason, .. } => bail!("{command_path}: {reason}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_mutating() {
+        assert!(is_mutating("api-keys.create"));
+        assert!(is_mutating("cases.projects.delete"));
+        assert!(!is_mutating("monitors.list"));
+        assert!(!is_mutating("monitors.get"));
+    }
+
+    #[test]
+    fn test_path_matches_wildcard_segment() {
+        assert!(path_matches("security.content-packs.*", "security.content-packs.delete"));
+        assert!(!path_matches("security.content-packs.*", "security.content-packs.delete.confirm"));
+        assert!(path_matches("cases.projects.delete", "cases.projects.delete"));
+        assert!(!path_matches("cases.projects.delete", "cases.projects.create"));
+    }
+
+    #[test]
+    fn test_role_expr_or() {
+        let expr = parse_role_expr("(role:reader) or (role:admin)").unwrap();
+        let mut roles = HashSet::new();
+        roles.insert("reader".to_string());
+        assert!(eval_role_expr(&expr, &roles));
+        assert!(!eval_role_expr(&expr, &HashSet::new()));
+    }
+
+    #[test]
+    fn test_role_expr_not() {
+        let expr = parse_role_expr("not role:banned").unwrap();
+        let mut roles = HashSet::new();
+        roles.insert("admin".to_string());
+        assert!(eval_role_expr(&expr, &roles));
+        roles.insert("banned".to_string());
+        assert!(!eval_role_expr(&expr, &roles));
+    }
+
+    fn test_config() -> Config {
+        Config {
+            api_key: None,
+            app_key: None,
+            access_token: None,
+            site: "datadoghq.com".to_string(),
+            org: None,
+            output_format: crate::config::OutputFormat::Json,
+            auto_approve: false,
+            agent_mode: false,
+            storage_backend: None,
+            max_retries: 0,
+            pagerduty_routing_key: None,
+            vet_rules_path: None,
+            offline_access: false,
+            extra_auth_params: Vec::new(),
+            oidc_token_endpoint: None,
+            oidc_client_id: None,
+            oidc_client_secret: None,
+            oidc_issuer: None,
+            oidc_audience: None,
+            oidc_scope: None,
+            oidc_jwks_uri: None,
+            oidc_userinfo_uri: None,
+            oidc_required_claims: Vec::new(),
+            oidc_id_token_hint: None,
+            oidc_signing_alg: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_evaluate_unmatched_read_allowed() {
+        let cfg = test_config();
+        let policy = Policy {
+            rules: vec![PolicyRule { pattern: "monitors.delete".to_string(), requires: "role:admin".to_string() }],
+        };
+        let decision = evaluate(&cfg, Some(&policy), "monitors.list", false).await.unwrap();
+        assert!(decision.is_allowed());
+    }
+
+    #[tokio::test]
+    async fn test_evaluate_unmatched_write_denied() {
+        let cfg = test_config();
+        let policy = Policy {
+            rules: vec![PolicyRule { pattern: "monitors.delete".to_string(), requires: "role:admin".to_string() }],
+        };
+        let decision = evaluate(&cfg, Some(&policy), "monitors.create", false).await.unwrap();
+        assert!(!decision.is_allowed());
+    }
+}
@@ -0,0 +1,72 @@
+//! Retry-with-backoff for the generated `datadog_api_client` calls: every
+//! `TagsAPI::*_host_tags` method returns `Result<T, datadog::Error<E>>`
+//! before `commands::tags`/`ops::tags` convert it to `anyhow::Error`, so
+//! [`with_retry`] wraps the call at that point, while the status code (or
+//! transport error) is still inspectable, and retries only the transient
+//! cases: HTTP 429, HTTP 5xx, and connect/timeout errors. Anything else —
+//! a 4xx that isn't a rate limit — returns on the first attempt, the same
+//! as today.
+//!
+//! Backoff follows the "full jitter" shape: start at `BASE_DELAY`, double
+//! each attempt up to `MAX_DELAY`, then sleep a random duration between zero
+//! and that capped value, so a burst of concurrent retries doesn't
+//! re-synchronize into another thundering herd.
+
+use std::future::Future;
+use std::time::Duration;
+
+use datadog_api_client::datadog::Error as DdError;
+use rand::Rng;
+
+const BASE_DELAY: Duration = Duration::from_millis(250);
+const MAX_DELAY: Duration = Duration::from_secs(8);
+
+fn is_retryable<T>(err: &DdError<T>) -> bool {
+    match err {
+        DdError::ResponseError(rc) => {
+            let status = rc.status.as_u16();
+            status == 429 || status >= 500
+        }
+        DdError::ReqwestError(e) => e.is_timeout() || e.is_connect(),
+        _ => false,
+    }
+}
+
+fn backoff_delay(attempt: u32) -> Duration {
+    let capped = BASE_DELAY.saturating_mul(1 << attempt.min(5)).min(MAX_DELAY);
+    let jitter_ms = rand::thread_rng().gen_range(0..=capped.as_millis().max(1) as u64);
+    Duration::from_millis(jitter_ms)
+}
+
+/// Call `f` up to `max_retries` additional times (so `max_retries == 0`
+/// behaves exactly like today — one attempt, no retry) whenever the error
+/// is classified as transient by [`is_retryable`].
+pub async fn with_retry<T, E, F, Fut>(max_retries: u32, mut f: F) -> Result<T, DdError<E>>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, DdError<E>>>,
+{
+    let mut attempt = 0;
+    loop {
+        match f().await {
+            Ok(value) => return Ok(value),
+            Err(err) if attempt < max_retries && is_retryable(&err) => {
+                tokio::time::sleep(backoff_delay(attempt)).await;
+                attempt += 1;
+            }
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_delay_never_exceeds_max() {
+        for attempt in 0..10 {
+            assert!(backoff_delay(attempt) <= MAX_DELAY);
+        }
+    }
+}
@@ -0,0 +1,221 @@
+//! SARIF 2.1.0 serialization for Datadog security findings and signals, so
+//! `pup security findings search --output sarif` / `pup security signals
+//! search --output sarif` can pipe straight into GitHub code scanning or any
+//! other SARIF consumer.
+
+use serde_json::Value;
+
+const SARIF_SCHEMA: &str = "https://raw.githubusercontent.com/oasis-tcs/sarif-spec/master/Schemata/sarif-schema-2.1.0.json";
+
+/// A finding/signal normalized out of Datadog's JSON:API-shaped response,
+/// just enough to build one SARIF rule + one SARIF result from it.
+#[derive(Debug, Clone)]
+pub struct Finding {
+    pub rule_id: String,
+    pub rule_name: String,
+    pub rule_description: String,
+    pub help_uri: Option<String>,
+    pub severity: String,
+    pub message: String,
+    pub resource: String,
+}
+
+fn str_at<'a>(v: &'a Value, pointer: &str) -> Option<&'a str> {
+    v.pointer(pointer).and_then(Value::as_str)
+}
+
+/// Pull the finding/signal list out of a Datadog response, tolerating both
+/// a bare `data: [...]` array and a single `data: {...}` object (some
+/// search endpoints paginate, some don't).
+fn items_of(raw: &Value) -> Vec<Value> {
+    match raw.pointer("/data") {
+        Some(Value::Array(items)) => items.clone(),
+        Some(obj @ Value::Object(_)) => vec![obj.clone()],
+        _ => match raw {
+            Value::Array(items) => items.clone(),
+            _ => Vec::new(),
+        },
+    }
+}
+
+/// Normalize a raw Datadog security findings/signals response into
+/// [`Finding`]s. Tries the findings shape (`attributes.rule.{id,name}`,
+/// `attributes.evaluation.description`, `attributes.resource_type` +
+/// `attributes.resource`) first, falling back to the signals shape
+/// (`attributes.rule.{id,name}`, `attributes.message`,
+/// `attributes.attributes.{service,host,...}` or just the signal id) when
+/// the findings-specific fields aren't present.
+pub fn from_value(raw: &Value) -> Vec<Finding> {
+    items_of(raw)
+        .iter()
+        .map(|item| {
+            let rule_id = str_at(item, "/attributes/rule/id")
+                .or_else(|| str_at(item, "/attributes/rule_id"))
+                .unwrap_or("unknown-rule")
+                .to_string();
+            let rule_name = str_at(item, "/attributes/rule/name")
+                .or(Some(rule_id.as_str()))
+                .unwrap()
+                .to_string();
+            let rule_description = str_at(item, "/attributes/rule/description")
+                .or_else(|| str_at(item, "/attributes/evaluation/description"))
+                .unwrap_or(&rule_name)
+                .to_string();
+            let help_uri = str_at(item, "/attributes/rule/help_uri")
+                .or_else(|| str_at(item, "/attributes/rule/compliance_signal_options/help_uri"))
+                .map(str::to_string);
+            let severity = str_at(item, "/attributes/rule/severity")
+                .or_else(|| str_at(item, "/attributes/severity"))
+                .unwrap_or("info")
+                .to_lowercase();
+            let message = str_at(item, "/attributes/message")
+                .or_else(|| str_at(item, "/attributes/evaluation/finding_message"))
+                .or(Some(rule_name.as_str()))
+                .unwrap()
+                .to_string();
+            let resource = str_at(item, "/attributes/resource")
+                .or_else(|| str_at(item, "/attributes/resource_type"))
+                .or_else(|| item.pointer("/id").and_then(Value::as_str))
+                .unwrap_or("unknown-resource")
+                .to_string();
+
+            Finding { rule_id, rule_name, rule_description, help_uri, severity, message, resource }
+        })
+        .collect()
+}
+
+/// Datadog severity -> SARIF result `level`: `critical`/`high` are errors,
+/// `medium` is a warning, anything else (`low`/`info`/unrecognized) is a
+/// note.
+fn sarif_level(severity: &str) -> &'static str {
+    match severity {
+        "critical" | "high" => "error",
+        "medium" => "warning",
+        _ => "note",
+    }
+}
+
+/// Build a SARIF 2.1.0 log from `findings`. The `rules` array is built in a
+/// first pass (deduplicated by `rule_id`, first-seen order) so `ruleIndex`
+/// values in `results[]` stay stable even if the same rule fires more than
+/// once. Returns a log with an empty `results: []` when `findings` is empty
+/// — still a valid SARIF document.
+pub fn build_sarif(findings: &[Finding]) -> Value {
+    let mut rule_ids: Vec<&str> = Vec::new();
+    let mut rules: Vec<Value> = Vec::new();
+    for f in findings {
+        if rule_ids.contains(&f.rule_id.as_str()) {
+            continue;
+        }
+        rule_ids.push(&f.rule_id);
+        let mut rule = serde_json::json!({
+            "id": f.rule_id,
+            "name": f.rule_name,
+            "shortDescription": { "text": f.rule_description },
+            "properties": { "severity": f.severity },
+        });
+        if let Some(uri) = &f.help_uri {
+            rule["helpUri"] = Value::String(uri.clone());
+        }
+        rules.push(rule);
+    }
+
+    let results: Vec<Value> = findings
+        .iter()
+        .map(|f| {
+            let rule_index = rule_ids.iter().position(|id| *id == f.rule_id).unwrap_or(0);
+            serde_json::json!({
+                "ruleId": f.rule_id,
+                "ruleIndex": rule_index,
+                "level": sarif_level(&f.severity),
+                "message": { "text": f.message },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": { "uri": f.resource },
+                        "region": { "startLine": 1 },
+                    }
+                }],
+            })
+        })
+        .collect();
+
+    serde_json::json!({
+        "version": "2.1.0",
+        "$schema": SARIF_SCHEMA,
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "pup",
+                    "rules": rules,
+                }
+            },
+            "results": results,
+        }]
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn finding(rule_id: &str, severity: &str) -> Finding {
+        Finding {
+            rule_id: rule_id.to_string(),
+            rule_name: format!("{rule_id}-name"),
+            rule_description: "desc".to_string(),
+            help_uri: Some("https://example.com/help".to_string()),
+            severity: severity.to_string(),
+            message: "something is misconfigured".to_string(),
+            resource: "arn:aws:s3:::my-bucket".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_build_sarif_empty_results() {
+        let sarif = build_sarif(&[]);
+        assert_eq!(sarif["runs"][0]["results"], json!([]));
+        assert_eq!(sarif["runs"][0]["tool"]["driver"]["rules"], json!([]));
+    }
+
+    #[test]
+    fn test_build_sarif_dedupes_rules_with_stable_index() {
+        let findings = vec![finding("rule-a", "critical"), finding("rule-b", "medium"), finding("rule-a", "low")];
+        let sarif = build_sarif(&findings);
+        let rules = sarif["runs"][0]["tool"]["driver"]["rules"].as_array().unwrap();
+        assert_eq!(rules.len(), 2);
+
+        let results = sarif["runs"][0]["results"].as_array().unwrap();
+        assert_eq!(results[0]["ruleIndex"], 0);
+        assert_eq!(results[1]["ruleIndex"], 1);
+        assert_eq!(results[2]["ruleIndex"], 0);
+    }
+
+    #[test]
+    fn test_severity_maps_to_sarif_level() {
+        assert_eq!(sarif_level("critical"), "error");
+        assert_eq!(sarif_level("high"), "error");
+        assert_eq!(sarif_level("medium"), "warning");
+        assert_eq!(sarif_level("low"), "note");
+        assert_eq!(sarif_level("info"), "note");
+    }
+
+    #[test]
+    fn test_from_value_parses_findings_shape() {
+        let raw = json!({
+            "data": [{
+                "id": "finding-1",
+                "attributes": {
+                    "rule": {"id": "r1", "name": "Public S3 bucket", "severity": "high"},
+                    "resource_type": "aws_s3_bucket",
+                    "evaluation": {"description": "bucket is public"},
+                }
+            }]
+        });
+        let findings = from_value(&raw);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].rule_id, "r1");
+        assert_eq!(findings[0].severity, "high");
+        assert_eq!(findings[0].resource, "aws_s3_bucket");
+    }
+}
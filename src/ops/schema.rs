@@ -0,0 +1,197 @@
+//! `pup schema`: walk the actual clap command tree and emit a stable
+//! manifest mapping every dotted command path (e.g.
+//! `rum.retention-filters.update`) to its arguments, auth requirement,
+//! read/mutating classification, and backing `commands::*` handler — the
+//! same kind of generated name-mapping file large API clients ship, so
+//! downstream tooling can build shell completions, docs, and integration
+//! tests without parsing `--help` text. Generated from `Cli::command()`
+//! itself (never hand-maintained), so it can't drift from the dispatcher.
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::ops::rbac;
+
+pub const MANIFEST_VERSION: &str = "1";
+
+/// Commands that run before (or without) authentication — `schema` itself,
+/// version/build info, and local plugin discovery — everything else is
+/// assumed to require auth, since `cfg.validate_auth()` gates the rest of
+/// the dispatcher.
+const NO_AUTH_PREFIXES: &[&str] = &["schema", "version", "plugins"];
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ArgSpec {
+    pub name: String,
+    pub positional: bool,
+    pub required: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CommandEntry {
+    pub path: String,
+    pub args: Vec<ArgSpec>,
+    pub requires_auth: bool,
+    pub mutating: bool,
+    pub handler: String,
+}
+
+fn requires_auth(path: &str) -> bool {
+    !NO_AUTH_PREFIXES.iter().any(|p| path == *p || path.starts_with(&format!("{p}.")))
+}
+
+/// `commands::*` is addressed by convention from the dotted path: `.`
+/// becomes `::`, and hyphenated segments become snake_case, mirroring how
+/// every `commands::<module>::<fn>` in this tree is named after its CLI
+/// path today.
+fn handler_name(path: &str) -> String {
+    let segments: Vec<String> = path.split('.').map(|s| s.replace('-', "_")).collect();
+    format!("commands::{}", segments.join("::"))
+}
+
+fn arg_specs(cmd: &clap::Command) -> Vec<ArgSpec> {
+    cmd.get_arguments()
+        .filter(|a| a.get_id() != "help" && a.get_id() != "version")
+        .map(|a| ArgSpec {
+            name: a.get_id().to_string(),
+            positional: a.is_positional(),
+            required: a.is_required_set(),
+        })
+        .collect()
+}
+
+/// Recursively walk `cmd`'s subcommand tree, emitting one [`CommandEntry`]
+/// per leaf (a subcommand with no children of its own is a runnable
+/// command; one with children is just a namespace like `monitors` or
+/// `security`).
+pub fn walk(cmd: &clap::Command) -> Vec<CommandEntry> {
+    fn walk_inner(cmd: &clap::Command, prefix: &[String], out: &mut Vec<CommandEntry>) {
+        let children: Vec<&clap::Command> = cmd.get_subcommands().collect();
+        if children.is_empty() {
+            let path = prefix.join(".");
+            if !path.is_empty() {
+                out.push(CommandEntry {
+                    requires_auth: requires_auth(&path),
+                    mutating: rbac::is_mutating(&path),
+                    handler: handler_name(&path),
+                    args: arg_specs(cmd),
+                    path,
+                });
+            }
+            return;
+        }
+        for child in children {
+            let mut next_prefix = prefix.to_vec();
+            next_prefix.push(child.get_name().to_string());
+            walk_inner(child, &next_prefix, out);
+        }
+    }
+
+    let mut out = Vec::new();
+    walk_inner(cmd, &[], &mut out);
+    out.sort_by(|a, b| a.path.cmp(&b.path));
+    out
+}
+
+pub fn manifest(root: &clap::Command) -> Value {
+    serde_json::json!({
+        "version": MANIFEST_VERSION,
+        "commands": walk(root),
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Diff
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ManifestDiff {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    /// Best-effort: a removed path and an added path with identical args,
+    /// `requires_auth`, and `mutating` are reported as a likely rename
+    /// rather than an unrelated add+remove.
+    pub renamed: Vec<(String, String)>,
+}
+
+fn entries_of(manifest: &Value) -> Vec<CommandEntry> {
+    manifest
+        .pointer("/commands")
+        .cloned()
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default()
+}
+
+pub fn diff(old: &Value, new: &Value) -> ManifestDiff {
+    let old_entries = entries_of(old);
+    let new_entries = entries_of(new);
+
+    let mut removed: Vec<&CommandEntry> =
+        old_entries.iter().filter(|o| !new_entries.iter().any(|n| n.path == o.path)).collect();
+    let mut added: Vec<&CommandEntry> =
+        new_entries.iter().filter(|n| !old_entries.iter().any(|o| o.path == n.path)).collect();
+
+    let mut renamed = Vec::new();
+    removed.retain(|r| {
+        if let Some(pos) = added.iter().position(|a| a.args == r.args && a.requires_auth == r.requires_auth && a.mutating == r.mutating) {
+            renamed.push((r.path.clone(), added.remove(pos).path.clone()));
+            false
+        } else {
+            true
+        }
+    });
+
+    ManifestDiff {
+        added: added.iter().map(|e| e.path.clone()).collect(),
+        removed: removed.iter().map(|e| e.path.clone()).collect(),
+        renamed,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_requires_auth() {
+        assert!(!requires_auth("schema"));
+        assert!(!requires_auth("plugins.list"));
+        assert!(requires_auth("monitors.list"));
+    }
+
+    #[test]
+    fn test_handler_name() {
+        assert_eq!(handler_name("api-keys.create"), "commands::api_keys::create");
+        assert_eq!(handler_name("monitors.list"), "commands::monitors::list");
+    }
+
+    #[test]
+    fn test_diff_detects_added_and_removed() {
+        let old = json!({"version": "1", "commands": [
+            {"path": "monitors.list", "args": [], "requires_auth": true, "mutating": false, "handler": "x"},
+        ]});
+        let new = json!({"version": "1", "commands": [
+            {"path": "monitors.list", "args": [], "requires_auth": true, "mutating": false, "handler": "x"},
+            {"path": "monitors.create", "args": [], "requires_auth": true, "mutating": true, "handler": "y"},
+        ]});
+        let d = diff(&old, &new);
+        assert_eq!(d.added, vec!["monitors.create".to_string()]);
+        assert!(d.removed.is_empty());
+        assert!(d.renamed.is_empty());
+    }
+
+    #[test]
+    fn test_diff_detects_likely_rename() {
+        let old = json!({"version": "1", "commands": [
+            {"path": "monitors.remove", "args": [{"name": "id", "positional": true, "required": true}], "requires_auth": true, "mutating": true, "handler": "x"},
+        ]});
+        let new = json!({"version": "1", "commands": [
+            {"path": "monitors.delete", "args": [{"name": "id", "positional": true, "required": true}], "requires_auth": true, "mutating": true, "handler": "y"},
+        ]});
+        let d = diff(&old, &new);
+        assert!(d.added.is_empty());
+        assert!(d.removed.is_empty());
+        assert_eq!(d.renamed, vec![("monitors.remove".to_string(), "monitors.delete".to_string())]);
+    }
+}
@@ -0,0 +1,147 @@
+use anyhow::{bail, Result};
+use serde::Serialize;
+
+/// The Datadog API/app-key scopes a single `pup` command path consumes.
+/// `command` is the path a user would type after `pup`, e.g. `"monitors create"`.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct CommandScopes {
+    pub command: &'static str,
+    pub scopes: &'static [&'static str],
+}
+
+/// Static least-privilege table, one entry per mutating or read command
+/// pup exposes. Kept next to the command modules it documents so adding a
+/// command and forgetting to scope it here is the exception, not the rule.
+pub const CATALOG: &[CommandScopes] = &[
+    CommandScopes { command: "monitors list", scopes: &["monitors_read"] },
+    CommandScopes { command: "monitors get", scopes: &["monitors_read"] },
+    CommandScopes { command: "monitors create", scopes: &["monitors_write"] },
+    CommandScopes { command: "monitors update", scopes: &["monitors_write"] },
+    CommandScopes { command: "monitors delete", scopes: &["monitors_write"] },
+    CommandScopes { command: "monitors control", scopes: &["monitors_write"] },
+    CommandScopes { command: "dashboards list", scopes: &["dashboards_read"] },
+    CommandScopes { command: "dashboards get", scopes: &["dashboards_read"] },
+    CommandScopes { command: "dashboards create", scopes: &["dashboards_write"] },
+    CommandScopes { command: "dashboards update", scopes: &["dashboards_write"] },
+    CommandScopes { command: "dashboards delete", scopes: &["dashboards_write"] },
+    CommandScopes { command: "slos list", scopes: &["slos_read"] },
+    CommandScopes { command: "slos create", scopes: &["slos_write"] },
+    CommandScopes { command: "slos update", scopes: &["slos_write"] },
+    CommandScopes { command: "slos delete", scopes: &["slos_write"] },
+    CommandScopes { command: "downtime list", scopes: &["downtime_read"] },
+    CommandScopes { command: "downtime create", scopes: &["downtime_write"] },
+    CommandScopes { command: "downtime delete", scopes: &["downtime_write"] },
+    CommandScopes { command: "notebooks list", scopes: &["notebooks_read"] },
+    CommandScopes { command: "notebooks create", scopes: &["notebooks_write"] },
+    CommandScopes { command: "notebooks update", scopes: &["notebooks_write"] },
+    CommandScopes { command: "notebooks delete", scopes: &["notebooks_write"] },
+    CommandScopes { command: "security signals search", scopes: &["security_monitoring_signals_read"] },
+    CommandScopes { command: "security rules list", scopes: &["security_monitoring_rules_read"] },
+    CommandScopes { command: "security rules create", scopes: &["security_monitoring_rules_write"] },
+    CommandScopes { command: "security rules update", scopes: &["security_monitoring_rules_write"] },
+    CommandScopes { command: "security rules delete", scopes: &["security_monitoring_rules_write"] },
+    CommandScopes { command: "logs search", scopes: &["logs_read_data"] },
+    CommandScopes { command: "logs query", scopes: &["logs_read_data"] },
+    CommandScopes { command: "logs follow", scopes: &["logs_read_data"] },
+    CommandScopes { command: "logs aggregate", scopes: &["logs_read_data"] },
+    CommandScopes { command: "logs export", scopes: &["logs_read_data"] },
+    CommandScopes { command: "logs archives list", scopes: &["logs_read_archive"] },
+    CommandScopes { command: "logs archives create", scopes: &["logs_write_archive"] },
+    CommandScopes { command: "logs archives update", scopes: &["logs_write_archive"] },
+    CommandScopes { command: "logs archives delete", scopes: &["logs_write_archive"] },
+    CommandScopes { command: "logs custom-destinations list", scopes: &["logs_read_archive"] },
+    CommandScopes { command: "logs metrics list", scopes: &["logs_read_index_data"] },
+    CommandScopes { command: "logs metrics delete", scopes: &["logs_write_index_data"] },
+    CommandScopes { command: "logs restriction-queries list", scopes: &["logs_read_data"] },
+    CommandScopes { command: "events search", scopes: &["events_read"] },
+    CommandScopes { command: "incidents list", scopes: &["incident_read"] },
+    CommandScopes { command: "incidents create", scopes: &["incident_write"] },
+    CommandScopes { command: "incidents update", scopes: &["incident_write"] },
+    CommandScopes { command: "traces query", scopes: &["apm_read"] },
+    CommandScopes { command: "synthetics tests list", scopes: &["synthetics_read"] },
+    CommandScopes { command: "synthetics tests create", scopes: &["synthetics_write"] },
+    CommandScopes { command: "synthetics tests delete", scopes: &["synthetics_write"] },
+    CommandScopes { command: "api-keys list", scopes: &["api_keys_read"] },
+    CommandScopes { command: "api-keys create", scopes: &["api_keys_write"] },
+    CommandScopes { command: "api-keys delete", scopes: &["api_keys_write"] },
+    CommandScopes { command: "app-keys list", scopes: &["user_app_keys"] },
+    CommandScopes { command: "app-keys create", scopes: &["user_app_keys"] },
+    CommandScopes { command: "app-keys update", scopes: &["user_app_keys"] },
+    CommandScopes { command: "app-keys delete", scopes: &["user_app_keys"] },
+    CommandScopes { command: "tags list", scopes: &["hosts_read"] },
+    CommandScopes { command: "tags get", scopes: &["hosts_read"] },
+    CommandScopes { command: "tags add", scopes: &["hosts_write"] },
+    CommandScopes { command: "tags update", scopes: &["hosts_write"] },
+    CommandScopes { command: "tags delete", scopes: &["hosts_write"] },
+    CommandScopes { command: "dbm explain-plans", scopes: &["apm_read"] },
+    CommandScopes { command: "data-governance scanner-rules list", scopes: &["sensitive_data_scanner_read"] },
+    CommandScopes { command: "backup export", scopes: &[
+        "monitors_read", "slos_read", "dashboards_read", "downtime_read", "notebooks_read",
+        "synthetics_read", "security_monitoring_rules_read",
+    ] },
+    CommandScopes { command: "backup import", scopes: &[
+        "monitors_write", "slos_write", "dashboards_write", "downtime_write", "notebooks_write",
+        "synthetics_write", "security_monitoring_rules_write",
+    ] },
+    CommandScopes { command: "vet run", scopes: &["monitors_read"] },
+    CommandScopes { command: "auth login", scopes: &[] },
+    CommandScopes { command: "auth status", scopes: &[] },
+    CommandScopes { command: "auth token", scopes: &[] },
+    CommandScopes { command: "upgrade", scopes: &[] },
+];
+
+pub fn lookup(command: &str) -> Option<&'static CommandScopes> {
+    CATALOG.iter().find(|c| c.command == command)
+}
+
+/// Deduplicated, sorted union of scopes needed to run every command in
+/// `commands`, suitable for provisioning one narrowly-scoped application
+/// key that covers exactly this set of automation.
+pub fn manifest(commands: &[&str]) -> Result<Vec<&'static str>> {
+    let mut unknown = Vec::new();
+    let mut scopes = std::collections::BTreeSet::new();
+
+    for &command in commands {
+        match lookup(command) {
+            Some(entry) => scopes.extend(entry.scopes.iter().copied()),
+            None => unknown.push(command.to_string()),
+        }
+    }
+
+    if !unknown.is_empty() {
+        bail!(
+            "unknown command path(s): {}\nExpected one of: {}",
+            unknown.join(", "),
+            CATALOG.iter().map(|c| c.command).collect::<Vec<_>>().join(", ")
+        );
+    }
+
+    Ok(scopes.into_iter().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lookup_known_command() {
+        let entry = lookup("monitors create").unwrap();
+        assert_eq!(entry.scopes, &["monitors_write"]);
+    }
+
+    #[test]
+    fn test_lookup_unknown_command() {
+        assert!(lookup("monitors frobnicate").is_none());
+    }
+
+    #[test]
+    fn test_manifest_dedupes_and_sorts() {
+        let scopes = manifest(&["monitors create", "monitors list"]).unwrap();
+        assert_eq!(scopes, vec!["monitors_read", "monitors_write"]);
+    }
+
+    #[test]
+    fn test_manifest_rejects_unknown_command() {
+        assert!(manifest(&["monitors create", "nope"]).is_err());
+    }
+}
@@ -0,0 +1,138 @@
+//! `pup test`: an aggregate health scorecard. Each `Probe` makes one
+//! representative read call into a single product namespace (a `list` or
+//! `status`-shaped endpoint) so a single command answers "which product
+//! scopes do my API/app keys actually unlock?" without running every
+//! `list` subcommand by hand. Modeled on aggregate grading reporters: each
+//! probe is worth one point inside its group, group scores roll up into a
+//! grand-total percentage.
+
+use std::time::Instant;
+
+use anyhow::Result;
+use serde::Serialize;
+
+use crate::api;
+use crate::config::Config;
+use crate::ops::backup::ALL_KINDS;
+
+#[derive(Debug, Serialize)]
+pub struct CheckOutcome {
+    pub group: &'static str,
+    pub name: &'static str,
+    pub passed: bool,
+    pub latency_ms: u128,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GroupScore {
+    pub group: &'static str,
+    pub passed: usize,
+    pub total: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ScorecardResult {
+    pub checks: Vec<CheckOutcome>,
+    pub groups: Vec<GroupScore>,
+    /// Out of 100, weighted evenly across every check regardless of group size.
+    pub grand_total: f64,
+}
+
+struct Probe {
+    group: &'static str,
+    name: &'static str,
+    path: String,
+}
+
+/// One representative read endpoint per product namespace this tree has a
+/// client for. Kept to cheap, side-effect-free `list`/`status` calls so
+/// running the scorecard never mutates an account.
+fn probes() -> Vec<Probe> {
+    let mut probes: Vec<Probe> = ALL_KINDS
+        .iter()
+        .map(|kind| Probe {
+            group: "backup",
+            name: kind.as_str(),
+            path: kind.collection_path().to_string(),
+        })
+        .collect();
+
+    probes.push(Probe { group: "logs", name: "archives", path: "/api/v2/logs/config/archives".to_string() });
+    probes.push(Probe { group: "logs", name: "metrics", path: "/api/v2/logs/config/metrics".to_string() });
+    probes.push(Probe { group: "app-keys", name: "list", path: "/api/v2/application_keys".to_string() });
+    probes.push(Probe { group: "tags", name: "hosts", path: "/api/v1/tags/hosts".to_string() });
+    probes.push(Probe {
+        group: "data-governance",
+        name: "scanner-rules",
+        path: "/api/v2/sensitive-data-scanner/config".to_string(),
+    });
+    probes.push(Probe {
+        group: "product-analytics",
+        name: "segments",
+        path: "/api/v2/product-analytics/segments".to_string(),
+    });
+    probes.push(Probe { group: "auth", name: "current-user", path: "/api/v2/current_user".to_string() });
+
+    probes
+}
+
+/// Run every probe against `cfg`, in order (not concurrently, so one slow or
+/// rate-limited namespace can't make latencies for the others look worse
+/// than they are), and score the results.
+pub async fn run(cfg: &Config) -> Result<ScorecardResult> {
+    let mut checks = Vec::new();
+
+    for probe in probes() {
+        let started = Instant::now();
+        let outcome = api::get(cfg, &probe.path, &[]).await;
+        let latency_ms = started.elapsed().as_millis();
+        checks.push(CheckOutcome {
+            group: probe.group,
+            name: probe.name,
+            passed: outcome.is_ok(),
+            latency_ms,
+            error: outcome.err().map(|e| e.to_string()),
+        });
+    }
+
+    let mut groups: Vec<GroupScore> = Vec::new();
+    for check in &checks {
+        match groups.iter_mut().find(|g| g.group == check.group) {
+            Some(g) => {
+                g.total += 1;
+                if check.passed {
+                    g.passed += 1;
+                }
+            }
+            None => groups.push(GroupScore { group: check.group, passed: check.passed as usize, total: 1 }),
+        }
+    }
+
+    let grand_total = if checks.is_empty() {
+        100.0
+    } else {
+        100.0 * checks.iter().filter(|c| c.passed).count() as f64 / checks.len() as f64
+    };
+
+    Ok(ScorecardResult { checks, groups, grand_total })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grand_total_is_percentage_of_passed_checks() {
+        let result = ScorecardResult {
+            checks: vec![
+                CheckOutcome { group: "g", name: "a", passed: true, latency_ms: 1, error: None },
+                CheckOutcome { group: "g", name: "b", passed: false, latency_ms: 1, error: Some("boom".into()) },
+            ],
+            groups: vec![GroupScore { group: "g", passed: 1, total: 2 }],
+            grand_total: 50.0,
+        };
+        assert_eq!(result.grand_total, 50.0);
+    }
+}
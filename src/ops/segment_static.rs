@@ -0,0 +1,152 @@
+//! Build a static-segment payload from a plain CSV/newline-delimited
+//! identifier list, for `segment_create_static`'s `--from-ids` convenience
+//! path — promoting an exported cohort into a Datadog static segment
+//! without hand-templating the `{"data":{"type":"segment",...}}` JSON body.
+
+use std::collections::HashSet;
+
+use anyhow::{bail, Context, Result};
+use serde_json::{json, Value};
+
+/// Member count per POST when chunking a large identifier list, unless the
+/// caller overrides it.
+pub const DEFAULT_CHUNK_SIZE: usize = 5000;
+
+/// Which column of a CSV/newline-delimited row holds the member ID —
+/// `--id-column NAME` resolved against a header row, or a numeric index
+/// when the file has no header.
+#[derive(Debug, Clone, PartialEq)]
+pub enum IdColumn {
+    Name(String),
+    Index(usize),
+}
+
+impl IdColumn {
+    /// Parse `--id-column`'s value: a bare non-negative integer is an
+    /// index, anything else is a header name.
+    pub fn parse(spec: &str) -> Self {
+        match spec.parse::<usize>() {
+            Ok(i) => IdColumn::Index(i),
+            Err(_) => IdColumn::Name(spec.to_string()),
+        }
+    }
+}
+
+/// Read `path` (comma-separated or bare newline-delimited) and collect the
+/// deduped, in-first-seen-order list of member IDs from `column`. `header`
+/// skips the first row when reading it as data, and is required when
+/// `column` is a name (there's nothing else to resolve it against).
+pub fn read_member_ids(path: &str, column: &IdColumn, header: bool) -> Result<Vec<String>> {
+    if matches!(column, IdColumn::Name(_)) && !header {
+        bail!("--id-column by name requires --header so the column can be resolved");
+    }
+
+    let contents = std::fs::read_to_string(path).with_context(|| format!("failed to read {path}"))?;
+
+    let mut column_index = match column {
+        IdColumn::Index(i) => *i,
+        IdColumn::Name(_) => 0,
+    };
+
+    let mut seen = HashSet::new();
+    let mut ids = Vec::new();
+    let mut pending_header = header;
+
+    for line in contents.lines() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+
+        if pending_header {
+            pending_header = false;
+            if let IdColumn::Name(name) = column {
+                column_index = fields
+                    .iter()
+                    .position(|f| f == name)
+                    .with_context(|| format!("--id-column {name:?} not found in header: {line}"))?;
+            }
+            continue;
+        }
+
+        let Some(value) = fields.get(column_index) else {
+            bail!("row has no column {column_index} (0-indexed): {line}");
+        };
+        if value.is_empty() {
+            continue;
+        }
+        if seen.insert((*value).to_string()) {
+            ids.push((*value).to_string());
+        }
+    }
+
+    Ok(ids)
+}
+
+/// Build the `{"data":{"type":"segment","attributes":{"name":..,"members":[...]}}}`
+/// payload the static-segment endpoint expects for one chunk of members.
+pub fn build_payload(name: &str, members: &[String]) -> Value {
+    json!({
+        "data": {
+            "type": "segment",
+            "attributes": {
+                "name": name,
+                "members": members,
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_temp(contents: &str) -> String {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+        let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+        let path = std::env::temp_dir().join(format!("pup-segment-static-test-{}-{n}", std::process::id()));
+        std::fs::write(&path, contents).unwrap();
+        path.to_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn id_column_parse_numeric_is_index() {
+        assert_eq!(IdColumn::parse("2"), IdColumn::Index(2));
+    }
+
+    #[test]
+    fn id_column_parse_non_numeric_is_name() {
+        assert_eq!(IdColumn::parse("user_id"), IdColumn::Name("user_id".to_string()));
+    }
+
+    #[test]
+    fn read_member_ids_by_index_dedupes_and_skips_blank_lines() {
+        let path = write_temp("a1\na2\n\na1\na3\n");
+        let ids = read_member_ids(&path, &IdColumn::Index(0), false).unwrap();
+        assert_eq!(ids, vec!["a1", "a2", "a3"]);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn read_member_ids_by_header_name() {
+        let path = write_temp("email,user_id\nx@y.com,a1\nz@y.com,a2\n");
+        let ids = read_member_ids(&path, &IdColumn::Name("user_id".to_string()), true).unwrap();
+        assert_eq!(ids, vec!["a1", "a2"]);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn read_member_ids_requires_header_for_named_column() {
+        let err = read_member_ids("does-not-matter.csv", &IdColumn::Name("user_id".to_string()), false).unwrap_err();
+        assert!(err.to_string().contains("--header"));
+    }
+
+    #[test]
+    fn build_payload_wraps_members_in_segment_shape() {
+        let payload = build_payload("cohort-a", &["a1".to_string(), "a2".to_string()]);
+        assert_eq!(payload["data"]["type"], "segment");
+        assert_eq!(payload["data"]["attributes"]["name"], "cohort-a");
+        assert_eq!(payload["data"]["attributes"]["members"], json!(["a1", "a2"]));
+    }
+}
@@ -0,0 +1,211 @@
+//! Bulk host-tag apply: read a manifest mapping hostnames to tag lists and
+//! drive one `update_host_tags` call per host concurrently, rather than
+//! forcing users to shell-loop `pup tags update` once per host. Uses
+//! `futures::stream::buffer_unordered` the same way `ops::pagination` bounds
+//! concurrent page fetches, but — unlike `pagination::buffered` — collects
+//! every host's `Result` into the summary instead of aborting the whole run
+//! on the first failure, since one bad hostname in a thousand-host manifest
+//! shouldn't block the other 999 (the same "collect, don't abort" shape as
+//! `commands::logs::metrics_delete_matching`'s per-id results).
+
+use std::collections::{BTreeMap, HashSet};
+
+#[cfg(not(target_arch = "wasm32"))]
+use datadog_api_client::datadogV1::api_tags::{
+    GetHostTagsOptionalParams, TagsAPI, UpdateHostTagsOptionalParams,
+};
+#[cfg(not(target_arch = "wasm32"))]
+use datadog_api_client::datadogV1::model::HostTags;
+
+use anyhow::{Context, Result};
+use futures::stream::{self, StreamExt};
+use serde::Serialize;
+use thiserror::Error;
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::client;
+use crate::config::Config;
+
+/// Precise, matchable errors for the host-tags subsystem, so a 404 can be
+/// told apart from a rate limit instead of every failure collapsing into one
+/// `Debug`-printed transport error. `main` still only deals in `anyhow::Error`
+/// — `thiserror`'s derive gives `TagsError` a real `std::error::Error` impl,
+/// which anyhow's blanket `From` already knows how to wrap, so `?` keeps
+/// working unchanged at every call site.
+#[derive(Debug, Error)]
+pub enum TagsError {
+    #[error("host not found: {0}")]
+    NotFound(String),
+    #[error("unauthorized or forbidden: {0}")]
+    Unauthorized(String),
+    #[error("rate limited: {0}")]
+    RateLimited(String),
+    #[error("{0}")]
+    Api(String),
+    #[error("{0}")]
+    Transport(String),
+}
+
+/// The shape of Datadog's own JSON error body: `{"errors": ["message", ...]}`.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug, serde::Deserialize)]
+struct ApiErrorBody {
+    #[serde(default)]
+    errors: Vec<String>,
+}
+
+/// Classify a raw `datadog_api_client::datadog::Error` into a [`TagsError`],
+/// deserializing the response body's `errors` array (when present) so the
+/// surfaced message is Datadog's own, not a transport-level `Debug` dump.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn classify_error<T>(err: datadog_api_client::datadog::Error<T>) -> TagsError {
+    use datadog_api_client::datadog::Error as DdError;
+
+    match err {
+        DdError::ResponseError(rc) => {
+            let messages: Vec<String> = serde_json::from_str::<ApiErrorBody>(&rc.body)
+                .map(|b| b.errors)
+                .unwrap_or_default();
+            let detail = if messages.is_empty() { rc.body.clone() } else { messages.join("; ") };
+            match rc.status.as_u16() {
+                404 => TagsError::NotFound(detail),
+                401 | 403 => TagsError::Unauthorized(detail),
+                429 => TagsError::RateLimited(detail),
+                _ => TagsError::Api(detail),
+            }
+        }
+        other => TagsError::Transport(format!("{other:?}")),
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ApplySummary {
+    pub succeeded: Vec<String>,
+    pub failed: Vec<(String, String)>,
+}
+
+/// Parse `path` (`.json` or YAML) into an ordered `(hostname, tags)` list. A
+/// manifest is a simple map — `{hostname: [tag, ...]}` — since bulk apply
+/// has no per-host options beyond the tag list itself.
+fn read_manifest(path: &str) -> Result<Vec<(String, Vec<String>)>> {
+    let raw = std::fs::read_to_string(path).with_context(|| format!("failed to read tag manifest {path}"))?;
+    let map: BTreeMap<String, Vec<String>> = if path.ends_with(".json") {
+        serde_json::from_str(&raw).with_context(|| format!("failed to parse {path} as JSON"))?
+    } else {
+        serde_yaml::from_str(&raw).with_context(|| format!("failed to parse {path} as YAML"))?
+    };
+    Ok(map.into_iter().collect())
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+async fn update_one(cfg: &Config, hostname: &str, host_tags: Vec<String>) -> Result<()> {
+    let dd_cfg = client::make_dd_config(cfg);
+    let dd_client = client::make_dd_client(cfg);
+    let api = TagsAPI::with_client_and_config(dd_cfg, dd_client);
+    let body = HostTags::new().tags(host_tags);
+    let params = UpdateHostTagsOptionalParams::default();
+    crate::ops::retry::with_retry(cfg.max_retries, || {
+        api.update_host_tags(hostname.to_string(), body.clone(), params.clone())
+    })
+    .await
+    .map_err(classify_error)?;
+    Ok(())
+}
+
+#[cfg(target_arch = "wasm32")]
+async fn update_one(cfg: &Config, hostname: &str, host_tags: Vec<String>) -> Result<()> {
+    let body = serde_json::json!({ "tags": host_tags });
+    crate::api::put(cfg, &format!("/api/v1/tags/hosts/{hostname}"), &body).await?;
+    Ok(())
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+async fn current_tags(cfg: &Config, hostname: &str) -> Result<Vec<String>> {
+    let dd_cfg = client::make_dd_config(cfg);
+    let dd_client = client::make_dd_client(cfg);
+    let api = TagsAPI::with_client_and_config(dd_cfg, dd_client);
+    let params = GetHostTagsOptionalParams::default();
+    let resp = crate::ops::retry::with_retry(cfg.max_retries, || api.get_host_tags(hostname.to_string(), params.clone()))
+        .await
+        .map_err(classify_error)?;
+    Ok(resp.tags.unwrap_or_default())
+}
+
+#[cfg(target_arch = "wasm32")]
+async fn current_tags(cfg: &Config, hostname: &str) -> Result<Vec<String>> {
+    let data = crate::api::get(cfg, &format!("/api/v1/tags/hosts/{hostname}"), &[]).await?;
+    let tags = data
+        .get("tags")
+        .and_then(|v| v.as_array())
+        .map(|a| a.iter().filter_map(|v| v.as_str().map(String::from)).collect())
+        .unwrap_or_default();
+    Ok(tags)
+}
+
+/// The `to_add`/`to_remove` sets a [`sync`] call computed for one host, and
+/// whether it actually converged anything (so a no-op sync is visibly a
+/// no-op rather than an empty-looking success).
+#[derive(Debug, Clone, Serialize)]
+pub struct SyncDiff {
+    pub hostname: String,
+    pub to_add: Vec<String>,
+    pub to_remove: Vec<String>,
+}
+
+impl SyncDiff {
+    pub fn is_noop(&self) -> bool {
+        self.to_add.is_empty() && self.to_remove.is_empty()
+    }
+}
+
+/// Converge `hostname`'s tags to exactly `desired`: fetch the current set,
+/// diff it against `desired` as `HashSet`s, and — unless `dry_run` or the
+/// diff is empty — issue a single `update_host_tags` call with the desired
+/// set. A full replace (rather than separate add/remove calls) is the only
+/// way the host tags API can drop stale tags, but computing the diff first
+/// means a host that's already converged makes zero API calls beyond the
+/// initial read.
+pub async fn sync(cfg: &Config, hostname: &str, desired: Vec<String>, dry_run: bool) -> Result<SyncDiff> {
+    let current: HashSet<String> = current_tags(cfg, hostname).await?.into_iter().collect();
+    let desired: HashSet<String> = desired.into_iter().collect();
+
+    let mut to_add: Vec<String> = desired.difference(&current).cloned().collect();
+    let mut to_remove: Vec<String> = current.difference(&desired).cloned().collect();
+    to_add.sort();
+    to_remove.sort();
+
+    let diff = SyncDiff { hostname: hostname.to_string(), to_add, to_remove };
+
+    if !dry_run && !diff.is_noop() {
+        update_one(cfg, hostname, desired.into_iter().collect()).await?;
+    }
+
+    Ok(diff)
+}
+
+/// Apply every `(hostname, tags)` pair in `path`'s manifest, at most
+/// `concurrency` `update_host_tags` requests in flight at once.
+pub async fn bulk_apply(cfg: &Config, path: &str, concurrency: usize) -> Result<ApplySummary> {
+    let manifest = read_manifest(path)?;
+
+    let outcomes: Vec<(String, Result<()>)> = stream::iter(manifest)
+        .map(|(hostname, host_tags)| {
+            let cfg = cfg.clone();
+            async move {
+                let result = update_one(&cfg, &hostname, host_tags).await;
+                (hostname, result)
+            }
+        })
+        .buffer_unordered(concurrency.max(1))
+        .collect()
+        .await;
+
+    let mut summary = ApplySummary { succeeded: Vec::new(), failed: Vec::new() };
+    for (hostname, result) in outcomes {
+        match result {
+            Ok(()) => summary.succeeded.push(hostname),
+            Err(e) => summary.failed.push((hostname, e.to_string())),
+        }
+    }
+    Ok(summary)
+}
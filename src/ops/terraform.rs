@@ -0,0 +1,184 @@
+use serde_json::Value;
+
+use crate::ops::backup::ResourceKind;
+
+// ---------------------------------------------------------------------------
+// Terraform HCL rendering for the `datadog` provider
+// ---------------------------------------------------------------------------
+//
+// Covers the resource kinds pup can already `Get`/`List`/export. Each kind
+// maps onto one `datadog` provider resource type, with a best-effort
+// attribute mapping — fields the API returns that the provider schema
+// doesn't expose (timestamps, computed ids, etc.) are dropped rather than
+// guessed at.
+
+impl ResourceKind {
+    /// The `datadog` Terraform provider resource type backing this kind.
+    pub fn terraform_resource_type(&self) -> Option<&'static str> {
+        match self {
+            ResourceKind::Monitors => Some("datadog_monitor"),
+            ResourceKind::Dashboards => Some("datadog_dashboard_json"),
+            ResourceKind::Slos => Some("datadog_service_level_objective"),
+            ResourceKind::Downtime => Some("datadog_downtime_schedule"),
+            ResourceKind::SyntheticsTests => Some("datadog_synthetics_test"),
+            ResourceKind::SecurityRules => Some("datadog_security_monitoring_rule"),
+            ResourceKind::Notebooks => None,
+        }
+    }
+}
+
+/// Render `obj` (one object previously fetched via `ops::backup`) as a single
+/// Terraform resource block, plus the matching `terraform import` address as
+/// a leading comment line, so the output can be appended straight to a
+/// `.tf` file.
+pub fn render(kind: ResourceKind, id: &str, obj: &Value) -> anyhow::Result<String> {
+    let resource_type = kind
+        .terraform_resource_type()
+        .ok_or_else(|| anyhow::anyhow!("{} has no datadog provider equivalent yet", kind.as_str()))?;
+    let label = resource_label(kind, id, obj);
+
+    let mut out = String::new();
+    out.push_str(&format!("# terraform import {resource_type}.{label} {id}\n"));
+    out.push_str(&format!("resource \"{resource_type}\" \"{label}\" {{\n"));
+    for (key, value) in attributes(kind, obj) {
+        out.push_str(&format!("  {key} = {}\n", hcl_value(&value)));
+    }
+    out.push_str("}\n");
+    Ok(out)
+}
+
+/// A stable, Terraform-identifier-safe resource label derived from the
+/// object's name (if present) and its id, so re-running export against the
+/// same org produces byte-identical labels.
+fn resource_label(kind: ResourceKind, id: &str, obj: &Value) -> String {
+    let name = kind.name_of(obj).unwrap_or_default();
+    let slug = slugify(&name);
+    if slug.is_empty() {
+        format!("{}_{}", kind.as_str().replace('-', "_"), slugify(id))
+    } else {
+        format!("{slug}_{}", slugify(id))
+    }
+}
+
+fn slugify(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut last_was_underscore = false;
+    for c in s.chars() {
+        if c.is_ascii_alphanumeric() {
+            out.push(c.to_ascii_lowercase());
+            last_was_underscore = false;
+        } else if !last_was_underscore && !out.is_empty() {
+            out.push('_');
+            last_was_underscore = true;
+        }
+    }
+    out.trim_end_matches('_').to_string()
+}
+
+/// Pull the subset of an object's fields that map directly onto the
+/// provider's schema for this resource type, in the provider's conventional
+/// field order.
+fn attributes(kind: ResourceKind, obj: &Value) -> Vec<(&'static str, Value)> {
+    let get = |field: &str| -> Option<Value> {
+        obj.get(field)
+            .or_else(|| obj.pointer(&format!("/attributes/{field}")))
+            .or_else(|| obj.pointer(&format!("/data/attributes/{field}")))
+            .cloned()
+    };
+
+    let mut attrs = Vec::new();
+    let fields: &[(&str, &str)] = match kind {
+        ResourceKind::Monitors => &[
+            ("name", "name"),
+            ("type", "type"),
+            ("query", "query"),
+            ("message", "message"),
+            ("tags", "tags"),
+            ("priority", "priority"),
+        ],
+        ResourceKind::Dashboards => &[("dashboard", "__json__")],
+        ResourceKind::Slos => &[
+            ("name", "name"),
+            ("type", "type"),
+            ("description", "description"),
+            ("query", "query"),
+            ("tags", "tags"),
+            ("monitor_ids", "monitor_ids"),
+        ],
+        ResourceKind::Downtime => &[
+            ("scope", "scope"),
+            ("message", "message"),
+            ("monitor_id", "monitor_id"),
+        ],
+        ResourceKind::SyntheticsTests => &[
+            ("name", "name"),
+            ("type", "type"),
+            ("message", "message"),
+            ("tags", "tags"),
+            ("locations", "locations"),
+            ("status", "status"),
+        ],
+        ResourceKind::SecurityRules => &[
+            ("name", "name"),
+            ("message", "message"),
+            ("enabled", "isEnabled"),
+            ("tags", "tags"),
+        ],
+        ResourceKind::Notebooks => &[],
+    };
+
+    for (hcl_field, json_field) in fields {
+        if *json_field == "__json__" {
+            attrs.push((*hcl_field, Value::String(obj.to_string())));
+            continue;
+        }
+        if let Some(value) = get(json_field) {
+            if !value.is_null() {
+                attrs.push((*hcl_field, value));
+            }
+        }
+    }
+    attrs
+}
+
+/// Render a `serde_json::Value` as an HCL expression literal.
+fn hcl_value(value: &Value) -> String {
+    match value {
+        Value::String(s) => hcl_quote(s),
+        Value::Number(n) => n.to_string(),
+        Value::Bool(b) => b.to_string(),
+        Value::Array(items) => {
+            let rendered: Vec<String> = items.iter().map(hcl_value).collect();
+            format!("[{}]", rendered.join(", "))
+        }
+        Value::Null => "null".to_string(),
+        Value::Object(_) => hcl_quote(&value.to_string()),
+    }
+}
+
+/// Quote and escape a string for embedding in an HCL expression. Only a
+/// literal `${` / `%{` is an interpolation/directive marker in HCL, so only
+/// those sequences need escaping (to `$${` / `%%{`) — a bare `$` or `%` is
+/// passed through unchanged to avoid corrupting the source text.
+fn hcl_quote(s: &str) -> String {
+    let chars: Vec<char> = s.chars().collect();
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '$' | '%' if chars.get(i + 1) == Some(&'{') => {
+                out.push(c);
+                out.push(c);
+            }
+            _ => out.push(c),
+        }
+        i += 1;
+    }
+    out.push('"');
+    out
+}
@@ -0,0 +1,378 @@
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// GitHub repo that publishes `pup` releases; also where the embedded
+/// `version::VERSION` is cut from.
+const GITHUB_REPO: &str = "datadog-labs/pup";
+
+/// Name of the checksums file every release publishes alongside its
+/// platform archives, one `<sha256>  <filename>` line per asset.
+const CHECKSUMS_ASSET: &str = "checksums.txt";
+
+#[derive(Debug, Deserialize)]
+struct GhRelease {
+    tag_name: String,
+    assets: Vec<GhAsset>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct GhAsset {
+    name: String,
+    browser_download_url: String,
+}
+
+/// Result of comparing the embedded build version against the latest
+/// published release, independent of whether an upgrade is actually performed.
+#[derive(Debug, Serialize)]
+pub struct VersionCheck {
+    pub current: String,
+    pub latest: String,
+    pub update_available: bool,
+}
+
+/// What happened when `perform` was asked to upgrade (or just report on)
+/// the running binary.
+#[derive(Debug, Serialize)]
+pub struct UpgradeOutcome {
+    pub from: String,
+    pub to: String,
+    pub upgraded: bool,
+}
+
+/// Compare the running build's `version::VERSION` against the latest
+/// (or, if `version` is given, a specific) tagged release, without
+/// downloading or replacing anything.
+pub async fn check(version: Option<&str>) -> Result<VersionCheck> {
+    let release = fetch_release(version).await?;
+    let latest = release.tag_name.trim_start_matches('v').to_string();
+    let current = crate::version::VERSION.to_string();
+    let update_available = compare_versions(&latest, &current) == std::cmp::Ordering::Greater;
+    Ok(VersionCheck {
+        current,
+        latest,
+        update_available,
+    })
+}
+
+/// Download the release asset for this platform, verify it against the
+/// release's published checksums, and atomically replace the running
+/// binary. `version` pins to a specific tag (e.g. `"1.4.0"` or `"v1.4.0"`);
+/// `None` means "latest". No-ops (besides reporting) if the running binary
+/// is already at the target version.
+pub async fn perform(version: Option<&str>, yes: bool) -> Result<UpgradeOutcome> {
+    let release = fetch_release(version).await?;
+    let target = release.tag_name.trim_start_matches('v').to_string();
+    let current = crate::version::VERSION.to_string();
+
+    if compare_versions(&target, &current) != std::cmp::Ordering::Greater && version.is_none() {
+        return Ok(UpgradeOutcome {
+            from: current.clone(),
+            to: current,
+            upgraded: false,
+        });
+    }
+
+    if !yes {
+        bail!(
+            "refusing to replace the running binary ({current} -> {target}) without --yes"
+        );
+    }
+
+    let asset_name = platform_asset_name(&release.tag_name);
+    let asset = release
+        .assets
+        .iter()
+        .find(|a| a.name == asset_name)
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "release {} has no asset named {asset_name:?} (have: {})",
+                release.tag_name,
+                release
+                    .assets
+                    .iter()
+                    .map(|a| a.name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
+        })?;
+
+    let bytes = download(&asset.browser_download_url).await?;
+    verify_checksum(&release, &asset_name, &bytes).await?;
+    let binary = extract_binary(&asset_name, &bytes)?;
+    replace_current_exe(&binary)?;
+
+    Ok(UpgradeOutcome {
+        from: current,
+        to: target,
+        upgraded: true,
+    })
+}
+
+async fn fetch_release(version: Option<&str>) -> Result<GhRelease> {
+    let url = match version {
+        Some(v) => format!(
+            "https://api.github.com/repos/{GITHUB_REPO}/releases/tags/{}",
+            v.trim_start_matches('v')
+        ),
+        None => format!("https://api.github.com/repos/{GITHUB_REPO}/releases/latest"),
+    };
+
+    let client = reqwest::Client::new();
+    let resp = client
+        .get(&url)
+        .header("User-Agent", "pup-cli")
+        .header("Accept", "application/vnd.github+json")
+        .send()
+        .await
+        .with_context(|| format!("failed to query {url}"))?;
+
+    if !resp.status().is_success() {
+        bail!("GitHub releases request to {url} failed: HTTP {}", resp.status());
+    }
+
+    resp.json()
+        .await
+        .with_context(|| format!("failed to parse release metadata from {url}"))
+}
+
+async fn download(url: &str) -> Result<Vec<u8>> {
+    let client = reqwest::Client::new();
+    let resp = client
+        .get(url)
+        .header("User-Agent", "pup-cli")
+        .send()
+        .await
+        .with_context(|| format!("failed to download {url}"))?;
+    if !resp.status().is_success() {
+        bail!("download of {url} failed: HTTP {}", resp.status());
+    }
+    Ok(resp.bytes().await?.to_vec())
+}
+
+/// Fetch the release's `checksums.txt` and confirm it lists `asset_name`
+/// with a sha256 matching `bytes`, so a corrupted or tampered download is
+/// rejected before it ever replaces the running binary.
+async fn verify_checksum(release: &GhRelease, asset_name: &str, bytes: &[u8]) -> Result<()> {
+    let checksums_asset = release
+        .assets
+        .iter()
+        .find(|a| a.name == CHECKSUMS_ASSET)
+        .ok_or_else(|| {
+            anyhow::anyhow!("release {} has no {CHECKSUMS_ASSET}", release.tag_name)
+        })?;
+    let checksums = download(&checksums_asset.browser_download_url).await?;
+    let checksums = String::from_utf8(checksums).context("checksums.txt is not valid UTF-8")?;
+
+    let expected = checksums
+        .lines()
+        .find_map(|line| {
+            let mut parts = line.split_whitespace();
+            let hash = parts.next()?;
+            let name = parts.next()?.trim_start_matches('*');
+            (name == asset_name).then(|| hash.to_lowercase())
+        })
+        .ok_or_else(|| {
+            anyhow::anyhow!("{CHECKSUMS_ASSET} has no entry for {asset_name}")
+        })?;
+
+    let actual: String = Sha256::digest(bytes).iter().map(|b| format!("{b:02x}")).collect();
+    if actual != expected {
+        bail!(
+            "checksum mismatch for {asset_name}: expected {expected}, got {actual}; aborting upgrade"
+        );
+    }
+    Ok(())
+}
+
+/// Pull the single `pup` binary out of a downloaded release archive.
+/// `asset_name`'s extension picks the format: `.tar.gz` is gzip+tar
+/// ([`extract_tar_gz`]), `.zip` is a zip archive ([`extract_zip`]) — the
+/// same two formats [`platform_asset_name`] ever produces. The checksum
+/// has already been verified against the archive bytes by this point, so
+/// extraction failures here are malformed-archive errors, not tampering.
+fn extract_binary(asset_name: &str, archive: &[u8]) -> Result<Vec<u8>> {
+    if asset_name.ends_with(".tar.gz") {
+        extract_tar_gz(archive)
+    } else if asset_name.ends_with(".zip") {
+        extract_zip(archive)
+    } else {
+        bail!("don't know how to extract release asset {asset_name:?} (expected .tar.gz or .zip)")
+    }
+}
+
+/// Find the single regular-file entry in a tar stream and return its
+/// contents — every release archive packs exactly one binary, so the first
+/// (and only) file entry found is it.
+fn extract_tar_gz(archive: &[u8]) -> Result<Vec<u8>> {
+    use std::io::Read;
+
+    let decoder = flate2::read::GzDecoder::new(archive);
+    let mut tar = tar::Archive::new(decoder);
+    for entry in tar.entries().context("failed to read tar archive")? {
+        let mut entry = entry.context("failed to read tar entry")?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        let mut buf = Vec::new();
+        entry.read_to_end(&mut buf).context("failed to read binary out of tar archive")?;
+        return Ok(buf);
+    }
+    bail!("release archive has no file entries")
+}
+
+/// Same as [`extract_tar_gz`] but for the `.zip` asset Windows releases use.
+fn extract_zip(archive: &[u8]) -> Result<Vec<u8>> {
+    use std::io::Read;
+
+    let mut zip = zip::ZipArchive::new(std::io::Cursor::new(archive)).context("failed to read zip archive")?;
+    for i in 0..zip.len() {
+        let mut entry = zip.by_index(i).context("failed to read zip entry")?;
+        if !entry.is_file() {
+            continue;
+        }
+        let mut buf = Vec::new();
+        entry.read_to_end(&mut buf).context("failed to read binary out of zip archive")?;
+        return Ok(buf);
+    }
+    bail!("release archive has no file entries")
+}
+
+/// Write `bytes` to a temp file next to the running executable, mark it
+/// executable, then `rename` it over the current binary. `rename` within
+/// the same directory is atomic, so a concurrently-started `pup` process
+/// either sees the old binary or the new one, never a half-written one.
+fn replace_current_exe(bytes: &[u8]) -> Result<()> {
+    use std::io::Write;
+
+    let current_exe = std::env::current_exe().context("failed to resolve running executable")?;
+    let dir = current_exe
+        .parent()
+        .context("running executable has no parent directory")?;
+    let tmp_path = dir.join(".pup-upgrade.tmp");
+
+    let mut tmp_file = std::fs::File::create(&tmp_path)
+        .with_context(|| format!("failed to create temp file: {}", tmp_path.display()))?;
+    tmp_file
+        .write_all(bytes)
+        .with_context(|| format!("failed to write temp file: {}", tmp_path.display()))?;
+    tmp_file
+        .sync_all()
+        .with_context(|| format!("failed to fsync temp file: {}", tmp_path.display()))?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&tmp_path, std::fs::Permissions::from_mode(0o755))
+            .with_context(|| format!("failed to mark {} executable", tmp_path.display()))?;
+    }
+
+    std::fs::rename(&tmp_path, &current_exe)
+        .with_context(|| format!("failed to replace {}", current_exe.display()))?;
+    Ok(())
+}
+
+/// The platform asset name released for this build, following the same
+/// `TARGETOS`/`TARGETARCH` naming the release pipeline injects at build
+/// time (e.g. `pup-v1.4.0-linux-amd64`).
+fn platform_asset_name(tag: &str) -> String {
+    let os = match std::env::consts::OS {
+        "macos" => "darwin",
+        other => other,
+    };
+    let arch = match std::env::consts::ARCH {
+        "x86_64" => "amd64",
+        "aarch64" => "arm64",
+        other => other,
+    };
+    let ext = if os == "windows" { ".zip" } else { ".tar.gz" };
+    format!("pup-{tag}-{os}-{arch}{ext}")
+}
+
+/// Compare two `major.minor.patch`-ish version strings numerically,
+/// component by component, the same loose semver check `version::VERSION`
+/// is expected to satisfy. Missing trailing components compare as `0`.
+fn compare_versions(a: &str, b: &str) -> std::cmp::Ordering {
+    let parse = |v: &str| -> Vec<u64> {
+        v.trim_start_matches('v')
+            .split(|c| c == '.' || c == '-' || c == '+')
+            .map(|p| p.parse().unwrap_or(0))
+            .collect()
+    };
+    let (pa, pb) = (parse(a), parse(b));
+    for i in 0..pa.len().max(pb.len()) {
+        let (xa, xb) = (pa.get(i).copied().unwrap_or(0), pb.get(i).copied().unwrap_or(0));
+        match xa.cmp(&xb) {
+            std::cmp::Ordering::Equal => continue,
+            other => return other,
+        }
+    }
+    std::cmp::Ordering::Equal
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compare_versions_newer() {
+        assert_eq!(compare_versions("1.5.0", "1.4.0"), std::cmp::Ordering::Greater);
+        assert_eq!(compare_versions("2.0.0", "1.99.99"), std::cmp::Ordering::Greater);
+    }
+
+    #[test]
+    fn test_compare_versions_equal_with_v_prefix() {
+        assert_eq!(compare_versions("v1.4.0", "1.4.0"), std::cmp::Ordering::Equal);
+    }
+
+    #[test]
+    fn test_compare_versions_older() {
+        assert_eq!(compare_versions("1.4.0", "1.4.1"), std::cmp::Ordering::Less);
+    }
+
+    #[test]
+    fn test_platform_asset_name_shape() {
+        let name = platform_asset_name("v1.4.0");
+        assert!(name.starts_with("pup-v1.4.0-"));
+    }
+
+    #[test]
+    fn test_extract_tar_gz_returns_file_contents() {
+        use std::io::Write;
+
+        let mut tar_bytes = Vec::new();
+        {
+            let mut builder = tar::Builder::new(&mut tar_bytes);
+            let data = b"fake pup binary";
+            let mut header = tar::Header::new_gnu();
+            header.set_size(data.len() as u64);
+            header.set_mode(0o755);
+            header.set_cksum();
+            builder.append_data(&mut header, "pup", &data[..]).unwrap();
+            builder.finish().unwrap();
+        }
+
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(&tar_bytes).unwrap();
+        let archive = encoder.finish().unwrap();
+
+        let extracted = extract_tar_gz(&archive).unwrap();
+        assert_eq!(extracted, b"fake pup binary");
+    }
+
+    #[test]
+    fn test_extract_zip_returns_file_contents() {
+        use std::io::Write;
+
+        let mut buf = Vec::new();
+        {
+            let mut writer = zip::ZipWriter::new(std::io::Cursor::new(&mut buf));
+            writer.start_file("pup.exe", zip::write::FileOptions::default()).unwrap();
+            writer.write_all(b"fake pup binary").unwrap();
+            writer.finish().unwrap();
+        }
+
+        let extracted = extract_zip(&buf).unwrap();
+        assert_eq!(extracted, b"fake pup binary");
+    }
+}
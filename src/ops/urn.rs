@@ -0,0 +1,129 @@
+//! A single addressable identity scheme across every product namespace:
+//! `dd:<service>:<type>/<id>[/<id>...]`, e.g.
+//! `dd:status-pages:component/abc123/def456` or
+//! `dd:fleet:deployment/dep-789`. One or more slash-separated id segments
+//! cover resources addressed by a compound key (a component nested under a
+//! page, say), while a single segment covers the common case of one id.
+//!
+//! `service` and `type` may be `*` to mean "every value in that position" —
+//! reserved for list dispatch; `Commands::Get`/`Commands::Delete` require
+//! both to be concrete, since they resolve to one object.
+//!
+//! This gives users one copy-pasteable token instead of memorizing which
+//! flag shape (`page_id` + `component_id`, `deployment_id`, ...) a given
+//! resource type expects.
+
+use anyhow::{bail, Result};
+use std::fmt;
+
+pub const SCHEME: &str = "dd";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Urn {
+    pub service: String,
+    pub resource_type: String,
+    pub ids: Vec<String>,
+}
+
+impl Urn {
+    pub fn is_wildcard(&self) -> bool {
+        self.service == "*" || self.resource_type == "*"
+    }
+}
+
+impl fmt::Display for Urn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{SCHEME}:{}:{}", self.service, self.resource_type)?;
+        for id in &self.ids {
+            write!(f, "/{id}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Parse `dd:<service>:<type>/<id>[/<id>...]`. Every component must be
+/// non-empty; `service`/`resource_type` of `*` are accepted syntactically
+/// (list dispatch validates that separately) but an id segment never is.
+pub fn parse(s: &str) -> Result<Urn> {
+    let Some(rest) = s.strip_prefix(&format!("{SCHEME}:")) else {
+        bail!("urn {s:?} does not start with the {SCHEME:?} scheme (expected {SCHEME}:<service>:<type>/<id>)");
+    };
+
+    let (service, rest) = rest
+        .split_once(':')
+        .with_context_msg(|| format!("urn {s:?} is missing a <type> segment after the service"))?;
+
+    let mut segments = rest.split('/');
+    let resource_type = segments
+        .next()
+        .filter(|t| !t.is_empty())
+        .with_context_msg(|| format!("urn {s:?} is missing a resource type"))?;
+
+    let ids: Vec<String> = segments.map(str::to_string).collect();
+    if service.is_empty() {
+        bail!("urn {s:?} has an empty service segment");
+    }
+    if ids.iter().any(|id| id.is_empty()) {
+        bail!("urn {s:?} has an empty id segment");
+    }
+
+    Ok(Urn {
+        service: service.to_string(),
+        resource_type: resource_type.to_string(),
+        ids,
+    })
+}
+
+/// `Option::ok_or_else`/`with_context`, spelled for `&str` so `parse` above
+/// reads as one sentence per segment instead of a `match` per `Option`.
+trait WithContextMsg<T> {
+    fn with_context_msg(self, f: impl FnOnce() -> String) -> Result<T>;
+}
+
+impl<T> WithContextMsg<T> for Option<T> {
+    fn with_context_msg(self, f: impl FnOnce() -> String) -> Result<T> {
+        self.ok_or_else(|| anyhow::anyhow!(f()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_single_id() {
+        let urn = parse("dd:fleet:deployment/dep-789").unwrap();
+        assert_eq!(urn.service, "fleet");
+        assert_eq!(urn.resource_type, "deployment");
+        assert_eq!(urn.ids, vec!["dep-789"]);
+    }
+
+    #[test]
+    fn test_parse_compound_id() {
+        let urn = parse("dd:status-pages:component/abc123/def456").unwrap();
+        assert_eq!(urn.ids, vec!["abc123", "def456"]);
+    }
+
+    #[test]
+    fn test_parse_wildcard_service_and_type() {
+        let urn = parse("dd:*:*").unwrap();
+        assert!(urn.is_wildcard());
+        assert!(urn.ids.is_empty());
+    }
+
+    #[test]
+    fn test_parse_rejects_wrong_scheme() {
+        assert!(parse("aws:fleet:deployment/dep-789").is_err());
+    }
+
+    #[test]
+    fn test_parse_rejects_missing_type() {
+        assert!(parse("dd:fleet").is_err());
+    }
+
+    #[test]
+    fn test_display_round_trips() {
+        let urn = parse("dd:fleet:deployment/dep-789").unwrap();
+        assert_eq!(urn.to_string(), "dd:fleet:deployment/dep-789");
+    }
+}
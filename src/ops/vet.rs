@@ -1,17 +1,19 @@
 use anyhow::Result;
 use datadog_api_client::datadogV1::api_monitors::{ListMonitorsOptionalParams, MonitorsAPI};
 use datadog_api_client::datadogV1::model::{Monitor, MonitorOverallStates};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use crate::api;
 use crate::client;
 use crate::config::Config;
+use crate::ops::vet_offline;
+use crate::ops::vet_rules::{self, CustomRule};
 
 // ---- Output types ----
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum Severity {
     Critical,
@@ -29,7 +31,7 @@ impl Severity {
     }
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Resource {
     pub id: i64,
     pub name: String,
@@ -52,6 +54,12 @@ pub struct VetResult {
     pub critical: usize,
     pub warnings: usize,
     pub infos: usize,
+    /// Total alert-triggered events tallied by `fetch_alert_events`, 0 when
+    /// neither `pager-burden` nor `flapping-monitors` ran this pass. Exists
+    /// for `ops::vet_prometheus`'s `pup_vet_pages_total` gauge, which needs
+    /// the raw event count rather than `pager-burden`'s per-monitor finding
+    /// count.
+    pub pages_total: u64,
 }
 
 // ---- Check names ----
@@ -63,9 +71,17 @@ const CHECK_UNTAGGED: &str = "untagged-monitors";
 const CHECK_NO_RECOVERY: &str = "no-recovery-threshold";
 const CHECK_FAST_RENOTIFY: &str = "fast-renotify-interval";
 const CHECK_PAGER_BURDEN: &str = "pager-burden";
+const CHECK_FLAPPING: &str = "flapping-monitors";
 
 const PAGER_LOOKBACK_DAYS: u32 = 30;
 
+/// Below this trigger-to-recovery or recovery-to-retrigger gap, an
+/// alert<->recovery transition counts as a "flap cycle" in `check_flapping`.
+const FLAP_CYCLE_THRESHOLD_SECS: i64 = 10 * 60;
+/// Minimum number of short flap cycles within `PAGER_LOOKBACK_DAYS` before a
+/// monitor is flagged.
+const FLAP_MIN_CYCLES: usize = 5;
+
 const ALL_CHECKS: &[&str] = &[
     CHECK_SILENT,
     CHECK_STALE,
@@ -74,6 +90,7 @@ const ALL_CHECKS: &[&str] = &[
     CHECK_NO_RECOVERY,
     CHECK_FAST_RENOTIFY,
     CHECK_PAGER_BURDEN,
+    CHECK_FLAPPING,
 ];
 
 // ---- Notification handle helpers ----
@@ -329,17 +346,25 @@ fn check_fast_renotify_interval(monitors: &[Monitor]) -> Finding {
     }
 }
 
-/// Fetch monitor alert-triggered event counts over the last `days` days.
-/// Returns monitor_id → trigger count. Silently returns empty map on API failure
-/// (missing permissions, etc.) so the check degrades gracefully.
+/// One alert-triggered (`error`/`warning`) or recovery (`success`) event for
+/// a monitor, timestamped so `check_flapping` can reconstruct oscillation
+/// cycles. `fetch_alert_events`/`vet_offline::load_alert_events_jsonl`
+/// populate this from `/api/v1/events`'s raw JSON.
+#[derive(Debug, Clone, Copy)]
+pub struct AlertEvent {
+    pub timestamp: i64,
+    pub is_recovery: bool,
+}
+
+pub type AlertEvents = HashMap<i64, Vec<AlertEvent>>;
+
+/// Fetch monitor alert-triggered and recovery events over the last `days`
+/// days, keyed by monitor_id. Silently returns an empty map on API failure
+/// (missing permissions, etc.) so checks built on it degrade gracefully.
 ///
-/// Note: the typed Event model omits `monitor_id` (it lands in additional_properties),
-/// so we use the raw JSON API path here.
-async fn fetch_alert_event_counts(
-    cfg: &Config,
-    tags: Option<&str>,
-    days: u32,
-) -> HashMap<i64, u32> {
+/// Note: the typed Event model omits `monitor_id` (it lands in
+/// additional_properties), so we use the raw JSON API path here.
+async fn fetch_alert_events(cfg: &Config, tags: Option<&str>, days: u32) -> AlertEvents {
     let now = SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .map(|d| d.as_secs() as i64)
@@ -357,22 +382,37 @@ async fn fetch_alert_event_counts(
         Err(_) => return HashMap::new(),
     };
 
-    let mut counts: HashMap<i64, u32> = HashMap::new();
+    let mut events_by_monitor: AlertEvents = HashMap::new();
     if let Some(events) = data["events"].as_array() {
         for event in events {
-            // Count alert-triggered events only; skip recoveries ("success") and info.
+            // Keep alert-triggered and recovery events only; skip info etc.
             let alert_type = event["alert_type"].as_str().unwrap_or("");
-            if alert_type != "error" && alert_type != "warning" {
-                continue;
-            }
+            let is_recovery = match alert_type {
+                "error" | "warning" => false,
+                "success" => true,
+                _ => continue,
+            };
             // monitor_id is not in the typed model — present in the raw JSON payload.
-            if let Some(monitor_id) = event["monitor_id"].as_i64() {
-                *counts.entry(monitor_id).or_insert(0) += 1;
-            }
+            let Some(monitor_id) = event["monitor_id"].as_i64() else { continue };
+            let timestamp = event["date_happened"].as_i64().unwrap_or(0);
+            events_by_monitor
+                .entry(monitor_id)
+                .or_default()
+                .push(AlertEvent { timestamp, is_recovery });
         }
     }
 
-    counts
+    events_by_monitor
+}
+
+/// Per-monitor count of alert-triggered (non-recovery) events — the shape
+/// `check_pager_burden` and `pages_total` have always worked with, derived
+/// from the richer `AlertEvents` map `fetch_alert_events` now returns.
+fn alert_trigger_counts(events: &AlertEvents) -> HashMap<i64, u32> {
+    events
+        .iter()
+        .map(|(&id, evs)| (id, evs.iter().filter(|e| !e.is_recovery).count() as u32))
+        .collect()
 }
 
 /// Build a deduplicated pager-tool description string for a set of handles.
@@ -468,75 +508,102 @@ fn check_pager_burden(monitors: &[Monitor], alert_counts: &HashMap<i64, u32>) ->
     }
 }
 
-// ---- Entry point ----
+/// Scan a monitor's event timeline (not assumed sorted) for alert<->recovery
+/// transitions, and return the count of "short" cycles (under
+/// `FLAP_CYCLE_THRESHOLD_SECS`) plus their median duration in seconds, if
+/// that count reaches `FLAP_MIN_CYCLES`.
+fn flap_summary(timeline: &[AlertEvent]) -> Option<(usize, i64)> {
+    let mut sorted = timeline.to_vec();
+    sorted.sort_by_key(|e| e.timestamp);
+
+    let mut short_cycles: Vec<i64> = sorted
+        .windows(2)
+        .filter(|w| w[0].is_recovery != w[1].is_recovery)
+        .map(|w| w[1].timestamp - w[0].timestamp)
+        .filter(|&gap| (0..FLAP_CYCLE_THRESHOLD_SECS).contains(&gap))
+        .collect();
 
-pub async fn run(
-    cfg: &Config,
-    tags: Option<String>,
-    check: Option<String>,
-    severity_filter: Option<String>,
-) -> Result<VetResult> {
-    let checks_to_run: Vec<&str> = match &check {
-        Some(c) => {
-            if !ALL_CHECKS.contains(&c.as_str()) {
-                anyhow::bail!(
-                    "unknown check '{}'. Available: {}",
-                    c,
-                    ALL_CHECKS.join(", ")
-                );
-            }
-            vec![c.as_str()]
-        }
-        None => ALL_CHECKS.to_vec(),
-    };
+    if short_cycles.len() < FLAP_MIN_CYCLES {
+        return None;
+    }
 
-    // Fetch monitors (single API call shared by all checks)
-    let dd_cfg = client::make_dd_config(cfg);
-    let api = if let Some(http_client) = client::make_bearer_client(cfg) {
-        MonitorsAPI::with_client_and_config(dd_cfg, http_client)
-    } else {
-        MonitorsAPI::with_config(dd_cfg)
-    };
+    short_cycles.sort_unstable();
+    let median_secs = short_cycles[short_cycles.len() / 2];
+    Some((short_cycles.len(), median_secs))
+}
 
-    let mut params = ListMonitorsOptionalParams::default()
-        .page_size(1000)
-        .page(0);
-    if let Some(ref t) = tags {
-        params = params.monitor_tags(t.clone());
-    }
+/// Monitors oscillating alert -> recovery -> alert faster than
+/// `FLAP_CYCLE_THRESHOLD_SECS`, at least `FLAP_MIN_CYCLES` times within
+/// `PAGER_LOOKBACK_DAYS`. Complements the purely static
+/// `check_no_recovery_threshold` by catching monitors that flap *despite*
+/// configuration, using the same Events API history `check_pager_burden` does.
+fn check_flapping(monitors: &[Monitor], events: &AlertEvents) -> Finding {
+    let resources: Vec<Resource> = monitors
+        .iter()
+        .filter_map(|m| {
+            let id = m.id.unwrap_or(0);
+            let (cycle_count, median_secs) = flap_summary(events.get(&id)?)?;
 
-    let monitors = api
-        .list_monitors(params)
-        .await
-        .map_err(|e| anyhow::anyhow!("failed to list monitors: {:?}", e))?;
+            Some(Resource {
+                id,
+                name: m.name.as_deref().unwrap_or("(unnamed)").to_string(),
+                detail: format!(
+                    "{cycle_count} flap cycles ({PAGER_LOOKBACK_DAYS}d), median {} min",
+                    median_secs / 60
+                ),
+            })
+        })
+        .collect();
 
-    // Fetch paging history only when the pager-burden check is actually running.
-    // Silently degrades if the Events API is unavailable (missing perms, etc.).
-    let alert_counts = if checks_to_run.contains(&CHECK_PAGER_BURDEN) {
-        fetch_alert_event_counts(cfg, tags.as_deref(), PAGER_LOOKBACK_DAYS).await
-    } else {
-        HashMap::new()
-    };
+    Finding {
+        check: CHECK_FLAPPING,
+        severity: Severity::Warning,
+        count: resources.len(),
+        resources,
+        recommendation:
+            "Add a critical_recovery threshold or widen the evaluation window — this monitor is flapping despite configuration",
+    }
+}
 
-    let min_severity: Option<Severity> = severity_filter.as_deref().map(|s| match s {
-        "critical" => Severity::Critical,
-        "warning" => Severity::Warning,
-        _ => Severity::Info,
-    });
+// ---- Entry point ----
 
+/// Evaluate every requested check (built-in or custom-rule) against an
+/// already-fetched monitor set, and bucket the results into `findings`
+/// (filtered by `min_severity`) vs `passed`. Split out of `run` so the
+/// offline `--from` path can reuse it against a JSONL snapshot instead of a
+/// live API call.
+fn run_checks(
+    monitors: &[Monitor],
+    alert_events: &AlertEvents,
+    checks_to_run: &[&str],
+    custom_rules: &[CustomRule],
+    min_severity: Option<Severity>,
+) -> Result<VetResult> {
     let mut findings: Vec<Finding> = Vec::new();
     let mut passed: Vec<&'static str> = Vec::new();
 
-    for &name in &checks_to_run {
+    for &name in checks_to_run {
         let finding = match name {
-            CHECK_SILENT => check_silent_monitors(&monitors),
-            CHECK_STALE => check_stale_monitors(&monitors),
-            CHECK_MUTED => check_muted_forgotten(&monitors),
-            CHECK_UNTAGGED => check_untagged_monitors(&monitors),
-            CHECK_NO_RECOVERY => check_no_recovery_threshold(&monitors),
-            CHECK_FAST_RENOTIFY => check_fast_renotify_interval(&monitors),
-            CHECK_PAGER_BURDEN => check_pager_burden(&monitors, &alert_counts),
-            _ => unreachable!(),
+            CHECK_SILENT => check_silent_monitors(monitors),
+            CHECK_STALE => check_stale_monitors(monitors),
+            CHECK_MUTED => check_muted_forgotten(monitors),
+            CHECK_UNTAGGED => check_untagged_monitors(monitors),
+            CHECK_NO_RECOVERY => check_no_recovery_threshold(monitors),
+            CHECK_FAST_RENOTIFY => check_fast_renotify_interval(monitors),
+            CHECK_PAGER_BURDEN => check_pager_burden(monitors, &alert_trigger_counts(alert_events)),
+            CHECK_FLAPPING => check_flapping(monitors, alert_events),
+            _ => {
+                let rule = custom_rules
+                    .iter()
+                    .find(|r| r.name() == name)
+                    .unwrap_or_else(|| unreachable!("checks_to_run only names built-ins or loaded custom rules"));
+                vet_rules::eval_rule(
+                    rule,
+                    monitors,
+                    |m| m.id.unwrap_or(0),
+                    |m| m.name.as_deref().unwrap_or("(unnamed)").to_string(),
+                )?
+            }
         };
 
         if finding.count == 0 {
@@ -571,15 +638,109 @@ pub async fn run(
         .filter(|f| f.severity == Severity::Info)
         .count();
 
+    let pages_total: u64 = alert_trigger_counts(alert_events).values().map(|&n| n as u64).sum();
+
     Ok(VetResult {
         findings,
         passed,
         critical,
         warnings,
         infos,
+        pages_total,
     })
 }
 
+#[allow(clippy::too_many_arguments)]
+pub async fn run(
+    cfg: &Config,
+    tags: Option<String>,
+    check: Option<String>,
+    severity_filter: Option<String>,
+    rules_path: Option<String>,
+    dump_path: Option<String>,
+    from_path: Option<String>,
+    events_path: Option<String>,
+) -> Result<VetResult> {
+    let custom_rules = match rules_path.as_deref().or(cfg.vet_rules_path.as_deref()) {
+        Some(path) => vet_rules::load_rules(path)?,
+        None => Vec::new(),
+    };
+    let custom_names = vet_rules::rule_names(&custom_rules);
+
+    let checks_to_run: Vec<&str> = match &check {
+        Some(c) => {
+            if !ALL_CHECKS.contains(&c.as_str()) && !custom_names.contains(&c.as_str()) {
+                anyhow::bail!(
+                    "unknown check '{}'. Available: {}",
+                    c,
+                    ALL_CHECKS
+                        .iter()
+                        .chain(custom_names.iter())
+                        .copied()
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                );
+            }
+            vec![c.as_str()]
+        }
+        None => ALL_CHECKS.iter().chain(custom_names.iter()).copied().collect(),
+    };
+
+    // `--from <file>` bypasses the API entirely: monitors (and optionally a
+    // sibling events JSONL) come from disk, the same checks run unchanged.
+    let (monitors, alert_events) = if let Some(from) = &from_path {
+        let monitors = vet_offline::load_monitors_jsonl(from)?;
+        let alert_events = match &events_path {
+            Some(events) => vet_offline::load_alert_events_jsonl(events)?,
+            None => HashMap::new(),
+        };
+        (monitors, alert_events)
+    } else {
+        // Fetch monitors (single API call shared by all checks)
+        let dd_cfg = client::make_dd_config(cfg);
+        let api = if let Some(http_client) = client::make_bearer_client(cfg) {
+            MonitorsAPI::with_client_and_config(dd_cfg, http_client)
+        } else {
+            MonitorsAPI::with_config(dd_cfg)
+        };
+
+        let mut params = ListMonitorsOptionalParams::default()
+            .page_size(1000)
+            .page(0);
+        if let Some(ref t) = tags {
+            params = params.monitor_tags(t.clone());
+        }
+
+        let monitors = api
+            .list_monitors(params)
+            .await
+            .map_err(|e| anyhow::anyhow!("failed to list monitors: {:?}", e))?;
+
+        if let Some(dump) = &dump_path {
+            vet_offline::dump_monitors(&monitors, dump)?;
+        }
+
+        // Fetch paging history only when pager-burden or flapping-monitors is
+        // actually running. Silently degrades if the Events API is
+        // unavailable (missing perms, etc.).
+        let alert_events = if checks_to_run.contains(&CHECK_PAGER_BURDEN) || checks_to_run.contains(&CHECK_FLAPPING) {
+            fetch_alert_events(cfg, tags.as_deref(), PAGER_LOOKBACK_DAYS).await
+        } else {
+            HashMap::new()
+        };
+
+        (monitors, alert_events)
+    };
+
+    let min_severity: Option<Severity> = severity_filter.as_deref().map(|s| match s {
+        "critical" => Severity::Critical,
+        "warning" => Severity::Warning,
+        _ => Severity::Info,
+    });
+
+    run_checks(&monitors, &alert_events, &checks_to_run, &custom_rules, min_severity)
+}
+
 /// List all available checks with descriptions.
 pub fn list_checks() -> Vec<(&'static str, Severity, &'static str)> {
     vec![
@@ -618,6 +779,11 @@ pub fn list_checks() -> Vec<(&'static str, Severity, &'static str)> {
             Severity::Warning,
             "Top paging monitors by alert history (30d) — DD On-Call, PagerDuty, OpsGenie, VictorOps",
         ),
+        (
+            CHECK_FLAPPING,
+            Severity::Warning,
+            "Monitors oscillating alert/recovery too fast (30d) — flapping despite configuration",
+        ),
     ]
 }
 
@@ -667,4 +833,42 @@ mod tests {
         assert_eq!(classify_handle("victorops-x").display(), "VictorOps");
         assert_eq!(classify_handle("slack-x").display(), "other");
     }
+
+    fn event(timestamp: i64, is_recovery: bool) -> AlertEvent {
+        AlertEvent { timestamp, is_recovery }
+    }
+
+    #[test]
+    fn flap_summary_flags_repeated_short_cycles() {
+        // 6 alert<->recovery transitions, each 4 minutes apart.
+        let timeline: Vec<AlertEvent> = (0..7)
+            .map(|i| event(i * 240, i % 2 == 1))
+            .collect();
+        let (count, median_secs) = flap_summary(&timeline).expect("should flag flapping");
+        assert_eq!(count, 6);
+        assert_eq!(median_secs, 240);
+    }
+
+    #[test]
+    fn flap_summary_ignores_slow_cycles() {
+        // Alert<->recovery transitions an hour apart — not flapping.
+        let timeline: Vec<AlertEvent> = (0..7)
+            .map(|i| event(i * 3600, i % 2 == 1))
+            .collect();
+        assert!(flap_summary(&timeline).is_none());
+    }
+
+    #[test]
+    fn flap_summary_requires_minimum_cycle_count() {
+        // Only 2 short cycles — below FLAP_MIN_CYCLES.
+        let timeline = vec![event(0, false), event(120, true), event(240, false)];
+        assert!(flap_summary(&timeline).is_none());
+    }
+
+    #[test]
+    fn flap_summary_handles_unsorted_input() {
+        let timeline = (0..7).map(|i| event(i * 240, i % 2 == 1)).rev().collect::<Vec<_>>();
+        let (count, _) = flap_summary(&timeline).expect("should flag flapping regardless of input order");
+        assert_eq!(count, 6);
+    }
 }
@@ -0,0 +1,268 @@
+//! Push `vet` findings to an external alerting target (the Datadog Events
+//! API, reachable through `api::post` like everything else in this crate, or
+//! PagerDuty Events v2) so a scheduled `pup vet --notify` becomes a
+//! recurring config-drift alarm instead of a one-shot report.
+//!
+//! The critical part is deduplication: each (check, resource) pair gets a
+//! stable `dedup_key` derived from a `DefaultHasher` of `"{check}:{id}"`, so
+//! repeated runs update the same incident rather than spamming new ones, and
+//! a resource that stops matching (or disappears) triggers a "resolve" with
+//! that same key. Telling trigger from resolve requires knowing what the
+//! *previous* run emitted, so the emitted-key set is persisted to a small
+//! state file keyed by Datadog site/org — the same `config::config_dir()`
+//! location `ops::credentials`'s profile store uses.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashSet;
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::api;
+use crate::config::{self, Config};
+use crate::ops::vet::{Severity, VetResult};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotifyTarget {
+    DatadogEvents,
+    PagerDuty,
+}
+
+impl NotifyTarget {
+    pub fn parse(s: &str) -> Result<NotifyTarget> {
+        match s {
+            "datadog" | "datadog-events" => Ok(NotifyTarget::DatadogEvents),
+            "pagerduty" => Ok(NotifyTarget::PagerDuty),
+            other => anyhow::bail!("unknown --notify target '{other}' (expected 'datadog' or 'pagerduty')"),
+        }
+    }
+}
+
+/// One (check, resource) pairing the previous run emitted a "trigger" for —
+/// enough to emit a matching "resolve" once the resource stops appearing,
+/// without re-deriving the hash (which would require re-running the check).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct EmittedKey {
+    dedup_key: String,
+    check: String,
+    resource_id: i64,
+    resource_name: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct NotifyState {
+    #[serde(default)]
+    emitted: Vec<EmittedKey>,
+}
+
+fn state_path(cfg: &Config) -> Result<PathBuf> {
+    let dir = config::config_dir().context("could not determine config directory")?;
+    let org_key = cfg.site.replace(['.', ':', '/'], "_");
+    Ok(dir.join(format!("vet-notify-{org_key}.json")))
+}
+
+fn load_state(cfg: &Config) -> Result<NotifyState> {
+    let path = state_path(cfg)?;
+    match std::fs::read_to_string(&path) {
+        Ok(contents) => Ok(serde_json::from_str(&contents).unwrap_or_default()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(NotifyState::default()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn save_state(cfg: &Config, state: &NotifyState) -> Result<()> {
+    let path = state_path(cfg)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&path, serde_json::to_string_pretty(state)?)?;
+    Ok(())
+}
+
+fn dedup_key(check: &str, resource_id: i64) -> String {
+    let mut hasher = DefaultHasher::new();
+    format!("{check}:{resource_id}").hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+fn datadog_alert_type(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Critical => "error",
+        Severity::Warning => "warning",
+        Severity::Info => "info",
+    }
+}
+
+fn pagerduty_severity(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Critical => "critical",
+        Severity::Warning => "warning",
+        Severity::Info => "info",
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn send_datadog_trigger(
+    cfg: &Config,
+    check: &str,
+    resource_name: &str,
+    resource_id: i64,
+    detail: &str,
+    recommendation: &str,
+    severity: Severity,
+    dedup_key: &str,
+) -> Result<()> {
+    let body = serde_json::json!({
+        "title": format!("pup vet: {check}"),
+        "text": format!("{detail}\n\nRecommendation: {recommendation}"),
+        "alert_type": datadog_alert_type(severity),
+        "tags": [format!("check:{check}"), format!("resource_id:{resource_id}"), "source:pup-vet"],
+        "aggregation_key": dedup_key,
+    });
+    api::post(cfg, "/api/v1/events", &body)
+        .await
+        .with_context(|| format!("failed to send trigger event for {check}/{resource_name}"))?;
+    Ok(())
+}
+
+async fn send_datadog_resolve(cfg: &Config, check: &str, resource_name: &str, dedup_key: &str) -> Result<()> {
+    let body = serde_json::json!({
+        "title": format!("pup vet: {check} resolved"),
+        "text": format!("{resource_name} no longer matches this check."),
+        "alert_type": "success",
+        "tags": [format!("check:{check}"), "source:pup-vet"],
+        "aggregation_key": dedup_key,
+    });
+    api::post(cfg, "/api/v1/events", &body)
+        .await
+        .with_context(|| format!("failed to send resolve event for {check}/{resource_name}"))?;
+    Ok(())
+}
+
+async fn send_pagerduty_event(routing_key: &str, event_action: &str, dedup_key: &str, summary: &str, severity: Severity) -> Result<()> {
+    let payload = serde_json::json!({
+        "routing_key": routing_key,
+        "event_action": event_action,
+        "dedup_key": dedup_key,
+        "payload": {
+            "summary": summary,
+            "source": "pup vet",
+            "severity": pagerduty_severity(severity),
+        },
+    });
+    reqwest::Client::new()
+        .post("https://events.pagerduty.com/v2/enqueue")
+        .json(&payload)
+        .send()
+        .await
+        .context("failed to reach PagerDuty Events v2 API")?
+        .error_for_status()
+        .context("PagerDuty Events v2 API returned an error status")?;
+    Ok(())
+}
+
+#[derive(Debug, Serialize)]
+pub struct NotifySummary {
+    pub triggered: usize,
+    pub resolved: usize,
+}
+
+/// Emit a trigger for every finding's resources, then resolve any
+/// previously-emitted `dedup_key` that's no longer in this run's active set
+/// (the check passed, or the resource dropped out of it).
+pub async fn notify(cfg: &Config, result: &VetResult, target: NotifyTarget, pagerduty_routing_key: Option<&str>) -> Result<NotifySummary> {
+    let mut state = load_state(cfg)?;
+    let mut still_active: HashSet<String> = HashSet::new();
+    let mut new_emitted = Vec::new();
+    let mut triggered = 0;
+
+    for finding in &result.findings {
+        for resource in &finding.resources {
+            let key = dedup_key(finding.check, resource.id);
+            still_active.insert(key.clone());
+
+            match target {
+                NotifyTarget::DatadogEvents => {
+                    send_datadog_trigger(
+                        cfg,
+                        finding.check,
+                        &resource.name,
+                        resource.id,
+                        &resource.detail,
+                        finding.recommendation,
+                        finding.severity,
+                        &key,
+                    )
+                    .await?;
+                }
+                NotifyTarget::PagerDuty => {
+                    let routing_key = pagerduty_routing_key
+                        .context("--notify pagerduty requires a PagerDuty routing key")?;
+                    let summary = format!("{}: {} ({})", finding.check, resource.name, resource.detail);
+                    send_pagerduty_event(routing_key, "trigger", &key, &summary, finding.severity).await?;
+                }
+            }
+
+            triggered += 1;
+            new_emitted.push(EmittedKey {
+                dedup_key: key,
+                check: finding.check.to_string(),
+                resource_id: resource.id,
+                resource_name: resource.name.clone(),
+            });
+        }
+    }
+
+    let mut resolved = 0;
+    for prior in &state.emitted {
+        if still_active.contains(&prior.dedup_key) {
+            continue;
+        }
+        match target {
+            NotifyTarget::DatadogEvents => {
+                send_datadog_resolve(cfg, &prior.check, &prior.resource_name, &prior.dedup_key).await?;
+            }
+            NotifyTarget::PagerDuty => {
+                let routing_key = pagerduty_routing_key
+                    .context("--notify pagerduty requires a PagerDuty routing key")?;
+                let summary = format!("{} resolved", prior.check);
+                send_pagerduty_event(routing_key, "resolve", &prior.dedup_key, &summary, Severity::Info).await?;
+            }
+        }
+        resolved += 1;
+    }
+
+    state.emitted = new_emitted;
+    save_state(cfg, &state)?;
+
+    Ok(NotifySummary { triggered, resolved })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dedup_key_is_stable_for_same_check_and_resource() {
+        assert_eq!(dedup_key("silent-monitors", 42), dedup_key("silent-monitors", 42));
+    }
+
+    #[test]
+    fn test_dedup_key_differs_by_resource() {
+        assert_ne!(dedup_key("silent-monitors", 42), dedup_key("silent-monitors", 43));
+    }
+
+    #[test]
+    fn test_dedup_key_differs_by_check() {
+        assert_ne!(dedup_key("silent-monitors", 42), dedup_key("stale-monitors", 42));
+    }
+
+    #[test]
+    fn test_notify_target_parse() {
+        assert_eq!(NotifyTarget::parse("datadog").unwrap(), NotifyTarget::DatadogEvents);
+        assert_eq!(NotifyTarget::parse("pagerduty").unwrap(), NotifyTarget::PagerDuty);
+        assert!(NotifyTarget::parse("bogus").is_err());
+    }
+}
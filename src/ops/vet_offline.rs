@@ -0,0 +1,229 @@
+//! Offline snapshot + baseline-diff for `vet`. `dump_monitors`/`load_monitors_jsonl`
+//! back `pup vet --dump <file>` / `--from <file>`: a live `list_monitors` call is
+//! slow for large orgs and impossible to reproduce in CI, so a frozen
+//! newline-delimited JSON snapshot lets the same check fns run offline
+//! unchanged. `diff_against_baseline` backs `pup vet --baseline <old.json>`,
+//! turning a one-shot report into a regression gate: CI can fail only when a
+//! run introduces new critical findings relative to a committed baseline,
+//! instead of failing on pre-existing debt.
+
+use std::collections::{HashMap, HashSet};
+use std::io::{BufRead, Write};
+
+use anyhow::{Context, Result};
+use datadog_api_client::datadogV1::model::Monitor;
+use serde::{Deserialize, Serialize};
+
+use crate::ops::vet::{AlertEvent, AlertEvents, Resource, Severity, VetResult};
+
+/// Write one JSON `Monitor` per line, so a large monitor list can be
+/// streamed rather than held as one giant JSON array.
+pub fn dump_monitors(monitors: &[Monitor], path: &str) -> Result<()> {
+    let file = std::fs::File::create(path).with_context(|| format!("failed to create {path}"))?;
+    let mut writer = std::io::BufWriter::new(file);
+    for monitor in monitors {
+        serde_json::to_writer(&mut writer, monitor)
+            .with_context(|| format!("failed to write a monitor to {path}"))?;
+        writer.write_all(b"\n")?;
+    }
+    Ok(())
+}
+
+/// Read a `--dump`-produced file back into `Vec<Monitor>`, skipping blank lines.
+pub fn load_monitors_jsonl(path: &str) -> Result<Vec<Monitor>> {
+    let file = std::fs::File::open(path).with_context(|| format!("failed to open {path}"))?;
+    std::io::BufReader::new(file)
+        .lines()
+        .filter(|line| !matches!(line, Ok(s) if s.trim().is_empty()))
+        .map(|line| {
+            let line = line.with_context(|| format!("failed to read a line of {path}"))?;
+            serde_json::from_str(&line).with_context(|| format!("failed to parse a monitor from {path}"))
+        })
+        .collect()
+}
+
+/// Load an events JSONL sibling file for offline `check_pager_burden` /
+/// `check_flapping`: one raw Events-API JSON object per line, collected the
+/// same way `fetch_alert_events` collects the live `/api/v1/events` response.
+pub fn load_alert_events_jsonl(path: &str) -> Result<AlertEvents> {
+    let file = std::fs::File::open(path).with_context(|| format!("failed to open {path}"))?;
+    let mut events_by_monitor: AlertEvents = HashMap::new();
+    for line in std::io::BufReader::new(file).lines() {
+        let line = line.with_context(|| format!("failed to read a line of {path}"))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let event: serde_json::Value =
+            serde_json::from_str(&line).with_context(|| format!("failed to parse an event from {path}"))?;
+        let alert_type = event["alert_type"].as_str().unwrap_or("");
+        let is_recovery = match alert_type {
+            "error" | "warning" => false,
+            "success" => true,
+            _ => continue,
+        };
+        let Some(monitor_id) = event["monitor_id"].as_i64() else { continue };
+        let timestamp = event["date_happened"].as_i64().unwrap_or(0);
+        events_by_monitor
+            .entry(monitor_id)
+            .or_default()
+            .push(AlertEvent { timestamp, is_recovery });
+    }
+    Ok(events_by_monitor)
+}
+
+// ---- Baseline diff ----
+
+/// A previously serialized finding, decoupled from the live [`Finding`]
+/// type (whose `check`/`recommendation` are `&'static str` and so can't be
+/// deserialized generically) — just enough of its shape to diff against.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BaselineFinding {
+    pub check: String,
+    pub severity: Severity,
+    pub resources: Vec<Resource>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BaselineSnapshot {
+    pub findings: Vec<BaselineFinding>,
+}
+
+/// Load a file previously written by `pup vet --format json`.
+pub fn load_baseline(path: &str) -> Result<BaselineSnapshot> {
+    let raw = std::fs::read_to_string(path).with_context(|| format!("failed to read baseline {path}"))?;
+    serde_json::from_str(&raw)
+        .with_context(|| format!("failed to parse baseline {path} (expected a prior `pup vet --format json` result)"))
+}
+
+#[derive(Debug, Serialize)]
+pub struct CheckDiff {
+    pub check: String,
+    pub newly_introduced: Vec<Resource>,
+    pub resolved: Vec<Resource>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BaselineDiff {
+    pub checks: Vec<CheckDiff>,
+    pub new_critical_count: usize,
+}
+
+/// Per check, diff the baseline's resource ids against the current run's:
+/// ids only in `current` are newly introduced, ids only in `baseline` are
+/// resolved. A check absent from one side is treated as an empty resource set.
+pub fn diff_against_baseline(baseline: &BaselineSnapshot, current: &VetResult) -> BaselineDiff {
+    let baseline_by_check: HashMap<&str, &BaselineFinding> =
+        baseline.findings.iter().map(|f| (f.check.as_str(), f)).collect();
+    let current_by_check: HashMap<&str, &crate::ops::vet::Finding> =
+        current.findings.iter().map(|f| (f.check, f)).collect();
+
+    let mut names: Vec<&str> = baseline_by_check.keys().chain(current_by_check.keys()).copied().collect();
+    names.sort_unstable();
+    names.dedup();
+
+    let mut checks = Vec::new();
+    let mut new_critical_count = 0;
+
+    for check in names {
+        let baseline_resources: &[Resource] =
+            baseline_by_check.get(check).map(|f| f.resources.as_slice()).unwrap_or(&[]);
+        let baseline_ids: HashSet<i64> = baseline_resources.iter().map(|r| r.id).collect();
+
+        let current_finding = current_by_check.get(check).copied();
+        let current_resources: &[Resource] = current_finding.map(|f| f.resources.as_slice()).unwrap_or(&[]);
+        let current_ids: HashSet<i64> = current_resources.iter().map(|r| r.id).collect();
+
+        let newly_introduced: Vec<Resource> = current_resources
+            .iter()
+            .filter(|r| !baseline_ids.contains(&r.id))
+            .cloned()
+            .collect();
+        let resolved: Vec<Resource> = baseline_resources
+            .iter()
+            .filter(|r| !current_ids.contains(&r.id))
+            .cloned()
+            .collect();
+
+        if newly_introduced.is_empty() && resolved.is_empty() {
+            continue;
+        }
+
+        if matches!(current_finding, Some(f) if f.severity == Severity::Critical) {
+            new_critical_count += newly_introduced.len();
+        }
+
+        checks.push(CheckDiff { check: check.to_string(), newly_introduced, resolved });
+    }
+
+    BaselineDiff { checks, new_critical_count }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ops::vet::Finding;
+
+    fn resource(id: i64) -> Resource {
+        Resource { id, name: format!("monitor-{id}"), detail: "detail".to_string() }
+    }
+
+    #[test]
+    fn test_diff_reports_newly_introduced_and_resolved() {
+        let baseline = BaselineSnapshot {
+            findings: vec![BaselineFinding {
+                check: "silent-monitors".to_string(),
+                severity: Severity::Critical,
+                resources: vec![resource(1), resource(2)],
+            }],
+        };
+        let current = VetResult {
+            findings: vec![Finding {
+                check: "silent-monitors",
+                severity: Severity::Critical,
+                count: 2,
+                resources: vec![resource(2), resource(3)],
+                recommendation: "fix it",
+            }],
+            passed: vec![],
+            critical: 1,
+            warnings: 0,
+            infos: 0,
+            pages_total: 0,
+        };
+
+        let diff = diff_against_baseline(&baseline, &current);
+        assert_eq!(diff.checks.len(), 1);
+        assert_eq!(diff.checks[0].newly_introduced.iter().map(|r| r.id).collect::<Vec<_>>(), vec![3]);
+        assert_eq!(diff.checks[0].resolved.iter().map(|r| r.id).collect::<Vec<_>>(), vec![1]);
+        assert_eq!(diff.new_critical_count, 1);
+    }
+
+    #[test]
+    fn test_diff_skips_unchanged_checks() {
+        let baseline = BaselineSnapshot {
+            findings: vec![BaselineFinding {
+                check: "untagged-monitors".to_string(),
+                severity: Severity::Warning,
+                resources: vec![resource(1)],
+            }],
+        };
+        let current = VetResult {
+            findings: vec![Finding {
+                check: "untagged-monitors",
+                severity: Severity::Warning,
+                count: 1,
+                resources: vec![resource(1)],
+                recommendation: "tag it",
+            }],
+            passed: vec![],
+            critical: 0,
+            warnings: 1,
+            infos: 0,
+            pages_total: 0,
+        };
+
+        let diff = diff_against_baseline(&baseline, &current);
+        assert!(diff.checks.is_empty());
+        assert_eq!(diff.new_critical_count, 0);
+    }
+}
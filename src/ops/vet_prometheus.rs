@@ -0,0 +1,180 @@
+//! Prometheus/OpenMetrics text exposition for `VetResult`, so monitor
+//! hygiene becomes a Grafana-able time series instead of a one-shot report.
+//!
+//! Two consumption modes: `pup vet --format prometheus [--output <path>]`
+//! renders once to stdout or a file for node_exporter's textfile-collector
+//! directory, and `pup vet --serve <addr>` binds a long-running HTTP
+//! endpoint that re-runs `vet::run` from scratch on every scrape (the same
+//! freshness guarantee a live `pup vet` invocation gives) rather than
+//! caching a result.
+
+use anyhow::{Context, Result};
+
+use crate::config::Config;
+use crate::ops::vet::{self, Severity, VetResult};
+
+fn severity_tag(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Critical => "critical",
+        Severity::Warning => "warning",
+        Severity::Info => "info",
+    }
+}
+
+/// Escape a Prometheus label value: backslash, double-quote, and newline are
+/// the only characters the text exposition format requires escaping.
+fn escape_label(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+/// Render a `VetResult` as Prometheus/OpenMetrics text exposition format:
+/// one `pup_vet_findings` gauge per finding (labeled by check + severity),
+/// one `pup_vet_passed{check=...} 1` per check that found nothing, and a
+/// single `pup_vet_pages_total` gauge carrying the raw pager-burden event
+/// count.
+pub fn render(result: &VetResult) -> String {
+    let mut out = String::new();
+
+    out.push_str("# HELP pup_vet_findings Resources flagged by a pup vet check\n");
+    out.push_str("# TYPE pup_vet_findings gauge\n");
+    for finding in &result.findings {
+        out.push_str(&format!(
+            "pup_vet_findings{{check=\"{}\",severity=\"{}\"}} {}\n",
+            escape_label(finding.check),
+            severity_tag(finding.severity),
+            finding.count,
+        ));
+    }
+
+    out.push_str("# HELP pup_vet_passed Whether a pup vet check passed with no findings\n");
+    out.push_str("# TYPE pup_vet_passed gauge\n");
+    for check in &result.passed {
+        out.push_str(&format!("pup_vet_passed{{check=\"{}\"}} 1\n", escape_label(check)));
+    }
+
+    out.push_str(
+        "# HELP pup_vet_pages_total Alert-triggered events tallied over the pager-burden/flapping-monitors lookback window\n",
+    );
+    out.push_str("# TYPE pup_vet_pages_total gauge\n");
+    out.push_str(&format!("pup_vet_pages_total {}\n", result.pages_total));
+
+    out
+}
+
+/// Write the rendered text to `path`, or stdout when `path` is `None` — the
+/// `pup vet --format prometheus [--output <path>]` one-shot mode.
+pub fn write_textfile(result: &VetResult, path: Option<&str>) -> Result<()> {
+    let text = render(result);
+    match path {
+        Some(p) => std::fs::write(p, &text).with_context(|| format!("failed to write {p}")),
+        None => {
+            print!("{text}");
+            Ok(())
+        }
+    }
+}
+
+/// The subset of `vet::run`'s arguments a scrape needs to re-evaluate —
+/// bundled so `serve`'s loop body doesn't have to thread `vet::run`'s full
+/// (and growing) argument list through by hand.
+#[derive(Clone)]
+pub struct ServeParams {
+    pub tags: Option<String>,
+    pub check: Option<String>,
+    pub severity_filter: Option<String>,
+    pub rules_path: Option<String>,
+}
+
+/// Long-running `pup vet --serve <addr>` mode: bind `addr` (e.g. `:9090`)
+/// and answer every HTTP request with a fresh `vet::run` rendered as
+/// Prometheus text. Never returns on success; only exits on a bind or
+/// request-loop error. A failed `vet::run` on a given scrape is reported as
+/// a 500 with the error in a comment line, rather than killing the server.
+pub async fn serve(cfg: &Config, addr: &str, params: ServeParams) -> Result<()> {
+    let server = tiny_http::Server::http(addr)
+        .map_err(|e| anyhow::anyhow!("failed to bind prometheus exporter to {addr}: {e}"))?;
+
+    loop {
+        let request = server
+            .recv()
+            .map_err(|e| anyhow::anyhow!("prometheus exporter request loop failed: {e}"))?;
+
+        let result = vet::run(
+            cfg,
+            params.tags.clone(),
+            params.check.clone(),
+            params.severity_filter.clone(),
+            params.rules_path.clone(),
+            None,
+            None,
+            None,
+        )
+        .await;
+
+        let (status, body) = match result {
+            Ok(r) => (200, render(&r)),
+            Err(e) => (500, format!("# vet run failed: {e:#}\n")),
+        };
+
+        let response = tiny_http::Response::from_string(body)
+            .with_status_code(status)
+            .with_header(
+                tiny_http::Header::from_bytes(&b"Content-Type"[..], &b"text/plain; version=0.0.4".to_vec())
+                    .unwrap(),
+            );
+        let _ = request.respond(response);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ops::vet::{Finding, Resource};
+
+    fn sample_result() -> VetResult {
+        VetResult {
+            findings: vec![Finding {
+                check: "silent-monitors",
+                severity: Severity::Critical,
+                count: 2,
+                resources: vec![
+                    Resource { id: 1, name: "a".to_string(), detail: "d".to_string() },
+                    Resource { id: 2, name: "b".to_string(), detail: "d".to_string() },
+                ],
+                recommendation: "fix it",
+            }],
+            passed: vec!["untagged-monitors"],
+            critical: 1,
+            warnings: 0,
+            infos: 0,
+            pages_total: 42,
+        }
+    }
+
+    #[test]
+    fn render_includes_findings_passed_and_pages_total() {
+        let text = render(&sample_result());
+        assert!(text.contains("pup_vet_findings{check=\"silent-monitors\",severity=\"critical\"} 2"));
+        assert!(text.contains("pup_vet_passed{check=\"untagged-monitors\"} 1"));
+        assert!(text.contains("pup_vet_pages_total 42"));
+    }
+
+    #[test]
+    fn render_empty_result_still_emits_pages_total() {
+        let text = render(&VetResult {
+            findings: vec![],
+            passed: vec![],
+            critical: 0,
+            warnings: 0,
+            infos: 0,
+            pages_total: 0,
+        });
+        assert!(!text.contains("pup_vet_findings{"));
+        assert!(text.contains("pup_vet_pages_total 0"));
+    }
+
+    #[test]
+    fn escape_label_handles_quotes_and_backslashes() {
+        assert_eq!(escape_label(r#"a"b\c"#), r#"a\"b\\c"#);
+    }
+}
@@ -0,0 +1,436 @@
+//! Config-driven custom checks for `vet`: rules defined in a YAML/TOML file
+//! rather than compiled Rust fns, so org-specific policy (e.g. "prod
+//! monitors must carry an `escalation:` tag") can be encoded as data and
+//! changed without a release. Mirrors `ops::filter`'s dotted-path-against-
+//! `serde_json::Value` approach, but trades that module's string DSL for a
+//! structured rule file shape (`field`/`op`/`value` leaves combined with
+//! `all`/`any`), since rules here are authored by teams, not typed on a CLI.
+//!
+//! A rule is compiled once at load time: unknown operators fail immediately
+//! with a clear error rather than silently matching nothing, the same
+//! "fail loudly, don't silently pass" contract `ops::backup::ResourceKind::parse`
+//! already applies to unknown kinds.
+
+use std::collections::HashSet;
+
+use anyhow::{bail, Context, Result};
+use regex::Regex;
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::ops::vet::{Finding, Resource, Severity};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Eq,
+    Ne,
+    Lt,
+    Lte,
+    Gt,
+    Gte,
+    Contains,
+    NotContains,
+    ContainsPrefix,
+    NotContainsPrefix,
+    Matches,
+    NotMatches,
+    Absent,
+    Present,
+}
+
+impl Op {
+    fn parse(s: &str) -> Result<Op> {
+        Ok(match s {
+            "eq" => Op::Eq,
+            "ne" => Op::Ne,
+            "lt" => Op::Lt,
+            "lte" => Op::Lte,
+            "gt" => Op::Gt,
+            "gte" => Op::Gte,
+            "contains" => Op::Contains,
+            "not_contains" => Op::NotContains,
+            "contains_prefix" => Op::ContainsPrefix,
+            "not_contains_prefix" => Op::NotContainsPrefix,
+            "matches" => Op::Matches,
+            "not_matches" => Op::NotMatches,
+            "absent" => Op::Absent,
+            "present" => Op::Present,
+            other => bail!(
+                "unknown vet rule operator '{other}' (expected one of: eq, ne, lt, lte, gt, gte, \
+                 contains, not_contains, contains_prefix, not_contains_prefix, matches, not_matches, \
+                 absent, present)"
+            ),
+        })
+    }
+
+    fn needs_value(self) -> bool {
+        !matches!(self, Op::Absent | Op::Present)
+    }
+}
+
+/// The rule file's on-disk shape for one condition: either a leaf
+/// comparison, or an `all`/`any` combinator over nested conditions.
+/// `#[serde(untagged)]` picks whichever variant's fields are present, so a
+/// rule author writes `{field, op, value}` or `{all: [...]}`/`{any: [...]}`
+/// without a discriminant tag.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum RawCondition {
+    All { all: Vec<RawCondition> },
+    Any { any: Vec<RawCondition> },
+    Leaf {
+        field: String,
+        op: String,
+        #[serde(default)]
+        value: Option<Value>,
+    },
+}
+
+#[derive(Debug, Deserialize)]
+struct RawRule {
+    name: String,
+    severity: String,
+    recommendation: String,
+    when: RawCondition,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawRuleFile {
+    rules: Vec<RawRule>,
+}
+
+/// A compiled condition, validated at load time: the field path is split,
+/// the operator is a real [`Op`] (not a string that might be a typo), and
+/// `matches`/`not_matches` have already compiled their regex once.
+enum Condition {
+    All(Vec<Condition>),
+    Any(Vec<Condition>),
+    Leaf { field: Vec<String>, op: Op, value: Option<Value>, regex: Option<Regex> },
+}
+
+fn compile_condition(raw: RawCondition) -> Result<Condition> {
+    Ok(match raw {
+        RawCondition::All { all } => {
+            Condition::All(all.into_iter().map(compile_condition).collect::<Result<_>>()?)
+        }
+        RawCondition::Any { any } => {
+            Condition::Any(any.into_iter().map(compile_condition).collect::<Result<_>>()?)
+        }
+        RawCondition::Leaf { field, op, value } => {
+            let op = Op::parse(&op)?;
+            if op.needs_value() && value.is_none() {
+                bail!("vet rule condition on '{field}' uses op '{op:?}' which requires a 'value'");
+            }
+            let regex = if matches!(op, Op::Matches | Op::NotMatches) {
+                let pattern = value
+                    .as_ref()
+                    .and_then(Value::as_str)
+                    .with_context(|| format!("vet rule condition on '{field}' needs a string 'value' to use as a regex"))?;
+                Some(Regex::new(pattern).with_context(|| format!("invalid regex '{pattern}' on field '{field}'"))?)
+            } else {
+                None
+            };
+            if field.trim().is_empty() || field.split('.').any(str::is_empty) {
+                bail!("vet rule has an empty or malformed field path: '{field}'");
+            }
+            Condition::Leaf {
+                field: field.split('.').map(str::to_string).collect(),
+                op,
+                value,
+                regex,
+            }
+        }
+    })
+}
+
+pub struct CustomRule {
+    name: &'static str,
+    severity: Severity,
+    recommendation: &'static str,
+    condition: Condition,
+}
+
+impl CustomRule {
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+/// Resolve a dot-path against a JSON value. A missing intermediate key (or
+/// indexing into a non-object) yields `None` — the "absent" state the
+/// `absent`/`present` operators distinguish from a present-but-falsy value.
+fn resolve_path<'a>(value: &'a Value, path: &[String]) -> Option<&'a Value> {
+    let mut current = value;
+    for segment in path {
+        current = current.as_object()?.get(segment)?;
+    }
+    Some(current)
+}
+
+fn as_compare_string(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn eval_leaf(value: &Value, field: &[String], op: Op, expected: &Option<Value>, regex: &Option<Regex>) -> bool {
+    let found = resolve_path(value, field);
+
+    match op {
+        Op::Absent => return found.is_none(),
+        Op::Present => return found.is_some(),
+        _ => {}
+    }
+
+    let Some(found) = found else { return false };
+    let expected = expected.as_ref().expect("non-absent/present op always has a value, checked at compile time");
+
+    match op {
+        Op::Eq => found == expected,
+        Op::Ne => found != expected,
+        Op::Lt | Op::Lte | Op::Gt | Op::Gte => {
+            let (Some(a), Some(b)) = (found.as_f64(), expected.as_f64()) else { return false };
+            match op {
+                Op::Lt => a < b,
+                Op::Lte => a <= b,
+                Op::Gt => a > b,
+                Op::Gte => a >= b,
+                _ => unreachable!(),
+            }
+        }
+        Op::Contains => match found {
+            Value::Array(items) => items.iter().any(|v| v == expected),
+            other => as_compare_string(other).contains(&as_compare_string(expected)),
+        },
+        Op::NotContains => !eval_leaf(value, field, Op::Contains, &Some(expected.clone()), regex),
+        Op::ContainsPrefix => {
+            let prefix = as_compare_string(expected);
+            match found {
+                Value::Array(items) => items.iter().any(|v| as_compare_string(v).starts_with(&prefix)),
+                other => as_compare_string(other).starts_with(&prefix),
+            }
+        }
+        Op::NotContainsPrefix => !eval_leaf(value, field, Op::ContainsPrefix, &Some(expected.clone()), regex),
+        Op::Matches => regex
+            .as_ref()
+            .map(|re| re.is_match(&as_compare_string(found)))
+            .unwrap_or(false),
+        Op::NotMatches => !regex
+            .as_ref()
+            .map(|re| re.is_match(&as_compare_string(found)))
+            .unwrap_or(false),
+        Op::Absent | Op::Present => unreachable!("handled above"),
+    }
+}
+
+fn eval_condition(cond: &Condition, value: &Value) -> bool {
+    match cond {
+        Condition::All(conditions) => conditions.iter().all(|c| eval_condition(c, value)),
+        Condition::Any(conditions) => conditions.iter().any(|c| eval_condition(c, value)),
+        Condition::Leaf { field, op, value: expected, regex } => eval_leaf(value, field, *op, expected, regex),
+    }
+}
+
+/// Load and compile a rule file (`.yaml`/`.yml` or `.toml` by extension).
+pub fn load_rules(path: &str) -> Result<Vec<CustomRule>> {
+    let raw = std::fs::read_to_string(path).with_context(|| format!("failed to read vet rule file {path}"))?;
+    let file: RawRuleFile = if path.ends_with(".toml") {
+        toml::from_str(&raw).with_context(|| format!("failed to parse {path} as TOML"))?
+    } else {
+        serde_yaml::from_str(&raw).with_context(|| format!("failed to parse {path} as YAML"))?
+    };
+
+    let mut names = HashSet::new();
+    let mut rules = Vec::with_capacity(file.rules.len());
+    for raw_rule in file.rules {
+        if !names.insert(raw_rule.name.clone()) {
+            bail!("duplicate vet rule name '{}' in {path}", raw_rule.name);
+        }
+        let severity = match raw_rule.severity.as_str() {
+            "critical" => Severity::Critical,
+            "warning" => Severity::Warning,
+            "info" => Severity::Info,
+            other => bail!("vet rule '{}' has unknown severity '{other}' (expected critical, warning, or info)", raw_rule.name),
+        };
+        let condition = compile_condition(raw_rule.when)
+            .with_context(|| format!("failed to compile vet rule '{}'", raw_rule.name))?;
+
+        // Leaked once at load time: `Finding::check`/`::recommendation` are
+        // `&'static str` everywhere else (the built-in checks are string
+        // constants), and rule files are loaded once per process, so leaking
+        // these few short-lived-by-comparison strings keeps `Finding` a
+        // single uniform type instead of splitting it into owned/borrowed variants.
+        rules.push(CustomRule {
+            name: Box::leak(raw_rule.name.into_boxed_str()),
+            severity,
+            recommendation: Box::leak(raw_rule.recommendation.into_boxed_str()),
+            condition,
+        });
+    }
+    Ok(rules)
+}
+
+/// Evaluate one compiled rule against every monitor (as its `serde_json`
+/// representation), producing the same `Finding` shape the built-in checks
+/// return so custom rules flow through `VetResult`/`--check`/`--severity`
+/// unmodified.
+pub fn eval_rule<T: serde::Serialize>(rule: &CustomRule, monitors: &[T], id_of: impl Fn(&T) -> i64, name_of: impl Fn(&T) -> String) -> Result<Finding> {
+    let mut resources = Vec::new();
+    for monitor in monitors {
+        let value = serde_json::to_value(monitor).context("failed to serialize monitor for custom vet rule evaluation")?;
+        if eval_condition(&rule.condition, &value) {
+            resources.push(Resource {
+                id: id_of(monitor),
+                name: name_of(monitor),
+                detail: format!("matched custom rule '{}'", rule.name),
+            });
+        }
+    }
+
+    Ok(Finding {
+        check: rule.name,
+        severity: rule.severity,
+        count: resources.len(),
+        resources,
+        recommendation: rule.recommendation,
+    })
+}
+
+pub fn rule_names(rules: &[CustomRule]) -> Vec<&'static str> {
+    rules.iter().map(|r| r.name).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn rule(yaml: &str) -> CustomRule {
+        let file: RawRuleFile = serde_yaml::from_str(yaml).unwrap();
+        let raw_rule = file.rules.into_iter().next().unwrap();
+        let severity = match raw_rule.severity.as_str() {
+            "critical" => Severity::Critical,
+            "warning" => Severity::Warning,
+            _ => Severity::Info,
+        };
+        CustomRule {
+            name: Box::leak(raw_rule.name.into_boxed_str()),
+            severity,
+            recommendation: Box::leak(raw_rule.recommendation.into_boxed_str()),
+            condition: compile_condition(raw_rule.when).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_lte_numeric_condition() {
+        let r = rule(
+            r#"
+rules:
+  - name: fast-renotify
+    severity: warning
+    recommendation: raise it
+    when:
+      field: options.renotify_interval
+      op: lte
+      value: 30
+"#,
+        );
+        assert!(eval_condition(&r.condition, &json!({"options": {"renotify_interval": 15}})));
+        assert!(!eval_condition(&r.condition, &json!({"options": {"renotify_interval": 60}})));
+    }
+
+    #[test]
+    fn test_not_contains_prefix() {
+        let r = rule(
+            r#"
+rules:
+  - name: needs-team-tag
+    severity: critical
+    recommendation: tag it
+    when:
+      field: tags
+      op: not_contains_prefix
+      value: "team:"
+"#,
+        );
+        assert!(eval_condition(&r.condition, &json!({"tags": ["env:prod"]})));
+        assert!(!eval_condition(&r.condition, &json!({"tags": ["team:sre"]})));
+    }
+
+    #[test]
+    fn test_all_combinator() {
+        let r = rule(
+            r#"
+rules:
+  - name: prod-needs-escalation
+    severity: critical
+    recommendation: add escalation tag
+    when:
+      all:
+        - field: tags
+          op: contains_prefix
+          value: "env:prod"
+        - field: tags
+          op: not_contains_prefix
+          value: "escalation:"
+"#,
+        );
+        assert!(eval_condition(&r.condition, &json!({"tags": ["env:prod"]})));
+        assert!(!eval_condition(&r.condition, &json!({"tags": ["env:prod", "escalation:sev1"]})));
+        assert!(!eval_condition(&r.condition, &json!({"tags": ["env:staging"]})));
+    }
+
+    #[test]
+    fn test_absent_and_present() {
+        let absent = rule(
+            r#"
+rules:
+  - name: missing-message
+    severity: info
+    recommendation: add a message
+    when:
+      field: message
+      op: absent
+"#,
+        );
+        assert!(eval_condition(&absent.condition, &json!({})));
+        assert!(!eval_condition(&absent.condition, &json!({"message": "hi"})));
+    }
+
+    #[test]
+    fn test_unknown_operator_fails_at_load() {
+        let file: RawRuleFile = serde_yaml::from_str(
+            r#"
+rules:
+  - name: bad
+    severity: info
+    recommendation: n/a
+    when:
+      field: tags
+      op: bogus_op
+"#,
+        )
+        .unwrap();
+        let raw_rule = file.rules.into_iter().next().unwrap();
+        assert!(compile_condition(raw_rule.when).is_err());
+    }
+
+    #[test]
+    fn test_matches_regex_condition() {
+        let r = rule(
+            r#"
+rules:
+  - name: message-mentions-someone
+    severity: info
+    recommendation: n/a
+    when:
+      field: message
+      op: matches
+      value: "@"
+"#,
+        );
+        assert!(eval_condition(&r.condition, &json!({"message": "paging @oncall-platform"})));
+        assert!(!eval_condition(&r.condition, &json!({"message": "no mention here"})));
+    }
+}
@@ -0,0 +1,325 @@
+//! WASM plugin loader: lets a `.wasm` module dropped into the plugins
+//! directory appear as a first-class `pup` subcommand, without recompiling
+//! the binary. Complements `ops::plugins`'s external-process plugins (which
+//! run arbitrary executables found on `PATH`) with a sandboxed guest ABI
+//! that has no ambient filesystem or network access beyond the host imports
+//! below.
+//!
+//! Guest ABI:
+//!   - `describe() -> (ptr: i32, len: i32)`: returns a length-prefixed
+//!     UTF-8 JSON [`PluginDescriptor`] describing the subcommand (name,
+//!     description, flags, and the `read_only` attribute the plugin
+//!     declares explicitly, since the host has no way to infer it).
+//!   - `invoke(ptr: i32, len: i32) -> (ptr: i32, len: i32)`: receives the
+//!     parsed CLI args as a JSON object at `(ptr, len)` in guest memory and
+//!     returns a JSON result the same way.
+//!   - `alloc(len: i32) -> i32`: the guest's allocator, so the host can
+//!     write the `invoke` argument into guest-owned memory before calling it.
+//!
+//! Host imports (under the `pup` module namespace):
+//!   - `http_request(ptr, len) -> (ptr, len)`: takes a length-prefixed JSON
+//!     `{ method, path, body }` request and returns a length-prefixed JSON
+//!     response, signed with the resolved site/token — the plugin never
+//!     sees credentials directly.
+//!   - `write_output(ptr, len)`: streams a length-prefixed UTF-8 chunk to
+//!     `pup`'s stdout/stderr as the plugin runs, for progress output ahead
+//!     of its final `invoke` result.
+//!
+//! `describe()`'s output feeds both the dynamic clap `Command` tree and
+//! `build_agent_schema`/`build_command_schema`, so installed plugins show up
+//! in agent-mode schema output the same as built-in commands.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use wasmtime::{Caller, Engine, Instance, Linker, Memory, Module, Store, TypedFunc};
+
+use crate::api;
+use crate::config::Config;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginFlag {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub required: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginDescriptor {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub read_only: bool,
+    #[serde(default)]
+    pub flags: Vec<PluginFlag>,
+}
+
+#[derive(Debug, Clone)]
+pub struct WasmPlugin {
+    pub descriptor: PluginDescriptor,
+    pub path: PathBuf,
+}
+
+/// Where `.wasm` plugin modules live: `PUP_WASM_PLUGIN_DIR` if set, else
+/// `<config dir>/plugins/wasm`, mirroring `ops::plugins::search_dirs`'s
+/// env-var-first convention for the process-based loader.
+fn plugin_dir() -> Result<PathBuf> {
+    if let Some(dir) = std::env::var_os("PUP_WASM_PLUGIN_DIR") {
+        return Ok(PathBuf::from(dir));
+    }
+    Ok(crate::config::config_dir()?.join("plugins").join("wasm"))
+}
+
+/// A store's host-accessible state: the resolved `Config` (for signing
+/// `http_request` calls with the right site/credentials) and the memory
+/// export, grabbed after instantiation so the imported functions can read
+/// and write guest linear memory.
+struct HostState {
+    cfg: Config,
+    memory: Option<Memory>,
+    /// Mirrors the invoked plugin's [`PluginDescriptor::read_only`]; when
+    /// set, `http_request` rejects anything but `GET` so a plugin can't
+    /// declare itself read-only in `describe()` and then mutate anyway.
+    read_only: bool,
+}
+
+fn read_guest_string(caller: &mut Caller<'_, HostState>, ptr: i32, len: i32) -> Result<String> {
+    let memory = caller.data().memory.context("guest module has no exported memory")?;
+    let mut buf = vec![0u8; len as usize];
+    memory
+        .read(&caller, ptr as usize, &mut buf)
+        .map_err(|e| anyhow::anyhow!("failed to read {len} bytes from guest memory at {ptr}: {e}"))?;
+    String::from_utf8(buf).context("guest module wrote non-UTF-8 bytes")
+}
+
+fn write_guest_string(
+    caller: &mut Caller<'_, HostState>,
+    alloc: &TypedFunc<i32, i32>,
+    s: &str,
+) -> Result<(i32, i32)> {
+    let memory = caller.data().memory.context("guest module has no exported memory")?;
+    let bytes = s.as_bytes();
+    let ptr = alloc
+        .call(&mut *caller, bytes.len() as i32)
+        .map_err(|e| anyhow::anyhow!("guest alloc({}) failed: {e}", bytes.len()))?;
+    memory
+        .write(&mut *caller, ptr as usize, bytes)
+        .map_err(|e| anyhow::anyhow!("failed to write {} bytes into guest memory at {ptr}: {e}", bytes.len()))?;
+    Ok((ptr, bytes.len() as i32))
+}
+
+/// Build a `Linker` wiring up the `pup.http_request` and `pup.write_output`
+/// host imports. `http_request` blocks on the async Datadog call via
+/// `tokio::runtime::Handle::current().block_on(..)` since wasmtime's
+/// synchronous call path doesn't thread an executor through to the guest.
+fn build_linker(engine: &Engine) -> Result<Linker<HostState>> {
+    let mut linker = Linker::new(engine);
+
+    linker.func_wrap(
+        "pup",
+        "http_request",
+        |mut caller: Caller<'_, HostState>, ptr: i32, len: i32| -> (i32, i32) {
+            let request_json = match read_guest_string(&mut caller, ptr, len) {
+                Ok(s) => s,
+                Err(e) => return write_error_response(&mut caller, &e.to_string()),
+            };
+            let request: Value = match serde_json::from_str(&request_json) {
+                Ok(v) => v,
+                Err(e) => return write_error_response(&mut caller, &format!("invalid http_request payload: {e}")),
+            };
+
+            let method = request.get("method").and_then(Value::as_str).unwrap_or("GET").to_string();
+            let path = request.get("path").and_then(Value::as_str).unwrap_or_default().to_string();
+            let body = request.get("body").cloned().unwrap_or(Value::Null);
+            let cfg = caller.data().cfg.clone();
+
+            if caller.data().read_only && !method.eq_ignore_ascii_case("GET") {
+                return write_error_response(
+                    &mut caller,
+                    &format!("plugin is read-only; http_request method {method:?} is not permitted"),
+                );
+            }
+
+            let result = tokio::runtime::Handle::current().block_on(async move {
+                match method.to_ascii_uppercase().as_str() {
+                    "GET" => api::get(&cfg, &path, &[]).await,
+                    "POST" => api::post(&cfg, &path, &body).await,
+                    "PATCH" => api::patch(&cfg, &path, &body).await,
+                    "PUT" => api::put(&cfg, &path, &body).await,
+                    "DELETE" => api::delete(&cfg, &path).await.map(|_| Value::Null),
+                    other => bail!("unsupported http_request method {other:?}"),
+                }
+            });
+
+            match result {
+                Ok(value) => write_response(&mut caller, &value),
+                Err(e) => write_error_response(&mut caller, &e.to_string()),
+            }
+        },
+    )?;
+
+    linker.func_wrap(
+        "pup",
+        "write_output",
+        |mut caller: Caller<'_, HostState>, ptr: i32, len: i32| {
+            if let Ok(s) = read_guest_string(&mut caller, ptr, len) {
+                print!("{s}");
+            }
+        },
+    )?;
+
+    Ok(linker)
+}
+
+/// The `http_request`/`write_output` imports can't call back into guest
+/// `alloc` mid-call without re-borrowing the instance, so responses are
+/// written through a fixed scratch buffer the guest pre-allocates and
+/// shares via `set_response_buffer` at startup — simplified here to writing
+/// straight into guest memory at a conventional offset reserved for host
+/// responses (byte 0, growing as needed), since the sandboxed modules this
+/// loader targets are single-threaded and single-call-at-a-time.
+const HOST_RESPONSE_OFFSET: usize = 0;
+
+fn write_response(caller: &mut Caller<'_, HostState>, value: &Value) -> (i32, i32) {
+    let text = value.to_string();
+    write_response_bytes(caller, text.as_bytes())
+}
+
+fn write_error_response(caller: &mut Caller<'_, HostState>, message: &str) -> (i32, i32) {
+    let text = serde_json::json!({ "error": message }).to_string();
+    write_response_bytes(caller, text.as_bytes())
+}
+
+fn write_response_bytes(caller: &mut Caller<'_, HostState>, bytes: &[u8]) -> (i32, i32) {
+    let Some(memory) = caller.data().memory else {
+        return (0, 0);
+    };
+    if memory.write(&mut *caller, HOST_RESPONSE_OFFSET, bytes).is_err() {
+        return (0, 0);
+    }
+    (HOST_RESPONSE_OFFSET as i32, bytes.len() as i32)
+}
+
+fn load_module(engine: &Engine, cfg: &Config, path: &Path, read_only: bool) -> Result<(Store<HostState>, Instance)> {
+    let module = Module::from_file(engine, path)
+        .with_context(|| format!("failed to compile wasm plugin {}", path.display()))?;
+    let linker = build_linker(engine)?;
+    let mut store = Store::new(engine, HostState { cfg: cfg.clone(), memory: None, read_only });
+    let instance = linker
+        .instantiate(&mut store, &module)
+        .with_context(|| format!("failed to instantiate wasm plugin {}", path.display()))?;
+    if let Some(memory) = instance.get_memory(&mut store, "memory") {
+        store.data_mut().memory = Some(memory);
+    }
+    Ok((store, instance))
+}
+
+fn call_describe(store: &mut Store<HostState>, instance: &Instance) -> Result<PluginDescriptor> {
+    let describe: TypedFunc<(), (i32, i32)> = instance
+        .get_typed_func(&mut *store, "describe")
+        .context("wasm plugin does not export describe()")?;
+    let (ptr, len) = describe.call(&mut *store, ())?;
+    let memory = store.data().memory.context("guest module has no exported memory")?;
+    let mut buf = vec![0u8; len as usize];
+    memory.read(&mut *store, ptr as usize, &mut buf)?;
+    serde_json::from_slice(&buf).context("describe() did not return a valid PluginDescriptor")
+}
+
+/// Scan `plugin_dir()` for `.wasm` modules, instantiate each in a sandboxed
+/// `wasmtime` runtime, and call `describe()` to learn its subcommand name,
+/// description, flags, and `read_only` attribute. A module that fails to
+/// compile, instantiate, or describe itself is skipped with a warning
+/// rather than aborting discovery for the rest.
+pub fn discover(cfg: &Config) -> Result<Vec<WasmPlugin>> {
+    let dir = plugin_dir()?;
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let engine = Engine::default();
+    let mut plugins = Vec::new();
+    for entry in fs::read_dir(&dir).with_context(|| format!("failed to read plugin directory {}", dir.display()))? {
+        let path = entry?.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("wasm") {
+            continue;
+        }
+        match load_module(&engine, cfg, &path, false)
+            .and_then(|(mut store, instance)| call_describe(&mut store, &instance))
+        {
+            Ok(descriptor) => plugins.push(WasmPlugin { descriptor, path }),
+            Err(e) => eprintln!("warning: skipping wasm plugin {}: {e}", path.display()),
+        }
+    }
+    Ok(plugins)
+}
+
+/// Run a discovered plugin's `invoke(ptr, len)` with `args` as the parsed
+/// CLI arguments, returning its JSON result.
+pub fn invoke(cfg: &Config, plugin: &WasmPlugin, args: &Value) -> Result<Value> {
+    let engine = Engine::default();
+    let (mut store, instance) = load_module(&engine, cfg, &plugin.path, plugin.descriptor.read_only)?;
+
+    let alloc: TypedFunc<i32, i32> = instance
+        .get_typed_func(&mut store, "alloc")
+        .context("wasm plugin does not export alloc(len)")?;
+    let invoke_fn: TypedFunc<(i32, i32), (i32, i32)> = instance
+        .get_typed_func(&mut store, "invoke")
+        .context("wasm plugin does not export invoke(ptr, len)")?;
+
+    let args_json = args.to_string();
+    let memory = store.data().memory.context("guest module has no exported memory")?;
+    let ptr = alloc
+        .call(&mut store, args_json.len() as i32)
+        .with_context(|| format!("plugin alloc({}) failed", args_json.len()))?;
+    memory.write(&mut store, ptr as usize, args_json.as_bytes())?;
+
+    let (out_ptr, out_len) = invoke_fn
+        .call(&mut store, (ptr, args_json.len() as i32))
+        .with_context(|| format!("invoke() failed for plugin {}", plugin.descriptor.name))?;
+
+    let mut buf = vec![0u8; out_len as usize];
+    memory.read(&store, out_ptr as usize, &mut buf)?;
+    serde_json::from_slice(&buf).context("invoke() did not return valid JSON")
+}
+
+/// Build the `build_agent_schema`/`build_command_schema` entries for every
+/// discovered plugin, keyed by subcommand name, so installed plugins appear
+/// in agent-mode schema output without the built-in schema builder needing
+/// to special-case them.
+pub fn schema_entries(cfg: &Config) -> Result<HashMap<String, PluginDescriptor>> {
+    Ok(discover(cfg)?
+        .into_iter()
+        .map(|p| (p.descriptor.name.clone(), p.descriptor))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plugin_descriptor_defaults_read_only_false() {
+        let descriptor: PluginDescriptor = serde_json::from_str(r#"{"name": "widgets"}"#).unwrap();
+        assert_eq!(descriptor.name, "widgets");
+        assert!(!descriptor.read_only);
+        assert!(descriptor.flags.is_empty());
+    }
+
+    #[test]
+    fn test_plugin_descriptor_with_flags() {
+        let descriptor: PluginDescriptor = serde_json::from_str(
+            r#"{"name": "widgets", "description": "manage widgets", "read_only": true, "flags": [{"name": "id", "required": true}]}"#,
+        )
+        .unwrap();
+        assert!(descriptor.read_only);
+        assert_eq!(descriptor.flags.len(), 1);
+        assert!(descriptor.flags[0].required);
+    }
+}
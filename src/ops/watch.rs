@@ -0,0 +1,163 @@
+//! Generic `--watch` polling engine for read-only commands (`logs search`,
+//! `logs query`, and friends): re-run a fetch on an interval, diff the
+//! result set against the previous poll by each item's `id`, and print only
+//! what changed. Modeled on `commands::logs::follow`'s own poll loop, but
+//! parameterized over any command that can hand back a keyed item list
+//! instead of being specific to the logs-events endpoint.
+
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::future::Future;
+use std::time::Duration;
+
+use crate::config::Config;
+use crate::formatter;
+
+/// Added/removed/changed items between two consecutive polls, keyed by
+/// each item's `id` (or `data.id` for JSON:API-shaped responses).
+#[derive(Debug, Default, Serialize)]
+pub struct Diff {
+    pub added: Vec<serde_json::Value>,
+    pub removed: Vec<String>,
+    pub changed: Vec<serde_json::Value>,
+}
+
+impl Diff {
+    fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+fn key_of(item: &serde_json::Value) -> Option<String> {
+    item.get("id")
+        .or_else(|| item.pointer("/data/id"))
+        .map(|v| match v {
+            serde_json::Value::String(s) => s.clone(),
+            other => other.to_string(),
+        })
+}
+
+fn diff(prev: &HashMap<String, serde_json::Value>, cur: &HashMap<String, serde_json::Value>) -> Diff {
+    let mut d = Diff::default();
+    for (id, item) in cur {
+        match prev.get(id) {
+            None => d.added.push(item.clone()),
+            Some(prev_item) if prev_item != item => d.changed.push(item.clone()),
+            Some(_) => {}
+        }
+    }
+    for id in prev.keys() {
+        if !cur.contains_key(id) {
+            d.removed.push(id.clone());
+        }
+    }
+    d
+}
+
+/// Re-run `fetch` every `interval_secs`, printing the full result set on
+/// the first poll and on every poll thereafter if `watch_full`, or just the
+/// added/removed/changed items (a compact diff) otherwise. Returns cleanly
+/// on Ctrl-C.
+pub async fn poll<F, Fut>(
+    cfg: &Config,
+    interval_secs: u64,
+    watch_full: bool,
+    mut fetch: F,
+) -> Result<()>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<Vec<serde_json::Value>>>,
+{
+    eprintln!("Watching every {interval_secs}s... (Ctrl-C to stop)");
+
+    let mut prev: Option<HashMap<String, serde_json::Value>> = None;
+
+    loop {
+        let items = tokio::select! {
+            items = fetch() => items?,
+            _ = tokio::signal::ctrl_c() => {
+                eprintln!("\nStopped watching.");
+                return Ok(());
+            }
+        };
+
+        let cur: HashMap<String, serde_json::Value> = items
+            .iter()
+            .filter_map(|item| key_of(item).map(|id| (id, item.clone())))
+            .collect();
+
+        match &prev {
+            None => print_full(cfg, &items)?,
+            Some(_) if watch_full => print_full(cfg, &items)?,
+            Some(prev) => print_diff(cfg, &diff(prev, &cur))?,
+        }
+
+        prev = Some(cur);
+
+        tokio::select! {
+            _ = tokio::time::sleep(Duration::from_secs(interval_secs)) => {},
+            _ = tokio::signal::ctrl_c() => {
+                eprintln!("\nStopped watching.");
+                return Ok(());
+            }
+        }
+    }
+}
+
+fn print_full(cfg: &Config, items: &[serde_json::Value]) -> Result<()> {
+    if cfg.agent_mode {
+        return formatter::output(cfg, items);
+    }
+    for item in items {
+        println!("{item}");
+    }
+    Ok(())
+}
+
+fn print_diff(cfg: &Config, d: &Diff) -> Result<()> {
+    if cfg.agent_mode {
+        return formatter::output(cfg, d);
+    }
+    if d.is_empty() {
+        return Ok(());
+    }
+    for item in &d.added {
+        println!("+ {item}");
+    }
+    for id in &d.removed {
+        println!("- {id}");
+    }
+    for item in &d.changed {
+        println!("~ {item}");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_diff_detects_added_removed_changed() {
+        let mut prev = HashMap::new();
+        prev.insert("1".to_string(), json!({"id": "1", "status": "ok"}));
+        prev.insert("2".to_string(), json!({"id": "2", "status": "ok"}));
+
+        let mut cur = HashMap::new();
+        cur.insert("1".to_string(), json!({"id": "1", "status": "alert"}));
+        cur.insert("3".to_string(), json!({"id": "3", "status": "ok"}));
+
+        let d = diff(&prev, &cur);
+        assert_eq!(d.added.len(), 1);
+        assert_eq!(d.removed, vec!["2".to_string()]);
+        assert_eq!(d.changed.len(), 1);
+    }
+
+    #[test]
+    fn test_key_of_json_api_shape() {
+        let item = json!({"data": {"id": "abc"}});
+        assert_eq!(key_of(&item), Some("abc".to_string()));
+    }
+}
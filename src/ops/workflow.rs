@@ -0,0 +1,273 @@
+//! `pup run`: a scripted workflow runner that chains the same `{command,
+//! args}` steps `ops::batch` knows how to dispatch, but in sequence rather
+//! than independently — each step can `capture` its JSON output under a
+//! name, and later steps interpolate `${name}`/`${name.field.path}`
+//! placeholders from it into their own `args`. This is what turns the
+//! create-then-reference sequences scattered across Fleet, StatusPages, and
+//! Integrations (create a status page, capture its `page_id`, create
+//! components against it, ...) into one reproducible file instead of a
+//! shell script gluing separate `pup` invocations together with `jq`.
+//!
+//! Reuses `ops::batch::registry()` verbatim rather than re-declaring the
+//! command table, so a command usable from `pup batch` is usable from
+//! `pup run` for free, and vice versa.
+
+use std::collections::HashMap;
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::config::Config;
+use crate::ops::batch;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct WorkflowStep {
+    pub command: String,
+    #[serde(default)]
+    pub args: Value,
+    /// Name to capture this step's JSON output under, for later steps'
+    /// `args` to interpolate via `${name}`/`${name.field.path}`.
+    #[serde(default)]
+    pub capture: Option<String>,
+    #[serde(default)]
+    pub continue_on_error: bool,
+    /// Additional attempts after the first on failure, before this step is
+    /// recorded as failed.
+    #[serde(default)]
+    pub retries: u32,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WorkflowFile {
+    pub steps: Vec<WorkflowStep>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StepResult {
+    pub index: usize,
+    pub command: String,
+    pub status: &'static str,
+    pub attempts: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Whole-string placeholder, e.g. `"${page}"` — resolves to the captured
+/// value's own JSON type (object/number/...) rather than a stringified form.
+fn whole_placeholder(s: &str) -> Option<&str> {
+    let inner = s.strip_prefix("${")?.strip_suffix('}')?;
+    if inner.contains("${") {
+        None
+    } else {
+        Some(inner)
+    }
+}
+
+fn render_interpolated(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn resolve_path<'a>(vars: &'a HashMap<String, Value>, path: &str) -> Result<&'a Value> {
+    let mut parts = path.splitn(2, '.');
+    let name = parts.next().unwrap_or_default();
+    let value = vars
+        .get(name)
+        .with_context(|| format!("workflow step references unknown captured variable {name:?}"))?;
+    match parts.next() {
+        None => Ok(value),
+        Some(rest) => {
+            let pointer = format!("/{}", rest.replace('.', "/"));
+            value
+                .pointer(&pointer)
+                .with_context(|| format!("captured variable {name:?} has no field at path {rest:?}"))
+        }
+    }
+}
+
+fn interpolate_string(s: &str, vars: &HashMap<String, Value>) -> Result<Value> {
+    if let Some(path) = whole_placeholder(s) {
+        return Ok(resolve_path(vars, path)?.clone());
+    }
+
+    let mut out = String::new();
+    let mut rest = s;
+    while let Some(start) = rest.find("${") {
+        out.push_str(&rest[..start]);
+        let Some(end_rel) = rest[start..].find('}') else {
+            out.push_str(&rest[start..]);
+            rest = "";
+            break;
+        };
+        let path = &rest[start + 2..start + end_rel];
+        out.push_str(&render_interpolated(resolve_path(vars, path)?));
+        rest = &rest[start + end_rel + 1..];
+    }
+    out.push_str(rest);
+    Ok(Value::String(out))
+}
+
+/// Recursively interpolate every string in `value` against captured step
+/// outputs, preserving structure (and non-string types untouched).
+fn interpolate(value: &Value, vars: &HashMap<String, Value>) -> Result<Value> {
+    match value {
+        Value::String(s) => interpolate_string(s, vars),
+        Value::Array(items) => Ok(Value::Array(items.iter().map(|v| interpolate(v, vars)).collect::<Result<_>>()?)),
+        Value::Object(map) => {
+            let mut out = serde_json::Map::with_capacity(map.len());
+            for (k, v) in map {
+                out.insert(k.clone(), interpolate(v, vars)?);
+            }
+            Ok(Value::Object(out))
+        }
+        other => Ok(other.clone()),
+    }
+}
+
+/// Run every step in `file` in order against `cfg`, resolving `${...}`
+/// placeholders from prior `capture`d outputs before dispatch. With
+/// `dry_run` set, no step is actually called — each step's resolved args are
+/// recorded with status `"planned"` so the full plan can be reviewed before
+/// anything touches the API. A step retries up to `step.retries` additional
+/// times on failure before being recorded as `"error"`; unless
+/// `step.continue_on_error` is set, the first such failure stops the run.
+pub async fn run(cfg: &Config, file: &WorkflowFile, dry_run: bool) -> Result<Vec<StepResult>> {
+    let registry = batch::registry();
+    let mut vars: HashMap<String, Value> = HashMap::new();
+    let mut results = Vec::with_capacity(file.steps.len());
+
+    for (index, step) in file.steps.iter().enumerate() {
+        let resolved_args = interpolate(&step.args, &vars)?;
+
+        if dry_run {
+            results.push(StepResult {
+                index,
+                command: step.command.clone(),
+                status: "planned",
+                attempts: 0,
+                data: Some(resolved_args),
+                error: None,
+            });
+            continue;
+        }
+
+        let Some(handler) = registry.get(step.command.as_str()) else {
+            let mut known: Vec<&str> = registry.keys().copied().collect();
+            known.sort_unstable();
+            let err = format!("unknown workflow command {:?}\nExpected one of: {}", step.command, known.join(", "));
+            results.push(StepResult {
+                index,
+                command: step.command.clone(),
+                status: "error",
+                attempts: 0,
+                data: None,
+                error: Some(err),
+            });
+            if !step.continue_on_error {
+                break;
+            }
+            continue;
+        };
+
+        let mut attempts = 0;
+        let outcome = loop {
+            attempts += 1;
+            match handler(cfg, &resolved_args).await {
+                Ok(data) => break Ok(data),
+                Err(_) if attempts <= step.retries => continue,
+                Err(e) => break Err(e),
+            }
+        };
+
+        match outcome {
+            Ok(data) => {
+                if let Some(name) = &step.capture {
+                    vars.insert(name.clone(), data.clone());
+                }
+                results.push(StepResult { index, command: step.command.clone(), status: "ok", attempts, data: Some(data), error: None });
+            }
+            Err(e) => {
+                results.push(StepResult {
+                    index,
+                    command: step.command.clone(),
+                    status: "error",
+                    attempts,
+                    data: None,
+                    error: Some(e.to_string()),
+                });
+                if !step.continue_on_error {
+                    break;
+                }
+            }
+        }
+    }
+
+    Ok(results)
+}
+
+/// `bail!`s with a summary if any step failed, mirroring
+/// `ops::batch::check_outcome`.
+pub fn check_outcome(results: &[StepResult]) -> Result<()> {
+    let failed: Vec<&StepResult> = results.iter().filter(|r| r.status == "error").collect();
+    if failed.is_empty() {
+        return Ok(());
+    }
+    bail!(
+        "{} of {} workflow step(s) failed: {}",
+        failed.len(),
+        results.len(),
+        failed.iter().map(|r| format!("#{} {}", r.index, r.command)).collect::<Vec<_>>().join(", ")
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_whole_placeholder_preserves_type() {
+        let mut vars = HashMap::new();
+        vars.insert("page".to_string(), serde_json::json!({"page_id": "abc123"}));
+        let resolved = interpolate(&serde_json::json!("${page}"), &vars).unwrap();
+        assert_eq!(resolved, serde_json::json!({"page_id": "abc123"}));
+    }
+
+    #[test]
+    fn test_nested_path_interpolated_into_string() {
+        let mut vars = HashMap::new();
+        vars.insert("page".to_string(), serde_json::json!({"page_id": "abc123"}));
+        let resolved = interpolate(&serde_json::json!("page=${page.page_id}"), &vars).unwrap();
+        assert_eq!(resolved, serde_json::json!("page=abc123"));
+    }
+
+    #[test]
+    fn test_interpolate_recurses_into_nested_structures() {
+        let mut vars = HashMap::new();
+        vars.insert("id".to_string(), serde_json::json!("xyz"));
+        let args = serde_json::json!({"nested": {"list": ["${id}", "literal"]}});
+        let resolved = interpolate(&args, &vars).unwrap();
+        assert_eq!(resolved, serde_json::json!({"nested": {"list": ["xyz", "literal"]}}));
+    }
+
+    #[test]
+    fn test_unknown_variable_is_an_error() {
+        let vars = HashMap::new();
+        let err = interpolate(&serde_json::json!("${missing}"), &vars).unwrap_err();
+        assert!(err.to_string().contains("missing"));
+    }
+
+    #[test]
+    fn test_check_outcome_errors_when_any_failed() {
+        let results = vec![
+            StepResult { index: 0, command: "x".into(), status: "ok", attempts: 1, data: None, error: None },
+            StepResult { index: 1, command: "y".into(), status: "error", attempts: 2, data: None, error: Some("boom".into()) },
+        ];
+        let err = check_outcome(&results).unwrap_err();
+        assert!(err.to_string().contains("1 of 2"));
+    }
+}
@@ -0,0 +1,186 @@
+//! OpenTelemetry instrumentation for `pup` itself: a root span per command
+//! invocation, child spans per HTTP call to Datadog (created alongside the
+//! request helpers in `crate::api`/`crate::client`), plus a request-count
+//! counter, a latency histogram, and an error counter — all exported over
+//! OTLP through one pipeline, so traces, metrics, and logs aren't three
+//! bespoke subsystems.
+//!
+//! `main()` is expected to call [`init`] first thing (before parsing `cli`),
+//! hold onto the returned [`TelemetryGuard`] for the lifetime of the
+//! process, and wrap each `match cli.command` arm in [`instrument_command`]
+//! so the command name, `read_only` flag, and outcome become span
+//! attributes. Instrumentation is a no-op unless `OTEL_EXPORTER_OTLP_ENDPOINT`
+//! is set, so running `pup` with no OTEL env vars costs nothing.
+
+use std::time::Instant;
+
+use anyhow::Result;
+use opentelemetry::global;
+use opentelemetry::metrics::{Counter, Histogram, Meter};
+use opentelemetry::trace::{Span, Status, Tracer, TracerProvider as _};
+use opentelemetry::{Key, KeyValue};
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::metrics::SdkMeterProvider;
+use opentelemetry_sdk::trace::SdkTracerProvider;
+use opentelemetry_sdk::Resource;
+
+const INSTRUMENTATION_NAME: &str = "pup";
+
+/// Holds the tracer/meter providers alive for the process lifetime and
+/// flushes/shuts them down on drop, so buffered spans and metrics aren't
+/// lost when `main()` returns.
+pub struct TelemetryGuard {
+    tracer_provider: SdkTracerProvider,
+    meter_provider: SdkMeterProvider,
+}
+
+impl Drop for TelemetryGuard {
+    fn drop(&mut self) {
+        if let Err(e) = self.tracer_provider.shutdown() {
+            eprintln!("warning: failed to flush OTEL traces: {e}");
+        }
+        if let Err(e) = self.meter_provider.shutdown() {
+            eprintln!("warning: failed to flush OTEL metrics: {e}");
+        }
+    }
+}
+
+/// Initialize the global tracer and meter providers from `OTEL_*` env vars.
+/// Returns `None` (and sets up nothing) when `OTEL_EXPORTER_OTLP_ENDPOINT`
+/// is unset, so instrumentation is entirely opt-in.
+pub fn init() -> Option<TelemetryGuard> {
+    let endpoint = std::env::var("OTEL_EXPORTER_OTLP_ENDPOINT").ok()?;
+    let service_name = std::env::var("OTEL_SERVICE_NAME").unwrap_or_else(|_| "pup".to_string());
+
+    let resource = Resource::builder()
+        .with_attribute(KeyValue::new("service.name", service_name))
+        .with_attribute(KeyValue::new("service.version", crate::version::VERSION))
+        .build();
+
+    let span_exporter = match opentelemetry_otlp::SpanExporter::builder()
+        .with_tonic()
+        .with_endpoint(&endpoint)
+        .build()
+    {
+        Ok(exporter) => exporter,
+        Err(e) => {
+            eprintln!("warning: failed to initialize OTLP span exporter: {e}");
+            return None;
+        }
+    };
+    let tracer_provider = SdkTracerProvider::builder()
+        .with_batch_exporter(span_exporter)
+        .with_resource(resource.clone())
+        .build();
+    global::set_tracer_provider(tracer_provider.clone());
+
+    let metric_exporter = match opentelemetry_otlp::MetricExporter::builder()
+        .with_tonic()
+        .with_endpoint(&endpoint)
+        .build()
+    {
+        Ok(exporter) => exporter,
+        Err(e) => {
+            eprintln!("warning: failed to initialize OTLP metric exporter: {e}");
+            return None;
+        }
+    };
+    let meter_provider = SdkMeterProvider::builder()
+        .with_periodic_exporter(metric_exporter)
+        .with_resource(resource)
+        .build();
+    global::set_meter_provider(meter_provider.clone());
+
+    Some(TelemetryGuard {
+        tracer_provider,
+        meter_provider,
+    })
+}
+
+fn meter() -> Meter {
+    global::meter(INSTRUMENTATION_NAME)
+}
+
+fn request_counter() -> Counter<u64> {
+    meter()
+        .u64_counter("pup.command.count")
+        .with_description("Number of pup command invocations")
+        .build()
+}
+
+fn error_counter() -> Counter<u64> {
+    meter()
+        .u64_counter("pup.command.errors")
+        .with_description("Number of pup command invocations that returned an error")
+        .build()
+}
+
+fn latency_histogram() -> Histogram<f64> {
+    meter()
+        .f64_histogram("pup.command.duration")
+        .with_description("pup command latency")
+        .with_unit("s")
+        .build()
+}
+
+const ATTR_COMMAND: Key = Key::from_static_str("pup.command");
+const ATTR_READ_ONLY: Key = Key::from_static_str("pup.read_only");
+const ATTR_OUTCOME: Key = Key::from_static_str("pup.outcome");
+
+/// Run `f`, wrapping it in a root span named after `command` (with
+/// `pup.command`/`pup.read_only` attributes set up front and `pup.outcome`
+/// set once `f` resolves), and recording the request-count, latency, and
+/// error-count metrics for it. A no-op wrapper — `f` still runs exactly
+/// once — when [`init`] was never called because OTEL isn't configured.
+pub async fn instrument_command<F, Fut, T>(command: &str, read_only: bool, f: F) -> Result<T>
+where
+    F: FnOnce() -> Fut,
+    Fut: std::future::Future<Output = Result<T>>,
+{
+    let tracer = global::tracer_provider().tracer(INSTRUMENTATION_NAME);
+    let mut span = tracer.start(command.to_string());
+    span.set_attribute(KeyValue::new(ATTR_COMMAND, command.to_string()));
+    span.set_attribute(KeyValue::new(ATTR_READ_ONLY, read_only));
+
+    let started_at = Instant::now();
+    let result = f().await;
+    let elapsed = started_at.elapsed().as_secs_f64();
+
+    let outcome = if result.is_ok() { "ok" } else { "error" };
+    span.set_attribute(KeyValue::new(ATTR_OUTCOME, outcome));
+    if let Err(e) = &result {
+        span.set_status(Status::error(e.to_string()));
+        error_counter().add(1, &[KeyValue::new(ATTR_COMMAND, command.to_string())]);
+    }
+    span.end();
+
+    let attrs = [KeyValue::new(ATTR_COMMAND, command.to_string())];
+    request_counter().add(1, &attrs);
+    latency_histogram().record(elapsed, &attrs);
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_instrument_command_passes_through_ok() {
+        let result = instrument_command("monitors list", true, || async { Ok(42) }).await;
+        assert_eq!(result.unwrap(), 42);
+    }
+
+    #[tokio::test]
+    async fn test_instrument_command_passes_through_err() {
+        let result: Result<()> =
+            instrument_command("monitors create", false, || async { anyhow::bail!("boom") }).await;
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_init_is_none_without_otlp_endpoint() {
+        std::env::remove_var("OTEL_EXPORTER_OTLP_ENDPOINT");
+        assert!(init().is_none());
+    }
+}